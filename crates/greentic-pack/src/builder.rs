@@ -32,6 +32,7 @@ use crate::repo::{InterfaceBinding, RepoPackSection};
 pub(crate) const SBOM_FORMAT: &str = "greentic-sbom-v1";
 pub(crate) const SIGNATURE_PATH: &str = "signatures/pack.sig";
 pub(crate) const SIGNATURE_CHAIN_PATH: &str = "signatures/chain.pem";
+pub(crate) const SIGNATURES_DIR: &str = "signatures/";
 pub const PACK_VERSION: u32 = 1;
 
 fn default_pack_version() -> u32 {
@@ -210,6 +211,64 @@ pub enum Signing {
     External(Arc<DynSigner>),
 }
 
+/// Compression applied to `.gtpack` entries.
+///
+/// Every mode is reproducible: the level is fixed, timestamps are pinned and
+/// entries are written in lexical order. Hashes in the SBOM and signatures
+/// always cover the uncompressed content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PackCompression {
+    #[default]
+    Stored,
+    Deflate,
+    Zstd,
+}
+
+impl PackCompression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PackCompression::Stored => "stored",
+            PackCompression::Deflate => "deflate",
+            PackCompression::Zstd => "zstd",
+        }
+    }
+
+    /// Value recorded in the SBOM document; `None` for stored archives so their
+    /// bytes are unchanged from packs built before compression existed.
+    pub fn sbom_value(self) -> Option<&'static str> {
+        match self {
+            PackCompression::Stored => None,
+            other => Some(other.as_str()),
+        }
+    }
+
+    /// Parse the `compression` recorded in an SBOM document.
+    pub fn from_sbom(value: Option<&str>) -> Result<Self> {
+        match value {
+            None | Some("stored") => Ok(PackCompression::Stored),
+            Some("deflate") => Ok(PackCompression::Deflate),
+            Some("zstd") => Ok(PackCompression::Zstd),
+            Some(other) => bail!("unsupported gtpack compression `{other}`"),
+        }
+    }
+
+    /// Zip entry options for this mode, with pinned timestamp and permissions.
+    pub fn zip_options(self) -> SimpleFileOptions {
+        let (method, level) = match self {
+            PackCompression::Stored => (CompressionMethod::Stored, None),
+            PackCompression::Deflate => (CompressionMethod::Deflated, Some(6)),
+            PackCompression::Zstd => (CompressionMethod::Zstd, Some(3)),
+        };
+        SimpleFileOptions::default()
+            .compression_method(method)
+            .compression_level(level)
+            .last_modified_time(zip_timestamp())
+            .unix_permissions(0o644)
+            .large_file(false)
+    }
+}
+
 pub struct PackBuilder {
     meta: PackMeta,
     flows: Vec<FlowBundle>,
@@ -219,6 +278,7 @@ pub struct PackBuilder {
     provenance: Option<Provenance>,
     component_descriptors: Vec<ComponentDescriptor>,
     distribution: Option<DistributionSection>,
+    compression: PackCompression,
 }
 
 struct Asset {
@@ -274,6 +334,46 @@ pub struct ComponentEntry {
     pub capabilities: Option<JsonValue>,
 }
 
+/// Role a signer takes in the release process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureRole {
+    Builder,
+    Approver,
+    Publisher,
+}
+
+impl SignatureRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureRole::Builder => "builder",
+            SignatureRole::Approver => "approver",
+            SignatureRole::Publisher => "publisher",
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SignatureRole {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "builder" => Ok(SignatureRole::Builder),
+            "approver" => Ok(SignatureRole::Approver),
+            "publisher" => Ok(SignatureRole::Publisher),
+            other => {
+                bail!("unknown signature role `{other}` (expected builder, approver or publisher)")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct SignatureEnvelope {
     pub alg: String,
@@ -282,6 +382,9 @@ pub(crate) struct SignatureEnvelope {
     pub signed_at_utc: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_fingerprint: Option<String>,
+    /// Role claimed by the signer; bound into the signed message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<SignatureRole>,
 }
 
 impl SignatureEnvelope {
@@ -300,18 +403,32 @@ impl SignatureEnvelope {
             digest: digest.to_hex().to_string(),
             signed_at_utc: signed_at,
             key_fingerprint,
+            role: None,
         }
     }
 }
 
-struct PendingFile {
+/// Bytes actually signed for `digest`.
+///
+/// Unroled signatures sign the raw digest; a role is appended so that it cannot
+/// be relabelled without invalidating the signature.
+pub(crate) fn signed_message(digest: &blake3::Hash, role: Option<SignatureRole>) -> Vec<u8> {
+    let mut message = digest.as_bytes().to_vec();
+    if let Some(role) = role {
+        message.extend_from_slice(b"\nrole=");
+        message.extend_from_slice(role.as_str().as_bytes());
+    }
+    message
+}
+
+pub(crate) struct PendingFile {
     path: String,
     media_type: String,
     bytes: Vec<u8>,
 }
 
 impl PendingFile {
-    fn new(path: String, media_type: impl Into<String>, bytes: Vec<u8>) -> Self {
+    pub(crate) fn new(path: String, media_type: impl Into<String>, bytes: Vec<u8>) -> Self {
         Self {
             path,
             media_type: media_type.into(),
@@ -339,6 +456,7 @@ impl PackBuilder {
             assets: Vec::new(),
             signing: Signing::Dev,
             provenance: None,
+            compression: PackCompression::Stored,
         }
     }
 
@@ -401,6 +519,11 @@ impl PackBuilder {
         self
     }
 
+    pub fn with_compression(mut self, compression: PackCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
     pub fn build(self, out_path: impl AsRef<Path>) -> Result<BuildResult> {
         let meta = self.meta;
         meta.validate()?;
@@ -585,10 +708,13 @@ impl PackBuilder {
         }
         let build_files = sbom_entries.clone();
 
-        let sbom_document = serde_json::json!({
+        let mut sbom_document = serde_json::json!({
             "format": SBOM_FORMAT,
             "files": sbom_entries,
         });
+        if let Some(compression) = self.compression.sbom_value() {
+            sbom_document["compression"] = serde_json::Value::from(compression);
+        }
         let sbom_bytes = serde_json::to_vec_pretty(&sbom_document)?;
         pending_files.push(PendingFile::new(
             "sbom.json".to_string(),
@@ -604,7 +730,7 @@ impl PackBuilder {
             let (signature_doc, chain_bytes) = match &self.signing {
                 Signing::Dev => dev_signature(&digest)?,
                 Signing::None => unreachable!(),
-                Signing::External(signer) => external_signature(&**signer, &digest)?,
+                Signing::External(signer) => external_signature(&**signer, &digest, None)?,
             };
 
             let sig_bytes = serde_json::to_vec_pretty(&signature_doc)?;
@@ -633,7 +759,7 @@ impl PackBuilder {
                 .with_context(|| format!("failed to create directory {}", parent.display()))?;
         }
 
-        write_zip(&out_path, &all_files)?;
+        write_zip(&out_path, &all_files, self.compression)?;
 
         Ok(BuildResult {
             out_path,
@@ -832,31 +958,40 @@ fn dev_signature(digest: &blake3::Hash) -> Result<(SignatureEnvelope, Option<Vec
     let signature = signing_key.sign(digest.as_bytes());
     let signature_bytes = signature.to_bytes();
 
+    let chain = self_signed_chain(&signing_key, "greentic-dev-local")?;
+    let fingerprint = hex_hash(signing_key.verifying_key().as_bytes());
+
+    let envelope = SignatureEnvelope::new("ed25519", &signature_bytes, digest, Some(fingerprint));
+    Ok((envelope, Some(chain)))
+}
+
+/// Self-signed Ed25519 certificate (PEM) for `signing_key`.
+///
+/// rcgen derives the serial from the public key and uses fixed validity dates,
+/// so the same key always yields the same certificate.
+pub(crate) fn self_signed_chain(signing_key: &SigningKey, common_name: &str) -> Result<Vec<u8>> {
     let pkcs8_doc = signing_key
         .to_pkcs8_der()
-        .map_err(|err| anyhow!("failed to encode dev keypair: {err}"))?;
+        .map_err(|err| anyhow!("failed to encode keypair: {err}"))?;
     let pkcs8_der = PrivatePkcs8KeyDer::from(pkcs8_doc.as_bytes().to_vec());
     let key_pair = KeyPair::from_pkcs8_der_and_sign_algo(&pkcs8_der, &PKCS_ED25519)
-        .map_err(|err| anyhow!("failed to load dev keypair for certificate: {err}"))?;
+        .map_err(|err| anyhow!("failed to load keypair for certificate: {err}"))?;
 
     let mut params = CertificateParams::new(Vec::<String>::new())?;
     params.distinguished_name = DistinguishedName::new();
     params
         .distinguished_name
-        .push(DnType::CommonName, "greentic-dev-local");
+        .push(DnType::CommonName, common_name);
     let cert = params.self_signed(&key_pair)?;
-    let chain = normalize_newlines(&cert.pem()).into_bytes();
-    let fingerprint = hex_hash(signing_key.verifying_key().as_bytes());
-
-    let envelope = SignatureEnvelope::new("ed25519", &signature_bytes, digest, Some(fingerprint));
-    Ok((envelope, Some(chain)))
+    Ok(normalize_newlines(&cert.pem()).into_bytes())
 }
 
-fn external_signature(
+pub(crate) fn external_signature(
     signer: &DynSigner,
     digest: &blake3::Hash,
+    role: Option<SignatureRole>,
 ) -> Result<(SignatureEnvelope, Option<Vec<u8>>)> {
-    let ExternalSignature { alg, sig } = signer.sign(digest.as_bytes())?;
+    let ExternalSignature { alg, sig } = signer.sign(&signed_message(digest, role))?;
     let chain = signer.chain_pem()?;
     let chain_bytes = if chain.is_empty() {
         None
@@ -864,7 +999,8 @@ fn external_signature(
         let chain_str = String::from_utf8(chain)?;
         Some(normalize_newlines(&chain_str).into_bytes())
     };
-    let envelope = SignatureEnvelope::new(alg, &sig, digest, None);
+    let mut envelope = SignatureEnvelope::new(alg, &sig, digest, None);
+    envelope.role = role;
     Ok((envelope, chain_bytes))
 }
 
@@ -872,18 +1008,17 @@ pub(crate) fn hex_hash(bytes: &[u8]) -> String {
     blake3::hash(bytes).to_hex().to_string()
 }
 
-fn write_zip(out_path: &Path, files: &[PendingFile]) -> Result<()> {
+pub(crate) fn write_zip(
+    out_path: &Path,
+    files: &[PendingFile],
+    compression: PackCompression,
+) -> Result<()> {
     let file = fs::File::create(out_path)
         .with_context(|| format!("failed to create {}", out_path.display()))?;
     let mut writer = ZipWriter::new(file);
-    let timestamp = zip_timestamp();
+    let options = compression.zip_options();
 
     for entry in files {
-        let options = SimpleFileOptions::default()
-            .compression_method(CompressionMethod::Stored)
-            .last_modified_time(timestamp)
-            .unix_permissions(0o644)
-            .large_file(false);
         writer
             .start_file(&entry.path, options)
             .with_context(|| format!("failed to add {} to archive", entry.path))?;
@@ -916,23 +1051,61 @@ mod tests {
         let wasm_path = temp.path().join("component.wasm");
         fs::write(&wasm_path, test_wasm_bytes()).unwrap();
 
-        let builder = || {
+        let builder = |compression| {
             PackBuilder::new(sample_meta())
                 .with_flow(sample_flow())
                 .with_component(sample_component(&wasm_path))
                 .with_signing(Signing::None)
                 .with_provenance(sample_provenance())
+                .with_compression(compression)
         };
 
-        let out_a = temp.path().join("a.gtpack");
-        let out_b = temp.path().join("b.gtpack");
+        let mut stored_files = None;
+        for compression in [
+            PackCompression::Stored,
+            PackCompression::Deflate,
+            PackCompression::Zstd,
+        ] {
+            let out_a = temp
+                .path()
+                .join(format!("a-{}.gtpack", compression.as_str()));
+            let out_b = temp
+                .path()
+                .join(format!("b-{}.gtpack", compression.as_str()));
+
+            let result = builder(compression).build(&out_a).unwrap();
+            builder(compression).build(&out_b).unwrap();
+
+            let bytes_a = fs::read(&out_a).unwrap();
+            let bytes_b = fs::read(&out_b).unwrap();
+            assert_eq!(
+                bytes_a,
+                bytes_b,
+                "{} gtpack output should be deterministic",
+                compression.as_str()
+            );
 
-        builder().build(&out_a).unwrap();
-        builder().build(&out_b).unwrap();
+            let mut archive = ZipArchive::new(File::open(&out_a).unwrap()).unwrap();
+            for i in 0..archive.len() {
+                let entry = archive.by_index(i).unwrap();
+                let expected = match compression {
+                    PackCompression::Stored => zip::CompressionMethod::Stored,
+                    PackCompression::Deflate => zip::CompressionMethod::Deflated,
+                    PackCompression::Zstd => zip::CompressionMethod::Zstd,
+                };
+                assert_eq!(entry.compression(), expected, "{}", entry.name());
+            }
 
-        let bytes_a = fs::read(&out_a).unwrap();
-        let bytes_b = fs::read(&out_b).unwrap();
-        assert_eq!(bytes_a, bytes_b, "gtpack output should be deterministic");
+            // SBOM hashes cover uncompressed content, so they match across modes.
+            let files = stored_files.get_or_insert_with(|| result.files.clone());
+            assert_eq!(files, &result.files);
+            let load = crate::reader::open_pack(&out_a, crate::reader::SigningPolicy::DevOk)
+                .unwrap_or_else(|err| panic!("{}: {}", compression.as_str(), err.message));
+            assert_eq!(
+                load.files["components/oauth@1.0.0/component.wasm"],
+                test_wasm_bytes()
+            );
+        }
 
         let result = PackBuilder::new(sample_meta())
             .with_flow(sample_flow())
@@ -0,0 +1,829 @@
+#![forbid(unsafe_code)]
+
+//! Resolution of pack-to-pack dependencies.
+//!
+//! `pack.yaml` lists dependencies as `alias`, `pack_id`, `version_req` and
+//! `required_capabilities`. The resolver looks for candidate `.gtpack` archives in
+//! local directories or registry mirrors, picks the highest version that satisfies
+//! every requirement on a pack id (including requirements declared by other
+//! dependencies), and checks that the chosen pack offers the required capabilities
+//! in its `greentic.ext.capabilities.v1` extension.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_types::pack::extensions::capabilities::CapabilitiesExtensionV1;
+use greentic_types::pack_manifest::{ExtensionInline, PackManifest as GpackManifest};
+use semver::{Version, VersionReq};
+use sha2::{Digest, Sha256};
+
+use crate::pack_lock::LockedDependency;
+use crate::reader::{SigningPolicy, open_pack};
+
+/// Extension key holding the capability offers of a pack.
+pub const CAPABILITIES_EXTENSION_KEY: &str = "greentic.ext.capabilities.v1";
+
+/// Upper bound on selection rounds; each round can only change a selection when
+/// a newly selected pack adds requirements.
+const MAX_ROUNDS: usize = 64;
+
+/// One dependency declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyRequest {
+    pub alias: String,
+    pub pack_id: String,
+    pub version_req: VersionReq,
+    pub required_capabilities: Vec<String>,
+}
+
+impl DependencyRequest {
+    pub fn new(
+        alias: impl Into<String>,
+        pack_id: impl Into<String>,
+        version_req: &str,
+        required_capabilities: Vec<String>,
+    ) -> Result<Self> {
+        let alias = alias.into();
+        let version_req = VersionReq::parse(version_req).with_context(|| {
+            format!("dependency `{alias}` has an invalid version requirement `{version_req}`")
+        })?;
+        Ok(Self {
+            alias,
+            pack_id: pack_id.into(),
+            version_req,
+            required_capabilities,
+        })
+    }
+}
+
+/// A pack archive that can satisfy dependencies.
+#[derive(Debug, Clone)]
+pub struct PackCandidate {
+    pub pack_id: String,
+    pub version: Version,
+    pub path: PathBuf,
+    /// `sha256:<hex>` digest of the archive file.
+    pub digest: String,
+    /// Capability ids (and offer ids) from `greentic.ext.capabilities.v1`.
+    pub capabilities: BTreeSet<String>,
+    pub dependencies: Vec<DependencyRequest>,
+}
+
+impl PackCandidate {
+    /// Read a candidate from a `.gtpack` archive.
+    ///
+    /// Legacy archives contribute their `imports` as dependencies (aliased by pack
+    /// id) and offer no capabilities.
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let load = open_pack(path, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
+        let digest = format!("sha256:{:x}", Sha256::digest(&bytes));
+        let Some(manifest) = load.gpack_manifest else {
+            let meta = load.manifest.meta;
+            return Ok(Self {
+                pack_id: meta.pack_id,
+                version: meta.version,
+                path: path.to_path_buf(),
+                digest,
+                capabilities: BTreeSet::new(),
+                dependencies: meta
+                    .imports
+                    .iter()
+                    .map(|import| {
+                        DependencyRequest::new(
+                            import.pack_id.clone(),
+                            import.pack_id.clone(),
+                            &import.version_req,
+                            Vec::new(),
+                        )
+                    })
+                    .collect::<Result<_>>()?,
+            });
+        };
+        Ok(Self {
+            pack_id: manifest.pack_id.to_string(),
+            version: manifest.version.clone(),
+            path: path.to_path_buf(),
+            digest,
+            capabilities: offered_capabilities(&manifest)?,
+            dependencies: manifest
+                .dependencies
+                .iter()
+                .map(|dep| {
+                    DependencyRequest::new(
+                        dep.alias.clone(),
+                        dep.pack_id.to_string(),
+                        &dep.version_req.to_string(),
+                        dep.required_capabilities.clone(),
+                    )
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    fn label(&self) -> String {
+        format!("{}@{}", self.pack_id, self.version)
+    }
+}
+
+/// Capability ids and offer ids a pack advertises in `greentic.ext.capabilities.v1`.
+pub fn offered_capabilities(manifest: &GpackManifest) -> Result<BTreeSet<String>> {
+    let Some(ext) = manifest
+        .extensions
+        .as_ref()
+        .and_then(|all| all.get(CAPABILITIES_EXTENSION_KEY))
+    else {
+        return Ok(BTreeSet::new());
+    };
+    let Some(ExtensionInline::Other(value)) = ext.inline.as_ref() else {
+        return Ok(BTreeSet::new());
+    };
+    let payload = CapabilitiesExtensionV1::from_extension_value(value)
+        .map_err(|err| anyhow!("invalid capabilities extension payload: {err}"))?;
+    Ok(payload
+        .offers
+        .iter()
+        .flat_map(|offer| [offer.cap_id.clone(), offer.offer_id.clone()])
+        .collect())
+}
+
+/// Candidate packs grouped by pack id.
+#[derive(Debug, Clone, Default)]
+pub struct PackIndex {
+    candidates: BTreeMap<String, Vec<PackCandidate>>,
+    /// Archives that were found but could not be read, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl PackIndex {
+    /// Index every `.gtpack` below `roots`.
+    ///
+    /// Roots may be flat directories of archives or registry mirrors laid out as
+    /// `<pack_id>/<version>/*.gtpack`; both are searched recursively.
+    pub fn scan(roots: &[PathBuf]) -> Result<Self> {
+        let mut index = Self::default();
+        for root in roots {
+            let mut archives = Vec::new();
+            collect_archives(root, &mut archives)?;
+            archives.sort();
+            for path in archives {
+                match PackCandidate::load(&path) {
+                    Ok(candidate) => index.insert(candidate),
+                    Err(err) => index.skipped.push(format!("{}: {err:#}", path.display())),
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Index the archives a previous resolution locked.
+    ///
+    /// Each entry must still point at a readable `file://` archive whose digest
+    /// matches the lock, so re-resolving against the result re-checks
+    /// `version_req` and `required_capabilities` without searching again.
+    pub fn from_locked(locked: &BTreeMap<String, LockedDependency>) -> Result<Self> {
+        let mut index = Self::default();
+        for dep in locked.values() {
+            let label = format!("{}@{}", dep.pack_id, dep.version);
+            let Some(path) = dep.r#ref.as_deref().and_then(|r| r.strip_prefix("file://")) else {
+                bail!("locked dependency {label} has no local archive ref");
+            };
+            let candidate = PackCandidate::load(Path::new(path))
+                .with_context(|| format!("locked dependency {label} is unavailable"))?;
+            if candidate.digest != dep.digest {
+                bail!(
+                    "locked dependency {label} changed: {} has digest {}, lock has {}",
+                    path,
+                    candidate.digest,
+                    dep.digest
+                );
+            }
+            index.insert(candidate);
+        }
+        Ok(index)
+    }
+
+    /// Add a candidate. The first archive seen for a pack id and version wins.
+    pub fn insert(&mut self, candidate: PackCandidate) {
+        let versions = self
+            .candidates
+            .entry(candidate.pack_id.clone())
+            .or_default();
+        if versions.iter().any(|c| c.version == candidate.version) {
+            return;
+        }
+        versions.push(candidate);
+        versions.sort_by(|a, b| b.version.cmp(&a.version));
+    }
+
+    /// Candidates for `pack_id`, highest version first.
+    pub fn candidates(&self, pack_id: &str) -> &[PackCandidate] {
+        self.candidates
+            .get(pack_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+}
+
+fn collect_archives(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("failed to read directory {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_archives(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "gtpack") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// A pack chosen by [`resolve_dependencies`].
+#[derive(Debug, Clone)]
+pub struct ResolvedPack {
+    pub pack_id: String,
+    pub version: Version,
+    pub digest: String,
+    pub path: PathBuf,
+    /// Root aliases that point at this pack.
+    pub aliases: Vec<String>,
+    /// Requesters (`<pack_id>@<version>`) of this pack.
+    pub required_by: Vec<String>,
+}
+
+/// Outcome of dependency resolution, ordered by pack id.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyResolution {
+    pub packs: Vec<ResolvedPack>,
+    /// Rendered dependency tree of the selection.
+    pub tree: String,
+}
+
+impl DependencyResolution {
+    /// Lock entries for `pack.lock.cbor`.
+    pub fn locked(&self) -> BTreeMap<String, LockedDependency> {
+        self.packs
+            .iter()
+            .map(|pack| {
+                (
+                    pack.pack_id.clone(),
+                    LockedDependency {
+                        pack_id: pack.pack_id.clone(),
+                        version: pack.version.to_string(),
+                        digest: pack.digest.clone(),
+                        r#ref: Some(format!("file://{}", pack.path.display())),
+                        aliases: pack.aliases.clone(),
+                        required_by: pack.required_by.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// The pack whose dependencies are being resolved.
+#[derive(Debug, Clone, Copy)]
+pub struct RootPack<'a> {
+    pub pack_id: &'a str,
+    pub version: &'a Version,
+    pub dependencies: &'a [DependencyRequest],
+}
+
+impl RootPack<'_> {
+    fn label(&self) -> String {
+        format!("{}@{}", self.pack_id, self.version)
+    }
+}
+
+struct Requirement<'a> {
+    requester: String,
+    request: &'a DependencyRequest,
+}
+
+impl Requirement<'_> {
+    fn accepts(&self, candidate: &PackCandidate) -> bool {
+        self.request.version_req.matches(&candidate.version) && self.missing(candidate).is_empty()
+    }
+
+    fn missing<'c>(&'c self, candidate: &PackCandidate) -> Vec<&'c str> {
+        self.request
+            .required_capabilities
+            .iter()
+            .filter(|cap| !candidate.capabilities.contains(*cap))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Select one pack per dependency pack id.
+///
+/// Every requirement on a pack id must be met by the same version; the highest
+/// such version wins. When none exists the error shows who asked for what, the
+/// available versions and the dependency tree.
+pub fn resolve_dependencies(root: RootPack<'_>, index: &PackIndex) -> Result<DependencyResolution> {
+    let mut selection: BTreeMap<String, &PackCandidate> = BTreeMap::new();
+    for _ in 0..MAX_ROUNDS {
+        let requirements = collect_requirements(root, &selection);
+        let mut next = BTreeMap::new();
+        for (pack_id, reqs) in &requirements {
+            let candidates = index.candidates(pack_id);
+            match candidates
+                .iter()
+                .find(|candidate| reqs.iter().all(|req| req.accepts(candidate)))
+            {
+                Some(candidate) => {
+                    next.insert(pack_id.clone(), candidate);
+                }
+                None => {
+                    bail!(
+                        "{}",
+                        conflict_report(root, &selection, pack_id, reqs, candidates)
+                    );
+                }
+            }
+        }
+
+        let settled = next.len() == selection.len()
+            && next
+                .iter()
+                .zip(selection.iter())
+                .all(|((a_id, a), (b_id, b))| a_id == b_id && a.version == b.version);
+        selection = next;
+        if settled {
+            return Ok(finish(root, &selection, &requirements));
+        }
+    }
+    bail!("dependency resolution for {} did not settle", root.label())
+}
+
+fn collect_requirements<'a>(
+    root: RootPack<'a>,
+    selection: &BTreeMap<String, &'a PackCandidate>,
+) -> BTreeMap<String, Vec<Requirement<'a>>> {
+    let mut out: BTreeMap<String, Vec<Requirement<'a>>> = BTreeMap::new();
+    for request in root.dependencies {
+        out.entry(request.pack_id.clone())
+            .or_default()
+            .push(Requirement {
+                requester: root.label(),
+                request,
+            });
+    }
+    for candidate in selection.values().copied() {
+        for request in &candidate.dependencies {
+            // A dependency cycle back to the root pack is satisfied by the root itself.
+            if request.pack_id == root.pack_id {
+                continue;
+            }
+            out.entry(request.pack_id.clone())
+                .or_default()
+                .push(Requirement {
+                    requester: candidate.label(),
+                    request,
+                });
+        }
+    }
+    out
+}
+
+fn finish(
+    root: RootPack<'_>,
+    selection: &BTreeMap<String, &PackCandidate>,
+    requirements: &BTreeMap<String, Vec<Requirement<'_>>>,
+) -> DependencyResolution {
+    let root_label = root.label();
+    let packs = selection
+        .iter()
+        .map(|(pack_id, candidate)| {
+            let reqs = requirements
+                .get(pack_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            ResolvedPack {
+                pack_id: pack_id.clone(),
+                version: candidate.version.clone(),
+                digest: candidate.digest.clone(),
+                path: candidate.path.clone(),
+                aliases: reqs
+                    .iter()
+                    .filter(|req| req.requester == root_label)
+                    .map(|req| req.request.alias.clone())
+                    .collect(),
+                required_by: reqs.iter().map(|req| req.requester.clone()).collect(),
+            }
+        })
+        .collect();
+    DependencyResolution {
+        packs,
+        tree: render_tree(root, selection, None),
+    }
+}
+
+fn conflict_report(
+    root: RootPack<'_>,
+    selection: &BTreeMap<String, &PackCandidate>,
+    pack_id: &str,
+    reqs: &[Requirement<'_>],
+    candidates: &[PackCandidate],
+) -> String {
+    let mut out = format!("cannot resolve dependency `{pack_id}`\n  required by:\n");
+    for req in reqs {
+        let _ = write!(
+            out,
+            "    {} (alias {}): {}",
+            req.requester, req.request.alias, req.request.version_req
+        );
+        if !req.request.required_capabilities.is_empty() {
+            let _ = write!(
+                out,
+                " with capabilities [{}]",
+                req.request.required_capabilities.join(", ")
+            );
+        }
+        out.push('\n');
+    }
+    if candidates.is_empty() {
+        out.push_str("  available: none\n");
+    } else {
+        out.push_str("  available:\n");
+        for candidate in candidates {
+            let rejected: Vec<String> = reqs
+                .iter()
+                .filter(|req| !req.accepts(candidate))
+                .map(|req| {
+                    let missing = req.missing(candidate);
+                    if req.request.version_req.matches(&candidate.version) {
+                        format!("{} lacks {}", req.requester, missing.join(", "))
+                    } else {
+                        format!("{} wants {}", req.requester, req.request.version_req)
+                    }
+                })
+                .collect();
+            let _ = writeln!(out, "    {} ({})", candidate.version, rejected.join("; "));
+        }
+    }
+    out.push_str("dependency tree:\n");
+    out.push_str(&render_tree(root, selection, Some(pack_id)));
+    out
+}
+
+/// Render the dependency tree of `root` under the current selection.
+fn render_tree(
+    root: RootPack<'_>,
+    selection: &BTreeMap<String, &PackCandidate>,
+    conflict: Option<&str>,
+) -> String {
+    let mut out = format!("{}\n", root.label());
+    let mut visiting = BTreeSet::from([root.pack_id.to_string()]);
+    render_children(
+        root.dependencies,
+        selection,
+        conflict,
+        "",
+        &mut visiting,
+        &mut out,
+    );
+    out
+}
+
+fn render_children(
+    requests: &[DependencyRequest],
+    selection: &BTreeMap<String, &PackCandidate>,
+    conflict: Option<&str>,
+    prefix: &str,
+    visiting: &mut BTreeSet<String>,
+    out: &mut String,
+) {
+    for (idx, request) in requests.iter().enumerate() {
+        let last = idx + 1 == requests.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        let selected = selection.get(&request.pack_id);
+        let status = if conflict == Some(request.pack_id.as_str()) {
+            " [conflict]".to_string()
+        } else if let Some(candidate) = selected {
+            format!(" = {}", candidate.version)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            out,
+            "{prefix}{branch}{} -> {} {}{status}",
+            request.alias, request.pack_id, request.version_req
+        );
+        if let Some(candidate) = selected
+            && visiting.insert(request.pack_id.clone())
+        {
+            render_children(
+                &candidate.dependencies,
+                selection,
+                conflict,
+                &format!("{prefix}{indent}"),
+                visiting,
+                out,
+            );
+            visiting.remove(&request.pack_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{FlowBundle, ImportRef, PACK_VERSION, PackBuilder, PackMeta, Signing};
+    use serde_json::{Map, json};
+    use tempfile::TempDir;
+
+    fn write_legacy_pack(path: &Path, pack_id: &str, version: &str, imports: Vec<ImportRef>) {
+        let meta = PackMeta {
+            pack_version: PACK_VERSION,
+            pack_id: pack_id.into(),
+            version: Version::parse(version).unwrap(),
+            name: pack_id.into(),
+            kind: None,
+            description: None,
+            authors: Vec::new(),
+            license: None,
+            homepage: None,
+            support: None,
+            vendor: None,
+            imports,
+            entry_flows: vec!["main".into()],
+            created_at_utc: "2025-01-01T00:00:00Z".into(),
+            events: None,
+            repo: None,
+            messaging: None,
+            interfaces: Vec::new(),
+            annotations: Map::new(),
+            distribution: None,
+            components: Vec::new(),
+        };
+        let flow = json!({"id": "main", "kind": "flow/v1", "entry": "start", "nodes": []});
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        PackBuilder::new(meta)
+            .with_flow(FlowBundle {
+                id: "main".into(),
+                kind: "flow/v1".into(),
+                entry: "start".into(),
+                yaml: "id: main\nentry: start\n".into(),
+                json: flow.clone(),
+                hash_blake3: blake3::hash(&serde_json::to_vec(&flow).unwrap())
+                    .to_hex()
+                    .to_string(),
+                nodes: Vec::new(),
+            })
+            .with_signing(Signing::None)
+            .build(path)
+            .unwrap();
+    }
+
+    fn candidate(
+        pack_id: &str,
+        version: &str,
+        capabilities: &[&str],
+        dependencies: Vec<DependencyRequest>,
+    ) -> PackCandidate {
+        PackCandidate {
+            pack_id: pack_id.to_string(),
+            version: Version::parse(version).unwrap(),
+            path: PathBuf::from(format!("/mirror/{pack_id}/{version}.gtpack")),
+            digest: format!("sha256:{}", "a".repeat(64)),
+            capabilities: capabilities.iter().map(|cap| cap.to_string()).collect(),
+            dependencies,
+        }
+    }
+
+    fn request(alias: &str, pack_id: &str, req: &str, caps: &[&str]) -> DependencyRequest {
+        DependencyRequest::new(
+            alias,
+            pack_id,
+            req,
+            caps.iter().map(|cap| cap.to_string()).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn selects_highest_version_meeting_all_requirements() {
+        let mut index = PackIndex::default();
+        index.insert(candidate("acme.storage", "1.2.0", &[], vec![]));
+        index.insert(candidate("acme.storage", "1.4.0", &[], vec![]));
+        index.insert(candidate("acme.storage", "2.0.0", &[], vec![]));
+        index.insert(candidate(
+            "acme.qa",
+            "1.1.0",
+            &["qa:process"],
+            vec![request("store", "acme.storage", ">=1.2, <1.4", &[])],
+        ));
+
+        let deps = vec![
+            request("qa", "acme.qa", "^1", &["qa:process"]),
+            request("storage", "acme.storage", "^1", &[]),
+        ];
+        let version = Version::new(0, 1, 0);
+        let resolution = resolve_dependencies(
+            RootPack {
+                pack_id: "demo",
+                version: &version,
+                dependencies: &deps,
+            },
+            &index,
+        )
+        .expect("resolve");
+
+        let storage = resolution
+            .packs
+            .iter()
+            .find(|pack| pack.pack_id == "acme.storage")
+            .unwrap();
+        assert_eq!(storage.version, Version::new(1, 2, 0));
+        assert_eq!(storage.aliases, vec!["storage".to_string()]);
+        assert_eq!(
+            storage.required_by,
+            vec!["demo@0.1.0".to_string(), "acme.qa@1.1.0".to_string()]
+        );
+        let locked = resolution.locked();
+        assert_eq!(locked["acme.qa"].version, "1.1.0");
+        assert!(resolution.tree.contains("qa -> acme.qa ^1 = 1.1.0"));
+    }
+
+    #[test]
+    fn missing_capability_is_reported_as_conflict() {
+        let mut index = PackIndex::default();
+        index.insert(candidate("acme.qa", "1.0.0", &["qa:process"], vec![]));
+        index.insert(candidate("acme.qa", "1.3.0", &[], vec![]));
+
+        let deps = vec![request("qa", "acme.qa", "^1", &["qa:process"])];
+        let version = Version::new(0, 1, 0);
+        let root = RootPack {
+            pack_id: "demo",
+            version: &version,
+            dependencies: &deps,
+        };
+        let resolution = resolve_dependencies(root, &index).expect("falls back to 1.0.0");
+        assert_eq!(resolution.packs[0].version, Version::new(1, 0, 0));
+
+        let mut index = PackIndex::default();
+        index.insert(candidate("acme.qa", "1.3.0", &[], vec![]));
+        let err = resolve_dependencies(root, &index).unwrap_err().to_string();
+        assert!(err.contains("1.3.0 (demo@0.1.0 lacks qa:process)"), "{err}");
+    }
+
+    #[test]
+    fn incompatible_requirements_render_dependency_tree() {
+        let mut index = PackIndex::default();
+        index.insert(candidate("acme.storage", "1.5.0", &[], vec![]));
+        index.insert(candidate("acme.storage", "2.1.0", &[], vec![]));
+        index.insert(candidate(
+            "acme.cache",
+            "3.0.0",
+            &[],
+            vec![request("backend", "acme.storage", "^2", &[])],
+        ));
+
+        let deps = vec![
+            request("cache", "acme.cache", "^3", &[]),
+            request("storage", "acme.storage", "^1", &[]),
+        ];
+        let version = Version::new(0, 1, 0);
+        let err = resolve_dependencies(
+            RootPack {
+                pack_id: "demo",
+                version: &version,
+                dependencies: &deps,
+            },
+            &index,
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(
+            err.contains("cannot resolve dependency `acme.storage`"),
+            "{err}"
+        );
+        assert!(
+            err.contains("acme.cache@3.0.0 (alias backend): ^2"),
+            "{err}"
+        );
+        assert!(
+            err.contains(
+                "demo@0.1.0\n├── cache -> acme.cache ^3 = 3.0.0\n│   └── backend -> acme.storage ^2 [conflict]\n└── storage -> acme.storage ^1 [conflict]\n"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn scan_reads_registry_mirror_layout() {
+        let temp = TempDir::new().unwrap();
+        let mirror = temp.path().join("mirror");
+        write_legacy_pack(
+            &mirror.join("acme.storage/1.0.0/pack.gtpack"),
+            "acme.storage",
+            "1.0.0",
+            Vec::new(),
+        );
+        write_legacy_pack(
+            &mirror.join("acme.cache/2.0.0/pack.gtpack"),
+            "acme.cache",
+            "2.0.0",
+            vec![ImportRef {
+                pack_id: "acme.storage".into(),
+                version_req: "^1".into(),
+            }],
+        );
+        fs::write(mirror.join("broken.gtpack"), b"not a zip").unwrap();
+
+        let index = PackIndex::scan(&[mirror]).expect("scan");
+        assert_eq!(index.skipped.len(), 1);
+        assert_eq!(index.candidates("acme.cache")[0].dependencies.len(), 1);
+
+        let deps = vec![request("cache", "acme.cache", "^2", &[])];
+        let version = Version::new(0, 1, 0);
+        let resolution = resolve_dependencies(
+            RootPack {
+                pack_id: "demo",
+                version: &version,
+                dependencies: &deps,
+            },
+            &index,
+        )
+        .expect("resolve");
+        let locked = resolution.locked();
+        assert_eq!(
+            locked.keys().collect::<Vec<_>>(),
+            vec!["acme.cache", "acme.storage"]
+        );
+        assert!(locked["acme.storage"].aliases.is_empty());
+        assert_eq!(locked["acme.storage"].required_by, vec!["acme.cache@2.0.0"]);
+        assert!(locked["acme.cache"].digest.starts_with("sha256:"));
+    }
+
+    #[test]
+    fn unknown_pack_lists_no_candidates() {
+        let deps = vec![request("qa", "acme.qa", "^1", &[])];
+        let version = Version::new(0, 1, 0);
+        let err = resolve_dependencies(
+            RootPack {
+                pack_id: "demo",
+                version: &version,
+                dependencies: &deps,
+            },
+            &PackIndex::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("available: none"), "{err}");
+    }
+
+    #[test]
+    fn locked_index_rechecks_requirements() {
+        let temp = TempDir::new().unwrap();
+        let mirror = temp.path().join("mirror");
+        write_legacy_pack(
+            &mirror.join("acme.storage/1.0.0/pack.gtpack"),
+            "acme.storage",
+            "1.0.0",
+            Vec::new(),
+        );
+        let version = Version::new(0, 1, 0);
+        let resolve = |deps: &[DependencyRequest], index: &PackIndex| {
+            resolve_dependencies(
+                RootPack {
+                    pack_id: "demo",
+                    version: &version,
+                    dependencies: deps,
+                },
+                index,
+            )
+        };
+        let deps = vec![request("storage", "acme.storage", "^1", &[])];
+        let locked = resolve(&deps, &PackIndex::scan(&[mirror]).unwrap())
+            .expect("resolve")
+            .locked();
+
+        let index = PackIndex::from_locked(&locked).expect("locked index");
+        assert_eq!(resolve(&deps, &index).expect("re-resolve").locked(), locked);
+
+        let raised = vec![request("storage", "acme.storage", "^2", &[])];
+        assert!(resolve(&raised, &index).is_err());
+        let with_cap = vec![request("storage", "acme.storage", "^1", &["storage:kv"])];
+        assert!(resolve(&with_cap, &index).is_err());
+
+        let mut tampered = locked.clone();
+        tampered.get_mut("acme.storage").unwrap().digest = format!("sha256:{}", "0".repeat(64));
+        let err = PackIndex::from_locked(&tampered).unwrap_err().to_string();
+        assert!(err.contains("changed"), "{err}");
+    }
+}
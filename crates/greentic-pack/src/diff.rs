@@ -0,0 +1,558 @@
+//! Compare two packs (or two lockfiles) and report what changed between them.
+//!
+//! Both sides are first reduced to a [`PackSnapshot`], built from a [`PackLoad`]
+//! or from a bare [`PackLockV1`]. Components come from the lock when one is
+//! present and fall back to the manifest entries otherwise. Flows, assets,
+//! extensions, secret requirements and capabilities are only compared when both
+//! sides carry pack contents.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::dependencies::offered_capabilities;
+use crate::pack_lock::{PackLockV1, decode_pack_lock};
+use crate::reader::PackLoad;
+
+const PACK_LOCK_PATH: &str = "pack.lock.cbor";
+
+/// The parts of a pack that `diff` compares.
+#[derive(Debug, Clone, Default)]
+pub struct PackSnapshot {
+    /// Human label, e.g. `demo@1.4.2` or the lockfile path.
+    pub label: String,
+    pub version: Option<String>,
+    pub components: BTreeMap<String, ComponentSnapshot>,
+    /// Locked dependency pack id -> `version (digest)`.
+    pub dependencies: BTreeMap<String, String>,
+    /// `None` for lockfile-only snapshots.
+    pub contents: Option<PackContents>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentSnapshot {
+    pub version: Option<String>,
+    pub digest: Option<String>,
+    pub world: Option<String>,
+    pub abi_version: Option<String>,
+    pub describe_hash: Option<String>,
+    /// Operation id -> `schema_hash`.
+    pub operations: BTreeMap<String, String>,
+    pub capabilities: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PackContents {
+    /// Flow id -> content hash.
+    pub flows: BTreeMap<String, String>,
+    /// Asset path -> `sha256:<hex>`.
+    pub assets: BTreeMap<String, String>,
+    /// Extension key -> digest of its JSON payload.
+    pub extensions: BTreeMap<String, String>,
+    /// Secret key (and scope) -> JSON requirement.
+    pub secret_requirements: BTreeMap<String, String>,
+    pub capabilities: BTreeSet<String>,
+}
+
+impl PackSnapshot {
+    /// Snapshot a bare lockfile.
+    pub fn from_lock(label: impl Into<String>, lock: &PackLockV1) -> Self {
+        let mut snapshot = Self {
+            label: label.into(),
+            ..Self::default()
+        };
+        snapshot.apply_lock(lock);
+        snapshot
+    }
+
+    /// Snapshot an opened pack, including its embedded `pack.lock.cbor` if any.
+    pub fn from_load(load: &PackLoad) -> Result<Self> {
+        let meta = &load.manifest.meta;
+        let mut snapshot = Self {
+            label: format!("{}@{}", meta.pack_id, meta.version),
+            version: Some(meta.version.to_string()),
+            ..Self::default()
+        };
+        if let Some(bytes) = load.files.get(PACK_LOCK_PATH) {
+            let lock = decode_pack_lock(bytes).context("embedded pack.lock.cbor")?;
+            snapshot.apply_lock(&lock);
+        }
+
+        for entry in &load.manifest.components {
+            let component = snapshot.components.entry(entry.name.clone()).or_default();
+            component
+                .version
+                .get_or_insert_with(|| entry.version.to_string());
+            component
+                .digest
+                .get_or_insert_with(|| format!("blake3:{}", entry.hash_blake3));
+            if component.world.is_none() {
+                component.world = entry.world.clone();
+            }
+            component.capabilities = entry.capabilities.clone();
+        }
+
+        let mut contents = PackContents {
+            flows: load
+                .manifest
+                .flows
+                .iter()
+                .map(|flow| (flow.id.clone(), format!("blake3:{}", flow.hash_blake3)))
+                .collect(),
+            assets: load
+                .files
+                .iter()
+                .filter(|(path, _)| path.starts_with("assets/"))
+                .map(|(path, bytes)| (path.clone(), sha256_digest(bytes)))
+                .collect(),
+            ..PackContents::default()
+        };
+        if let Some(manifest) = load.gpack_manifest.as_ref() {
+            for (key, extension) in manifest.extensions.iter().flatten() {
+                let value = serde_json::to_vec(extension)
+                    .with_context(|| format!("encode extension {key}"))?;
+                contents
+                    .extensions
+                    .insert(key.clone(), sha256_digest(&value));
+            }
+            for requirement in &manifest.secret_requirements {
+                let mut id = json_label(&serde_json::to_value(&requirement.key)?);
+                if let Some(scope) = requirement.scope.as_ref() {
+                    id.push_str(&format!(" ({})", serde_json::to_string(scope)?));
+                }
+                contents
+                    .secret_requirements
+                    .insert(id, serde_json::to_string(requirement)?);
+            }
+            contents.capabilities = offered_capabilities(manifest)?;
+        }
+        snapshot.contents = Some(contents);
+        Ok(snapshot)
+    }
+
+    fn apply_lock(&mut self, lock: &PackLockV1) {
+        for (id, locked) in &lock.components {
+            self.components.insert(
+                id.clone(),
+                ComponentSnapshot {
+                    version: locked.component_version.clone(),
+                    digest: Some(locked.resolved_digest.clone()),
+                    world: locked.world.clone(),
+                    abi_version: Some(locked.abi_version.clone()),
+                    describe_hash: Some(locked.describe_hash.clone()),
+                    operations: locked
+                        .operations
+                        .iter()
+                        .map(|op| (op.operation_id.clone(), op.schema_hash.clone()))
+                        .collect(),
+                    capabilities: None,
+                },
+            );
+        }
+        self.dependencies = lock
+            .dependencies
+            .iter()
+            .map(|(id, dep)| (id.clone(), format!("{} ({})", dep.version, dep.digest)))
+            .collect();
+    }
+}
+
+/// Before/after value of a changed field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationChangeKind {
+    Added,
+    Removed,
+    SchemaChanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationChange {
+    pub operation_id: String,
+    pub kind: OperationChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_schema_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_schema_hash: Option<String>,
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentSummary {
+    pub id: String,
+    pub version: Option<String>,
+    pub digest: Option<String>,
+    pub world: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentChange {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub world: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi_version: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub describe_hash: Option<Change>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<OperationChange>,
+    pub capabilities_changed: bool,
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentsDiff {
+    pub added: Vec<ComponentSummary>,
+    pub removed: Vec<ComponentSummary>,
+    pub changed: Vec<ComponentChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryChange {
+    pub id: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Added, removed and changed keys of a keyed section.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EntryDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<EntryChange>,
+}
+
+impl EntryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Pack-content sections; only present when both sides are packs.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContentsDiff {
+    pub flows: EntryDiff,
+    pub assets: EntryDiff,
+    pub extensions: EntryDiff,
+    pub secret_requirements: EntryDiff,
+    pub capabilities: SetDiff,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackDiff {
+    pub old: String,
+    pub new: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Change>,
+    pub components: ComponentsDiff,
+    pub dependencies: EntryDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contents: Option<ContentsDiff>,
+    /// Set when a component or operation was removed, or a schema or world changed.
+    pub breaking: bool,
+}
+
+impl PackDiff {
+    pub fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.components.added.is_empty()
+            && self.components.removed.is_empty()
+            && self.components.changed.is_empty()
+            && self.dependencies.is_empty()
+            && self.contents.as_ref().is_none_or(|contents| {
+                contents.flows.is_empty()
+                    && contents.assets.is_empty()
+                    && contents.extensions.is_empty()
+                    && contents.secret_requirements.is_empty()
+                    && contents.capabilities.is_empty()
+            })
+    }
+}
+
+/// Compare two snapshots.
+pub fn diff_packs(old: &PackSnapshot, new: &PackSnapshot) -> PackDiff {
+    let mut components = ComponentsDiff::default();
+    for (id, component) in &new.components {
+        if !old.components.contains_key(id) {
+            components.added.push(summary(id, component));
+        }
+    }
+    for (id, old_component) in &old.components {
+        match new.components.get(id) {
+            None => components.removed.push(summary(id, old_component)),
+            Some(new_component) if new_component != old_component => {
+                components
+                    .changed
+                    .push(diff_component(id, old_component, new_component));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let contents = match (old.contents.as_ref(), new.contents.as_ref()) {
+        (Some(old), Some(new)) => Some(ContentsDiff {
+            flows: diff_entries(&old.flows, &new.flows),
+            assets: diff_entries(&old.assets, &new.assets),
+            extensions: diff_entries(&old.extensions, &new.extensions),
+            secret_requirements: diff_entries(&old.secret_requirements, &new.secret_requirements),
+            capabilities: SetDiff {
+                added: new
+                    .capabilities
+                    .difference(&old.capabilities)
+                    .cloned()
+                    .collect(),
+                removed: old
+                    .capabilities
+                    .difference(&new.capabilities)
+                    .cloned()
+                    .collect(),
+            },
+        }),
+        _ => None,
+    };
+
+    let breaking =
+        !components.removed.is_empty() || components.changed.iter().any(|change| change.breaking);
+    PackDiff {
+        old: old.label.clone(),
+        new: new.label.clone(),
+        version: change(&old.version, &new.version),
+        components,
+        dependencies: diff_entries(&old.dependencies, &new.dependencies),
+        contents,
+        breaking,
+    }
+}
+
+fn summary(id: &str, component: &ComponentSnapshot) -> ComponentSummary {
+    ComponentSummary {
+        id: id.to_string(),
+        version: component.version.clone(),
+        digest: component.digest.clone(),
+        world: component.world.clone(),
+    }
+}
+
+fn diff_component(id: &str, old: &ComponentSnapshot, new: &ComponentSnapshot) -> ComponentChange {
+    let mut operations = Vec::new();
+    for (op, hash) in &new.operations {
+        if !old.operations.contains_key(op) {
+            operations.push(OperationChange {
+                operation_id: op.clone(),
+                kind: OperationChangeKind::Added,
+                old_schema_hash: None,
+                new_schema_hash: Some(hash.clone()),
+                breaking: false,
+            });
+        }
+    }
+    for (op, old_hash) in &old.operations {
+        match new.operations.get(op) {
+            None => operations.push(OperationChange {
+                operation_id: op.clone(),
+                kind: OperationChangeKind::Removed,
+                old_schema_hash: Some(old_hash.clone()),
+                new_schema_hash: None,
+                breaking: true,
+            }),
+            Some(new_hash) if new_hash != old_hash => operations.push(OperationChange {
+                operation_id: op.clone(),
+                kind: OperationChangeKind::SchemaChanged,
+                old_schema_hash: Some(old_hash.clone()),
+                new_schema_hash: Some(new_hash.clone()),
+                breaking: true,
+            }),
+            Some(_) => {}
+        }
+    }
+    operations.sort_by(|a, b| a.operation_id.cmp(&b.operation_id));
+
+    let world = change(&old.world, &new.world);
+    let breaking = world.is_some() || operations.iter().any(|op| op.breaking);
+    ComponentChange {
+        id: id.to_string(),
+        digest: change(&old.digest, &new.digest),
+        version: change(&old.version, &new.version),
+        world,
+        abi_version: change(&old.abi_version, &new.abi_version),
+        describe_hash: change(&old.describe_hash, &new.describe_hash),
+        operations,
+        capabilities_changed: old.capabilities != new.capabilities,
+        breaking,
+    }
+}
+
+fn change(old: &Option<String>, new: &Option<String>) -> Option<Change> {
+    (old != new).then(|| Change {
+        old: old.clone(),
+        new: new.clone(),
+    })
+}
+
+fn diff_entries(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> EntryDiff {
+    let mut diff = EntryDiff {
+        added: new
+            .keys()
+            .filter(|key| !old.contains_key(*key))
+            .cloned()
+            .collect(),
+        ..EntryDiff::default()
+    };
+    for (key, old_value) in old {
+        match new.get(key) {
+            None => diff.removed.push(key.clone()),
+            Some(new_value) if new_value != old_value => diff.changed.push(EntryChange {
+                id: key.clone(),
+                old: old_value.clone(),
+                new: new_value.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff
+}
+
+fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+fn json_label(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_lock::{LockedComponent, LockedOperation};
+
+    fn locked(id: &str, digest: char, ops: &[(&str, char)]) -> LockedComponent {
+        LockedComponent {
+            component_id: id.to_string(),
+            r#ref: Some(format!("oci://example/{id}:1")),
+            abi_version: "0.6.0".to_string(),
+            resolved_digest: format!("sha256:{}", digest.to_string().repeat(64)),
+            describe_hash: digest.to_string().repeat(64),
+            operations: ops
+                .iter()
+                .map(|(op, hash)| LockedOperation {
+                    operation_id: op.to_string(),
+                    schema_hash: hash.to_string().repeat(64),
+                })
+                .collect(),
+            world: Some("greentic:component/component@0.6.0".to_string()),
+            component_version: Some("1.0.0".to_string()),
+            role: Some("tool".to_string()),
+        }
+    }
+
+    fn lock(components: Vec<LockedComponent>) -> PackLockV1 {
+        PackLockV1::new(
+            components
+                .into_iter()
+                .map(|component| (component.component_id.clone(), component))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn reports_component_and_operation_changes() {
+        let old = PackSnapshot::from_lock(
+            "old",
+            &lock(vec![
+                locked("a.keep", 'a', &[("run", '1')]),
+                locked(
+                    "b.change",
+                    'b',
+                    &[("run", '1'), ("drop", '2'), ("same", '3')],
+                ),
+                locked("c.gone", 'c', &[]),
+            ]),
+        );
+        let mut changed = locked(
+            "b.change",
+            'e',
+            &[("run", '9'), ("new", '4'), ("same", '3')],
+        );
+        changed.component_version = Some("1.1.0".to_string());
+        let new = PackSnapshot::from_lock(
+            "new",
+            &lock(vec![
+                locked("a.keep", 'a', &[("run", '1')]),
+                changed,
+                locked("d.added", 'd', &[]),
+            ]),
+        );
+
+        let diff = diff_packs(&old, &new);
+        assert!(diff.breaking);
+        assert!(diff.contents.is_none());
+        assert_eq!(diff.components.added.len(), 1);
+        assert_eq!(diff.components.added[0].id, "d.added");
+        assert_eq!(diff.components.removed[0].id, "c.gone");
+        assert_eq!(diff.components.changed.len(), 1);
+
+        let change = &diff.components.changed[0];
+        assert_eq!(change.id, "b.change");
+        assert_eq!(
+            change.version,
+            Some(Change {
+                old: Some("1.0.0".to_string()),
+                new: Some("1.1.0".to_string()),
+            })
+        );
+        assert!(change.digest.is_some());
+        assert!(change.world.is_none());
+        let ops: Vec<_> = change
+            .operations
+            .iter()
+            .map(|op| (op.operation_id.as_str(), op.kind, op.breaking))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                ("drop", OperationChangeKind::Removed, true),
+                ("new", OperationChangeKind::Added, false),
+                ("run", OperationChangeKind::SchemaChanged, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let lock = lock(vec![locked("a.keep", 'a', &[("run", '1')])]);
+        let diff = diff_packs(
+            &PackSnapshot::from_lock("old", &lock),
+            &PackSnapshot::from_lock("new", &lock),
+        );
+        assert!(diff.is_empty());
+        assert!(!diff.breaking);
+    }
+}
@@ -1,15 +1,21 @@
 #![forbid(unsafe_code)]
 
 pub mod builder;
+pub mod dependencies;
+pub mod diff;
 pub mod events;
 pub mod kind;
 pub mod messaging;
 pub mod pack_lock;
 pub mod path_safety;
 pub mod plan;
+pub mod plan_overlay;
+pub mod plan_render;
 pub mod reader;
 pub mod repo;
 pub mod resolver;
+pub mod signing;
+pub mod trust;
 pub mod validate;
 
 pub use kind::PackKind;
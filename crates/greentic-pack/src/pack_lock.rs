@@ -13,6 +13,9 @@ use serde::{Deserialize, Serialize};
 pub struct PackLockV1 {
     pub version: u32,
     pub components: BTreeMap<String, LockedComponent>,
+    /// Packs selected for `pack.yaml` dependencies (including transitive ones), keyed by pack id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, LockedDependency>,
 }
 
 /// Locked component entry.
@@ -33,6 +36,23 @@ pub struct LockedComponent {
     pub role: Option<String>,
 }
 
+/// Locked pack dependency entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedDependency {
+    pub pack_id: String,
+    pub version: String,
+    /// `sha256:<hex>` digest of the selected `.gtpack` file.
+    pub digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#ref: Option<String>,
+    /// `pack.yaml` aliases resolved to this pack; empty for transitive dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Packs (`<pack_id>@<version>`) that declared a dependency on this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_by: Vec<String>,
+}
+
 /// Locked operation entry.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LockedOperation {
@@ -45,6 +65,7 @@ impl PackLockV1 {
         Self {
             version: 1,
             components,
+            dependencies: BTreeMap::new(),
         }
     }
 }
@@ -129,6 +150,29 @@ pub fn validate_pack_lock(lock: &PackLockV1) -> Result<()> {
         }
     }
 
+    for (key, dependency) in &lock.dependencies {
+        if key != &dependency.pack_id {
+            anyhow::bail!(
+                "pack.lock dependency key {} does not match pack_id {}",
+                key,
+                dependency.pack_id
+            );
+        }
+        if semver::Version::parse(&dependency.version).is_err() {
+            anyhow::bail!(
+                "pack.lock dependency {} version {} is not valid semver",
+                dependency.pack_id,
+                dependency.version
+            );
+        }
+        if !dependency.digest.starts_with("sha256:") || dependency.digest.len() <= 7 {
+            anyhow::bail!(
+                "pack.lock dependency digest for {} must start with sha256:<hex>",
+                dependency.pack_id
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -139,7 +183,13 @@ fn is_hex_64(value: &str) -> bool {
 /// Read a pack.lock.cbor file from disk.
 pub fn read_pack_lock(path: &Path) -> Result<PackLockV1> {
     let raw = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
-    let lock: PackLockV1 = canonical::from_cbor(&raw).context("failed to decode pack.lock.cbor")?;
+    decode_pack_lock(&raw)
+}
+
+/// Decode and validate pack.lock.cbor bytes (e.g. the copy embedded in a .gtpack).
+pub fn decode_pack_lock(bytes: &[u8]) -> Result<PackLockV1> {
+    let lock: PackLockV1 =
+        canonical::from_cbor(bytes).context("failed to decode pack.lock.cbor")?;
     validate_pack_lock(&lock)?;
     Ok(lock)
 }
@@ -153,6 +203,12 @@ pub fn write_pack_lock(path: &Path, lock: &PackLockV1) -> Result<()> {
             .operations
             .sort_by(|a, b| a.operation_id.cmp(&b.operation_id));
     }
+    for dependency in normalized.dependencies.values_mut() {
+        dependency.aliases.sort();
+        dependency.aliases.dedup();
+        dependency.required_by.sort();
+        dependency.required_by.dedup();
+    }
 
     let bytes =
         canonical::to_canonical_cbor(&normalized).context("failed to encode pack.lock.cbor")?;
@@ -1,5 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use greentic_types::ResourceHints;
 use greentic_types::SecretRequirement;
 use greentic_types::TenantCtx;
 use greentic_types::component::ComponentManifest;
@@ -23,12 +24,16 @@ pub fn infer_base_deployment_plan(
     tenant: &TenantCtx,
     environment: &str,
 ) -> DeploymentPlan {
+    let mut capabilities = json!({
+        "flows": flows.iter().map(|flow| flow.id.clone()).collect::<Vec<_>>(),
+    });
+    if let Some(resources) = infer_resource_hints(components) {
+        capabilities["resources"] = resources;
+    }
     let runners = vec![RunnerPlan {
         name: format!("{}-runner", meta.pack_id),
         replicas: 1,
-        capabilities: json!({
-            "flows": flows.iter().map(|flow| flow.id.clone()).collect::<Vec<_>>(),
-        }),
+        capabilities,
     }];
 
     let messaging = infer_messaging_plan(connectors);
@@ -51,6 +56,24 @@ pub fn infer_base_deployment_plan(
     }
 }
 
+/// Non-default `ResourceHints` keyed by component id, so overlays and renderers
+/// can size the runner; `None` when no component declares any.
+fn infer_resource_hints(components: &HashMap<String, ComponentManifest>) -> Option<JsonValue> {
+    let default = serde_json::to_value(ResourceHints::default()).ok()?;
+    let hints: BTreeMap<&str, JsonValue> = components
+        .iter()
+        .filter_map(|(id, component)| {
+            let value = serde_json::to_value(&component.resources).ok()?;
+            (value != default).then_some((id.as_str(), value))
+        })
+        .collect();
+    if hints.is_empty() {
+        None
+    } else {
+        Some(json!(hints))
+    }
+}
+
 fn infer_messaging_plan(connectors: Option<&JsonValue>) -> Option<MessagingPlan> {
     let connectors = connectors?.as_object()?;
     let messaging_entry = connectors.get("messaging")?;
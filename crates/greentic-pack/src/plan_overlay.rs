@@ -0,0 +1,563 @@
+//! Layer environment-specific overlays on top of an inferred [`DeploymentPlan`].
+//!
+//! An overlay is a YAML or JSON document. A mapping is applied as a JSON merge
+//! patch (RFC 7396): objects merge key by key, `null` deletes a key and any
+//! other value replaces what was there. A sequence is applied as a JSON Patch
+//! (RFC 6902). Once every overlay is applied the result must still be a valid
+//! [`DeploymentPlan`] with no unknown fields, and each field records which layer
+//! last set it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_types::deployment::DeploymentPlan;
+use serde::Deserialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Source recorded for fields that no overlay touched.
+pub const INFERRED_SOURCE: &str = "inferred";
+
+/// One overlay file, in the order it was given.
+#[derive(Debug, Clone)]
+pub struct PlanOverlay {
+    /// Label used in provenance and errors, usually the file path.
+    pub source: String,
+    pub patch: OverlayPatch,
+}
+
+#[derive(Debug, Clone)]
+pub enum OverlayPatch {
+    /// RFC 7396 merge patch.
+    Merge(JsonValue),
+    /// RFC 6902 JSON Patch.
+    Json(Vec<PatchOperation>),
+}
+
+/// A single RFC 6902 operation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: JsonValue },
+    Remove { path: String },
+    Replace { path: String, value: JsonValue },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: JsonValue },
+}
+
+impl PlanOverlay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read overlay {}", path.display()))?;
+        Self::parse(path.display().to_string(), &text)
+    }
+
+    /// Parse an overlay document: a mapping is a merge patch, a sequence a JSON Patch.
+    pub fn parse(source: impl Into<String>, text: &str) -> Result<Self> {
+        let source = source.into();
+        let value: JsonValue = serde_yaml_bw::from_str(text)
+            .with_context(|| format!("overlay {source} is not valid YAML or JSON"))?;
+        let patch = match value {
+            JsonValue::Object(_) => OverlayPatch::Merge(value),
+            JsonValue::Array(_) => OverlayPatch::Json(
+                serde_json::from_value(value)
+                    .with_context(|| format!("overlay {source} is not a valid JSON Patch"))?,
+            ),
+            _ => bail!(
+                "overlay {source} must be a mapping (merge patch) or a list of JSON Patch operations"
+            ),
+        };
+        Ok(Self { source, patch })
+    }
+}
+
+/// A plan with overlays applied, plus where each field came from.
+#[derive(Debug, Clone)]
+pub struct LayeredPlan {
+    pub plan: DeploymentPlan,
+    /// JSON pointer of every leaf value (scalars and empty containers) -> the
+    /// overlay that set it, or [`INFERRED_SOURCE`].
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Apply `overlays` in order on top of `base` and validate the result.
+pub fn apply_overlays(base: &DeploymentPlan, overlays: &[PlanOverlay]) -> Result<LayeredPlan> {
+    let mut layer = Layer {
+        doc: serde_json::to_value(base).context("failed to serialize deployment plan")?,
+        sources: BTreeMap::new(),
+        source: INFERRED_SOURCE,
+    };
+    let doc = layer.doc.clone();
+    layer.record("", &doc);
+
+    for overlay in overlays {
+        layer.source = &overlay.source;
+        match &overlay.patch {
+            OverlayPatch::Merge(patch) => {
+                let mut doc = std::mem::take(&mut layer.doc);
+                layer.merge(&mut doc, patch, "");
+                layer.doc = doc;
+            }
+            OverlayPatch::Json(operations) => {
+                for (idx, operation) in operations.iter().enumerate() {
+                    layer.apply(operation).with_context(|| {
+                        format!(
+                            "overlay {}: JSON Patch operation {idx} failed",
+                            overlay.source
+                        )
+                    })?;
+                }
+            }
+        }
+    }
+
+    let plan = validate_plan(&layer.doc)?;
+    Ok(LayeredPlan {
+        plan,
+        sources: layer.sources,
+    })
+}
+
+struct Layer<'a> {
+    doc: JsonValue,
+    sources: BTreeMap<String, String>,
+    source: &'a str,
+}
+
+impl Layer<'_> {
+    /// Attribute every leaf of `value`, stored at `pointer`, to the current source.
+    fn record(&mut self, pointer: &str, value: &JsonValue) {
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    self.record(&format!("{pointer}/{}", escape(key)), child);
+                }
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                for (idx, child) in items.iter().enumerate() {
+                    self.record(&format!("{pointer}/{idx}"), child);
+                }
+            }
+            _ => {
+                self.sources
+                    .insert(pointer.to_string(), self.source.to_string());
+            }
+        }
+    }
+
+    /// Drop provenance for `pointer` and everything below it.
+    fn forget(&mut self, pointer: &str) {
+        let prefix = format!("{pointer}/");
+        self.sources
+            .retain(|key, _| key != pointer && !(pointer.is_empty() || key.starts_with(&prefix)));
+    }
+
+    fn replace_sources(&mut self, pointer: &str, value: &JsonValue) {
+        self.forget(pointer);
+        self.record(pointer, value);
+    }
+
+    /// Renumber provenance below `array` after inserting (`delta` = 1) or
+    /// removing (`delta` = -1) the element at `index`.
+    fn shift(&mut self, array: &str, index: usize, delta: isize) {
+        let prefix = format!("{array}/");
+        let renamed: Vec<(String, String)> = self
+            .sources
+            .keys()
+            .filter_map(|key| {
+                let rest = key.strip_prefix(&prefix)?;
+                let (head, tail) = match rest.split_once('/') {
+                    Some((head, tail)) => (head, Some(tail)),
+                    None => (rest, None),
+                };
+                let idx: usize = head.parse().ok()?;
+                if idx < index {
+                    return None;
+                }
+                let new_idx = idx.checked_add_signed(delta)?;
+                let new_key = match tail {
+                    Some(tail) => format!("{prefix}{new_idx}/{tail}"),
+                    None => format!("{prefix}{new_idx}"),
+                };
+                Some((key.clone(), new_key))
+            })
+            .collect();
+        let moved: Vec<(String, String)> = renamed
+            .into_iter()
+            .filter_map(|(old, new)| self.sources.remove(&old).map(|source| (new, source)))
+            .collect();
+        self.sources.extend(moved);
+    }
+
+    fn merge(&mut self, target: &mut JsonValue, patch: &JsonValue, pointer: &str) {
+        let JsonValue::Object(patch) = patch else {
+            *target = patch.clone();
+            self.replace_sources(pointer, target);
+            return;
+        };
+        if !target.is_object() {
+            *target = JsonValue::Object(JsonMap::new());
+            self.forget(pointer);
+        }
+        // `pointer` is about to gain children, so it is no longer a leaf.
+        self.sources.remove(pointer);
+        let map = target.as_object_mut().expect("target is an object");
+        for (key, value) in patch {
+            let child = format!("{pointer}/{}", escape(key));
+            if value.is_null() {
+                map.remove(key);
+                self.forget(&child);
+                continue;
+            }
+            let entry = map.entry(key.clone()).or_insert(JsonValue::Null);
+            self.merge(entry, value, &child);
+        }
+        if map.is_empty() {
+            self.sources
+                .insert(pointer.to_string(), self.source.to_string());
+        }
+    }
+
+    fn apply(&mut self, operation: &PatchOperation) -> Result<()> {
+        match operation {
+            PatchOperation::Add { path, value } => self.add(path, value.clone()),
+            PatchOperation::Remove { path } => self.remove(path).map(|_| ()),
+            PatchOperation::Replace { path, value } => {
+                let target = self
+                    .doc
+                    .pointer_mut(path)
+                    .ok_or_else(|| anyhow!("path `{path}` does not exist"))?;
+                *target = value.clone();
+                self.replace_sources(path, value);
+                Ok(())
+            }
+            PatchOperation::Move { from, path } => {
+                if path.starts_with(&format!("{from}/")) {
+                    bail!("cannot move `{from}` into its own child `{path}`");
+                }
+                let value = self.remove(from)?;
+                self.add(path, value)
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = self
+                    .doc
+                    .pointer(from)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("path `{from}` does not exist"))?;
+                self.add(path, value)
+            }
+            PatchOperation::Test { path, value } => match self.doc.pointer(path) {
+                Some(current) if current == value => Ok(()),
+                Some(current) => bail!("test failed: `{path}` is {current}, expected {value}"),
+                None => bail!("test failed: `{path}` does not exist"),
+            },
+        }
+    }
+
+    fn add(&mut self, path: &str, value: JsonValue) -> Result<()> {
+        if path.is_empty() {
+            self.replace_sources(path, &value);
+            self.doc = value;
+            return Ok(());
+        }
+        let (parent, key) = split_pointer(path)?;
+        let container = self
+            .doc
+            .pointer_mut(parent)
+            .ok_or_else(|| anyhow!("parent `{parent}` of `{path}` does not exist"))?;
+        match container {
+            JsonValue::Object(map) => {
+                map.insert(key, value.clone());
+                self.replace_sources(path, &value);
+            }
+            JsonValue::Array(items) => {
+                let index = if key == "-" {
+                    items.len()
+                } else {
+                    array_index(&key, items.len() + 1, path)?
+                };
+                items.insert(index, value.clone());
+                self.sources.remove(parent);
+                self.shift(parent, index, 1);
+                self.record(&format!("{parent}/{index}"), &value);
+            }
+            _ => bail!("parent `{parent}` of `{path}` is not an object or array"),
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, path: &str) -> Result<JsonValue> {
+        if path.is_empty() {
+            bail!("cannot remove the whole plan");
+        }
+        let (parent, key) = split_pointer(path)?;
+        let container = self
+            .doc
+            .pointer_mut(parent)
+            .ok_or_else(|| anyhow!("path `{path}` does not exist"))?;
+        let (removed, now_empty) = match container {
+            JsonValue::Object(map) => {
+                let removed = map
+                    .remove(&key)
+                    .ok_or_else(|| anyhow!("path `{path}` does not exist"))?;
+                let now_empty = map.is_empty();
+                self.forget(path);
+                (removed, now_empty)
+            }
+            JsonValue::Array(items) => {
+                let index = array_index(&key, items.len(), path)?;
+                let removed = items.remove(index);
+                let now_empty = items.is_empty();
+                self.forget(&format!("{parent}/{index}"));
+                self.shift(parent, index + 1, -1);
+                (removed, now_empty)
+            }
+            _ => bail!("path `{path}` does not exist"),
+        };
+        if now_empty {
+            self.sources
+                .insert(parent.to_string(), self.source.to_string());
+        }
+        Ok(removed)
+    }
+}
+
+/// Split a JSON pointer into its parent pointer and unescaped last token.
+fn split_pointer(pointer: &str) -> Result<(&str, String)> {
+    if !pointer.starts_with('/') {
+        bail!("invalid JSON pointer `{pointer}`");
+    }
+    let idx = pointer.rfind('/').expect("pointer starts with `/`");
+    Ok((&pointer[..idx], unescape(&pointer[idx + 1..])))
+}
+
+fn array_index(token: &str, bound: usize, path: &str) -> Result<usize> {
+    let index: usize = token
+        .parse()
+        .map_err(|_| anyhow!("`{token}` in `{path}` is not an array index"))?;
+    if index >= bound {
+        bail!("array index {index} in `{path}` is out of bounds");
+    }
+    Ok(index)
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Deserialize the overlaid document and reject fields the plan type drops.
+fn validate_plan(doc: &JsonValue) -> Result<DeploymentPlan> {
+    let plan: DeploymentPlan = serde_json::from_value(doc.clone())
+        .context("overlaid plan does not match the DeploymentPlan schema")?;
+    let known = serde_json::to_value(&plan).context("failed to serialize deployment plan")?;
+    if let Some(pointer) = unknown_field(doc, &known, "") {
+        bail!("overlaid plan does not match the DeploymentPlan schema: unknown field `{pointer}`");
+    }
+    Ok(plan)
+}
+
+fn unknown_field(value: &JsonValue, known: &JsonValue, pointer: &str) -> Option<String> {
+    match (value, known) {
+        (JsonValue::Object(map), JsonValue::Object(known_map)) => {
+            map.iter().find_map(|(key, child)| {
+                let child_pointer = format!("{pointer}/{}", escape(key));
+                match known_map.get(key) {
+                    Some(known_child) => unknown_field(child, known_child, &child_pointer),
+                    None if child.is_null() => None,
+                    None => Some(child_pointer),
+                }
+            })
+        }
+        (JsonValue::Array(items), JsonValue::Array(known_items)) => items
+            .iter()
+            .zip(known_items)
+            .enumerate()
+            .find_map(|(idx, (child, known_child))| {
+                unknown_field(child, known_child, &format!("{pointer}/{idx}"))
+            }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_types::deployment::{
+        MessagingPlan, MessagingSubjectPlan, RunnerPlan, TelemetryPlan,
+    };
+    use semver::Version;
+    use serde_json::json;
+
+    fn base_plan() -> DeploymentPlan {
+        DeploymentPlan {
+            pack_id: "demo.pack".to_string(),
+            pack_version: Version::parse("1.2.3").unwrap(),
+            tenant: "tenant-1".to_string(),
+            environment: "prod".to_string(),
+            runners: vec![RunnerPlan {
+                name: "demo.pack-runner".to_string(),
+                replicas: 1,
+                capabilities: json!({ "flows": ["main"] }),
+            }],
+            messaging: Some(MessagingPlan {
+                logical_cluster: "default".to_string(),
+                subjects: vec![MessagingSubjectPlan {
+                    name: "primary".to_string(),
+                    purpose: "messaging".to_string(),
+                    durable: true,
+                    extra: json!({}),
+                }],
+                extra: json!({}),
+            }),
+            channels: Vec::new(),
+            secrets: Vec::new(),
+            oauth: Vec::new(),
+            telemetry: Some(TelemetryPlan {
+                required: true,
+                suggested_endpoint: None,
+                extra: json!({}),
+            }),
+            extra: json!({ "keep": true, "drop": "me" }),
+        }
+    }
+
+    fn overlay(source: &str, text: &str) -> PlanOverlay {
+        PlanOverlay::parse(source, text).expect("parse overlay")
+    }
+
+    #[test]
+    fn merge_overlays_apply_in_order_with_sources() {
+        let layered = apply_overlays(
+            &base_plan(),
+            &[
+                overlay(
+                    "base.yaml",
+                    "runners:\n  - name: demo.pack-runner\n    replicas: 2\n    capabilities:\n      flows: [main]\nmessaging:\n  logical_cluster: nats-prod\n",
+                ),
+                overlay(
+                    "prod.yaml",
+                    "runners:\n  - name: demo.pack-runner\n    replicas: 3\n    capabilities:\n      flows: [main]\n      resources:\n        cpu: 500m\ntelemetry:\n  suggested_endpoint: http://otel:4317\nextra:\n  drop: null\n",
+                ),
+            ],
+        )
+        .expect("apply overlays");
+
+        assert_eq!(layered.plan.runners[0].replicas, 3);
+        assert_eq!(
+            layered.plan.messaging.as_ref().unwrap().logical_cluster,
+            "nats-prod"
+        );
+        assert_eq!(
+            layered
+                .plan
+                .telemetry
+                .as_ref()
+                .unwrap()
+                .suggested_endpoint
+                .as_deref(),
+            Some("http://otel:4317")
+        );
+        assert_eq!(layered.plan.extra, json!({ "keep": true }));
+
+        let source = |pointer: &str| layered.sources.get(pointer).map(String::as_str);
+        assert_eq!(source("/runners/0/replicas"), Some("prod.yaml"));
+        assert_eq!(
+            source("/runners/0/capabilities/resources/cpu"),
+            Some("prod.yaml")
+        );
+        assert_eq!(source("/messaging/logical_cluster"), Some("base.yaml"));
+        assert_eq!(source("/messaging/subjects/0/name"), Some(INFERRED_SOURCE));
+        assert_eq!(source("/telemetry/suggested_endpoint"), Some("prod.yaml"));
+        assert_eq!(source("/extra/keep"), Some(INFERRED_SOURCE));
+        assert_eq!(source("/extra/drop"), None);
+        assert_eq!(source("/pack_id"), Some(INFERRED_SOURCE));
+    }
+
+    #[test]
+    fn json_patch_inserts_and_renumbers_sources() {
+        let layered = apply_overlays(
+            &base_plan(),
+            &[overlay(
+                "patch.json",
+                r#"[
+                    {"op": "test", "path": "/runners/0/replicas", "value": 1},
+                    {"op": "add", "path": "/runners/0", "value": {"name": "edge", "replicas": 2, "capabilities": {}}},
+                    {"op": "replace", "path": "/runners/1/replicas", "value": 4},
+                    {"op": "copy", "from": "/messaging/subjects/0", "path": "/messaging/subjects/-"},
+                    {"op": "remove", "path": "/messaging/subjects/0"}
+                ]"#,
+            )],
+        )
+        .expect("apply patch");
+
+        let names: Vec<&str> = layered
+            .plan
+            .runners
+            .iter()
+            .map(|runner| runner.name.as_str())
+            .collect();
+        assert_eq!(names, ["edge", "demo.pack-runner"]);
+        assert_eq!(layered.plan.runners[1].replicas, 4);
+
+        let source = |pointer: &str| layered.sources.get(pointer).map(String::as_str);
+        assert_eq!(source("/runners/0/name"), Some("patch.json"));
+        assert_eq!(source("/runners/0/capabilities"), Some("patch.json"));
+        assert_eq!(source("/runners/1/name"), Some(INFERRED_SOURCE));
+        assert_eq!(source("/runners/1/replicas"), Some("patch.json"));
+        assert_eq!(source("/messaging/subjects/0/name"), Some("patch.json"));
+        assert_eq!(source("/messaging/subjects/1/name"), None);
+    }
+
+    #[test]
+    fn failed_test_operation_names_the_overlay() {
+        let err = apply_overlays(
+            &base_plan(),
+            &[overlay(
+                "guard.yaml",
+                "- op: test\n  path: /environment\n  value: staging\n",
+            )],
+        )
+        .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("guard.yaml"), "{message}");
+        assert!(message.contains("test failed"), "{message}");
+    }
+
+    #[test]
+    fn overlays_must_keep_the_plan_schema() {
+        let unknown = apply_overlays(&base_plan(), &[overlay("typo.yaml", "replica_count: 2\n")])
+            .unwrap_err();
+        assert!(
+            format!("{unknown:#}").contains("DeploymentPlan schema"),
+            "{unknown:#}"
+        );
+
+        let wrong_type = apply_overlays(
+            &base_plan(),
+            &[overlay(
+                "bad.json",
+                r#"[{"op": "replace", "path": "/runners/0/replicas", "value": "three"}]"#,
+            )],
+        )
+        .unwrap_err();
+        assert!(
+            format!("{wrong_type:#}").contains("DeploymentPlan schema"),
+            "{wrong_type:#}"
+        );
+    }
+
+    #[test]
+    fn scalar_overlays_are_rejected() {
+        assert!(PlanOverlay::parse("scalar.yaml", "42\n").is_err());
+        assert!(matches!(
+            overlay("patch.yaml", "- op: remove\n  path: /extra/drop\n").patch,
+            OverlayPatch::Json(_)
+        ));
+    }
+}
@@ -0,0 +1,694 @@
+//! Render a [`DeploymentPlan`] into Kubernetes manifests or a docker-compose file.
+//!
+//! Rendering is deterministic: the same plan always yields byte-identical files.
+//! Secret values are never written. Manifests only reference secrets by name,
+//! and operators provide the values out of band.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_types::deployment::DeploymentPlan;
+use serde_json::Value as JsonValue;
+use serde_yaml_bw::{Mapping, Value as Yaml};
+
+/// Image used for runners unless `capabilities.image` overrides it.
+pub const DEFAULT_RUNNER_IMAGE: &str = "ghcr.io/greentic-ai/greentic-runner:latest";
+/// OTLP endpoint used when the telemetry plan does not suggest one.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://otel-collector:4317";
+const RUNNER_PORT: u64 = 8080;
+const SERVICE_PORT: u64 = 80;
+const CHANNELS_FILE: &str = "channels.json";
+const CONFIG_DIR: &str = "/etc/greentic";
+const HEADER: &str = "# Generated by greentic-pack plan --render. Do not edit by hand.\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RenderTarget {
+    /// Kubernetes manifests plus a kustomization.yaml.
+    K8s,
+    /// A docker-compose.yaml.
+    Compose,
+}
+
+/// Render `plan` for `target`, returning file contents keyed by relative path.
+pub fn render_plan(
+    plan: &DeploymentPlan,
+    target: RenderTarget,
+) -> Result<BTreeMap<String, String>> {
+    let model = RenderModel::from_plan(plan)?;
+    match target {
+        RenderTarget::K8s => render_k8s(&model),
+        RenderTarget::Compose => render_compose(&model),
+    }
+}
+
+/// The parts of a plan that renderers need, already sanitized and sorted.
+struct RenderModel {
+    slug: String,
+    labels: Vec<(String, String)>,
+    /// Environment shared by every runner.
+    env: BTreeMap<String, String>,
+    /// NATS settings from the messaging plan.
+    messaging: Option<BTreeMap<String, String>>,
+    /// Canonical JSON of the channel plan.
+    channels: Option<String>,
+    secrets: Vec<SecretRef>,
+    runners: Vec<RunnerSpec>,
+}
+
+struct RunnerSpec {
+    name: String,
+    slug: String,
+    replicas: u64,
+    image: String,
+    env: BTreeMap<String, String>,
+}
+
+struct SecretRef {
+    /// Secret object key / compose secret name.
+    name: String,
+    /// Environment variable the value is exposed as.
+    env: String,
+    required: bool,
+}
+
+impl RenderModel {
+    fn from_plan(plan: &DeploymentPlan) -> Result<Self> {
+        let doc = serde_json::to_value(plan).context("failed to serialize deployment plan")?;
+        let field = |name: &str| -> Result<String> {
+            match doc.get(name) {
+                Some(JsonValue::String(value)) => Ok(value.clone()),
+                Some(value) if !value.is_null() => Ok(value.to_string()),
+                _ => Err(anyhow!("deployment plan has no `{name}`")),
+            }
+        };
+        let pack_id = field("pack_id")?;
+        let tenant = field("tenant")?;
+        let environment = field("environment")?;
+        let slug = dns_label(&pack_id);
+
+        let labels = vec![
+            (
+                "app.kubernetes.io/managed-by".to_string(),
+                "greentic-pack".to_string(),
+            ),
+            ("app.kubernetes.io/part-of".to_string(), slug.clone()),
+            (
+                "greentic.ai/environment".to_string(),
+                label_value(&environment),
+            ),
+            ("greentic.ai/tenant".to_string(), label_value(&tenant)),
+        ];
+
+        let mut env = BTreeMap::from([
+            ("GREENTIC_ENVIRONMENT".to_string(), environment.clone()),
+            ("GREENTIC_PACK_ID".to_string(), pack_id.clone()),
+            ("GREENTIC_PACK_VERSION".to_string(), field("pack_version")?),
+            ("GREENTIC_TENANT".to_string(), tenant.clone()),
+        ]);
+        let telemetry = present(&doc, "telemetry");
+        if let Some(telemetry) = telemetry {
+            let endpoint = telemetry
+                .get("suggested_endpoint")
+                .and_then(JsonValue::as_str)
+                .unwrap_or(DEFAULT_OTLP_ENDPOINT);
+            env.insert(
+                "OTEL_EXPORTER_OTLP_ENDPOINT".to_string(),
+                endpoint.to_string(),
+            );
+            env.insert(
+                "OTEL_RESOURCE_ATTRIBUTES".to_string(),
+                format!(
+                    "deployment.environment={environment},greentic.pack.id={pack_id},greentic.tenant={tenant}"
+                ),
+            );
+        }
+
+        let messaging = present(&doc, "messaging").map(|messaging| {
+            let subjects = messaging
+                .get("subjects")
+                .and_then(JsonValue::as_array)
+                .cloned()
+                .unwrap_or_default();
+            let names = |durable_only: bool| {
+                subjects
+                    .iter()
+                    .filter(|subject| !durable_only || subject["durable"].as_bool() == Some(true))
+                    .filter_map(|subject| subject["name"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            let mut settings = BTreeMap::from([
+                (
+                    "GREENTIC_NATS_CLUSTER".to_string(),
+                    messaging["logical_cluster"]
+                        .as_str()
+                        .unwrap_or("default")
+                        .to_string(),
+                ),
+                ("GREENTIC_NATS_SUBJECTS".to_string(), names(false)),
+            ]);
+            let durable = names(true);
+            if !durable.is_empty() {
+                settings.insert("GREENTIC_NATS_DURABLE_SUBJECTS".to_string(), durable);
+            }
+            settings
+        });
+
+        let mut channels: Vec<&JsonValue> = doc
+            .get("channels")
+            .and_then(JsonValue::as_array)
+            .map(|channels| channels.iter().collect())
+            .unwrap_or_default();
+        channels.sort_by_key(|channel| {
+            (
+                channel["name"].as_str().unwrap_or_default().to_string(),
+                channel["flow_id"].as_str().unwrap_or_default().to_string(),
+            )
+        });
+        let channels = (!channels.is_empty()).then(|| {
+            env.insert(
+                "GREENTIC_CHANNELS_FILE".to_string(),
+                format!("{CONFIG_DIR}/{CHANNELS_FILE}"),
+            );
+            let items: Vec<String> = channels.iter().map(|c| canonical_json(c)).collect();
+            format!("[{}]", items.join(","))
+        });
+
+        let mut secrets = BTreeMap::new();
+        let mut secret_envs = BTreeMap::new();
+        for secret in doc
+            .get("secrets")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let key = secret["key"]
+                .as_str()
+                .ok_or_else(|| anyhow!("secret requirement without a key: {secret}"))?;
+            let secret = SecretRef {
+                name: secret_name(key),
+                env: env_name(key),
+                required: secret["required"].as_bool().unwrap_or(true),
+            };
+            // Both sanitized names must stay unique, or one secret would shadow another.
+            if let Some((other, _)) = secrets.get(&secret.name) {
+                bail!(
+                    "secrets `{other}` and `{key}` both render as secret {}",
+                    secret.name
+                );
+            }
+            if let Some(other) = secret_envs.insert(secret.env.clone(), key) {
+                bail!(
+                    "secrets `{other}` and `{key}` both render as environment variable {}",
+                    secret.env
+                );
+            }
+            secrets.insert(secret.name.clone(), (key, secret));
+        }
+
+        let mut runners = doc
+            .get("runners")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .map(|runner| {
+                let name = runner["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("runner without a name: {runner}"))?;
+                let capabilities = &runner["capabilities"];
+                let mut runner_env =
+                    BTreeMap::from([("GREENTIC_RUNNER".to_string(), name.to_string())]);
+                let flows: Vec<&str> = capabilities["flows"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(JsonValue::as_str)
+                    .collect();
+                if !flows.is_empty() {
+                    runner_env.insert("GREENTIC_FLOWS".to_string(), flows.join(","));
+                }
+                if telemetry.is_some() {
+                    runner_env.insert("OTEL_SERVICE_NAME".to_string(), name.to_string());
+                }
+                Ok(RunnerSpec {
+                    name: name.to_string(),
+                    slug: dns_label(name),
+                    replicas: runner["replicas"].as_u64().unwrap_or(1),
+                    image: capabilities["image"]
+                        .as_str()
+                        .unwrap_or(DEFAULT_RUNNER_IMAGE)
+                        .to_string(),
+                    env: runner_env,
+                })
+            })
+            .collect::<Result<Vec<RunnerSpec>>>()?;
+        runners.sort_by(|a, b| a.slug.cmp(&b.slug));
+        if let Some(pair) = runners.windows(2).find(|pair| pair[0].slug == pair[1].slug) {
+            bail!(
+                "runners `{}` and `{}` both render as {}",
+                pair[0].name,
+                pair[1].name,
+                pair[0].slug
+            );
+        }
+
+        Ok(Self {
+            slug,
+            labels,
+            env,
+            messaging,
+            channels,
+            secrets: secrets.into_values().map(|(_, secret)| secret).collect(),
+            runners,
+        })
+    }
+
+    fn runner_env(&self, runner: &RunnerSpec) -> BTreeMap<String, String> {
+        let mut env = self.env.clone();
+        env.extend(runner.env.clone());
+        env
+    }
+
+    fn runner_labels(&self, runner: &RunnerSpec) -> Yaml {
+        let mut labels = self.labels.clone();
+        labels.push(("app.kubernetes.io/name".to_string(), runner.slug.clone()));
+        labels.sort();
+        string_map(labels)
+    }
+
+    fn selector(&self, runner: &RunnerSpec) -> Yaml {
+        map([
+            ("app.kubernetes.io/name", string(&runner.slug)),
+            ("app.kubernetes.io/part-of", string(&self.slug)),
+        ])
+    }
+
+    fn metadata(&self, name: &str, labels: Yaml) -> Yaml {
+        map([("labels", labels), ("name", string(name))])
+    }
+
+    fn common_labels(&self) -> Yaml {
+        string_map(self.labels.clone())
+    }
+}
+
+fn render_k8s(model: &RenderModel) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    let config_map = |suffix: &str, data: Yaml| {
+        map([
+            ("apiVersion", string("v1")),
+            ("kind", string("ConfigMap")),
+            (
+                "metadata",
+                model.metadata(&format!("{}-{suffix}", model.slug), model.common_labels()),
+            ),
+            ("data", data),
+        ])
+    };
+    if let Some(settings) = &model.messaging {
+        files.insert(
+            "configmap-messaging.yaml".to_string(),
+            to_yaml(&config_map("messaging", string_map(settings.clone())))?,
+        );
+    }
+    if let Some(channels) = &model.channels {
+        let data = map([(CHANNELS_FILE, string(channels))]);
+        files.insert(
+            "configmap-channels.yaml".to_string(),
+            to_yaml(&config_map("channels", data))?,
+        );
+    }
+
+    for runner in &model.runners {
+        files.insert(
+            format!("deployment-{}.yaml", runner.slug),
+            to_yaml(&k8s_deployment(model, runner))?,
+        );
+        files.insert(
+            format!("service-{}.yaml", runner.slug),
+            to_yaml(&map([
+                ("apiVersion", string("v1")),
+                ("kind", string("Service")),
+                (
+                    "metadata",
+                    model.metadata(&runner.slug, model.runner_labels(runner)),
+                ),
+                (
+                    "spec",
+                    map([
+                        (
+                            "ports",
+                            Yaml::from(vec![map([
+                                ("name", string("http")),
+                                ("port", Yaml::from(SERVICE_PORT)),
+                                ("targetPort", string("http")),
+                            ])]),
+                        ),
+                        ("selector", model.selector(runner)),
+                    ]),
+                ),
+            ]))?,
+        );
+    }
+
+    let resources: Vec<Yaml> = files.keys().map(|name| string(name)).collect();
+    files.insert(
+        "kustomization.yaml".to_string(),
+        to_yaml(&map([
+            ("apiVersion", string("kustomize.config.k8s.io/v1beta1")),
+            ("kind", string("Kustomization")),
+            ("resources", Yaml::from(resources)),
+        ]))?,
+    );
+    Ok(files)
+}
+
+fn k8s_deployment(model: &RenderModel, runner: &RunnerSpec) -> Yaml {
+    let mut env: Vec<Yaml> = model
+        .runner_env(runner)
+        .into_iter()
+        .map(|(name, value)| map([("name", Yaml::from(name)), ("value", Yaml::from(value))]))
+        .collect();
+    env.extend(model.secrets.iter().map(|secret| {
+        map([
+            ("name", string(&secret.env)),
+            (
+                "valueFrom",
+                map([(
+                    "secretKeyRef",
+                    map([
+                        ("key", string(&secret.name)),
+                        ("name", string(&format!("{}-secrets", model.slug))),
+                        ("optional", Yaml::from(!secret.required)),
+                    ]),
+                )]),
+            ),
+        ])
+    }));
+
+    let mut container = vec![
+        ("name", string("runner")),
+        ("image", string(&runner.image)),
+        (
+            "ports",
+            Yaml::from(vec![map([
+                ("containerPort", Yaml::from(RUNNER_PORT)),
+                ("name", string("http")),
+            ])]),
+        ),
+        ("env", Yaml::from(env)),
+    ];
+    if model.messaging.is_some() {
+        container.push((
+            "envFrom",
+            Yaml::from(vec![map([(
+                "configMapRef",
+                map([("name", string(&format!("{}-messaging", model.slug)))]),
+            )])]),
+        ));
+    }
+    let mut pod = vec![];
+    if model.channels.is_some() {
+        container.push((
+            "volumeMounts",
+            Yaml::from(vec![map([
+                ("mountPath", string(CONFIG_DIR)),
+                ("name", string("channels")),
+                ("readOnly", Yaml::from(true)),
+            ])]),
+        ));
+        pod.push((
+            "volumes",
+            Yaml::from(vec![map([
+                (
+                    "configMap",
+                    map([("name", string(&format!("{}-channels", model.slug)))]),
+                ),
+                ("name", string("channels")),
+            ])]),
+        ));
+    }
+    pod.insert(0, ("containers", Yaml::from(vec![map(container)])));
+
+    map([
+        ("apiVersion", string("apps/v1")),
+        ("kind", string("Deployment")),
+        (
+            "metadata",
+            model.metadata(&runner.slug, model.runner_labels(runner)),
+        ),
+        (
+            "spec",
+            map([
+                ("replicas", Yaml::from(runner.replicas)),
+                ("selector", map([("matchLabels", model.selector(runner))])),
+                (
+                    "template",
+                    map([
+                        ("metadata", map([("labels", model.runner_labels(runner))])),
+                        ("spec", map(pod)),
+                    ]),
+                ),
+            ]),
+        ),
+    ])
+}
+
+fn render_compose(model: &RenderModel) -> Result<BTreeMap<String, String>> {
+    let mut services = Vec::new();
+    for runner in &model.runners {
+        let mut env = model.runner_env(runner);
+        if let Some(settings) = &model.messaging {
+            env.extend(settings.clone());
+        }
+        let mut service = vec![
+            ("image", string(&runner.image)),
+            ("deploy", map([("replicas", Yaml::from(runner.replicas))])),
+            ("environment", string_map(env)),
+        ];
+        if model.channels.is_some() {
+            service.push((
+                "configs",
+                Yaml::from(vec![map([
+                    ("source", string("channels")),
+                    ("target", string(&format!("{CONFIG_DIR}/{CHANNELS_FILE}"))),
+                ])]),
+            ));
+        }
+        if !model.secrets.is_empty() {
+            service.push((
+                "secrets",
+                model
+                    .secrets
+                    .iter()
+                    .map(|secret| string(&secret.name))
+                    .collect::<Yaml>(),
+            ));
+        }
+        services.push((runner.slug.clone(), map(service)));
+    }
+
+    let mut compose = vec![
+        ("name", string(&model.slug)),
+        ("services", string_map(services)),
+    ];
+    let mut files = BTreeMap::new();
+    if let Some(channels) = &model.channels {
+        compose.push((
+            "configs",
+            map([(
+                "channels",
+                map([("file", string(&format!("./{CHANNELS_FILE}")))]),
+            )]),
+        ));
+        files.insert(CHANNELS_FILE.to_string(), format!("{channels}\n"));
+    }
+    if !model.secrets.is_empty() {
+        compose.push((
+            "secrets",
+            string_map(model.secrets.iter().map(|secret| {
+                (
+                    secret.name.clone(),
+                    map([("environment", string(&secret.env))]),
+                )
+            })),
+        ));
+    }
+    files.insert("docker-compose.yaml".to_string(), to_yaml(&map(compose))?);
+    Ok(files)
+}
+
+fn present<'a>(doc: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    doc.get(key).filter(|value| !value.is_null())
+}
+
+/// Lowercase RFC 1123 label: `[a-z0-9-]`, at most 63 characters.
+fn dns_label(value: &str) -> String {
+    let mut label = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_alphanumeric() {
+            label.push(ch.to_ascii_lowercase());
+        } else if !label.ends_with('-') {
+            label.push('-');
+        }
+    }
+    let label: String = label.trim_matches('-').chars().take(63).collect();
+    let label = label.trim_end_matches('-');
+    if label.is_empty() {
+        "greentic".to_string()
+    } else {
+        label.to_string()
+    }
+}
+
+/// Kubernetes label value: `[A-Za-z0-9._-]`, alphanumeric at both ends, at most 63 characters.
+fn label_value(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let trimmed: String = sanitized
+        .trim_matches(|ch: char| !ch.is_ascii_alphanumeric())
+        .chars()
+        .take(63)
+        .collect();
+    trimmed
+        .trim_end_matches(|ch: char| !ch.is_ascii_alphanumeric())
+        .to_string()
+}
+
+/// Secret object key / compose secret name: `[A-Za-z0-9._-]`.
+fn secret_name(key: &str) -> String {
+    key.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Environment variable name: uppercase, `_` for anything else.
+fn env_name(key: &str) -> String {
+    key.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Compact JSON with object keys sorted.
+fn canonical_json(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<(&String, &JsonValue)> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let items: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}:{}",
+                        JsonValue::from(key.as_str()),
+                        canonical_json(value)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", items.join(","))
+        }
+        JsonValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        scalar => scalar.to_string(),
+    }
+}
+
+fn string(value: &str) -> Yaml {
+    Yaml::from(value)
+}
+
+/// Mapping that keeps `entries` in the order given.
+fn map<'a>(entries: impl IntoIterator<Item = (&'a str, Yaml)>) -> Yaml {
+    Yaml::Mapping(
+        entries
+            .into_iter()
+            .map(|(key, value)| (string(key), value))
+            .collect::<Mapping>(),
+    )
+}
+
+fn string_map<V: Into<Yaml>>(entries: impl IntoIterator<Item = (String, V)>) -> Yaml {
+    Yaml::Mapping(
+        entries
+            .into_iter()
+            .map(|(key, value)| (Yaml::from(key), value.into()))
+            .collect::<Mapping>(),
+    )
+}
+
+fn to_yaml(doc: &Yaml) -> Result<String> {
+    let body = serde_yaml_bw::to_string(doc).context("failed to serialize rendered manifest")?;
+    Ok(format!("{HEADER}{body}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_keeps_entry_order_and_quotes_ambiguous_strings() {
+        let doc = map([
+            ("name", string("demo")),
+            (
+                "items",
+                Yaml::from(vec![
+                    map([("b", Yaml::from(1u64)), ("a", string("true"))]),
+                    string("0.1.0"),
+                ]),
+            ),
+        ]);
+        let out = to_yaml(&doc).expect("yaml");
+        let body = out.strip_prefix(HEADER).expect("header");
+        assert!(
+            body.find("name").unwrap() < body.find("items").unwrap(),
+            "{body}"
+        );
+        assert!(
+            body.find("b:").unwrap() < body.find("a:").unwrap(),
+            "{body}"
+        );
+        let parsed: Yaml = serde_yaml_bw::from_str(body).expect("round trip");
+        assert_eq!(parsed["items"][0]["a"].as_str(), Some("true"));
+        assert_eq!(parsed["items"][1].as_str(), Some("0.1.0"));
+    }
+
+    #[test]
+    fn names_are_sanitized() {
+        assert_eq!(dns_label("Demo.Pack__runner-"), "demo-pack-runner");
+        assert_eq!(dns_label("..."), "greentic");
+        assert_eq!(label_value("tenant/one!"), "tenant-one");
+        assert_eq!(secret_name("db/password"), "db-password");
+        assert_eq!(env_name("db/password"), "DB_PASSWORD");
+    }
+
+    #[test]
+    fn canonical_json_sorts_keys() {
+        let value = serde_json::json!({"b": [1, {"d": null, "c": "x"}], "a": true});
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":true,"b":[1,{"c":"x","d":null}]}"#
+        );
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::fs::File;
-use std::io::{Read, Seek};
-use std::path::Path;
+use std::io::{self, Read, Seek};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow, bail};
 use base64::Engine;
@@ -14,29 +14,52 @@ use greentic_types::pack::extensions::component_manifests::{
     ComponentManifestIndexV1, EXT_COMPONENT_MANIFEST_INDEX_V1, ManifestEncoding,
 };
 use greentic_types::pack_manifest::{ExtensionInline, PackManifest as GpackManifest};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_cbor;
 use serde_json;
 use sha2::{Digest, Sha256};
 use x509_parser::pem::parse_x509_pem;
 use x509_parser::prelude::*;
 use zip::ZipArchive;
+use zip::read::ZipFile;
 
 use crate::builder::{
     ComponentEntry, FlowEntry, ImportRef, PackManifest, PackMeta, SBOM_FORMAT,
-    SIGNATURE_CHAIN_PATH, SIGNATURE_PATH, SbomEntry, SignatureEnvelope, hex_hash,
-    signature_digest_from_entries,
+    SIGNATURE_CHAIN_PATH, SIGNATURE_PATH, SIGNATURES_DIR, SbomEntry, SignatureEnvelope,
+    SignatureRole, hex_hash, signature_digest_from_entries, signed_message,
 };
+use crate::trust::{TrustDecision, TrustPolicy};
 
-#[cfg(test)]
-const MAX_ARCHIVE_BYTES: u64 = 256 * 1024;
-#[cfg(not(test))]
-const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+/// Size limits enforced while reading a `.gtpack` archive.
+///
+/// Sizes refer to uncompressed entry bytes; entries are measured while they are
+/// streamed, so a lying central directory cannot bypass the caps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReaderLimits {
+    pub max_archive_bytes: u64,
+    pub max_file_bytes: u64,
+}
 
-#[cfg(test)]
-const MAX_FILE_BYTES: u64 = 64 * 1024;
-#[cfg(not(test))]
-const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+impl ReaderLimits {
+    pub const DEFAULT_MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+    pub const DEFAULT_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+    pub fn new(max_archive_bytes: u64, max_file_bytes: u64) -> Self {
+        Self {
+            max_archive_bytes,
+            max_file_bytes,
+        }
+    }
+}
+
+impl Default for ReaderLimits {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_MAX_ARCHIVE_BYTES,
+            Self::DEFAULT_MAX_FILE_BYTES,
+        )
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SigningPolicy {
@@ -49,6 +72,28 @@ pub struct VerifyReport {
     pub signature_ok: bool,
     pub sbom_ok: bool,
     pub warnings: Vec<String>,
+    /// Trust policy decision; `None` when no policy was supplied.
+    pub trust: Option<TrustDecision>,
+    /// Every signature found in the archive, primary signature first.
+    pub signatures: Vec<SignatureCheck>,
+}
+
+/// Verification result for one signature envelope in the archive.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureCheck {
+    pub path: String,
+    pub role: Option<SignatureRole>,
+    pub alg: Option<String>,
+    /// Fingerprint of the signing key; taken from the verified certificate when valid.
+    pub key_fingerprint: Option<String>,
+    pub signed_at_utc: Option<String>,
+    /// The signature matches the archive contents and its certificate.
+    pub valid: bool,
+    /// The trust policy accepted the signing certificate.
+    pub trusted: bool,
+    /// Per-signature trust policy decision, present once the signature is valid.
+    pub trust: Option<TrustDecision>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -134,7 +179,28 @@ impl ManifestFileVerificationReport {
 }
 
 pub fn open_pack(path: &Path, policy: SigningPolicy) -> Result<PackLoad, PackVerifyResult> {
-    match open_pack_inner(path, policy) {
+    open_pack_with_limits(path, policy, &ReaderLimits::default())
+}
+
+/// Same as [`open_pack`] but with caller-supplied size limits.
+pub fn open_pack_with_limits(
+    path: &Path,
+    policy: SigningPolicy,
+    limits: &ReaderLimits,
+) -> Result<PackLoad, PackVerifyResult> {
+    open_pack_with_trust(path, policy, limits, &TrustPolicy::default())
+}
+
+/// Same as [`open_pack_with_limits`] but also evaluates the signing chain against
+/// `trust`. The decision is recorded in [`VerifyReport::trust`]; a rejection is an
+/// error under [`SigningPolicy::Strict`] and a warning otherwise.
+pub fn open_pack_with_trust(
+    path: &Path,
+    policy: SigningPolicy,
+    limits: &ReaderLimits,
+    trust: &TrustPolicy,
+) -> Result<PackLoad, PackVerifyResult> {
+    match open_pack_inner(path, policy, limits, trust) {
         Ok(result) => Ok(result),
         Err(err) => Err(PackVerifyResult::from_error(err)),
     }
@@ -142,106 +208,16 @@ pub fn open_pack(path: &Path, policy: SigningPolicy) -> Result<PackLoad, PackVer
 
 impl PackLoad {
     pub fn component_manifest_index_v1(&self) -> ComponentManifestIndexState {
-        let mut state = ComponentManifestIndexState {
-            present: false,
-            index: None,
-            error: None,
-        };
-
-        let manifest = match self.gpack_manifest.as_ref() {
-            Some(manifest) => manifest,
-            None => return state,
-        };
-
-        let Some(extension) = manifest
-            .extensions
-            .as_ref()
-            .and_then(|map| map.get(EXT_COMPONENT_MANIFEST_INDEX_V1))
-        else {
-            return state;
-        };
-        state.present = true;
-
-        let inline = match extension.inline.as_ref() {
-            Some(inline) => inline,
-            None => {
-                state.error = Some("component manifest index missing inline payload".into());
-                return state;
-            }
-        };
-
-        let payload = match inline {
-            ExtensionInline::Other(value) => value,
-            _ => {
-                state.error =
-                    Some("component manifest index inline payload has unexpected shape".into());
-                return state;
-            }
-        };
-
-        match ComponentManifestIndexV1::from_extension_value(payload) {
-            Ok(index) => state.index = Some(index),
-            Err(err) => state.error = Some(err.to_string()),
-        }
-
-        state
+        component_manifest_index_state(self.gpack_manifest.as_ref())
     }
 
     pub fn get_component_manifest_prefer_file(
         &self,
         component_id: &str,
     ) -> Result<Option<ComponentManifest>> {
-        let state = self.component_manifest_index_v1();
-        if let Some(err) = state.error {
-            return Err(anyhow!(err));
-        }
-
-        if let Some(entry) = state.index.as_ref().and_then(|index| {
-            index
-                .entries
-                .iter()
-                .find(|entry| entry.component_id == component_id)
-        }) {
-            if entry.encoding != ManifestEncoding::Cbor {
-                bail!("unsupported manifest encoding {:?}", entry.encoding);
-            }
-
-            if let Some(bytes) = self.files.get(&entry.manifest_file) {
-                if let Some(expected) = entry.content_hash.as_deref() {
-                    let actual = sha256_prefixed(bytes);
-                    if !expected.eq_ignore_ascii_case(&actual) {
-                        bail!(
-                            "manifest hash mismatch for {}: expected {}, got {}",
-                            entry.manifest_file,
-                            expected,
-                            actual
-                        );
-                    }
-                }
-
-                let decoded: ComponentManifest =
-                    serde_cbor::from_slice(bytes).context("decode component manifest")?;
-                if decoded.id.to_string() != entry.component_id {
-                    bail!(
-                        "manifest id {} does not match index component_id {}",
-                        decoded.id,
-                        entry.component_id
-                    );
-                }
-                return Ok(Some(decoded));
-            }
-        }
-
-        if let Some(component) = self.gpack_manifest.as_ref().and_then(|manifest| {
-            manifest
-                .components
-                .iter()
-                .find(|c| c.id.to_string() == component_id)
-        }) {
-            return Ok(Some(component.clone()));
-        }
-
-        Ok(None)
+        component_manifest_prefer_file(self.gpack_manifest.as_ref(), component_id, |path| {
+            Ok(self.files.get(path).cloned())
+        })
     }
 
     pub fn verify_component_manifest_files(&self) -> ManifestFileVerificationReport {
@@ -355,20 +331,391 @@ impl PackLoad {
     }
 }
 
-fn open_pack_inner(path: &Path, policy: SigningPolicy) -> Result<PackLoad> {
-    let mut archive = ZipArchive::new(
-        File::open(path).with_context(|| format!("failed to open {}", path.display()))?,
-    )
-    .with_context(|| format!("{} is not a valid gtpack archive", path.display()))?;
+/// Lazily-reading view over a `.gtpack` archive.
+///
+/// Opening verifies the SBOM and signature exactly like [`open_pack`], but every
+/// entry is streamed through BLAKE3 instead of being buffered. Only the manifest,
+/// the SBOM and the signature files stay in memory; other entries are fetched on
+/// demand with [`PackReader::read_entry`] or [`PackReader::entry_reader`].
+pub struct PackReader {
+    pub manifest: PackManifest,
+    pub report: VerifyReport,
+    pub sbom: Vec<SbomEntry>,
+    pub gpack_manifest: Option<GpackManifest>,
+    path: PathBuf,
+    limits: ReaderLimits,
+    archive: ZipArchive<File>,
+    entries: BTreeMap<String, ArchiveEntry>,
+    retained: HashMap<String, Vec<u8>>,
+}
 
-    let (files, total) = read_archive_entries(&mut archive)?;
-    if total > MAX_ARCHIVE_BYTES {
-        bail!(
-            "gtpack archive exceeds maximum allowed size ({} bytes)",
-            MAX_ARCHIVE_BYTES
+impl PackReader {
+    pub fn open(
+        path: &Path,
+        policy: SigningPolicy,
+        limits: &ReaderLimits,
+    ) -> Result<Self, PackVerifyResult> {
+        Self::open_with_trust(path, policy, limits, &TrustPolicy::default())
+    }
+
+    /// Open the archive and evaluate its signing chain against `trust`.
+    pub fn open_with_trust(
+        path: &Path,
+        policy: SigningPolicy,
+        limits: &ReaderLimits,
+        trust: &TrustPolicy,
+    ) -> Result<Self, PackVerifyResult> {
+        Self::open_inner(path, policy, limits, trust).map_err(PackVerifyResult::from_error)
+    }
+
+    fn open_inner(
+        path: &Path,
+        policy: SigningPolicy,
+        limits: &ReaderLimits,
+        trust: &TrustPolicy,
+    ) -> Result<Self> {
+        let mut archive = open_archive(path)?;
+        let scan = scan_archive(&mut archive, limits, is_metadata_entry)?;
+        let verified = verify_scan(&scan, policy, trust)?;
+        Ok(Self {
+            manifest: verified.manifest,
+            report: verified.report,
+            sbom: verified.sbom,
+            gpack_manifest: verified.gpack_manifest,
+            path: path.to_path_buf(),
+            limits: *limits,
+            archive,
+            entries: scan.entries,
+            retained: scan.retained,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn limits(&self) -> &ReaderLimits {
+        &self.limits
+    }
+
+    /// Logical paths of every file entry, sorted.
+    pub fn entry_names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    pub fn entry_size(&self, path: &str) -> Option<u64> {
+        self.entries.get(path).map(|entry| entry.size)
+    }
+
+    /// BLAKE3 hex digest observed for `path` while the archive was verified.
+    pub fn entry_hash(&self, path: &str) -> Option<&str> {
+        self.entries
+            .get(path)
+            .map(|entry| entry.hash_blake3.as_str())
+    }
+
+    /// Read a whole entry into memory, re-checking it against the hash recorded at open.
+    pub fn read_entry(&mut self, path: &str) -> Result<Vec<u8>> {
+        if let Some(bytes) = self.retained.get(path) {
+            return Ok(bytes.clone());
+        }
+        let mut reader = self.entry_reader(path)?;
+        let mut buf = Vec::new();
+        reader
+            .read_to_end(&mut buf)
+            .with_context(|| format!("failed to read {}", path))?;
+        Ok(buf)
+    }
+
+    /// Stream an entry. The reader fails with `InvalidData` at end of input if the
+    /// content no longer matches the hash recorded when the archive was verified.
+    pub fn entry_reader(&mut self, path: &str) -> Result<EntryReader<'_>> {
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| anyhow!("entry `{}` not found in {}", path, self.path.display()))?;
+        let expected = entry.hash_blake3.clone();
+        let file = self
+            .archive
+            .by_index(entry.index)
+            .with_context(|| format!("failed to open entry {}", path))?;
+        Ok(EntryReader {
+            inner: file.take(self.limits.max_file_bytes),
+            hasher: blake3::Hasher::new(),
+            expected,
+            path: path.to_string(),
+        })
+    }
+
+    pub fn component_manifest_index_v1(&self) -> ComponentManifestIndexState {
+        component_manifest_index_state(self.gpack_manifest.as_ref())
+    }
+
+    pub fn get_component_manifest_prefer_file(
+        &mut self,
+        component_id: &str,
+    ) -> Result<Option<ComponentManifest>> {
+        let gpack_manifest = self.gpack_manifest.clone();
+        component_manifest_prefer_file(gpack_manifest.as_ref(), component_id, |path| {
+            if !self.contains(path) {
+                return Ok(None);
+            }
+            self.read_entry(path).map(Some)
+        })
+    }
+
+    /// Buffer every entry and return the equivalent of [`open_pack`].
+    pub fn into_pack_load(mut self) -> Result<PackLoad> {
+        let names: Vec<String> = self.entries.keys().cloned().collect();
+        let mut files = HashMap::with_capacity(names.len());
+        for name in names {
+            let bytes = self.read_entry(&name)?;
+            files.insert(name, bytes);
+        }
+        Ok(PackLoad {
+            manifest: self.manifest,
+            report: self.report,
+            sbom: self.sbom,
+            files,
+            gpack_manifest: self.gpack_manifest,
+        })
+    }
+}
+
+/// Streaming reader over a single archive entry; see [`PackReader::entry_reader`].
+pub struct EntryReader<'a> {
+    inner: io::Take<ZipFile<'a, File>>,
+    hasher: blake3::Hasher,
+    expected: String,
+    path: String,
+}
+
+impl Read for EntryReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() {
+            let actual = self.hasher.finalize().to_hex().to_string();
+            if !actual.eq_ignore_ascii_case(&self.expected) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "hash mismatch for {}: expected {}, found {}",
+                        self.path, self.expected, actual
+                    ),
+                ));
+            }
+            return Ok(0);
+        }
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+fn component_manifest_index_state(manifest: Option<&GpackManifest>) -> ComponentManifestIndexState {
+    let mut state = ComponentManifestIndexState {
+        present: false,
+        index: None,
+        error: None,
+    };
+
+    let manifest = match manifest {
+        Some(manifest) => manifest,
+        None => return state,
+    };
+
+    let Some(extension) = manifest
+        .extensions
+        .as_ref()
+        .and_then(|map| map.get(EXT_COMPONENT_MANIFEST_INDEX_V1))
+    else {
+        return state;
+    };
+    state.present = true;
+
+    let inline = match extension.inline.as_ref() {
+        Some(inline) => inline,
+        None => {
+            state.error = Some("component manifest index missing inline payload".into());
+            return state;
+        }
+    };
+
+    let payload = match inline {
+        ExtensionInline::Other(value) => value,
+        _ => {
+            state.error =
+                Some("component manifest index inline payload has unexpected shape".into());
+            return state;
+        }
+    };
+
+    match ComponentManifestIndexV1::from_extension_value(payload) {
+        Ok(index) => state.index = Some(index),
+        Err(err) => state.error = Some(err.to_string()),
+    }
+
+    state
+}
+
+fn component_manifest_prefer_file(
+    manifest: Option<&GpackManifest>,
+    component_id: &str,
+    mut fetch: impl FnMut(&str) -> Result<Option<Vec<u8>>>,
+) -> Result<Option<ComponentManifest>> {
+    let state = component_manifest_index_state(manifest);
+    if let Some(err) = state.error {
+        return Err(anyhow!(err));
+    }
+
+    if let Some(entry) = state.index.as_ref().and_then(|index| {
+        index
+            .entries
+            .iter()
+            .find(|entry| entry.component_id == component_id)
+    }) {
+        if entry.encoding != ManifestEncoding::Cbor {
+            bail!("unsupported manifest encoding {:?}", entry.encoding);
+        }
+
+        if let Some(bytes) = fetch(&entry.manifest_file)? {
+            if let Some(expected) = entry.content_hash.as_deref() {
+                let actual = sha256_prefixed(&bytes);
+                if !expected.eq_ignore_ascii_case(&actual) {
+                    bail!(
+                        "manifest hash mismatch for {}: expected {}, got {}",
+                        entry.manifest_file,
+                        expected,
+                        actual
+                    );
+                }
+            }
+
+            let decoded: ComponentManifest =
+                serde_cbor::from_slice(&bytes).context("decode component manifest")?;
+            if decoded.id.to_string() != entry.component_id {
+                bail!(
+                    "manifest id {} does not match index component_id {}",
+                    decoded.id,
+                    entry.component_id
+                );
+            }
+            return Ok(Some(decoded));
+        }
+    }
+
+    if let Some(component) = manifest.and_then(|manifest| {
+        manifest
+            .components
+            .iter()
+            .find(|c| c.id.to_string() == component_id)
+    }) {
+        return Ok(Some(component.clone()));
+    }
+
+    Ok(None)
+}
+
+fn open_pack_inner(
+    path: &Path,
+    policy: SigningPolicy,
+    limits: &ReaderLimits,
+    trust: &TrustPolicy,
+) -> Result<PackLoad> {
+    let mut archive = open_archive(path)?;
+    let scan = scan_archive(&mut archive, limits, |_| true)?;
+    let verified = verify_scan(&scan, policy, trust)?;
+    Ok(PackLoad {
+        manifest: verified.manifest,
+        report: verified.report,
+        sbom: verified.sbom,
+        files: scan.retained,
+        gpack_manifest: verified.gpack_manifest,
+    })
+}
+
+/// Verify pack entries that are already in memory, keyed by logical path.
+///
+/// This is what [`open_pack`] does after reading an archive, for callers (such as
+/// an in-process build) that assembled the entries themselves.
+pub fn open_pack_from_files(
+    files: HashMap<String, Vec<u8>>,
+    policy: SigningPolicy,
+) -> Result<PackLoad, PackVerifyResult> {
+    open_files_inner(files, policy, &TrustPolicy::default()).map_err(PackVerifyResult::from_error)
+}
+
+fn open_files_inner(
+    files: HashMap<String, Vec<u8>>,
+    policy: SigningPolicy,
+    trust: &TrustPolicy,
+) -> Result<PackLoad> {
+    let mut paths: Vec<&String> = files.keys().collect();
+    paths.sort();
+    let mut entries = BTreeMap::new();
+    for (index, path) in paths.into_iter().enumerate() {
+        let logical = normalize_entry_path(Path::new(path))?;
+        if &logical != path {
+            bail!("pack entry contains unsafe path: {path}");
+        }
+        let bytes = &files[path];
+        entries.insert(
+            logical,
+            ArchiveEntry {
+                index,
+                size: bytes.len() as u64,
+                hash_blake3: blake3::hash(bytes).to_hex().to_string(),
+            },
         );
     }
+    let scan = ArchiveScan {
+        entries,
+        retained: files,
+    };
+    let verified = verify_scan(&scan, policy, trust)?;
+    Ok(PackLoad {
+        manifest: verified.manifest,
+        report: verified.report,
+        sbom: verified.sbom,
+        files: scan.retained,
+        gpack_manifest: verified.gpack_manifest,
+    })
+}
+
+pub(crate) fn open_archive(path: &Path) -> Result<ZipArchive<File>> {
+    ZipArchive::new(File::open(path).with_context(|| format!("failed to open {}", path.display()))?)
+        .with_context(|| format!("{} is not a valid gtpack archive", path.display()))
+}
+
+/// Entries the verifier needs in memory regardless of reading mode.
+pub(crate) fn is_metadata_entry(path: &str) -> bool {
+    matches!(path, "manifest.cbor" | "sbom.cbor" | "sbom.json") || is_signature_entry(path)
+}
 
+/// Signature envelopes (`*.sig`) and certificate chains (`*.pem`) directly under
+/// `signatures/`. These are not listed in the SBOM since they sign it.
+pub(crate) fn is_signature_entry(path: &str) -> bool {
+    path.strip_prefix(SIGNATURES_DIR).is_some_and(|name| {
+        !name.contains('/') && (name.ends_with(".sig") || name.ends_with(".pem"))
+    })
+}
+
+struct VerifiedPack {
+    manifest: PackManifest,
+    report: VerifyReport,
+    sbom: Vec<SbomEntry>,
+    gpack_manifest: Option<GpackManifest>,
+}
+
+fn verify_scan(
+    scan: &ArchiveScan,
+    policy: SigningPolicy,
+    trust: &TrustPolicy,
+) -> Result<VerifiedPack> {
+    let files = &scan.retained;
     let manifest_bytes = files
         .get("manifest.cbor")
         .cloned()
@@ -377,42 +724,46 @@ fn open_pack_inner(path: &Path, policy: SigningPolicy) -> Result<PackLoad> {
     match decode_manifest(&manifest_bytes).context("manifest.cbor is invalid")? {
         ManifestModel::Pack(manifest) => {
             let manifest = *manifest;
-            let (sbom_doc, sbom_bytes, sbom_name) = read_sbom_required(&files)?;
+            let (sbom_doc, sbom_bytes, sbom_name) = read_sbom_required(files)?;
             if sbom_doc.format != SBOM_FORMAT {
                 bail!("unexpected SBOM format: {}", sbom_doc.format);
             }
 
             let mut warnings = Vec::new();
-            verify_sbom(&files, &sbom_doc.files, sbom_name)?;
-            let signature_ok = match (
-                files.get(SIGNATURE_PATH),
-                files.get(SIGNATURE_CHAIN_PATH),
-                Some(&sbom_bytes),
-            ) {
-                (Some(_), Some(_), Some(sbom_bytes)) => match verify_signature(
-                    &files,
-                    &manifest_bytes,
-                    sbom_bytes,
-                    &sbom_doc.files,
-                    policy,
-                    &mut warnings,
-                ) {
-                    Ok(()) => true,
-                    Err(err) => {
-                        if matches!(policy, SigningPolicy::Strict) {
-                            return Err(err);
+            verify_sbom(&scan.entries, &sbom_doc.files, sbom_name)?;
+            let content = SignedContent {
+                manifest: &manifest_bytes,
+                sbom: &sbom_bytes,
+                entries: &sbom_doc.files,
+            };
+            let publisher = manifest.meta.vendor.clone();
+            let signatures = match (files.get(SIGNATURE_PATH), files.get(SIGNATURE_CHAIN_PATH)) {
+                (Some(_), Some(_)) => {
+                    let checks = check_signatures(
+                        files,
+                        &content,
+                        policy,
+                        trust,
+                        publisher.as_deref(),
+                        &mut warnings,
+                    );
+                    for check in &checks {
+                        if let Some(err) = check.error.as_deref() {
+                            if matches!(policy, SigningPolicy::Strict) {
+                                bail!("{err}");
+                            }
+                            warnings.push(signature_failure_warning(check, err));
                         }
-                        warnings.push(format!("signature verification failed: {err}"));
-                        false
                     }
-                },
-                (None, None, _) => match policy {
+                    checks
+                }
+                (None, None) => match policy {
                     SigningPolicy::Strict => {
                         bail!("signature file `{}` missing", SIGNATURE_PATH)
                     }
                     SigningPolicy::DevOk => {
                         warnings.push("signature files missing; skipping verification".into());
-                        false
+                        Vec::new()
                     }
                 },
                 _ => {
@@ -421,19 +772,27 @@ fn open_pack_inner(path: &Path, policy: SigningPolicy) -> Result<PackLoad> {
                         SigningPolicy::DevOk => warnings
                             .push("signature files incomplete; skipping verification".into()),
                     }
-                    false
+                    Vec::new()
                 }
             };
-
-            Ok(PackLoad {
+            let (signature_ok, decision) = apply_trust(
+                trust,
+                &signatures,
+                publisher.as_deref(),
+                policy,
+                &mut warnings,
+            )?;
+
+            Ok(VerifiedPack {
                 manifest,
                 report: VerifyReport {
                     signature_ok,
                     sbom_ok: true,
                     warnings,
+                    trust: decision,
+                    signatures,
                 },
                 sbom: sbom_doc.files,
-                files,
                 gpack_manifest: decoded_gpack_manifest,
             })
         }
@@ -447,86 +806,140 @@ fn open_pack_inner(path: &Path, policy: SigningPolicy) -> Result<PackLoad> {
                 ));
             }
 
-            let (sbom, sbom_ok, sbom_bytes, sbom_name) = read_sbom_optional(&files, &mut warnings);
+            let (sbom, sbom_ok, sbom_bytes, sbom_name) = read_sbom_optional(scan, &mut warnings);
 
-            let signature_ok = match (
+            let publisher = Some(manifest.publisher.as_str());
+            let signatures = match (
                 files.get(SIGNATURE_PATH),
                 files.get(SIGNATURE_CHAIN_PATH),
                 sbom_bytes.as_deref(),
                 sbom_ok,
             ) {
                 (Some(_), Some(_), Some(sbom_bytes), true) => {
-                    match verify_signature(
-                        &files,
-                        &manifest_bytes,
-                        sbom_bytes,
-                        &sbom,
-                        policy,
-                        &mut warnings,
-                    ) {
-                        Ok(()) => true,
-                        Err(err) => {
-                            warnings.push(format!("signature verification failed: {err}"));
-                            false
+                    let content = SignedContent {
+                        manifest: &manifest_bytes,
+                        sbom: sbom_bytes,
+                        entries: &sbom,
+                    };
+                    let checks =
+                        check_signatures(files, &content, policy, trust, publisher, &mut warnings);
+                    for check in &checks {
+                        if let Some(err) = check.error.as_deref() {
+                            warnings.push(signature_failure_warning(check, err));
                         }
                     }
+                    checks
                 }
                 (Some(_), Some(_), Some(_), false) => {
                     warnings.push(
                         "signature present but sbom validation failed; skipping verification"
                             .into(),
                     );
-                    false
+                    Vec::new()
                 }
                 (Some(_), Some(_), None, _) => {
                     warnings.push(format!(
                         "signature present but {} missing; skipping verification",
                         sbom_name
                     ));
-                    false
+                    Vec::new()
                 }
                 (None, None, _, _) => {
                     warnings.push("signature files missing; skipping verification".into());
-                    false
+                    Vec::new()
                 }
                 _ => {
                     warnings.push("signature files incomplete; skipping verification".into());
-                    false
+                    Vec::new()
                 }
             };
+            let (signature_ok, decision) =
+                apply_trust(trust, &signatures, publisher, policy, &mut warnings)?;
 
-            Ok(PackLoad {
-                manifest: convert_gpack_manifest(&manifest, &files),
+            Ok(VerifiedPack {
+                manifest: convert_gpack_manifest(&manifest, &scan.entries),
                 report: VerifyReport {
                     signature_ok,
                     sbom_ok,
                     warnings,
+                    trust: decision,
+                    signatures,
                 },
                 sbom,
-                files,
                 gpack_manifest: Some(manifest),
             })
         }
     }
 }
 
+fn signature_failure_warning(check: &SignatureCheck, err: &str) -> String {
+    if check.path == SIGNATURE_PATH {
+        format!("signature verification failed: {err}")
+    } else {
+        format!("signature {} verification failed: {err}", check.path)
+    }
+}
+
+/// Combine the per-signature results into `signature_ok` and, when a policy is
+/// configured, an overall [`TrustDecision`].
+///
+/// Under [`SigningPolicy::Strict`] a rejection by the trust policy fails the read.
+fn apply_trust(
+    trust: &TrustPolicy,
+    signatures: &[SignatureCheck],
+    publisher: Option<&str>,
+    policy: SigningPolicy,
+    warnings: &mut Vec<String>,
+) -> Result<(bool, Option<TrustDecision>)> {
+    let valid = !signatures.is_empty() && signatures.iter().all(|check| check.valid);
+    if trust.is_empty() {
+        return Ok((valid, None));
+    }
+    let decision = trust.decide(signatures, publisher);
+    if !decision.accepted {
+        if matches!(policy, SigningPolicy::Strict) {
+            bail!(
+                "pack rejected by trust rule `{}`: {}",
+                decision.rule,
+                decision.detail
+            );
+        }
+        warnings.push(format!(
+            "trust rule `{}` rejected the pack: {}",
+            decision.rule, decision.detail
+        ));
+    }
+    Ok((valid && decision.accepted, Some(decision)))
+}
+
+/// Publisher declared by `manifest.cbor`, used to select publisher trust rules.
+pub(crate) fn manifest_publisher(manifest_bytes: &[u8]) -> Option<String> {
+    match decode_manifest(manifest_bytes).ok()? {
+        ManifestModel::Pack(manifest) => manifest.meta.vendor,
+        ManifestModel::Gpack(manifest) => Some(manifest.publisher),
+    }
+}
+
 #[derive(Deserialize)]
-struct SbomDocument {
-    format: String,
-    files: Vec<SbomEntry>,
+pub(crate) struct SbomDocument {
+    pub(crate) format: String,
+    pub(crate) files: Vec<SbomEntry>,
+    /// Entry compression chosen at build time; absent for stored archives.
+    #[serde(default)]
+    pub(crate) compression: Option<String>,
 }
 
-fn verify_sbom(
-    files: &HashMap<String, Vec<u8>>,
+pub(crate) fn verify_sbom(
+    files: &BTreeMap<String, ArchiveEntry>,
     entries: &[SbomEntry],
     sbom_name: &str,
 ) -> Result<()> {
     let mut listed = HashSet::new();
     for entry in entries {
-        let data = files
+        let actual = &files
             .get(&entry.path)
-            .ok_or_else(|| anyhow!("sbom references missing file `{}`", entry.path))?;
-        let actual = hex_hash(data);
+            .ok_or_else(|| anyhow!("sbom references missing file `{}`", entry.path))?
+            .hash_blake3;
         if !actual.eq_ignore_ascii_case(&entry.hash_blake3) {
             bail!(
                 "hash mismatch for {}: expected {}, found {}",
@@ -539,11 +952,7 @@ fn verify_sbom(
     }
 
     for path in files.keys() {
-        if path == SIGNATURE_PATH
-            || path == SIGNATURE_CHAIN_PATH
-            || path == "sbom.json"
-            || path == "sbom.cbor"
-        {
+        if is_signature_entry(path) || path == "sbom.json" || path == "sbom.cbor" {
             continue;
         }
         if !listed.contains(path) {
@@ -554,7 +963,7 @@ fn verify_sbom(
     Ok(())
 }
 
-fn read_sbom_required(
+pub(crate) fn read_sbom_required(
     files: &HashMap<String, Vec<u8>>,
 ) -> Result<(SbomDocument, Vec<u8>, &'static str)> {
     if let Some(sbom_bytes) = files.get("sbom.cbor") {
@@ -571,9 +980,10 @@ fn read_sbom_required(
 }
 
 fn read_sbom_optional(
-    files: &HashMap<String, Vec<u8>>,
+    scan: &ArchiveScan,
     warnings: &mut Vec<String>,
 ) -> (Vec<SbomEntry>, bool, Option<Vec<u8>>, &'static str) {
+    let files = &scan.retained;
     if let Some(sbom_bytes) = files.get("sbom.cbor") {
         match serde_cbor::from_slice::<SbomDocument>(sbom_bytes) {
             Ok(sbom_doc) => {
@@ -581,7 +991,7 @@ fn read_sbom_optional(
                 if !ok {
                     warnings.push(format!("unexpected SBOM format: {}", sbom_doc.format));
                 }
-                match verify_sbom(files, &sbom_doc.files, "sbom.cbor") {
+                match verify_sbom(&scan.entries, &sbom_doc.files, "sbom.cbor") {
                     Ok(()) => {}
                     Err(err) => {
                         warnings.push(err.to_string());
@@ -603,7 +1013,7 @@ fn read_sbom_optional(
                 if !ok {
                     warnings.push(format!("unexpected SBOM format: {}", sbom_doc.format));
                 }
-                match verify_sbom(files, &sbom_doc.files, "sbom.json") {
+                match verify_sbom(&scan.entries, &sbom_doc.files, "sbom.json") {
                     Ok(()) => {}
                     Err(err) => {
                         warnings.push(err.to_string());
@@ -619,38 +1029,130 @@ fn read_sbom_optional(
         }
     }
     warnings.push("sbom.cbor missing; synthesized inventory for validation".into());
-    (synthesize_sbom(files), false, None, "sbom.cbor")
+    (synthesize_sbom(&scan.entries), false, None, "sbom.cbor")
+}
+
+/// Bytes covered by archive signatures.
+pub(crate) struct SignedContent<'a> {
+    pub(crate) manifest: &'a [u8],
+    pub(crate) sbom: &'a [u8],
+    pub(crate) entries: &'a [SbomEntry],
+}
+
+/// Location of one signature envelope and its certificate chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignatureSlot {
+    pub(crate) sig_path: String,
+    pub(crate) chain_path: String,
+}
+
+impl SignatureSlot {
+    /// `signatures/pack.sig` with `signatures/chain.pem`.
+    pub(crate) fn primary() -> Self {
+        Self {
+            sig_path: SIGNATURE_PATH.to_string(),
+            chain_path: SIGNATURE_CHAIN_PATH.to_string(),
+        }
+    }
+
+    /// Additional signature `signatures/<name>.sig` with `signatures/<name>.chain.pem`.
+    pub(crate) fn named(name: &str) -> Self {
+        Self {
+            sig_path: format!("{SIGNATURES_DIR}{name}.sig"),
+            chain_path: format!("{SIGNATURES_DIR}{name}.chain.pem"),
+        }
+    }
+}
+
+/// Signature slots present in `files`, primary signature first.
+pub(crate) fn signature_slots(files: &HashMap<String, Vec<u8>>) -> Vec<SignatureSlot> {
+    let mut names: Vec<&str> = files
+        .keys()
+        .filter(|path| path.as_str() != SIGNATURE_PATH && is_signature_entry(path))
+        .filter_map(|path| path.strip_prefix(SIGNATURES_DIR)?.strip_suffix(".sig"))
+        .collect();
+    names.sort_unstable();
+
+    let mut slots = Vec::new();
+    if files.contains_key(SIGNATURE_PATH) {
+        slots.push(SignatureSlot::primary());
+    }
+    slots.extend(names.into_iter().map(SignatureSlot::named));
+    slots
 }
 
-fn verify_signature(
+/// Verify every signature in `files`; failures are recorded per signature.
+pub(crate) fn check_signatures(
     files: &HashMap<String, Vec<u8>>,
-    manifest_bytes: &[u8],
-    sbom_bytes: &[u8],
-    entries: &[SbomEntry],
+    content: &SignedContent<'_>,
     policy: SigningPolicy,
+    trust: &TrustPolicy,
+    publisher: Option<&str>,
     warnings: &mut Vec<String>,
-) -> Result<()> {
+) -> Vec<SignatureCheck> {
+    signature_slots(files)
+        .iter()
+        .map(|slot| {
+            let envelope = files
+                .get(&slot.sig_path)
+                .and_then(|bytes| serde_json::from_slice::<SignatureEnvelope>(bytes).ok());
+            let mut check = SignatureCheck {
+                path: slot.sig_path.clone(),
+                role: envelope.as_ref().and_then(|env| env.role),
+                alg: envelope.as_ref().map(|env| env.alg.clone()),
+                key_fingerprint: envelope
+                    .as_ref()
+                    .and_then(|env| env.key_fingerprint.clone()),
+                signed_at_utc: envelope.as_ref().map(|env| env.signed_at_utc.clone()),
+                valid: false,
+                trusted: false,
+                trust: None,
+                error: None,
+            };
+            match verify_signature(files, slot, content, policy, warnings) {
+                Ok(chain) => {
+                    check.valid = true;
+                    check.key_fingerprint = leaf_fingerprint(&chain).ok();
+                    if !trust.is_empty() {
+                        let decision = trust.evaluate(&chain, publisher);
+                        check.trusted = decision.accepted;
+                        check.trust = Some(decision);
+                    }
+                }
+                Err(err) => check.error = Some(err.to_string()),
+            }
+            check
+        })
+        .collect()
+}
+
+/// Verify the envelope in `slot` and return its certificate chain (DER, leaf first).
+pub(crate) fn verify_signature(
+    files: &HashMap<String, Vec<u8>>,
+    slot: &SignatureSlot,
+    content: &SignedContent<'_>,
+    policy: SigningPolicy,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Vec<u8>>> {
     let signature_bytes = files
-        .get(SIGNATURE_PATH)
-        .ok_or_else(|| anyhow!("signature file `{}` missing", SIGNATURE_PATH))?;
+        .get(&slot.sig_path)
+        .ok_or_else(|| anyhow!("signature file `{}` missing", slot.sig_path))?;
     let chain_bytes = files
-        .get(SIGNATURE_CHAIN_PATH)
-        .ok_or_else(|| anyhow!("certificate chain `{}` missing", SIGNATURE_CHAIN_PATH))?;
+        .get(&slot.chain_path)
+        .ok_or_else(|| anyhow!("certificate chain `{}` missing", slot.chain_path))?;
 
-    let envelope: SignatureEnvelope =
-        serde_json::from_slice(signature_bytes).context("signatures/pack.sig is not valid JSON")?;
-    let digest = signature_digest_from_entries(entries, manifest_bytes, sbom_bytes);
+    let envelope: SignatureEnvelope = serde_json::from_slice(signature_bytes)
+        .with_context(|| format!("{} is not valid JSON", slot.sig_path))?;
+    let digest = signature_digest_from_entries(content.entries, content.manifest, content.sbom);
     let digest_hex = digest.to_hex().to_string();
     if !digest_hex.eq_ignore_ascii_case(&envelope.digest) {
         bail!("signature digest mismatch");
     }
 
     match envelope.alg.to_ascii_lowercase().as_str() {
-        "ed25519" => verify_ed25519_signature(&envelope, digest, chain_bytes, policy, warnings)?,
+        "ed25519" => verify_ed25519_signature(&envelope, digest, chain_bytes, policy, warnings),
         other => bail!("unsupported signature algorithm: {}", other),
     }
-
-    Ok(())
 }
 
 fn verify_ed25519_signature(
@@ -659,7 +1161,7 @@ fn verify_ed25519_signature(
     chain_bytes: &[u8],
     policy: SigningPolicy,
     warnings: &mut Vec<String>,
-) -> Result<()> {
+) -> Result<Vec<Vec<u8>>> {
     let sig_raw = URL_SAFE_NO_PAD
         .decode(envelope.sig.as_bytes())
         .map_err(|err| anyhow!("invalid signature encoding: {err}"))?;
@@ -674,12 +1176,21 @@ fn verify_ed25519_signature(
     let first_cert = parse_certificate(&cert_der[0])?;
     let verifying_key = extract_ed25519_key(&first_cert)?;
     verifying_key
-        .verify(digest.as_bytes(), &signature)
+        .verify(&signed_message(&digest, envelope.role), &signature)
         .map_err(|err| anyhow!("signature verification failed: {err}"))?;
-    Ok(())
+    Ok(cert_der)
+}
+
+/// Fingerprint (BLAKE3 of the raw public key) of the chain's leaf certificate.
+pub(crate) fn leaf_fingerprint(chain: &[Vec<u8>]) -> Result<String> {
+    let leaf = chain
+        .first()
+        .ok_or_else(|| anyhow!("certificate chain is empty"))?;
+    let key = extract_ed25519_key(&parse_certificate(leaf)?)?;
+    Ok(hex_hash(key.as_bytes()))
 }
 
-fn extract_ed25519_key(cert: &X509Certificate<'_>) -> Result<VerifyingKey> {
+pub(crate) fn extract_ed25519_key(cert: &X509Certificate<'_>) -> Result<VerifyingKey> {
     let spki = cert.public_key();
     let key_bytes: &[u8] = spki.subject_public_key.data.as_ref();
     if key_bytes.len() != 32 {
@@ -693,13 +1204,13 @@ fn extract_ed25519_key(cert: &X509Certificate<'_>) -> Result<VerifyingKey> {
     VerifyingKey::from_bytes(&raw).map_err(|err| anyhow!("invalid ed25519 key: {err}"))
 }
 
-fn parse_certificate(bytes: &[u8]) -> Result<X509Certificate<'_>> {
+pub(crate) fn parse_certificate(bytes: &[u8]) -> Result<X509Certificate<'_>> {
     let (_, cert) =
         X509Certificate::from_der(bytes).map_err(|err| anyhow!("invalid certificate: {err}"))?;
     Ok(cert)
 }
 
-fn parse_certificate_chain(mut data: &[u8]) -> Result<Vec<Vec<u8>>> {
+pub(crate) fn parse_certificate_chain(mut data: &[u8]) -> Result<Vec<Vec<u8>>> {
     let mut certs = Vec::new();
     loop {
         data = trim_leading(data);
@@ -771,10 +1282,27 @@ fn trim_leading(mut data: &[u8]) -> &[u8] {
     data
 }
 
-fn read_archive_entries<R: Read + Seek>(
+/// File entry observed while scanning an archive.
+pub(crate) struct ArchiveEntry {
+    index: usize,
+    size: u64,
+    hash_blake3: String,
+}
+
+pub(crate) struct ArchiveScan {
+    pub(crate) entries: BTreeMap<String, ArchiveEntry>,
+    /// Entry contents kept in memory (selected by the `retain` predicate).
+    pub(crate) retained: HashMap<String, Vec<u8>>,
+}
+
+/// Walk every entry, enforcing `limits` and hashing content as it streams past.
+pub(crate) fn scan_archive<R: Read + Seek>(
     archive: &mut ZipArchive<R>,
-) -> Result<(HashMap<String, Vec<u8>>, u64)> {
-    let mut files = HashMap::new();
+    limits: &ReaderLimits,
+    retain: impl Fn(&str) -> bool,
+) -> Result<ArchiveScan> {
+    let mut entries = BTreeMap::new();
+    let mut retained = HashMap::new();
     let mut total = 0u64;
 
     for idx in 0..archive.len() {
@@ -804,31 +1332,76 @@ fn read_archive_entries<R: Read + Seek>(
             .ok_or_else(|| anyhow!("archive entry contains unsafe path: {}", entry.name()))?
             .to_path_buf();
         let logical = normalize_entry_path(&enclosed_path)?;
-        if files.contains_key(&logical) {
+        if entries.contains_key(&logical) {
             bail!("duplicate entry detected: {}", logical);
         }
 
-        let size = entry.size();
-        if size > MAX_FILE_BYTES {
+        let declared = entry.size();
+        if declared > limits.max_file_bytes {
             bail!(
                 "entry {} exceeds maximum allowed size of {} bytes",
                 logical,
-                MAX_FILE_BYTES
+                limits.max_file_bytes
             );
         }
+        if total.saturating_add(declared) > limits.max_archive_bytes {
+            bail!(
+                "gtpack archive exceeds maximum allowed size ({} bytes)",
+                limits.max_archive_bytes
+            );
+        }
+
+        let keep = retain(&logical);
+        let mut buf = Vec::new();
+        let mut hasher = blake3::Hasher::new();
+        let mut chunk = [0u8; 64 * 1024];
+        let mut size = 0u64;
+        let mut limited = (&mut entry).take(limits.max_file_bytes.saturating_add(1));
+        loop {
+            let read = limited
+                .read(&mut chunk)
+                .with_context(|| format!("failed to read {}", logical))?;
+            if read == 0 {
+                break;
+            }
+            size += read as u64;
+            if size > limits.max_file_bytes {
+                bail!(
+                    "entry {} exceeds maximum allowed size of {} bytes",
+                    logical,
+                    limits.max_file_bytes
+                );
+            }
+            hasher.update(&chunk[..read]);
+            if keep {
+                buf.extend_from_slice(&chunk[..read]);
+            }
+        }
 
         total = total
             .checked_add(size)
             .ok_or_else(|| anyhow!("archive size overflow"))?;
+        if total > limits.max_archive_bytes {
+            bail!(
+                "gtpack archive exceeds maximum allowed size ({} bytes)",
+                limits.max_archive_bytes
+            );
+        }
 
-        let mut buf = Vec::with_capacity(size as usize);
-        entry
-            .read_to_end(&mut buf)
-            .with_context(|| format!("failed to read {}", logical))?;
-        files.insert(logical, buf);
+        if keep {
+            retained.insert(logical.clone(), buf);
+        }
+        entries.insert(
+            logical,
+            ArchiveEntry {
+                index: idx,
+                size,
+                hash_blake3: hasher.finalize().to_hex().to_string(),
+            },
+        );
     }
 
-    Ok((files, total))
+    Ok(ArchiveScan { entries, retained })
 }
 
 fn normalize_entry_path(path: &Path) -> Result<String> {
@@ -890,19 +1463,17 @@ fn decode_manifest(bytes: &[u8]) -> Result<ManifestModel> {
     Ok(ManifestModel::Gpack(Box::new(manifest)))
 }
 
-fn synthesize_sbom(files: &HashMap<String, Vec<u8>>) -> Vec<SbomEntry> {
-    let mut entries: Vec<_> = files
+fn synthesize_sbom(files: &BTreeMap<String, ArchiveEntry>) -> Vec<SbomEntry> {
+    files
         .iter()
         .filter(|(path, _)| *path != SIGNATURE_PATH && *path != SIGNATURE_CHAIN_PATH)
-        .map(|(path, data)| SbomEntry {
+        .map(|(path, entry)| SbomEntry {
             path: path.clone(),
-            size: data.len() as u64,
-            hash_blake3: hex_hash(data),
+            size: entry.size,
+            hash_blake3: entry.hash_blake3.clone(),
             media_type: media_type_for(path).to_string(),
         })
-        .collect();
-    entries.sort_by(|a, b| a.path.cmp(&b.path));
-    entries
+        .collect()
 }
 
 fn media_type_for(path: &str) -> &'static str {
@@ -927,7 +1498,7 @@ fn sha256_prefixed(bytes: &[u8]) -> String {
 
 fn convert_gpack_manifest(
     manifest: &GpackManifest,
-    files: &HashMap<String, Vec<u8>>,
+    files: &BTreeMap<String, ArchiveEntry>,
 ) -> PackManifest {
     let publisher = manifest.publisher.clone();
     let entry_flows = derive_entry_flows(manifest);
@@ -1030,20 +1601,24 @@ fn derive_entry_flows(manifest: &GpackManifest) -> Vec<String> {
     entries
 }
 
-fn component_hash(path: &str, files: &HashMap<String, Vec<u8>>) -> String {
+fn component_hash(path: &str, files: &BTreeMap<String, ArchiveEntry>) -> String {
     files
         .get(path)
-        .map(|bytes| hex_hash(bytes))
+        .map(|entry| entry.hash_blake3.clone())
         .unwrap_or_default()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{MAX_ARCHIVE_BYTES, MAX_FILE_BYTES, SigningPolicy, open_pack};
+    use super::{
+        PackReader, ReaderLimits, SigningPolicy, open_pack, open_pack_with_limits,
+        open_pack_with_trust,
+    };
     use crate::builder::SIGNATURE_CHAIN_PATH;
     use crate::builder::{
         ComponentArtifact, FlowBundle, PackBuilder, PackMeta, Provenance, Signing,
     };
+    use crate::trust::TrustPolicy;
     use blake3;
     use semver::Version;
     use serde_json::{Map, json};
@@ -1082,6 +1657,68 @@ mod tests {
         assert!(err.message.contains("strict"));
     }
 
+    #[test]
+    fn trust_policy_reports_accepting_rule() {
+        let (_dir, path) = build_pack(true);
+        let load = open_pack(&path, SigningPolicy::DevOk).expect("open pack");
+        let chain = load.files[SIGNATURE_CHAIN_PATH].clone();
+        let mut trust = TrustPolicy::new();
+        trust.add_roots_pem("dev", &chain).expect("root");
+
+        let load = open_pack_with_trust(
+            &path,
+            SigningPolicy::DevOk,
+            &ReaderLimits::default(),
+            &trust,
+        )
+        .expect("trusted pack");
+        assert!(load.report.signature_ok);
+        let decision = load.report.trust.expect("trust decision");
+        assert!(decision.accepted);
+        assert_eq!(decision.rule, "root:dev");
+    }
+
+    #[test]
+    fn trust_policy_rejection_is_reported() {
+        let (_dir, path) = build_pack(true);
+        let mut trust = TrustPolicy::new();
+        trust
+            .pin_fingerprint("other", &"ab".repeat(32))
+            .expect("fingerprint");
+
+        let load = open_pack_with_trust(
+            &path,
+            SigningPolicy::DevOk,
+            &ReaderLimits::default(),
+            &trust,
+        )
+        .expect("dev policy tolerates untrusted signer");
+        assert!(!load.report.signature_ok);
+        assert_eq!(
+            load.report.trust.as_ref().map(|d| d.rule.as_str()),
+            Some("untrusted")
+        );
+        assert!(
+            load.report
+                .warnings
+                .iter()
+                .any(|w| w.contains("trust rule `untrusted`"))
+        );
+
+        let (_unsigned_dir, unsigned) = build_pack(false);
+        let load = open_pack_with_trust(
+            &unsigned,
+            SigningPolicy::DevOk,
+            &ReaderLimits::default(),
+            &trust,
+        )
+        .expect("dev policy tolerates unsigned pack");
+        assert_eq!(
+            load.report.trust.as_ref().map(|d| d.rule.as_str()),
+            Some("unsigned")
+        );
+    }
+
     #[test]
     fn dev_policy_warns_for_multi_certificate_chain() {
         let (_dir, original) = build_pack(true);
@@ -1111,26 +1748,129 @@ mod tests {
 
     #[test]
     fn oversized_entry_is_rejected() {
-        let huge = vec![0u8; (MAX_FILE_BYTES + 1) as usize];
+        let limits = test_limits();
+        let huge = vec![0u8; (limits.max_file_bytes + 1) as usize];
         let (_dir, path) = custom_zip(&[zip_entry("huge.bin", &huge)]);
-        let err = open_pack(&path, SigningPolicy::DevOk).unwrap_err();
+        let err = open_pack_with_limits(&path, SigningPolicy::DevOk, &limits).unwrap_err();
         assert!(err.message.contains("exceeds maximum"));
     }
 
     #[test]
     fn oversized_archive_is_rejected() {
-        let chunk = vec![0u8; (MAX_FILE_BYTES / 2) as usize];
-        let needed = (MAX_ARCHIVE_BYTES / chunk.len() as u64) + 1;
+        let limits = test_limits();
+        let chunk = vec![0u8; (limits.max_file_bytes / 2) as usize];
+        let needed = (limits.max_archive_bytes / chunk.len() as u64) + 1;
         let mut entries = Vec::new();
         for idx in 0..needed {
             let name = format!("chunk{idx}");
             entries.push((name, chunk.clone()));
         }
         let (_dir, path) = custom_zip(&entries);
-        let err = open_pack(&path, SigningPolicy::DevOk).unwrap_err();
+        let err = open_pack_with_limits(&path, SigningPolicy::DevOk, &limits).unwrap_err();
         assert!(err.message.contains("archive exceeds"));
     }
 
+    #[test]
+    fn caller_limits_admit_large_entries() {
+        let (_dir, path) = build_pack(true);
+        let tight = ReaderLimits::new(256 * 1024, 4);
+        let err = open_pack_with_limits(&path, SigningPolicy::DevOk, &tight).unwrap_err();
+        assert!(err.message.contains("exceeds maximum"));
+
+        let load = open_pack_with_limits(&path, SigningPolicy::DevOk, &test_limits())
+            .expect("caller limits accept pack");
+        assert!(load.report.sbom_ok);
+    }
+
+    #[test]
+    fn default_limits_reject_entries_caller_limits_admit() {
+        let dir = tempdir().unwrap();
+        let wasm = dir.path().join("component.wasm");
+        let mut bytes = vec![0x00u8, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        bytes.resize(ReaderLimits::DEFAULT_MAX_FILE_BYTES as usize + 1, 0);
+        std::fs::write(&wasm, &bytes).unwrap();
+        let path = dir.path().join("large.gtpack");
+        PackBuilder::new(sample_meta())
+            .with_flow(sample_flow())
+            .with_component(ComponentArtifact {
+                name: "demo".into(),
+                version: Version::parse("1.0.0").unwrap(),
+                wasm_path: wasm,
+                schema_json: None,
+                manifest_json: None,
+                capabilities: None,
+                world: None,
+                hash_blake3: None,
+            })
+            .with_provenance(sample_provenance())
+            .build(&path)
+            .unwrap();
+
+        let err = open_pack(&path, SigningPolicy::DevOk).unwrap_err();
+        assert!(err.message.contains("exceeds maximum"), "{}", err.message);
+
+        let roomy = ReaderLimits::new(
+            ReaderLimits::DEFAULT_MAX_ARCHIVE_BYTES,
+            ReaderLimits::DEFAULT_MAX_FILE_BYTES * 2,
+        );
+        let load = open_pack_with_limits(&path, SigningPolicy::DevOk, &roomy)
+            .expect("caller limits admit the large entry");
+        assert_eq!(
+            load.files["components/demo@1.0.0/component.wasm"].len(),
+            bytes.len()
+        );
+    }
+
+    #[test]
+    fn pack_reader_streams_entries_on_demand() {
+        let (_dir, path) = build_pack(true);
+        let eager = open_pack(&path, SigningPolicy::DevOk).expect("eager open");
+        let mut reader = PackReader::open(&path, SigningPolicy::DevOk, &ReaderLimits::default())
+            .expect("streaming open");
+
+        assert_eq!(reader.manifest.meta.pack_id, eager.manifest.meta.pack_id);
+        assert_eq!(reader.sbom, eager.sbom);
+        assert!(reader.report.signature_ok);
+
+        let wasm_path = "components/demo@1.0.0/component.wasm";
+        assert!(reader.contains(wasm_path));
+        let bytes = reader.read_entry(wasm_path).expect("read wasm");
+        assert_eq!(&bytes, eager.files.get(wasm_path).unwrap());
+        assert_eq!(reader.entry_size(wasm_path), Some(bytes.len() as u64));
+
+        let mut streamed = Vec::new();
+        reader
+            .entry_reader(wasm_path)
+            .unwrap()
+            .read_to_end(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, bytes);
+
+        assert!(reader.read_entry("components/missing.wasm").is_err());
+
+        let names: Vec<_> = reader.entry_names().map(str::to_string).collect();
+        let mut eager_names: Vec<_> = eager.files.keys().cloned().collect();
+        eager_names.sort();
+        assert_eq!(names, eager_names);
+
+        let load = reader.into_pack_load().expect("buffer entries");
+        assert_eq!(load.files, eager.files);
+    }
+
+    #[test]
+    fn pack_reader_rejects_tampered_entry() {
+        let (_dir, path) = build_pack(true);
+        let (_tmp, tampered) = rewrite_entry(&path, "flows/demo/flow.ygtc", b"id: evil\n");
+        let err = PackReader::open(&tampered, SigningPolicy::DevOk, &ReaderLimits::default())
+            .err()
+            .expect("tampered pack must fail");
+        assert!(err.message.contains("hash mismatch"));
+    }
+
+    fn test_limits() -> ReaderLimits {
+        ReaderLimits::new(256 * 1024, 64 * 1024)
+    }
+
     fn temp_wasm(dir: &Path) -> PathBuf {
         let path = dir.join("component.wasm");
         std::fs::write(&path, [0x00u8, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]).unwrap();
@@ -1255,6 +1995,34 @@ mod tests {
         fs::write(path, bytes).unwrap();
     }
 
+    fn rewrite_entry(original: &Path, name: &str, contents: &[u8]) -> (TempDir, PathBuf) {
+        use zip::DateTime;
+
+        let mut archive = ZipArchive::new(File::open(original).unwrap()).unwrap();
+        let dir = tempdir().unwrap();
+        let new_path = dir.path().join("tampered.gtpack");
+        let mut writer = ZipWriter::new(File::create(&new_path).unwrap());
+        let timestamp = DateTime::from_date_and_time(1980, 1, 1, 0, 0, 0).unwrap();
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            if entry.name() == name {
+                data = contents.to_vec();
+            }
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .last_modified_time(timestamp)
+                .unix_permissions(0o644);
+            writer.start_file(entry.name(), options).unwrap();
+            writer.write_all(&data).unwrap();
+        }
+
+        writer.finish().unwrap();
+        (dir, new_path)
+    }
+
     fn duplicate_chain(original: &Path) -> (TempDir, PathBuf) {
         use zip::DateTime;
 
@@ -0,0 +1,634 @@
+//! Archive-level signing for already-built `.gtpack` files.
+//!
+//! Signing rewrites the archive deterministically (lexical entry order, pinned
+//! timestamps) with a `signatures/` envelope covering the manifest, the SBOM and
+//! every SBOM-listed entry digest. Verification reuses the reader's signature
+//! check and then asks a [`TrustPolicy`] whether the signing certificate is
+//! acceptable for the pack's publisher.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use ed25519_dalek::{Signer as _, SigningKey};
+use serde::Serialize;
+
+pub use crate::builder::SignatureRole;
+pub use crate::reader::SignatureCheck;
+
+use crate::builder::{
+    ExternalSignature, PackCompression, PendingFile, SIGNATURES_DIR, Signer, external_signature,
+    hex_hash, self_signed_chain, signature_digest_from_entries, write_zip,
+};
+use crate::reader::{
+    ReaderLimits, SignatureSlot, SignedContent, SigningPolicy, check_signatures, leaf_fingerprint,
+    manifest_publisher, open_archive, parse_certificate_chain, read_sbom_required, scan_archive,
+    signature_slots, verify_sbom,
+};
+use crate::trust::{TrustDecision, TrustPolicy};
+
+const SIGNER_COMMON_NAME: &str = "greentic-pack-signer";
+
+/// Ed25519 signer backed by a PKCS#8 private key.
+///
+/// Without an explicit chain a self-signed certificate is derived from the key,
+/// which is stable across runs.
+pub struct KeySigner {
+    key: SigningKey,
+    chain: Option<Vec<u8>>,
+}
+
+impl KeySigner {
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self> {
+        let key = SigningKey::from_pkcs8_pem(pem).context("failed to parse ed25519 private key")?;
+        Ok(Self { key, chain: None })
+    }
+
+    /// Use a CA-issued certificate chain (leaf first) instead of a self-signed one.
+    pub fn with_chain_pem(mut self, chain: Vec<u8>) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn key_fingerprint(&self) -> String {
+        hex_hash(self.key.verifying_key().as_bytes())
+    }
+}
+
+impl Signer for KeySigner {
+    fn sign(&self, message: &[u8]) -> Result<ExternalSignature> {
+        Ok(ExternalSignature {
+            alg: "ed25519".to_string(),
+            sig: self.key.sign(message).to_bytes().to_vec(),
+        })
+    }
+
+    fn chain_pem(&self) -> Result<Vec<u8>> {
+        match &self.chain {
+            Some(chain) => Ok(chain.clone()),
+            None => self_signed_chain(&self.key, SIGNER_COMMON_NAME),
+        }
+    }
+}
+
+/// Options for [`sign_archive_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct SignOptions {
+    /// Role recorded in, and bound into, the new signature.
+    pub role: Option<SignatureRole>,
+    /// Keep existing signatures and add this one alongside them (countersigning).
+    pub append: bool,
+}
+
+/// Outcome of [`sign_archive`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedArchive {
+    pub path: PathBuf,
+    pub digest: String,
+    pub key_fingerprint: Option<String>,
+    pub role: Option<SignatureRole>,
+    /// Archive path of the new signature envelope.
+    pub signature_path: String,
+    /// Whether an existing signature envelope was replaced.
+    pub replaced: bool,
+    /// Signatures in the archive after signing.
+    pub signatures: usize,
+    pub entries: usize,
+}
+
+/// Sign `input` and write the signed archive to `output` (which may be `input`),
+/// replacing any existing signatures.
+///
+/// The archive SBOM is verified first so that a tampered archive is never signed.
+pub fn sign_archive(
+    input: &Path,
+    output: &Path,
+    signer: &(dyn Signer + Send + Sync + 'static),
+    limits: &ReaderLimits,
+) -> Result<SignedArchive> {
+    sign_archive_with_options(input, output, signer, limits, &SignOptions::default())
+}
+
+/// Same as [`sign_archive`] but with a signer role and optional countersigning.
+///
+/// The first signature always lands in `signatures/pack.sig`. Appended signatures
+/// are stored as `signatures/<role>-<fingerprint prefix>.sig` with a matching
+/// `.chain.pem`, so re-signing with the same key and role replaces its own slot.
+pub fn sign_archive_with_options(
+    input: &Path,
+    output: &Path,
+    signer: &(dyn Signer + Send + Sync + 'static),
+    limits: &ReaderLimits,
+    options: &SignOptions,
+) -> Result<SignedArchive> {
+    let mut archive = open_archive(input)?;
+    let scan = scan_archive(&mut archive, limits, |_| true)?;
+    let files = scan.retained;
+
+    let manifest_bytes = files
+        .get("manifest.cbor")
+        .ok_or_else(|| anyhow!("manifest.cbor missing from archive"))?;
+    let (sbom_doc, sbom_bytes, sbom_name) = read_sbom_required(&files)?;
+    verify_sbom(&scan.entries, &sbom_doc.files, sbom_name)?;
+
+    let digest = signature_digest_from_entries(&sbom_doc.files, manifest_bytes, &sbom_bytes);
+    let (mut envelope, chain) = external_signature(signer, &digest, options.role)?;
+    let chain = chain.ok_or_else(|| anyhow!("signer did not provide a certificate chain"))?;
+    let fingerprint = leaf_fingerprint(&parse_certificate_chain(&chain)?)?;
+    envelope.key_fingerprint = Some(fingerprint.clone());
+
+    let existing = signature_slots(&files);
+    let slot = if options.append && !existing.is_empty() {
+        let label = options.role.map_or("cosign", |role| role.as_str());
+        SignatureSlot::named(&format!("{label}-{}", &fingerprint[..16]))
+    } else {
+        SignatureSlot::primary()
+    };
+    let replaced = if options.append {
+        existing.contains(&slot)
+    } else {
+        !existing.is_empty()
+    };
+    let signatures = if options.append && !replaced {
+        existing.len() + 1
+    } else if options.append {
+        existing.len()
+    } else {
+        1
+    };
+
+    let mut contents: BTreeMap<String, (&str, Vec<u8>)> = files
+        .into_iter()
+        .filter(|(path, _)| {
+            if options.append {
+                path != &slot.sig_path && path != &slot.chain_path
+            } else {
+                !path.starts_with(SIGNATURES_DIR)
+            }
+        })
+        .map(|(path, bytes)| (path, ("application/octet-stream", bytes)))
+        .collect();
+    contents.insert(
+        slot.sig_path.clone(),
+        ("application/json", serde_json::to_vec_pretty(&envelope)?),
+    );
+    contents.insert(slot.chain_path.clone(), ("application/x-pem-file", chain));
+    let entries = contents.len();
+    let pending: Vec<PendingFile> = contents
+        .into_iter()
+        .map(|(path, (media_type, bytes))| PendingFile::new(path, media_type, bytes))
+        .collect();
+
+    let parent = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)
+        .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    let staged = tempfile::Builder::new()
+        .prefix(".gtpack-sign")
+        .tempfile_in(parent)
+        .context("failed to create temporary archive")?;
+    // Keep the compression the pack was built with.
+    let compression = PackCompression::from_sbom(sbom_doc.compression.as_deref())?;
+    write_zip(staged.path(), &pending, compression)?;
+    staged
+        .persist(output)
+        .map_err(|err| anyhow!("failed to write {}: {}", output.display(), err.error))?;
+
+    Ok(SignedArchive {
+        path: output.to_path_buf(),
+        digest: envelope.digest,
+        key_fingerprint: envelope.key_fingerprint,
+        role: options.role,
+        signature_path: slot.sig_path,
+        replaced,
+        signatures,
+        entries,
+    })
+}
+
+/// Outcome of [`verify_archive`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveVerification {
+    pub path: PathBuf,
+    pub digest: String,
+    pub publisher: Option<String>,
+    pub signatures: Vec<SignatureCheck>,
+    /// Overall trust policy decision across all signatures.
+    pub trust: Option<TrustDecision>,
+    pub warnings: Vec<String>,
+}
+
+impl ArchiveVerification {
+    /// The trust policy accepted the archive's signatures.
+    pub fn is_trusted(&self) -> bool {
+        self.trust
+            .as_ref()
+            .is_some_and(|decision| decision.accepted)
+    }
+}
+
+/// Verify every signature of a `.gtpack` against `trust`.
+///
+/// Structural problems (missing manifest, SBOM mismatches) are returned as errors;
+/// signature problems are reported per signature, and the overall outcome in
+/// [`ArchiveVerification::trust`].
+pub fn verify_archive(
+    path: &Path,
+    trust: &TrustPolicy,
+    limits: &ReaderLimits,
+) -> Result<ArchiveVerification> {
+    let mut archive = open_archive(path)?;
+    let scan = scan_archive(&mut archive, limits, |entry| {
+        entry == "manifest.cbor"
+            || entry == "sbom.cbor"
+            || entry == "sbom.json"
+            || entry.starts_with(SIGNATURES_DIR)
+    })?;
+    let files = &scan.retained;
+    let manifest_bytes = files
+        .get("manifest.cbor")
+        .ok_or_else(|| anyhow!("manifest.cbor missing from archive"))?;
+    let (sbom_doc, sbom_bytes, sbom_name) = read_sbom_required(files)?;
+    verify_sbom(&scan.entries, &sbom_doc.files, sbom_name)?;
+    let digest = signature_digest_from_entries(&sbom_doc.files, manifest_bytes, &sbom_bytes);
+    let publisher = manifest_publisher(manifest_bytes);
+
+    let content = SignedContent {
+        manifest: manifest_bytes,
+        sbom: &sbom_bytes,
+        entries: &sbom_doc.files,
+    };
+    let mut warnings = Vec::new();
+    let signatures = check_signatures(
+        files,
+        &content,
+        SigningPolicy::Strict,
+        trust,
+        publisher.as_deref(),
+        &mut warnings,
+    );
+    let decision = (!trust.is_empty()).then(|| trust.decide(&signatures, publisher.as_deref()));
+
+    Ok(ArchiveVerification {
+        path: path.to_path_buf(),
+        digest: digest.to_hex().to_string(),
+        publisher,
+        signatures,
+        trust: decision,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{
+        ComponentArtifact, FlowBundle, PackBuilder, PackMeta, SIGNATURE_CHAIN_PATH, Signing,
+    };
+    use crate::reader::open_pack;
+    use crate::trust::SignatureRequirement;
+    use pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+    use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, PKCS_ED25519};
+    use rustls_pki_types::PrivatePkcs8KeyDer;
+    use semver::Version;
+    use serde_json::{Map, json};
+    use std::io::Read;
+    use tempfile::TempDir;
+    use zip::ZipArchive;
+
+    fn unsigned_pack(dir: &TempDir) -> PathBuf {
+        unsigned_pack_with(dir, PackCompression::Stored)
+    }
+
+    fn unsigned_pack_with(dir: &TempDir, compression: PackCompression) -> PathBuf {
+        let meta = PackMeta {
+            pack_version: crate::builder::PACK_VERSION,
+            pack_id: "ai.greentic.demo.signing".into(),
+            version: Version::parse("0.1.0").unwrap(),
+            name: "Signing Demo".into(),
+            kind: None,
+            description: None,
+            authors: vec!["Greentic".into()],
+            license: None,
+            homepage: None,
+            support: None,
+            vendor: None,
+            imports: vec![],
+            entry_flows: vec!["demo".into()],
+            created_at_utc: "2025-01-01T00:00:00Z".into(),
+            events: None,
+            repo: None,
+            messaging: None,
+            interfaces: Vec::new(),
+            annotations: Map::new(),
+            distribution: None,
+            components: Vec::new(),
+        };
+        let flow = json!({"id": "demo", "kind": "flow/v1", "entry": "start", "nodes": []});
+        let wasm = dir.path().join("demo.wasm");
+        fs::write(&wasm, [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]).unwrap();
+
+        let out = dir.path().join("demo.gtpack");
+        PackBuilder::new(meta)
+            .with_flow(FlowBundle {
+                id: "demo".into(),
+                kind: "flow/v1".into(),
+                entry: "start".into(),
+                yaml: "id: demo\nentry: start\n".into(),
+                json: flow.clone(),
+                hash_blake3: blake3::hash(&serde_json::to_vec(&flow).unwrap())
+                    .to_hex()
+                    .to_string(),
+                nodes: Vec::new(),
+            })
+            .with_component(ComponentArtifact {
+                name: "demo".into(),
+                version: Version::parse("1.0.0").unwrap(),
+                wasm_path: wasm,
+                schema_json: None,
+                manifest_json: None,
+                capabilities: None,
+                world: None,
+                hash_blake3: None,
+            })
+            .with_signing(Signing::None)
+            .with_compression(compression)
+            .build(&out)
+            .unwrap();
+        out
+    }
+
+    fn signing_key(seed: u8) -> (String, String) {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let private = key
+            .to_pkcs8_pem(LineEnding::LF)
+            .expect("private pem")
+            .to_string();
+        let public = key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .expect("public pem");
+        (private, public)
+    }
+
+    #[test]
+    fn signed_archive_verifies_against_public_key() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack(&dir);
+        let (private, public) = signing_key(7);
+        let signer = KeySigner::from_pkcs8_pem(&private).expect("signer");
+
+        let signed =
+            sign_archive(&pack, &pack, &signer, &ReaderLimits::default()).expect("sign archive");
+        assert!(!signed.replaced);
+        assert_eq!(signed.key_fingerprint, Some(signer.key_fingerprint()));
+
+        let load = open_pack(&pack, SigningPolicy::Strict).expect("strict reader accepts");
+        assert!(load.report.signature_ok);
+
+        let mut trust = TrustPolicy::new();
+        trust
+            .pin_public_key_pem("release", &public)
+            .expect("public key");
+        let report = verify_archive(&pack, &trust, &ReaderLimits::default()).expect("verify");
+        assert!(report.is_trusted(), "{report:?}");
+        assert_eq!(report.signatures.len(), 1);
+        assert_eq!(
+            report.signatures[0].trust.as_ref().map(|d| d.rule.as_str()),
+            Some("pinned-key:release")
+        );
+
+        let (_, other_public) = signing_key(9);
+        let mut other = TrustPolicy::new();
+        other
+            .pin_public_key_pem("other", &other_public)
+            .expect("public key");
+        let report = verify_archive(&pack, &other, &ReaderLimits::default()).expect("verify");
+        assert!(report.signatures[0].valid);
+        assert!(!report.is_trusted());
+    }
+
+    #[test]
+    fn signing_keeps_archive_compression() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack_with(&dir, PackCompression::Zstd);
+        let (private, _) = signing_key(7);
+        let signer = KeySigner::from_pkcs8_pem(&private).expect("signer");
+
+        sign_archive(&pack, &pack, &signer, &ReaderLimits::default()).expect("sign archive");
+
+        let mut archive = ZipArchive::new(fs::File::open(&pack).unwrap()).unwrap();
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i).unwrap();
+            assert_eq!(
+                entry.compression(),
+                zip::CompressionMethod::Zstd,
+                "{}",
+                entry.name()
+            );
+        }
+        let load = open_pack(&pack, SigningPolicy::Strict).expect("strict reader accepts");
+        assert!(load.report.signature_ok);
+    }
+
+    fn rcgen_key(key: &SigningKey) -> KeyPair {
+        let der = key.to_pkcs8_der().expect("pkcs8 der");
+        KeyPair::from_pkcs8_der_and_sign_algo(
+            &PrivatePkcs8KeyDer::from(der.as_bytes().to_vec()),
+            &PKCS_ED25519,
+        )
+        .expect("rcgen key")
+    }
+
+    fn test_ca(seed: u8) -> (String, Issuer<'static, KeyPair>) {
+        let key = rcgen_key(&SigningKey::from_bytes(&[seed; 32]));
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, format!("greentic-test-ca-{seed}"));
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = params.self_signed(&key).expect("ca cert");
+        (cert.pem(), Issuer::new(params, key))
+    }
+
+    #[test]
+    fn ca_issued_chain_verifies_against_trust_root() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack(&dir);
+        let (ca_pem, issuer) = test_ca(11);
+
+        let leaf_key = SigningKey::from_bytes(&[12; 32]);
+        let mut leaf_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        leaf_params
+            .distinguished_name
+            .push(DnType::CommonName, "greentic-test-publisher");
+        let leaf_cert = leaf_params
+            .signed_by(&rcgen_key(&leaf_key), &issuer)
+            .expect("leaf cert");
+
+        let private = leaf_key.to_pkcs8_pem(LineEnding::LF).expect("private pem");
+        let signer = KeySigner::from_pkcs8_pem(&private)
+            .expect("signer")
+            .with_chain_pem(format!("{}{}", leaf_cert.pem(), ca_pem).into_bytes());
+        sign_archive(&pack, &pack, &signer, &ReaderLimits::default()).expect("sign");
+
+        let mut trust = TrustPolicy::new();
+        trust
+            .add_roots_pem("corp", ca_pem.as_bytes())
+            .expect("root");
+        let report = verify_archive(&pack, &trust, &ReaderLimits::default()).expect("verify");
+        assert!(report.is_trusted(), "{report:?}");
+        assert_eq!(
+            report.signatures[0].trust.as_ref().map(|d| d.rule.as_str()),
+            Some("root:corp")
+        );
+
+        let (other_pem, _) = test_ca(13);
+        let mut other = TrustPolicy::new();
+        other
+            .add_roots_pem("other", other_pem.as_bytes())
+            .expect("root");
+        let report = verify_archive(&pack, &other, &ReaderLimits::default()).expect("verify");
+        assert!(!report.is_trusted());
+    }
+
+    #[test]
+    fn countersigned_archive_meets_role_and_threshold_requirement() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack(&dir);
+        let (builder_private, builder_public) = signing_key(21);
+        let (approver_private, approver_public) = signing_key(22);
+        let builder = KeySigner::from_pkcs8_pem(&builder_private).expect("signer");
+        let approver = KeySigner::from_pkcs8_pem(&approver_private).expect("signer");
+
+        let built = dir.path().join("built.gtpack");
+        let approved = dir.path().join("approved.gtpack");
+        let options = |role, append| SignOptions {
+            role: Some(role),
+            append,
+        };
+        let first = sign_archive_with_options(
+            &pack,
+            &built,
+            &builder,
+            &ReaderLimits::default(),
+            &options(SignatureRole::Builder, true),
+        )
+        .expect("builder signature");
+        assert_eq!(first.signature_path, "signatures/pack.sig");
+        let second = sign_archive_with_options(
+            &built,
+            &approved,
+            &approver,
+            &ReaderLimits::default(),
+            &options(SignatureRole::Approver, true),
+        )
+        .expect("approver countersignature");
+        assert_eq!(second.signatures, 2);
+        assert!(second.signature_path.starts_with("signatures/approver-"));
+
+        let mut trust = TrustPolicy::new();
+        trust
+            .pin_public_key_pem("builder", &builder_public)
+            .expect("key");
+        trust
+            .pin_public_key_pem("approver", &approver_public)
+            .expect("key");
+        trust.set_requirement(SignatureRequirement {
+            threshold: 2,
+            keys: vec!["builder".into(), "approver".into()],
+            roles: vec![SignatureRole::Approver],
+        });
+
+        let report = verify_archive(&approved, &trust, &ReaderLimits::default()).expect("verify");
+        assert!(report.is_trusted(), "{report:?}");
+        let roles: Vec<_> = report.signatures.iter().map(|sig| sig.role).collect();
+        assert_eq!(
+            roles,
+            vec![Some(SignatureRole::Builder), Some(SignatureRole::Approver)]
+        );
+
+        let report = verify_archive(&built, &trust, &ReaderLimits::default()).expect("verify");
+        assert!(!report.is_trusted());
+        assert_eq!(
+            report.trust.as_ref().map(|d| d.detail.as_str()),
+            Some("1 of 2 required signatures from {builder, approver}")
+        );
+
+        let load = open_pack(&approved, SigningPolicy::Strict).expect("strict reader accepts");
+        assert_eq!(load.report.signatures.len(), 2);
+        assert!(load.report.signatures.iter().all(|sig| sig.valid));
+    }
+
+    #[test]
+    fn resigning_is_deterministic_apart_from_envelope() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack(&dir);
+        let (private, _) = signing_key(3);
+        let signer = KeySigner::from_pkcs8_pem(&private).expect("signer");
+
+        let first = dir.path().join("first.gtpack");
+        let second = dir.path().join("second.gtpack");
+        sign_archive(&pack, &first, &signer, &ReaderLimits::default()).expect("sign");
+        let resigned =
+            sign_archive(&first, &second, &signer, &ReaderLimits::default()).expect("re-sign");
+        assert!(resigned.replaced);
+
+        let names = |path: &Path| {
+            let archive = ZipArchive::new(fs::File::open(path).expect("open")).expect("zip");
+            archive.file_names().map(str::to_string).collect::<Vec<_>>()
+        };
+        let first_names = names(&first);
+        let mut sorted = first_names.clone();
+        sorted.sort();
+        assert_eq!(first_names, sorted, "entries are written in lexical order");
+        assert_eq!(first_names, names(&second));
+
+        let chain = |path: &Path| {
+            let mut archive = ZipArchive::new(fs::File::open(path).expect("open")).expect("zip");
+            let mut buf = Vec::new();
+            archive
+                .by_name(SIGNATURE_CHAIN_PATH)
+                .expect("chain")
+                .read_to_end(&mut buf)
+                .expect("read chain");
+            buf
+        };
+        assert_eq!(chain(&first), chain(&second));
+    }
+
+    #[test]
+    fn sign_refuses_tampered_archive() {
+        let dir = TempDir::new().expect("temp dir");
+        let pack = unsigned_pack(&dir);
+        let tampered = dir.path().join("tampered.gtpack");
+        {
+            let mut source = ZipArchive::new(fs::File::open(&pack).expect("open")).expect("zip");
+            let mut writer = zip::ZipWriter::new(fs::File::create(&tampered).expect("create"));
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            for idx in 0..source.len() {
+                let mut entry = source.by_index(idx).expect("entry");
+                let name = entry.name().to_string();
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes).expect("read");
+                if name == "manifest.json" {
+                    bytes.extend_from_slice(b" ");
+                }
+                writer.start_file(name, options).expect("start");
+                std::io::Write::write_all(&mut writer, &bytes).expect("write");
+            }
+            writer.finish().expect("finish");
+        }
+
+        let (private, _) = signing_key(5);
+        let signer = KeySigner::from_pkcs8_pem(&private).expect("signer");
+        let err = sign_archive(&tampered, &tampered, &signer, &ReaderLimits::default())
+            .expect_err("tampered archive must not be signed");
+        assert!(err.to_string().contains("hash mismatch"), "{err}");
+    }
+}
@@ -0,0 +1,824 @@
+//! Trust policy for pack signatures.
+//!
+//! A [`TrustPolicy`] decides whether the certificate chain in
+//! `signatures/chain.pem` is anchored in something the operator trusts: a CA
+//! root or a pinned Ed25519 key fingerprint. Publisher rules narrow that further
+//! ("packs with `publisher: acme` must be signed by key X"). A
+//! [`SignatureRequirement`] asks for several signatures, e.g. two of a set of
+//! keys or one signature in the `approver` role. Every evaluation yields a
+//! [`TrustDecision`] naming the rule that accepted or rejected the pack.
+//!
+//! Policies are usually loaded from a directory:
+//!
+//! ```text
+//! trust/
+//!   roots/*.pem     trusted CA certificates (name = file stem)
+//!   keys/*.pem      pinned Ed25519 public keys (name = file stem)
+//!   policy.yaml     optional pinned fingerprints, signature requirements and
+//!                   publisher rules
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use ed25519_dalek::pkcs8::DecodePublicKey;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use x509_parser::prelude::*;
+
+use crate::builder::{SignatureRole, hex_hash};
+use crate::reader::{
+    SignatureCheck, extract_ed25519_key, parse_certificate, parse_certificate_chain,
+};
+
+/// Optional policy file inside a trust directory.
+pub const POLICY_FILE: &str = "policy.yaml";
+
+const ROOTS_DIR: &str = "roots";
+const KEYS_DIR: &str = "keys";
+const OID_ED25519: &str = "1.3.101.112";
+
+/// Signers accepted for packs declaring a given publisher.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublisherRule {
+    /// Pinned key names or raw key fingerprints.
+    pub keys: Vec<String>,
+    /// Names of trusted roots the signing chain may terminate in.
+    pub roots: Vec<String>,
+    /// Signatures required for this publisher, replacing the global requirement.
+    pub require: Option<SignatureRequirement>,
+}
+
+/// Signatures a pack must carry, e.g. "2 of {release, ci}" or "role approver".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignatureRequirement {
+    /// Minimum number of distinct signing keys (at least 1).
+    pub threshold: usize,
+    /// Keys counted towards the threshold (pinned key names or fingerprints).
+    /// Empty means any signature accepted by the policy counts.
+    pub keys: Vec<String>,
+    /// Roles that must each be covered by at least one counted signature.
+    pub roles: Vec<SignatureRole>,
+}
+
+/// On-disk shape of `policy.yaml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyFile {
+    /// Pinned key fingerprints keyed by a human-readable name.
+    pinned: BTreeMap<String, String>,
+    require: Option<SignatureRequirement>,
+    publishers: BTreeMap<String, PublisherRule>,
+}
+
+#[derive(Debug, Clone)]
+struct TrustedRoot {
+    name: String,
+    der: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct PinnedKey {
+    name: String,
+    fingerprint: String,
+}
+
+/// Roots, pinned keys and publisher rules used to accept or reject signed packs.
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicy {
+    roots: Vec<TrustedRoot>,
+    pinned: Vec<PinnedKey>,
+    publishers: BTreeMap<String, PublisherRule>,
+    require: Option<SignatureRequirement>,
+    source: Option<PathBuf>,
+}
+
+/// Outcome of evaluating a signing chain against a [`TrustPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrustDecision {
+    pub accepted: bool,
+    /// Rule that produced the decision, e.g. `pinned-key:release`,
+    /// `root:corp-ca`, `publisher:acme` or `untrusted`.
+    pub rule: String,
+    pub detail: String,
+}
+
+impl TrustDecision {
+    fn accept(rule: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            accepted: true,
+            rule: rule.into(),
+            detail: detail.into(),
+        }
+    }
+
+    pub(crate) fn reject(rule: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            accepted: false,
+            rule: rule.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for TrustDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.accepted {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        write!(f, "{verdict} by {}: {}", self.rule, self.detail)
+    }
+}
+
+impl TrustPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `roots/*.pem`, `keys/*.pem` and the optional `policy.yaml` from `dir`.
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let mut policy = Self {
+            source: Some(dir.to_path_buf()),
+            ..Self::default()
+        };
+
+        for (name, path) in pem_files(&dir.join(ROOTS_DIR))? {
+            let pem =
+                fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+            policy
+                .add_roots_pem(&name, &pem)
+                .with_context(|| format!("invalid trust root {}", path.display()))?;
+        }
+        for (name, path) in pem_files(&dir.join(KEYS_DIR))? {
+            let pem = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            policy
+                .pin_public_key_pem(&name, &pem)
+                .with_context(|| format!("invalid pinned key {}", path.display()))?;
+        }
+
+        let policy_path = dir.join(POLICY_FILE);
+        if policy_path.exists() {
+            let text = fs::read_to_string(&policy_path)
+                .with_context(|| format!("failed to read {}", policy_path.display()))?;
+            let file: PolicyFile = serde_yaml_bw::from_str(&text).with_context(|| {
+                format!("{} is not a valid trust policy", policy_path.display())
+            })?;
+            for (name, fingerprint) in file.pinned {
+                policy.pin_fingerprint(&name, &fingerprint)?;
+            }
+            policy.require = file.require;
+            for (publisher, rule) in file.publishers {
+                policy.set_publisher_rule(publisher, rule);
+            }
+        }
+
+        Ok(policy)
+    }
+
+    /// Directory the policy was loaded from, if any.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Trust every certificate in `pem` as a root named `name`.
+    pub fn add_roots_pem(&mut self, name: &str, pem: &[u8]) -> Result<()> {
+        for der in parse_certificate_chain(pem)? {
+            parse_certificate(&der)?;
+            self.roots.push(TrustedRoot {
+                name: name.to_string(),
+                der,
+            });
+        }
+        Ok(())
+    }
+
+    /// Pin an Ed25519 public key (SPKI PEM) under `name`.
+    pub fn pin_public_key_pem(&mut self, name: &str, pem: &str) -> Result<()> {
+        let key = VerifyingKey::from_public_key_pem(pem).context("failed to parse public key")?;
+        self.pinned.push(PinnedKey {
+            name: name.to_string(),
+            fingerprint: hex_hash(key.as_bytes()),
+        });
+        Ok(())
+    }
+
+    /// Pin a key by its BLAKE3 fingerprint (64 hex characters).
+    pub fn pin_fingerprint(&mut self, name: &str, fingerprint: &str) -> Result<()> {
+        let fingerprint = normalize_fingerprint(fingerprint)
+            .ok_or_else(|| anyhow!("pinned key `{name}` has an invalid fingerprint"))?;
+        self.pinned.push(PinnedKey {
+            name: name.to_string(),
+            fingerprint,
+        });
+        Ok(())
+    }
+
+    pub fn set_publisher_rule(&mut self, publisher: impl Into<String>, rule: PublisherRule) {
+        self.publishers.insert(publisher.into(), rule);
+    }
+
+    /// Require these signatures for every pack without a publisher-specific requirement.
+    pub fn set_requirement(&mut self, requirement: SignatureRequirement) {
+        self.require = Some(requirement);
+    }
+
+    pub fn requirement(&self) -> Option<&SignatureRequirement> {
+        self.require.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+            && self.pinned.is_empty()
+            && self.publishers.is_empty()
+            && self.require.is_none()
+    }
+
+    /// Decide whether a pack carrying `signatures` is trusted.
+    ///
+    /// Every signature must be valid. Without a requirement the first signature
+    /// accepted by [`TrustPolicy::evaluate`] decides; otherwise the publisher's or
+    /// the global [`SignatureRequirement`] must be met.
+    pub fn decide(&self, signatures: &[SignatureCheck], publisher: Option<&str>) -> TrustDecision {
+        if signatures.is_empty() {
+            return TrustDecision::reject("unsigned", "pack has no verified signature");
+        }
+        if let Some(invalid) = signatures.iter().find(|check| !check.valid) {
+            return TrustDecision::reject(
+                "invalid-signature",
+                format!(
+                    "{}: {}",
+                    invalid.path,
+                    invalid.error.as_deref().unwrap_or("verification failed")
+                ),
+            );
+        }
+
+        let requirement = match publisher.and_then(|name| self.publishers.get_key_value(name)) {
+            Some((
+                name,
+                PublisherRule {
+                    require: Some(require),
+                    ..
+                },
+            )) => Some((format!("publisher:{name}"), require)),
+            _ => self
+                .require
+                .as_ref()
+                .map(|require| ("require".to_string(), require)),
+        };
+        match requirement {
+            Some((rule, requirement)) => self.check_requirement(rule, requirement, signatures),
+            None => signatures
+                .iter()
+                .filter_map(|check| check.trust.as_ref())
+                .find(|decision| decision.accepted)
+                .or_else(|| signatures[0].trust.as_ref())
+                .cloned()
+                .unwrap_or_else(|| {
+                    TrustDecision::reject("untrusted", "no signature was evaluated")
+                }),
+        }
+    }
+
+    fn check_requirement(
+        &self,
+        rule: String,
+        requirement: &SignatureRequirement,
+        signatures: &[SignatureCheck],
+    ) -> TrustDecision {
+        let listed: BTreeSet<String> = requirement
+            .keys
+            .iter()
+            .filter_map(|key| self.resolve_key(key))
+            .collect();
+        let counted: Vec<&SignatureCheck> = signatures
+            .iter()
+            .filter(|check| {
+                let Some(fingerprint) = check.key_fingerprint.as_deref() else {
+                    return false;
+                };
+                if requirement.keys.is_empty() {
+                    check.trusted
+                } else {
+                    listed.contains(fingerprint)
+                }
+            })
+            .collect();
+        let signers: BTreeSet<&str> = counted
+            .iter()
+            .filter_map(|check| check.key_fingerprint.as_deref())
+            .collect();
+
+        let threshold = requirement.threshold.max(1);
+        let pool = if requirement.keys.is_empty() {
+            "trusted keys".to_string()
+        } else {
+            format!("{{{}}}", requirement.keys.join(", "))
+        };
+        if signers.len() < threshold {
+            return TrustDecision::reject(
+                rule,
+                format!(
+                    "{} of {threshold} required signatures from {pool}",
+                    signers.len()
+                ),
+            );
+        }
+        for role in &requirement.roles {
+            if !counted.iter().any(|check| check.role == Some(*role)) {
+                return TrustDecision::reject(
+                    rule,
+                    format!("no signature from {pool} in role `{role}`"),
+                );
+            }
+        }
+
+        let mut detail = format!(
+            "{} of {threshold} required signatures from {pool}",
+            signers.len()
+        );
+        if !requirement.roles.is_empty() {
+            let roles: Vec<&str> = requirement
+                .roles
+                .iter()
+                .map(SignatureRole::as_str)
+                .collect();
+            detail.push_str(&format!("; roles {}", roles.join(", ")));
+        }
+        TrustDecision::accept(rule, detail)
+    }
+
+    /// Fingerprint for a pinned key name or a literal fingerprint.
+    fn resolve_key(&self, key: &str) -> Option<String> {
+        self.pinned
+            .iter()
+            .find(|pinned| pinned.name == key)
+            .map(|pinned| pinned.fingerprint.clone())
+            .or_else(|| normalize_fingerprint(key))
+    }
+
+    /// Evaluate a PEM certificate chain (leaf first).
+    pub fn evaluate_pem(&self, chain_pem: &[u8], publisher: Option<&str>) -> TrustDecision {
+        match parse_certificate_chain(chain_pem) {
+            Ok(chain) => self.evaluate(&chain, publisher),
+            Err(err) => TrustDecision::reject("certificate", err.to_string()),
+        }
+    }
+
+    /// Evaluate a DER certificate chain (leaf first) for a pack by `publisher`.
+    pub fn evaluate(&self, chain: &[Vec<u8>], publisher: Option<&str>) -> TrustDecision {
+        match self.evaluate_inner(chain, publisher) {
+            Ok(decision) => decision,
+            Err(err) => TrustDecision::reject("certificate", err.to_string()),
+        }
+    }
+
+    fn evaluate_inner(&self, chain: &[Vec<u8>], publisher: Option<&str>) -> Result<TrustDecision> {
+        let leaf_der = chain
+            .first()
+            .ok_or_else(|| anyhow!("certificate chain is empty"))?;
+        let leaf = parse_certificate(leaf_der)?;
+        check_leaf(&leaf)?;
+        let fingerprint = hex_hash(extract_ed25519_key(&leaf)?.as_bytes());
+
+        let mut anchors = Vec::new();
+        for pinned in &self.pinned {
+            if pinned.fingerprint == fingerprint {
+                anchors.push(Anchor::Key(pinned));
+            }
+        }
+        let mut path_errors = Vec::new();
+        for root in &self.roots {
+            match chain_reaches_root(chain, &root.der) {
+                Ok(true) => anchors.push(Anchor::Root(root)),
+                Ok(false) => {}
+                Err(err) => path_errors.push(format!("root `{}`: {err}", root.name)),
+            }
+        }
+
+        if let Some((publisher, rule)) =
+            publisher.and_then(|name| self.publishers.get_key_value(name))
+        {
+            let rule_name = format!("publisher:{publisher}");
+            if rule
+                .keys
+                .iter()
+                .any(|key| normalize_fingerprint(key).is_some_and(|pinned| pinned == fingerprint))
+            {
+                return Ok(TrustDecision::accept(
+                    rule_name,
+                    format!("signed by pinned fingerprint {fingerprint}"),
+                ));
+            }
+            if let Some(anchor) = anchors.iter().find(|anchor| anchor.allowed_by(rule)) {
+                return Ok(TrustDecision::accept(
+                    rule_name,
+                    format!("signed via {}", anchor.rule()),
+                ));
+            }
+            return Ok(TrustDecision::reject(
+                rule_name,
+                format!("signing key {fingerprint} is not allowed for publisher `{publisher}`"),
+            ));
+        }
+
+        if let Some(anchor) = anchors.first() {
+            return Ok(TrustDecision::accept(anchor.rule(), anchor.detail()));
+        }
+        let mut detail =
+            format!("signing key {fingerprint} is not pinned and does not chain to a trusted root");
+        if !path_errors.is_empty() {
+            detail = format!("{detail} ({})", path_errors.join("; "));
+        }
+        Ok(TrustDecision::reject("untrusted", detail))
+    }
+}
+
+enum Anchor<'a> {
+    Key(&'a PinnedKey),
+    Root(&'a TrustedRoot),
+}
+
+impl Anchor<'_> {
+    fn rule(&self) -> String {
+        match self {
+            Anchor::Key(key) => format!("pinned-key:{}", key.name),
+            Anchor::Root(root) => format!("root:{}", root.name),
+        }
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            Anchor::Key(key) => format!("leaf key matches pinned fingerprint {}", key.fingerprint),
+            Anchor::Root(root) => format!("certificate chain terminates in root `{}`", root.name),
+        }
+    }
+
+    fn allowed_by(&self, rule: &PublisherRule) -> bool {
+        match self {
+            Anchor::Key(key) => rule
+                .keys
+                .iter()
+                .any(|name| name == &key.name || name.eq_ignore_ascii_case(&key.fingerprint)),
+            Anchor::Root(root) => rule.roots.iter().any(|name| name == &root.name),
+        }
+    }
+}
+
+/// `*.pem` files in `dir`, sorted by name; a missing directory yields nothing.
+fn pem_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+            continue;
+        }
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            files.push((stem.to_string(), path.clone()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn normalize_fingerprint(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value.strip_prefix("blake3:").unwrap_or(value);
+    (value.len() == 64 && value.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .then(|| value.to_ascii_lowercase())
+}
+
+/// The signing certificate must be within its validity period and, when it
+/// carries a key usage extension, allow digital signatures.
+fn check_leaf(cert: &X509Certificate<'_>) -> Result<()> {
+    check_validity(cert)?;
+    if let Some(usage) = cert
+        .key_usage()
+        .map_err(|err| anyhow!("invalid key usage extension: {err}"))?
+        && !usage.value.digital_signature()
+    {
+        bail!(
+            "signing certificate {} does not allow digital signatures",
+            cert.subject()
+        );
+    }
+    Ok(())
+}
+
+/// Issuers must be valid CA certificates allowed to sign certificates.
+fn check_issuer(cert: &X509Certificate<'_>) -> Result<()> {
+    check_validity(cert)?;
+    let is_ca = cert
+        .basic_constraints()
+        .map_err(|err| anyhow!("invalid basic constraints extension: {err}"))?
+        .is_some_and(|constraints| constraints.value.ca);
+    if !is_ca {
+        bail!("issuer {} is not a CA certificate", cert.subject());
+    }
+    if let Some(usage) = cert
+        .key_usage()
+        .map_err(|err| anyhow!("invalid key usage extension: {err}"))?
+        && !usage.value.key_cert_sign()
+    {
+        bail!("issuer {} may not sign certificates", cert.subject());
+    }
+    Ok(())
+}
+
+fn check_validity(cert: &X509Certificate<'_>) -> Result<()> {
+    let validity = cert.validity();
+    if !validity.is_valid() {
+        bail!(
+            "certificate {} is outside its validity period ({} to {})",
+            cert.subject(),
+            validity.not_before,
+            validity.not_after
+        );
+    }
+    Ok(())
+}
+
+/// Walk `chain` (leaf first) until a certificate issued by, or equal to, `root`.
+///
+/// Returns `Ok(false)` when the chain is unrelated to `root` and an error when it
+/// links to `root` through a certificate that may not act as an issuer.
+pub(crate) fn chain_reaches_root(chain: &[Vec<u8>], root: &[u8]) -> Result<bool> {
+    for (idx, der) in chain.iter().enumerate() {
+        if der.as_slice() == root {
+            return Ok(true);
+        }
+        let cert = parse_certificate(der)?;
+        let issuer_der = chain.get(idx + 1).map(Vec::as_slice).unwrap_or(root);
+        let issuer = parse_certificate(issuer_der)?;
+        if cert.issuer() != issuer.subject() || !issued_by(&cert, &issuer)? {
+            return Ok(false);
+        }
+        check_issuer(&issuer)?;
+        if issuer_der == root {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Check the certificate signature of `cert` against `issuer`'s key (Ed25519 only).
+fn issued_by(cert: &X509Certificate<'_>, issuer: &X509Certificate<'_>) -> Result<bool> {
+    if cert.signature_algorithm.algorithm.to_id_string() != OID_ED25519 {
+        bail!(
+            "unsupported certificate signature algorithm {}",
+            cert.signature_algorithm.algorithm
+        );
+    }
+    let key = extract_ed25519_key(issuer)?;
+    let Ok(signature) = Signature::from_slice(cert.signature_value.data.as_ref()) else {
+        return Ok(false);
+    };
+    Ok(key
+        .verify(cert.tbs_certificate.as_ref(), &signature)
+        .is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+    use rcgen::{
+        BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose,
+        PKCS_ED25519, date_time_ymd,
+    };
+    use rustls_pki_types::PrivatePkcs8KeyDer;
+    use tempfile::TempDir;
+
+    fn rcgen_key(seed: u8) -> KeyPair {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let der = key.to_pkcs8_der().expect("pkcs8 der");
+        KeyPair::from_pkcs8_der_and_sign_algo(
+            &PrivatePkcs8KeyDer::from(der.as_bytes().to_vec()),
+            &PKCS_ED25519,
+        )
+        .expect("rcgen key")
+    }
+
+    fn fingerprint(seed: u8) -> String {
+        hex_hash(
+            SigningKey::from_bytes(&[seed; 32])
+                .verifying_key()
+                .as_bytes(),
+        )
+    }
+
+    fn test_ca(seed: u8) -> (String, Issuer<'static, KeyPair>) {
+        let key = rcgen_key(seed);
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, format!("greentic-test-ca-{seed}"));
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = params.self_signed(&key).expect("ca cert");
+        (cert.pem(), Issuer::new(params, key))
+    }
+
+    fn leaf_params() -> CertificateParams {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "greentic-test-publisher");
+        params
+    }
+
+    fn self_signed(seed: u8) -> Vec<Vec<u8>> {
+        let cert = leaf_params().self_signed(&rcgen_key(seed)).expect("cert");
+        vec![cert.der().to_vec()]
+    }
+
+    #[test]
+    fn pinned_fingerprint_accepts_matching_key() {
+        let mut policy = TrustPolicy::new();
+        policy.pin_fingerprint("release", &fingerprint(1)).unwrap();
+
+        let decision = policy.evaluate(&self_signed(1), None);
+        assert!(decision.accepted, "{decision}");
+        assert_eq!(decision.rule, "pinned-key:release");
+
+        let decision = policy.evaluate(&self_signed(2), None);
+        assert!(!decision.accepted);
+        assert_eq!(decision.rule, "untrusted");
+    }
+
+    #[test]
+    fn publisher_rule_restricts_signing_keys() {
+        let mut policy = TrustPolicy::new();
+        policy
+            .pin_fingerprint("acme-release", &fingerprint(1))
+            .unwrap();
+        policy.pin_fingerprint("other", &fingerprint(2)).unwrap();
+        policy.set_publisher_rule(
+            "acme",
+            PublisherRule {
+                keys: vec!["acme-release".into()],
+                ..PublisherRule::default()
+            },
+        );
+
+        let decision = policy.evaluate(&self_signed(1), Some("acme"));
+        assert!(decision.accepted, "{decision}");
+        assert_eq!(decision.rule, "publisher:acme");
+
+        let decision = policy.evaluate(&self_signed(2), Some("acme"));
+        assert!(!decision.accepted);
+        assert_eq!(decision.rule, "publisher:acme");
+
+        let decision = policy.evaluate(&self_signed(2), Some("globex"));
+        assert_eq!(decision.rule, "pinned-key:other");
+        assert!(decision.accepted);
+    }
+
+    #[test]
+    fn ca_chain_is_checked_for_validity_and_key_usage() {
+        let (ca_pem, issuer) = test_ca(10);
+        let mut policy = TrustPolicy::new();
+        policy.add_roots_pem("corp", ca_pem.as_bytes()).unwrap();
+
+        let leaf = leaf_params().signed_by(&rcgen_key(11), &issuer).unwrap();
+        let decision = policy.evaluate(&[leaf.der().to_vec()], None);
+        assert!(decision.accepted, "{decision}");
+        assert_eq!(decision.rule, "root:corp");
+
+        let mut expired = leaf_params();
+        expired.not_before = date_time_ymd(2000, 1, 1);
+        expired.not_after = date_time_ymd(2001, 1, 1);
+        let leaf = expired.signed_by(&rcgen_key(11), &issuer).unwrap();
+        let decision = policy.evaluate(&[leaf.der().to_vec()], None);
+        assert!(!decision.accepted);
+        assert!(decision.detail.contains("validity period"), "{decision}");
+
+        let mut no_signing = leaf_params();
+        no_signing.key_usages = vec![KeyUsagePurpose::KeyEncipherment];
+        // rcgen only writes the key usage extension alongside other extensions.
+        no_signing.is_ca = IsCa::ExplicitNoCa;
+        let leaf = no_signing.signed_by(&rcgen_key(11), &issuer).unwrap();
+        let decision = policy.evaluate(&[leaf.der().to_vec()], None);
+        assert!(!decision.accepted);
+        assert!(decision.detail.contains("digital signatures"), "{decision}");
+    }
+
+    #[test]
+    fn non_ca_issuer_is_rejected() {
+        let issuer_key = rcgen_key(20);
+        let mut issuer_params = leaf_params();
+        issuer_params.distinguished_name = rcgen::DistinguishedName::new();
+        issuer_params
+            .distinguished_name
+            .push(DnType::CommonName, "not-a-ca");
+        let issuer_cert = issuer_params.self_signed(&issuer_key).unwrap();
+        let issuer = Issuer::new(issuer_params, issuer_key);
+
+        let mut policy = TrustPolicy::new();
+        policy
+            .add_roots_pem("fake", issuer_cert.pem().as_bytes())
+            .unwrap();
+        let leaf = leaf_params().signed_by(&rcgen_key(21), &issuer).unwrap();
+        let decision = policy.evaluate(&[leaf.der().to_vec()], None);
+        assert!(!decision.accepted);
+        assert!(decision.detail.contains("not a CA"), "{decision}");
+    }
+
+    fn check(seed: u8, role: Option<SignatureRole>, trusted: bool) -> SignatureCheck {
+        SignatureCheck {
+            path: format!("signatures/{seed}.sig"),
+            role,
+            alg: Some("ed25519".into()),
+            key_fingerprint: Some(fingerprint(seed)),
+            signed_at_utc: None,
+            valid: true,
+            trusted,
+            trust: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn requirement_counts_distinct_listed_keys_and_roles() {
+        let mut policy = TrustPolicy::new();
+        policy.pin_fingerprint("builder", &fingerprint(1)).unwrap();
+        policy.set_requirement(SignatureRequirement {
+            threshold: 2,
+            keys: vec!["builder".into(), fingerprint(2)],
+            roles: vec![SignatureRole::Approver],
+        });
+
+        let signed = [
+            check(1, Some(SignatureRole::Builder), false),
+            check(2, Some(SignatureRole::Approver), false),
+        ];
+        let decision = policy.decide(&signed, None);
+        assert!(decision.accepted, "{decision}");
+        assert_eq!(decision.rule, "require");
+
+        // The same key twice does not satisfy "2 of".
+        let same_key = [
+            check(1, Some(SignatureRole::Builder), false),
+            check(1, Some(SignatureRole::Approver), false),
+        ];
+        assert!(!policy.decide(&same_key, None).accepted);
+
+        // An unlisted approver does not cover the role.
+        let unlisted = [
+            check(1, Some(SignatureRole::Builder), false),
+            check(2, Some(SignatureRole::Builder), false),
+            check(3, Some(SignatureRole::Approver), true),
+        ];
+        let decision = policy.decide(&unlisted, None);
+        assert!(!decision.accepted);
+        assert!(decision.detail.contains("role `approver`"), "{decision}");
+
+        let mut invalid = check(2, Some(SignatureRole::Approver), false);
+        invalid.valid = false;
+        invalid.error = Some("signature digest mismatch".into());
+        let decision = policy.decide(&[check(1, None, false), invalid], None);
+        assert_eq!(decision.rule, "invalid-signature");
+    }
+
+    #[test]
+    fn load_dir_reads_roots_keys_and_rules() {
+        let dir = TempDir::new().unwrap();
+        let (ca_pem, _) = test_ca(30);
+        fs::create_dir_all(dir.path().join("roots")).unwrap();
+        fs::create_dir_all(dir.path().join("keys")).unwrap();
+        fs::write(dir.path().join("roots/corp.pem"), ca_pem).unwrap();
+        let public = SigningKey::from_bytes(&[31; 32])
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        fs::write(dir.path().join("keys/release.pem"), public).unwrap();
+        fs::write(
+            dir.path().join(POLICY_FILE),
+            format!(
+                "pinned:\n  ci: {}\npublishers:\n  acme:\n    keys: [ci]\n",
+                fingerprint(32)
+            ),
+        )
+        .unwrap();
+
+        let policy = TrustPolicy::load_dir(dir.path()).unwrap();
+        assert_eq!(policy.source(), Some(dir.path()));
+        assert_eq!(
+            policy.evaluate(&self_signed(31), None).rule,
+            "pinned-key:release"
+        );
+        assert!(policy.evaluate(&self_signed(32), Some("acme")).accepted);
+        assert!(!policy.evaluate(&self_signed(31), Some("acme")).accepted);
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::fs;
 
 use greentic_pack::pack_lock::{
-    LockedComponent, LockedOperation, PackLockV1, read_pack_lock, write_pack_lock,
+    LockedComponent, LockedDependency, LockedOperation, PackLockV1, read_pack_lock, write_pack_lock,
 };
 use tempfile::TempDir;
 
@@ -67,3 +67,39 @@ fn pack_lock_roundtrips_with_sorted_components() {
     let second = fs::read(&path).expect("read file again");
     assert_eq!(first, second, "lock bytes should be deterministic");
 }
+
+#[test]
+fn pack_lock_records_dependencies() {
+    let temp = TempDir::new().expect("temp dir");
+    let path = temp.path().join("pack.lock.cbor");
+
+    let mut lock = PackLockV1::new(BTreeMap::new());
+    write_pack_lock(&path, &lock).expect("write lock without dependencies");
+    let without = fs::read(&path).expect("read file");
+
+    lock.dependencies.insert(
+        "acme.storage".to_string(),
+        LockedDependency {
+            pack_id: "acme.storage".to_string(),
+            version: "1.2.0".to_string(),
+            digest: format!("sha256:{}", "a".repeat(64)),
+            r#ref: Some("file:///mirror/acme.storage/1.2.0/pack.gtpack".to_string()),
+            aliases: vec!["storage".to_string()],
+            required_by: vec!["demo@0.1.0".to_string(), "acme.cache@3.0.0".to_string()],
+        },
+    );
+    write_pack_lock(&path, &lock).expect("write lock");
+    assert_ne!(fs::read(&path).expect("read file"), without);
+
+    let roundtrip = read_pack_lock(&path).expect("read lock");
+    let storage = &roundtrip.dependencies["acme.storage"];
+    assert_eq!(storage.version, "1.2.0");
+    assert_eq!(storage.required_by, vec!["acme.cache@3.0.0", "demo@0.1.0"]);
+
+    let mut mismatched = roundtrip.clone();
+    let entry = mismatched.dependencies.remove("acme.storage").unwrap();
+    mismatched
+        .dependencies
+        .insert("acme.other".to_string(), entry);
+    assert!(write_pack_lock(&path, &mismatched).is_err());
+}
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use greentic_pack::plan_render::{RenderTarget, render_plan};
+use greentic_types::SecretRequirement;
+use greentic_types::deployment::{
+    ChannelPlan, DeploymentPlan, MessagingPlan, MessagingSubjectPlan, RunnerPlan, TelemetryPlan,
+};
+use semver::Version;
+use serde_json::json;
+
+fn secret(key: &str, required: bool) -> SecretRequirement {
+    serde_json::from_value(json!({
+        "key": key,
+        "required": required,
+        "scope": { "env": "prod", "tenant": "tenant-1" },
+        "format": "text"
+    }))
+    .expect("secret requirement")
+}
+
+fn demo_plan() -> DeploymentPlan {
+    DeploymentPlan {
+        pack_id: "demo.pack".to_string(),
+        pack_version: Version::parse("1.2.3").unwrap(),
+        tenant: "tenant-1".to_string(),
+        environment: "prod".to_string(),
+        runners: vec![
+            RunnerPlan {
+                name: "demo.pack-runner".to_string(),
+                replicas: 2,
+                capabilities: json!({ "flows": ["main", "support"] }),
+            },
+            RunnerPlan {
+                name: "webhooks".to_string(),
+                replicas: 1,
+                capabilities: json!({
+                    "flows": ["inbound"],
+                    "image": "registry.example.com/greentic/webhooks:1.4"
+                }),
+            },
+        ],
+        messaging: Some(MessagingPlan {
+            logical_cluster: "default".to_string(),
+            subjects: vec![
+                MessagingSubjectPlan {
+                    name: "primary".to_string(),
+                    purpose: "messaging".to_string(),
+                    durable: true,
+                    extra: json!({}),
+                },
+                MessagingSubjectPlan {
+                    name: "events".to_string(),
+                    purpose: "events".to_string(),
+                    durable: false,
+                    extra: json!({}),
+                },
+            ],
+            extra: json!({}),
+        }),
+        channels: vec![
+            ChannelPlan {
+                name: "slack".to_string(),
+                flow_id: "support".to_string(),
+                kind: "slack".to_string(),
+                config: json!({ "channel": "#help", "bot": true }),
+            },
+            ChannelPlan {
+                name: "email".to_string(),
+                flow_id: "main".to_string(),
+                kind: "email".to_string(),
+                config: json!({}),
+            },
+        ],
+        secrets: vec![secret("slack.token", false), secret("db/password", true)],
+        oauth: Vec::new(),
+        telemetry: Some(TelemetryPlan {
+            required: true,
+            suggested_endpoint: Some("http://collector.observability:4317".to_string()),
+            extra: json!({}),
+        }),
+        extra: json!({}),
+    }
+}
+
+/// Compare rendered files with `tests/golden/plan_render/<name>`; set
+/// `UPDATE_GOLDEN=1` to rewrite the golden files instead.
+fn assert_golden(name: &str, files: &BTreeMap<String, String>) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/plan_render")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create golden dir");
+        for (path, contents) in files {
+            fs::write(dir.join(path), contents).expect("write golden file");
+        }
+    }
+
+    let mut golden: Vec<String> = fs::read_dir(&dir)
+        .expect("read golden dir")
+        .map(|entry| {
+            entry
+                .expect("golden entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    golden.sort();
+    assert_eq!(files.keys().cloned().collect::<Vec<_>>(), golden);
+    for (path, contents) in files {
+        let expected = fs::read_to_string(dir.join(path)).expect("read golden file");
+        assert_eq!(
+            contents, &expected,
+            "{name}/{path} differs from the golden file (rerun with UPDATE_GOLDEN=1 to accept)"
+        );
+    }
+}
+
+#[test]
+fn k8s_manifests_match_golden() {
+    let files = render_plan(&demo_plan(), RenderTarget::K8s).expect("render k8s");
+    assert_golden("k8s", &files);
+}
+
+#[test]
+fn compose_file_matches_golden() {
+    let files = render_plan(&demo_plan(), RenderTarget::Compose).expect("render compose");
+    assert_golden("compose", &files);
+}
+
+#[test]
+fn rendering_ignores_plan_ordering() {
+    let mut shuffled = demo_plan();
+    shuffled.runners.reverse();
+    shuffled.channels.reverse();
+    shuffled.secrets.reverse();
+    for target in [RenderTarget::K8s, RenderTarget::Compose] {
+        assert_eq!(
+            render_plan(&shuffled, target).expect("render shuffled"),
+            render_plan(&demo_plan(), target).expect("render")
+        );
+    }
+}
+
+#[test]
+fn minimal_plan_renders_without_optional_resources() {
+    let mut plan = demo_plan();
+    plan.messaging = None;
+    plan.channels.clear();
+    plan.secrets.clear();
+    plan.telemetry = None;
+
+    let files = render_plan(&plan, RenderTarget::K8s).expect("render k8s");
+    assert_eq!(
+        files.keys().map(String::as_str).collect::<Vec<_>>(),
+        [
+            "deployment-demo-pack-runner.yaml",
+            "deployment-webhooks.yaml",
+            "kustomization.yaml",
+            "service-demo-pack-runner.yaml",
+            "service-webhooks.yaml",
+        ]
+    );
+    let deployment = &files["deployment-webhooks.yaml"];
+    assert!(!deployment.contains("OTEL_"), "{deployment}");
+    assert!(!deployment.contains("secretKeyRef"), "{deployment}");
+    assert!(!deployment.contains("volumes"), "{deployment}");
+
+    let compose = render_plan(&plan, RenderTarget::Compose).expect("render compose");
+    assert_eq!(compose.keys().collect::<Vec<_>>(), ["docker-compose.yaml"]);
+    assert!(!compose["docker-compose.yaml"].contains("secrets:"));
+}
+
+#[test]
+fn colliding_names_are_rejected() {
+    let mut plan = demo_plan();
+    plan.runners[1].name = "Demo.Pack_Runner".to_string();
+    let err = render_plan(&plan, RenderTarget::K8s).expect_err("runner slugs collide");
+    assert!(
+        err.to_string().contains("both render as demo-pack-runner"),
+        "{err}"
+    );
+
+    let mut plan = demo_plan();
+    plan.secrets.push(secret("db-password", true));
+    let err = render_plan(&plan, RenderTarget::Compose).expect_err("secret names collide");
+    assert!(
+        err.to_string()
+            .contains("both render as secret db-password"),
+        "{err}"
+    );
+
+    let mut plan = demo_plan();
+    plan.secrets.push(secret("db.password", true));
+    let err = render_plan(&plan, RenderTarget::Compose).expect_err("secret env vars collide");
+    assert!(
+        err.to_string()
+            .contains("both render as environment variable DB_PASSWORD"),
+        "{err}"
+    );
+}
@@ -152,6 +152,12 @@ fn is_known_root_command(command: &str) -> bool {
             | "add-extension"
             | "wizard"
             | "resolve"
+            | "cache"
+            | "diff"
+            | "dev"
+            | "bundle"
+            | "unbundle"
+            | "extract"
     )
 }
 
@@ -162,6 +168,8 @@ fn is_known_child_command(root: &str, child: &str) -> bool {
             | ("providers", "list")
             | ("providers", "info")
             | ("providers", "validate")
+            | ("cache", "stats")
+            | ("cache", "prune")
             | ("add-extension", "provider")
             | ("add-extension", "capability")
             | ("wizard", "new-app")
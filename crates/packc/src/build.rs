@@ -1,17 +1,21 @@
+use crate::build_cache::{self, BuildCache, BuildStage};
 use crate::cli::resolve::{self, ResolveArgs};
 use crate::config::{
     AssetConfig, ComponentConfig, ComponentOperationConfig, FlowConfig, PackConfig,
 };
+use crate::distribution::{self, DistributionArtifact};
 use crate::extensions::{validate_capabilities_extension, validate_components_extension};
 use crate::flow_resolve::load_flow_resolve_summary;
 use crate::runtime::{NetworkPolicy, RuntimeContext};
+use crate::sbom::{SbomFormat, SbomInventory};
 use anyhow::{Context, Result, anyhow};
 use greentic_distributor_client::{DistClient, DistOptions};
 use greentic_flow::add_step::normalize::normalize_node_map;
 use greentic_flow::compile_ygtc_file;
 use greentic_flow::loader::load_ygtc_from_path;
-use greentic_pack::builder::SbomEntry;
-use greentic_pack::pack_lock::read_pack_lock;
+use greentic_pack::builder::{PackCompression, SbomEntry};
+use greentic_pack::pack_lock::{PackLockV1, read_pack_lock};
+use greentic_pack::{PackLoad, SigningPolicy, open_pack_from_files};
 use greentic_types::cbor::canonical;
 use greentic_types::component_source::ComponentSourceRef;
 use greentic_types::flow_resolve_summary::FlowResolveSummaryV1;
@@ -43,16 +47,18 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use tracing::{info, warn};
 use walkdir::WalkDir;
+use zip::ZipWriter;
 use zip::write::SimpleFileOptions;
-use zip::{CompressionMethod, ZipWriter};
 
 const SBOM_FORMAT: &str = "greentic-sbom-v1";
-const EXT_BUILD_MODE_ID: &str = "greentic.pack-mode.v1";
+pub(crate) const EXT_BUILD_MODE_ID: &str = "greentic.pack-mode.v1";
 
 #[derive(Serialize)]
 struct SbomDocument {
     format: String,
     files: Vec<SbomEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compression: Option<&'static str>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -67,6 +73,8 @@ pub struct BuildOptions {
     pub component_out: Option<PathBuf>,
     pub manifest_out: PathBuf,
     pub sbom_out: Option<PathBuf>,
+    pub spdx_out: Option<PathBuf>,
+    pub embed_sbom: Vec<SbomFormat>,
     pub gtpack_out: Option<PathBuf>,
     pub lock_path: PathBuf,
     pub bundle: BundleMode,
@@ -80,6 +88,11 @@ pub struct BuildOptions {
     pub runtime: RuntimeContext,
     pub skip_update: bool,
     pub allow_pack_schema: bool,
+    pub compression: PackCompression,
+    /// Skip build stages whose inputs match the `.packc/` build cache.
+    pub incremental: bool,
+    /// Print why each build stage was skipped or re-run.
+    pub explain: bool,
 }
 
 impl BuildOptions {
@@ -99,6 +112,12 @@ impl BuildOptions {
         let sbom_out = args
             .sbom
             .map(|p| if p.is_absolute() { p } else { pack_dir.join(p) });
+        let spdx_out = args
+            .spdx
+            .map(|p| if p.is_absolute() { p } else { pack_dir.join(p) });
+        let mut embed_sbom = args.embed_sbom;
+        embed_sbom.sort();
+        embed_sbom.dedup();
         let default_gtpack_name = pack_dir
             .file_name()
             .and_then(|name| name.to_str())
@@ -121,6 +140,8 @@ impl BuildOptions {
             component_out,
             manifest_out,
             sbom_out,
+            spdx_out,
+            embed_sbom,
             gtpack_out,
             lock_path,
             bundle: args.bundle,
@@ -134,6 +155,44 @@ impl BuildOptions {
             runtime: runtime.clone(),
             skip_update: args.no_update,
             allow_pack_schema: args.allow_pack_schema,
+            compression: args.compression,
+            incremental: !args.no_cache,
+            explain: args.explain,
+        })
+    }
+
+    /// Dev build options for inspecting a source directory with [`build_in_memory`].
+    pub fn for_source_dir(
+        pack_dir: &Path,
+        runtime: &RuntimeContext,
+        allow_oci_tags: bool,
+    ) -> Result<Self> {
+        let pack_dir = pack_dir
+            .canonicalize()
+            .with_context(|| format!("failed to resolve pack dir {}", pack_dir.display()))?;
+        Ok(Self {
+            component_out: None,
+            manifest_out: pack_dir.join("dist").join("manifest.cbor"),
+            sbom_out: None,
+            spdx_out: None,
+            embed_sbom: Vec::new(),
+            gtpack_out: None,
+            lock_path: pack_dir.join("pack.lock.cbor"),
+            bundle: BundleMode::Cache,
+            dry_run: false,
+            secrets_req: None,
+            default_secret_scope: None,
+            allow_oci_tags,
+            require_component_manifests: false,
+            no_extra_dirs: false,
+            dev: true,
+            runtime: runtime.clone(),
+            skip_update: false,
+            allow_pack_schema: false,
+            compression: PackCompression::Stored,
+            incremental: false,
+            explain: false,
+            pack_dir,
         })
     }
 }
@@ -147,25 +206,222 @@ pub async fn run(opts: &BuildOptions) -> Result<()> {
         "building greentic pack"
     );
 
+    let mut cache = BuildCache::open(&opts.pack_dir, opts.incremental, opts.explain);
     if !opts.skip_update {
-        // Keep pack.yaml in sync before building.
-        crate::cli::update::update_pack(&opts.pack_dir, false)?;
+        let fingerprint = build_cache::update_fingerprint(opts)?;
+        if cache.check(BuildStage::Update, &fingerprint).rerun {
+            // Keep pack.yaml in sync before building.
+            crate::cli::update::update_pack(&opts.pack_dir, false)?;
+            cache.record(
+                BuildStage::Update,
+                build_cache::update_fingerprint(opts)?,
+                &[],
+            )?;
+        }
     }
 
-    // Resolve component references into pack.lock.cbor before building to ensure
-    // manifests/extensions can rely on the lockfile contents.
     if !(opts.dry_run && opts.lock_path.exists()) {
-        resolve::handle(
-            ResolveArgs {
-                input: opts.pack_dir.clone(),
-                lock: Some(opts.lock_path.clone()),
-            },
-            &opts.runtime,
+        let fingerprint = build_cache::resolve_fingerprint(opts)?;
+        if cache.check(BuildStage::Resolve, &fingerprint).rerun {
+            resolve::handle(resolve_args(opts), &opts.runtime, false).await?;
+            let outputs = build_cache::resolve_outputs(opts);
+            cache.record(
+                BuildStage::Resolve,
+                build_cache::resolve_fingerprint(opts)?,
+                &outputs.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+            )?;
+        }
+    }
+
+    if opts.dry_run {
+        assemble_resolved(opts, None).await?;
+        info!("dry-run complete; no files written");
+        return Ok(());
+    }
+
+    let fingerprint = build_cache::package_fingerprint(opts)?;
+    if !cache.check(BuildStage::Package, &fingerprint).rerun {
+        info!("build outputs are up to date");
+        if let Some(gtpack_out) = opts.gtpack_out.as_ref() {
+            eprintln!("up to date {}", gtpack_out.display());
+        }
+        return Ok(());
+    }
+    // Outputs are about to be rewritten; a failure below must not leave a
+    // record that matches them.
+    cache.invalidate(BuildStage::Package)?;
+
+    let AssembledPack {
+        mut build,
+        pack_lock,
+        bundled_paths,
+        secret_requirements,
+        manifest_bytes,
+    } = assemble_resolved(opts, None).await?;
+    greentic_pack::pack_lock::write_pack_lock(&opts.lock_path, &pack_lock)?;
+
+    if let Some(component_out) = opts.component_out.as_ref() {
+        write_stub_wasm(component_out)?;
+    }
+
+    write_bytes(&opts.manifest_out, &manifest_bytes)?;
+
+    let sbom_request = SbomRequest {
+        lock: &pack_lock,
+        bundled_paths: &bundled_paths,
+        embed: &opts.embed_sbom,
+        compression: opts.compression,
+    };
+    let mut archive_files = Vec::new();
+    if let Some(gtpack_out) = opts.gtpack_out.as_ref() {
+        add_dev_secret_requirements(opts, &mut build, &secret_requirements)?;
+        let packaged = package_gtpack(
+            gtpack_out,
+            &manifest_bytes,
+            &build,
+            opts.bundle,
+            opts.dev,
+            &sbom_request,
+        )?;
+        for warning in packaged.warnings {
+            warn!(warning);
+        }
+        archive_files = packaged.files;
+        info!(gtpack_out = %gtpack_out.display(), "gtpack archive ready");
+        eprintln!("wrote {}", gtpack_out.display());
+    } else if opts.sbom_out.is_some() || opts.spdx_out.is_some() {
+        // No archive is written, but the SBOMs still describe the one this
+        // build would produce.
+        add_dev_secret_requirements(opts, &mut build, &secret_requirements)?;
+        archive_files = assemble_archive(
+            &manifest_bytes,
+            &build,
+            opts.bundle,
+            opts.dev,
+            &sbom_request,
             false,
-        )
-        .await?;
+        )?
+        .files;
+    }
+
+    let inventory = SbomInventory {
+        manifest: &build.manifest,
+        lock: &pack_lock,
+        files: &archive_files,
+        bundled_paths: &bundled_paths,
+    };
+    for (format, out) in [
+        (SbomFormat::Cyclonedx, opts.sbom_out.as_ref()),
+        (SbomFormat::Spdx, opts.spdx_out.as_ref()),
+    ] {
+        if let Some(out) = out {
+            write_bytes(out, &format.render(&inventory)?)?;
+        }
+    }
+
+    cache.record(
+        BuildStage::Package,
+        build_cache::package_fingerprint(opts)?,
+        &build_cache::package_outputs(opts),
+    )?;
+    Ok(())
+}
+
+/// A pack assembled in memory by [`build_in_memory`].
+pub struct BuiltPack {
+    pub manifest: PackManifest,
+    pub lock: PackLockV1,
+    /// Archive entries by logical path, exactly as `run` would write them to the `.gtpack`.
+    pub files: BTreeMap<String, Vec<u8>>,
+    pub warnings: Vec<String>,
+}
+
+impl BuiltPack {
+    /// Verify the assembled entries the same way an opened `.gtpack` is verified.
+    pub fn into_pack_load(self) -> Result<PackLoad> {
+        open_pack_from_files(self.files.into_iter().collect(), SigningPolicy::DevOk)
+            .map_err(|err| anyhow!(err.message))
+            .context("in-process build produced an invalid pack")
     }
+}
 
+/// Build the pack described by `opts` without writing the archive, manifest,
+/// SBOMs or lockfile; the output paths in `opts` are ignored.
+///
+/// `pack.yaml` is still synced first unless `opts.skip_update` is set, and the
+/// components are resolved into an in-memory lock.
+pub async fn build_in_memory(opts: &BuildOptions) -> Result<BuiltPack> {
+    let AssembledPack {
+        mut build,
+        pack_lock,
+        bundled_paths,
+        secret_requirements,
+        manifest_bytes,
+    } = assemble_pack(opts).await?;
+    add_dev_secret_requirements(opts, &mut build, &secret_requirements)?;
+    let contents = assemble_archive(
+        &manifest_bytes,
+        &build,
+        opts.bundle,
+        opts.dev,
+        &SbomRequest {
+            lock: &pack_lock,
+            bundled_paths: &bundled_paths,
+            embed: &opts.embed_sbom,
+            compression: opts.compression,
+        },
+        true,
+    )?;
+    Ok(BuiltPack {
+        manifest: build.manifest,
+        lock: pack_lock,
+        files: contents
+            .entries
+            .into_iter()
+            .map(|(path, bytes)| Ok((path, bytes.into_bytes()?)))
+            .collect::<Result<_>>()?,
+        warnings: contents.warnings,
+    })
+}
+
+/// Everything a build produces before outputs are written.
+struct AssembledPack {
+    build: BuildProducts,
+    pack_lock: PackLockV1,
+    bundled_paths: BTreeMap<String, String>,
+    secret_requirements: Vec<SecretRequirement>,
+    manifest_bytes: Vec<u8>,
+}
+
+/// Sync `pack.yaml` (unless `opts.skip_update`), resolve the lock in memory and
+/// assemble the pack without touching `opts.lock_path`.
+async fn assemble_pack(opts: &BuildOptions) -> Result<AssembledPack> {
+    if !opts.skip_update {
+        // Keep pack.yaml in sync before building.
+        crate::cli::update::update_pack(&opts.pack_dir, false)?;
+    }
+
+    // Resolve component references before building so manifests/extensions can
+    // rely on the lock contents.
+    let (_, lock) = resolve::resolve_pack_lock(&resolve_args(opts), &opts.runtime, false)?;
+    assemble_resolved(opts, Some(lock)).await
+}
+
+fn resolve_args(opts: &BuildOptions) -> ResolveArgs {
+    ResolveArgs {
+        input: opts.pack_dir.clone(),
+        lock: Some(opts.lock_path.clone()),
+        packs_dir: Vec::new(),
+        jobs: None,
+    }
+}
+
+/// Assemble the pack once `pack.yaml` is synced and the lock resolved; with
+/// `resolved_lock` unset the lock is read from `opts.lock_path`.
+async fn assemble_resolved(
+    opts: &BuildOptions,
+    resolved_lock: Option<PackLockV1>,
+) -> Result<AssembledPack> {
     let config = crate::config::load_pack_config(&opts.pack_dir)?;
     info!(
         id = %config.pack_id,
@@ -192,18 +448,23 @@ pub async fn run(opts: &BuildOptions) -> Result<()> {
         opts.default_secret_scope.as_deref(),
     )?;
 
-    if !opts.lock_path.exists() {
-        anyhow::bail!(
-            "pack.lock.cbor is required (run `greentic-pack resolve`); missing: {}",
-            opts.lock_path.display()
-        );
-    }
-    let pack_lock = read_pack_lock(&opts.lock_path).with_context(|| {
-        format!(
-            "failed to read pack lock {} (try `greentic-pack resolve`)",
-            opts.lock_path.display()
-        )
-    })?;
+    let pack_lock = match resolved_lock {
+        Some(lock) => lock,
+        None => {
+            if !opts.lock_path.exists() {
+                anyhow::bail!(
+                    "pack.lock.cbor is required (run `greentic-pack resolve`); missing: {}",
+                    opts.lock_path.display()
+                );
+            }
+            read_pack_lock(&opts.lock_path).with_context(|| {
+                format!(
+                    "failed to read pack lock {} (try `greentic-pack resolve`)",
+                    opts.lock_path.display()
+                )
+            })?
+        }
+    };
 
     let mut build = assemble_manifest(
         &config,
@@ -213,6 +474,10 @@ pub async fn run(opts: &BuildOptions) -> Result<()> {
         opts.dev,
         opts.allow_pack_schema,
     )?;
+    for (manifest, binary) in build_mcp_components(&config, &pack_lock)? {
+        build.manifest.components.push(manifest);
+        build.components.push(binary);
+    }
     build.lock_components =
         collect_lock_component_artifacts(&pack_lock, &opts.runtime, opts.bundle, opts.dry_run)
             .await?;
@@ -244,47 +509,34 @@ pub async fn run(opts: &BuildOptions) -> Result<()> {
         &bundled_paths,
         materialized.manifest_paths.as_ref(),
     )?;
-    if !opts.dry_run {
-        greentic_pack::pack_lock::write_pack_lock(&opts.lock_path, &pack_lock)?;
-    }
 
     let manifest_bytes = encode_pack_manifest(&build.manifest)?;
     info!(len = manifest_bytes.len(), "encoded manifest.cbor");
 
-    if opts.dry_run {
-        info!("dry-run complete; no files written");
-        return Ok(());
-    }
-
-    if let Some(component_out) = opts.component_out.as_ref() {
-        write_stub_wasm(component_out)?;
-    }
-
-    write_bytes(&opts.manifest_out, &manifest_bytes)?;
-
-    if let Some(sbom_out) = opts.sbom_out.as_ref() {
-        write_bytes(sbom_out, br#"{"files":[]} "#)?;
-    }
+    Ok(AssembledPack {
+        build,
+        pack_lock,
+        bundled_paths,
+        secret_requirements,
+        manifest_bytes,
+    })
+}
 
-    if let Some(gtpack_out) = opts.gtpack_out.as_ref() {
-        let mut build = build;
-        if opts.dev && !secret_requirements.is_empty() {
-            let logical = "secret-requirements.json".to_string();
-            let req_path =
-                write_secret_requirements_file(&opts.pack_dir, &secret_requirements, &logical)?;
-            build.assets.push(AssetFile {
-                logical_path: logical,
-                source: req_path,
-            });
-        }
-        let warnings = package_gtpack(gtpack_out, &manifest_bytes, &build, opts.bundle, opts.dev)?;
-        for warning in warnings {
-            warn!(warning);
-        }
-        info!(gtpack_out = %gtpack_out.display(), "gtpack archive ready");
-        eprintln!("wrote {}", gtpack_out.display());
+/// Dev builds ship the aggregated secret requirements as a JSON asset.
+fn add_dev_secret_requirements(
+    opts: &BuildOptions,
+    build: &mut BuildProducts,
+    secret_requirements: &[SecretRequirement],
+) -> Result<()> {
+    if opts.dev && !secret_requirements.is_empty() {
+        let logical = "secret-requirements.json".to_string();
+        let req_path =
+            write_secret_requirements_file(&opts.pack_dir, secret_requirements, &logical)?;
+        build.assets.push(AssetFile {
+            logical_path: logical,
+            source: req_path,
+        });
     }
-
     Ok(())
 }
 
@@ -296,6 +548,8 @@ struct BuildProducts {
     flow_files: Vec<FlowFile>,
     assets: Vec<AssetFile>,
     extra_files: Vec<ExtraFile>,
+    /// `distribution-bundle` artifacts, stored at their `artifact_path`.
+    distribution_artifacts: Vec<DistributionArtifact>,
 }
 
 #[derive(Clone)]
@@ -358,7 +612,17 @@ fn assemble_manifest(
     };
     let component_manifests: Vec<_> = components.iter().map(|c| c.0.clone()).collect();
     let bootstrap = build_bootstrap(config, &flows, &component_manifests)?;
-    let extensions = normalize_extensions(&config.extensions);
+    let mut extensions = normalize_extensions(&config.extensions);
+    let distribution_artifacts = match config.distribution.as_ref() {
+        Some(section) => {
+            extensions.get_or_insert_with(BTreeMap::new).insert(
+                distribution::EXT_DISTRIBUTION_BUNDLE_V1.to_string(),
+                distribution::manifest_extension(section, &config.pack_id)?,
+            );
+            distribution::collect_artifacts(section, pack_root)?
+        }
+        None => Vec::new(),
+    };
 
     let mut manifest = PackManifest {
         schema_version: "pack-v1".to_string(),
@@ -388,6 +652,7 @@ fn assemble_manifest(
         flow_files,
         assets,
         extra_files,
+        distribution_artifacts,
     })
 }
 
@@ -496,6 +761,68 @@ fn resolve_component_artifacts(
     Ok((manifest, binary))
 }
 
+/// Bundle the MCP components composed by `resolve`, checking each artifact
+/// against the digest pinned in the lock.
+fn build_mcp_components(
+    config: &PackConfig,
+    pack_lock: &PackLockV1,
+) -> Result<Vec<(ComponentManifest, ComponentBinary)>> {
+    if config.mcp_components.is_empty() {
+        return Ok(Vec::new());
+    }
+    let version =
+        Version::parse(&config.version).context("invalid pack version (expected semver)")?;
+
+    let mut result = Vec::new();
+    for mcp in &config.mcp_components {
+        let locked = pack_lock.components.get(&mcp.id).ok_or_else(|| {
+            anyhow!(
+                "MCP component {} is missing from pack.lock.cbor (run `greentic-pack resolve`)",
+                mcp.id
+            )
+        })?;
+        let source = locked
+            .r#ref
+            .as_deref()
+            .and_then(|reference| reference.strip_prefix("file://"))
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                anyhow!(
+                    "MCP component {} must be locked to its composed file:// artifact",
+                    mcp.id
+                )
+            })?;
+        let bytes = fs::read(&source).with_context(|| {
+            format!(
+                "failed to read composed MCP component {} (run `greentic-pack resolve`)",
+                source.display()
+            )
+        })?;
+        let digest = format!("sha256:{:x}", Sha256::digest(&bytes));
+        if digest != locked.resolved_digest {
+            anyhow::bail!(
+                "composed MCP component {} does not match pack.lock.cbor ({} vs {}); run `greentic-pack resolve`",
+                mcp.id,
+                digest,
+                locked.resolved_digest
+            );
+        }
+
+        info!(id = %mcp.id, wasm = %source.display(), "adding composed MCP component");
+        let manifest = crate::mcp::component_manifest(&mcp.id, &version)?;
+        let manifest_file = component_manifest_file_from_manifest(&manifest)?;
+        let binary = ComponentBinary {
+            id: mcp.id.clone(),
+            source,
+            manifest_bytes: manifest_file.manifest_bytes,
+            manifest_path: manifest_file.manifest_path,
+            manifest_hash_sha256: manifest_file.manifest_hash_sha256,
+        };
+        result.push((manifest, binary));
+    }
+    Ok(result)
+}
+
 fn is_legacy_pack_schema_component(component_id: &str) -> bool {
     matches!(
         component_id,
@@ -894,7 +1221,10 @@ fn collect_assets(configs: &[AssetConfig], pack_root: &Path) -> Result<Vec<Asset
 }
 
 fn is_reserved_extra_file(logical_path: &str) -> bool {
-    matches!(logical_path, "sbom.cbor" | "sbom.json")
+    matches!(
+        logical_path,
+        "sbom.cbor" | "sbom.json" | crate::sbom::CYCLONEDX_PATH | crate::sbom::SPDX_PATH
+    )
 }
 
 fn collect_extra_dir_files(pack_root: &Path) -> Result<Vec<ExtraFile>> {
@@ -1016,6 +1346,8 @@ fn is_reserved_source_file(path: &str) -> bool {
             | "manifest.cbor"
             | "sbom.json"
             | "sbom.cbor"
+            | crate::sbom::CYCLONEDX_PATH
+            | crate::sbom::SPDX_PATH
             | "provenance.json"
             | "secret-requirements.json"
             | "secrets_requirements.json"
@@ -1215,17 +1547,181 @@ fn map_kind(raw: &str) -> Result<PackKind> {
         "provider" => Ok(PackKind::Provider),
         "infrastructure" => Ok(PackKind::Infrastructure),
         "library" => Ok(PackKind::Library),
+        // greentic-types has no bundle kind; the manifest extension marks the bundle.
+        distribution::DISTRIBUTION_BUNDLE_KIND => Ok(PackKind::Infrastructure),
         other => Err(anyhow!("unknown pack kind {}", other)),
     }
 }
 
+/// Inputs for the standard SBOM documents produced alongside an archive.
+struct SbomRequest<'a> {
+    lock: &'a PackLockV1,
+    bundled_paths: &'a BTreeMap<String, String>,
+    /// Formats embedded into the archive (listed in `sbom.cbor`).
+    embed: &'a [SbomFormat],
+    /// Entry compression, recorded in `sbom.cbor`.
+    compression: PackCompression,
+}
+
+struct PackagedArchive {
+    warnings: Vec<String>,
+    /// Archive inventory excluding `sbom.cbor` and embedded SBOM documents.
+    files: Vec<SbomEntry>,
+}
+
+/// Bytes of an archive entry at the time it is written.
+enum EntryBytes {
+    Inline(Vec<u8>),
+    /// Re-read from disk when the archive is written, so file builds hold one
+    /// component in memory at a time.
+    File(PathBuf),
+}
+
+impl EntryBytes {
+    fn into_bytes(self) -> Result<Vec<u8>> {
+        match self {
+            EntryBytes::Inline(bytes) => Ok(bytes),
+            EntryBytes::File(path) => {
+                fs::read(&path).with_context(|| format!("failed to read {}", path.display()))
+            }
+        }
+    }
+}
+
+/// Archive entries in assembly order, plus the `sbom.cbor` inventory of them.
+struct ArchiveEntries {
+    /// Keep file-backed entries in memory instead of re-reading them on write.
+    buffer: bool,
+    entries: Vec<(String, EntryBytes)>,
+    sbom_entries: Vec<SbomEntry>,
+}
+
+impl ArchiveEntries {
+    fn new(buffer: bool) -> Self {
+        Self {
+            buffer,
+            entries: Vec::new(),
+            sbom_entries: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, path: &str, bytes: &[u8], media_type: &str) {
+        record_sbom_entry(&mut self.sbom_entries, path, bytes, media_type);
+        self.entries
+            .push((path.to_string(), EntryBytes::Inline(bytes.to_vec())));
+    }
+
+    /// Record an entry read from `source`; `bytes` are its current contents.
+    fn push_file(&mut self, path: &str, source: &Path, bytes: &[u8], media_type: &str) {
+        if self.buffer {
+            return self.push(path, bytes, media_type);
+        }
+        record_sbom_entry(&mut self.sbom_entries, path, bytes, media_type);
+        self.entries
+            .push((path.to_string(), EntryBytes::File(source.to_path_buf())));
+    }
+}
+
+struct ArchiveContents {
+    /// Entries in assembly order (`sbom.cbor` last); the archive writes them sorted by path.
+    entries: Vec<(String, EntryBytes)>,
+    warnings: Vec<String>,
+    /// Archive inventory excluding `sbom.cbor` and embedded SBOM documents.
+    files: Vec<SbomEntry>,
+}
+
 fn package_gtpack(
     out_path: &Path,
     manifest_bytes: &[u8],
     build: &BuildProducts,
     bundle: BundleMode,
     dev_mode: bool,
-) -> Result<Vec<String>> {
+    sbom: &SbomRequest<'_>,
+) -> Result<PackagedArchive> {
+    let contents = assemble_archive(manifest_bytes, build, bundle, dev_mode, sbom, false)?;
+    let mut writer = create_archive(out_path)?;
+    let options = sbom.compression.zip_options();
+    let mut entries = contents.entries;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, entry) in entries {
+        let source = match &entry {
+            EntryBytes::File(source) => Some(source.clone()),
+            EntryBytes::Inline(_) => None,
+        };
+        let bytes = entry.into_bytes()?;
+        if let Some(source) = source {
+            // The inventory hashed the file during assembly; refuse to ship an
+            // archive whose `sbom.cbor` no longer matches it.
+            let recorded = contents.files.iter().find(|file| file.path == path);
+            let hash = blake3::hash(&bytes).to_hex().to_string();
+            if recorded.is_some_and(|file| file.hash_blake3 != hash) {
+                anyhow::bail!(
+                    "{} changed while the pack was being built",
+                    source.display()
+                );
+            }
+        }
+        write_zip_entry(&mut writer, &path, &bytes, options)?;
+    }
+    writer
+        .finish()
+        .context("failed to finalise gtpack archive")?;
+    Ok(PackagedArchive {
+        warnings: contents.warnings,
+        files: contents.files,
+    })
+}
+
+/// Write `entries` as a `.gtpack` with its `sbom.cbor` inventory; each entry is
+/// `(path, bytes, media type)`.
+pub(crate) fn package_entries(
+    out_path: &Path,
+    entries: &[(String, Vec<u8>, &str)],
+    compression: PackCompression,
+) -> Result<()> {
+    let mut archive = ArchiveEntries::new(true);
+    for (path, bytes, media_type) in entries {
+        archive.push(path, bytes, media_type);
+    }
+    let mut sbom_entries = archive.sbom_entries;
+    sbom_entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let sbom_doc = SbomDocument {
+        format: SBOM_FORMAT.to_string(),
+        files: sbom_entries,
+        compression: compression.sbom_value(),
+    };
+    let sbom_bytes = canonical::to_canonical_cbor_allow_floats(&sbom_doc)
+        .context("failed to encode canonical sbom.cbor")?;
+    let mut entries = archive
+        .entries
+        .into_iter()
+        .map(|(path, bytes)| Ok((path, bytes.into_bytes()?)))
+        .collect::<Result<Vec<_>>>()?;
+    entries.push(("sbom.cbor".to_string(), sbom_bytes));
+    write_archive(out_path, &entries, compression)
+}
+
+/// Write already-inventoried entries as a zip, sorted by path.
+pub(crate) fn write_archive(
+    out_path: &Path,
+    entries: &[(String, Vec<u8>)],
+    compression: PackCompression,
+) -> Result<()> {
+    let mut writer = create_archive(out_path)?;
+    let options = compression.zip_options();
+    let mut entries: Vec<_> = entries.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, bytes) in entries {
+        write_zip_entry(&mut writer, path, bytes, options)?;
+    }
+
+    writer
+        .finish()
+        .context("failed to finalise gtpack archive")?;
+    Ok(())
+}
+
+fn create_archive(out_path: &Path) -> Result<ZipWriter<fs::File>> {
     if let Some(parent) = out_path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create {}", parent.display()))?;
@@ -1233,41 +1729,36 @@ fn package_gtpack(
 
     let file = fs::File::create(out_path)
         .with_context(|| format!("failed to create {}", out_path.display()))?;
-    let mut writer = ZipWriter::new(file);
-    let options = SimpleFileOptions::default()
-        .compression_method(CompressionMethod::Stored)
-        .unix_permissions(0o644);
+    Ok(ZipWriter::new(file))
+}
 
-    let mut sbom_entries = Vec::new();
+/// Assemble the archive entries and their inventory; with `buffer` unset,
+/// entries read from disk are only hashed and re-read when the archive is written.
+fn assemble_archive(
+    manifest_bytes: &[u8],
+    build: &BuildProducts,
+    bundle: BundleMode,
+    dev_mode: bool,
+    sbom: &SbomRequest<'_>,
+    buffer: bool,
+) -> Result<ArchiveContents> {
+    let mut archive = ArchiveEntries::new(buffer);
     let mut written_paths = BTreeSet::new();
     let mut warnings = Vec::new();
     let mut asset_paths = BTreeSet::new();
-    record_sbom_entry(
-        &mut sbom_entries,
-        "manifest.cbor",
-        manifest_bytes,
-        "application/cbor",
-    );
     written_paths.insert("manifest.cbor".to_string());
-    write_zip_entry(&mut writer, "manifest.cbor", manifest_bytes, options)?;
+    archive.push("manifest.cbor", manifest_bytes, "application/cbor");
 
     if dev_mode {
         let mut flow_files = build.flow_files.clone();
         flow_files.sort_by(|a, b| a.logical_path.cmp(&b.logical_path));
         for flow_file in flow_files {
             if written_paths.insert(flow_file.logical_path.clone()) {
-                record_sbom_entry(
-                    &mut sbom_entries,
+                archive.push(
                     &flow_file.logical_path,
                     &flow_file.bytes,
                     flow_file.media_type,
                 );
-                write_zip_entry(
-                    &mut writer,
-                    &flow_file.logical_path,
-                    &flow_file.bytes,
-                    options,
-                )?;
             }
         }
     }
@@ -1295,13 +1786,7 @@ fn package_gtpack(
         let bytes = fs::read(&comp.source).with_context(|| {
             format!("failed to read cached component {}", comp.source.display())
         })?;
-        record_sbom_entry(
-            &mut sbom_entries,
-            &comp.logical_path,
-            &bytes,
-            "application/wasm",
-        );
-        write_zip_entry(&mut writer, &comp.logical_path, &bytes, options)?;
+        archive.push_file(&comp.logical_path, &comp.source, &bytes, "application/wasm");
         let describe_source = PathBuf::from(format!("{}.describe.cbor", comp.source.display()));
         if describe_source.exists() {
             let describe_bytes = fs::read(&describe_source).with_context(|| {
@@ -1312,21 +1797,19 @@ fn package_gtpack(
             })?;
             let describe_logical = format!("{}.describe.cbor", comp.logical_path);
             if written_paths.insert(describe_logical.clone()) {
-                record_sbom_entry(
-                    &mut sbom_entries,
+                archive.push_file(
                     &describe_logical,
+                    &describe_source,
                     &describe_bytes,
                     "application/cbor",
                 );
-                write_zip_entry(&mut writer, &describe_logical, &describe_bytes, options)?;
             }
         }
 
         if manifest_component_ids.contains(&comp.component_id) {
             let alias_path = format!("components/{}.wasm", comp.component_id);
             if written_paths.insert(alias_path.clone()) {
-                record_sbom_entry(&mut sbom_entries, &alias_path, &bytes, "application/wasm");
-                write_zip_entry(&mut writer, &alias_path, &bytes, options)?;
+                archive.push_file(&alias_path, &comp.source, &bytes, "application/wasm");
             }
             let describe_source = PathBuf::from(format!("{}.describe.cbor", comp.source.display()));
             if describe_source.exists() {
@@ -1338,13 +1821,12 @@ fn package_gtpack(
                 })?;
                 let alias_describe = format!("{alias_path}.describe.cbor");
                 if written_paths.insert(alias_describe.clone()) {
-                    record_sbom_entry(
-                        &mut sbom_entries,
+                    archive.push_file(
                         &alias_describe,
+                        &describe_source,
                         &describe_bytes,
                         "application/cbor",
                     );
-                    write_zip_entry(&mut writer, &alias_describe, &describe_bytes, options)?;
                 }
             }
         }
@@ -1354,18 +1836,11 @@ fn package_gtpack(
     lock_manifests.sort_by(|a, b| a.manifest_path.cmp(&b.manifest_path));
     for manifest in lock_manifests {
         if written_paths.insert(manifest.manifest_path.clone()) {
-            record_sbom_entry(
-                &mut sbom_entries,
+            archive.push(
                 &manifest.manifest_path,
                 &manifest.manifest_bytes,
                 "application/cbor",
             );
-            write_zip_entry(
-                &mut writer,
-                &manifest.manifest_path,
-                &manifest.manifest_bytes,
-                options,
-            )?;
         }
     }
 
@@ -1377,13 +1852,7 @@ fn package_gtpack(
             let wasm_bytes = fs::read(&comp.source)
                 .with_context(|| format!("failed to read component {}", comp.source.display()))?;
             if written_paths.insert(logical_wasm.clone()) {
-                record_sbom_entry(
-                    &mut sbom_entries,
-                    &logical_wasm,
-                    &wasm_bytes,
-                    "application/wasm",
-                );
-                write_zip_entry(&mut writer, &logical_wasm, &wasm_bytes, options)?;
+                archive.push_file(&logical_wasm, &comp.source, &wasm_bytes, "application/wasm");
             }
             let describe_source = PathBuf::from(format!("{}.describe.cbor", comp.source.display()));
             if describe_source.exists() {
@@ -1395,33 +1864,46 @@ fn package_gtpack(
                 })?;
                 let describe_logical = format!("{logical_wasm}.describe.cbor");
                 if written_paths.insert(describe_logical.clone()) {
-                    record_sbom_entry(
-                        &mut sbom_entries,
+                    archive.push_file(
                         &describe_logical,
+                        &describe_source,
                         &describe_bytes,
                         "application/cbor",
                     );
-                    write_zip_entry(&mut writer, &describe_logical, &describe_bytes, options)?;
                 }
             }
 
             if written_paths.insert(comp.manifest_path.clone()) {
-                record_sbom_entry(
-                    &mut sbom_entries,
+                archive.push(
                     &comp.manifest_path,
                     &comp.manifest_bytes,
                     "application/cbor",
                 );
-                write_zip_entry(
-                    &mut writer,
-                    &comp.manifest_path,
-                    &comp.manifest_bytes,
-                    options,
-                )?;
             }
         }
     }
 
+    for artifact in &build.distribution_artifacts {
+        if !written_paths.insert(artifact.logical_path.clone()) {
+            anyhow::bail!(
+                "distribution artifact {} collides with a file the build already wrote",
+                artifact.logical_path
+            );
+        }
+        let bytes = fs::read(&artifact.source).with_context(|| {
+            format!(
+                "failed to read distribution artifact {}",
+                artifact.source.display()
+            )
+        })?;
+        archive.push_file(
+            &artifact.logical_path,
+            &artifact.source,
+            &bytes,
+            "application/octet-stream",
+        );
+    }
+
     let mut extra_entries: Vec<_> = Vec::new();
     for asset in &build.assets {
         let logical = format!("assets/{}", asset.logical_path);
@@ -1442,28 +1924,43 @@ fn package_gtpack(
         }
         let bytes = fs::read(&source)
             .with_context(|| format!("failed to read extra file {}", source.display()))?;
-        record_sbom_entry(
-            &mut sbom_entries,
-            &logical,
-            &bytes,
-            "application/octet-stream",
-        );
-        write_zip_entry(&mut writer, &logical, &bytes, options)?;
+        archive.push_file(&logical, &source, &bytes, "application/octet-stream");
     }
 
+    archive.sbom_entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let inventory_files = archive.sbom_entries.clone();
+    let inventory = SbomInventory {
+        manifest: &build.manifest,
+        lock: sbom.lock,
+        files: &inventory_files,
+        bundled_paths: sbom.bundled_paths,
+    };
+    for format in sbom.embed {
+        let logical = format.archive_path();
+        if !written_paths.insert(logical.to_string()) {
+            continue;
+        }
+        let bytes = format.render(&inventory)?;
+        archive.push(logical, &bytes, "application/json");
+    }
+
+    let mut sbom_entries = archive.sbom_entries;
     sbom_entries.sort_by(|a, b| a.path.cmp(&b.path));
     let sbom_doc = SbomDocument {
         format: SBOM_FORMAT.to_string(),
         files: sbom_entries,
+        compression: sbom.compression.sbom_value(),
     };
     let sbom_bytes = canonical::to_canonical_cbor_allow_floats(&sbom_doc)
         .context("failed to encode canonical sbom.cbor")?;
-    write_zip_entry(&mut writer, "sbom.cbor", &sbom_bytes, options)?;
+    let mut entries = archive.entries;
+    entries.push(("sbom.cbor".to_string(), EntryBytes::Inline(sbom_bytes)));
 
-    writer
-        .finish()
-        .context("failed to finalise gtpack archive")?;
-    Ok(warnings)
+    Ok(ArchiveContents {
+        entries,
+        warnings,
+        files: inventory_files,
+    })
 }
 
 async fn collect_lock_component_artifacts(
@@ -1768,7 +2265,7 @@ fn collect_flow_component_ids(flows: &[PackFlowEntry]) -> BTreeSet<String> {
     ids
 }
 
-fn is_builtin_component_id(id: &str) -> bool {
+pub(crate) fn is_builtin_component_id(id: &str) -> bool {
     matches!(id, "session.wait" | "flow.call" | "provider.invoke") || id.starts_with("emit.")
 }
 
@@ -2423,12 +2920,12 @@ mod tests {
             component_manifest_files: Vec::new(),
             flow_files: Vec::new(),
             assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
             extra_files: Vec::new(),
         };
 
         let out = temp.path().join("demo.gtpack");
-        let warnings = package_gtpack(&out, &manifest_bytes, &build, BundleMode::Cache, false)
-            .expect("package gtpack");
+        let warnings = package_for_test(&out, &manifest_bytes, &build).expect("package gtpack");
         assert!(warnings.is_empty(), "expected no packaging warnings");
 
         let mut archive = ZipArchive::new(fs::File::open(&out).expect("open gtpack"))
@@ -2481,6 +2978,7 @@ mod tests {
             component_manifest_files: Vec::new(),
             flow_files: Vec::new(),
             assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
             extra_files: vec![
                 ExtraFile {
                     logical_path: "pack.yaml".to_string(),
@@ -2494,8 +2992,7 @@ mod tests {
         };
 
         let out = temp.path().join("prod.gtpack");
-        let warnings = package_gtpack(&out, &manifest_bytes, &build, BundleMode::Cache, false)
-            .expect("package gtpack");
+        let warnings = package_for_test(&out, &manifest_bytes, &build).expect("package gtpack");
         assert!(
             warnings.is_empty(),
             "no warnings expected for forbidden drop"
@@ -2541,6 +3038,7 @@ mod tests {
             component_manifest_files: Vec::new(),
             flow_files: Vec::new(),
             assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
             extra_files: vec![
                 ExtraFile {
                     logical_path: "assets/README.md".to_string(),
@@ -2554,8 +3052,7 @@ mod tests {
         };
 
         let out = temp.path().join("conflict.gtpack");
-        let warnings = package_gtpack(&out, &manifest_bytes, &build, BundleMode::Cache, false)
-            .expect("package gtpack");
+        let warnings = package_for_test(&out, &manifest_bytes, &build).expect("package gtpack");
         assert!(
             warnings
                 .iter()
@@ -2597,6 +3094,7 @@ mod tests {
             component_manifest_files: Vec::new(),
             flow_files: Vec::new(),
             assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
             extra_files: vec![ExtraFile {
                 logical_path: "notes.txt".to_string(),
                 source: root_asset,
@@ -2604,8 +3102,7 @@ mod tests {
         };
 
         let out = temp.path().join("root-assets.gtpack");
-        let warnings = package_gtpack(&out, &manifest_bytes, &build, BundleMode::Cache, false)
-            .expect("package gtpack");
+        let warnings = package_for_test(&out, &manifest_bytes, &build).expect("package gtpack");
         assert!(
             warnings.iter().all(|w| !w.contains("notes.txt")),
             "root asset mapping should not warn without conflict"
@@ -2617,6 +3114,286 @@ mod tests {
         assert!(archive.by_name("notes.txt").is_err());
     }
 
+    #[test]
+    fn compressed_gtpack_is_reproducible_and_recorded() {
+        let component = manifest_with_dev_flow();
+        let pack_manifest = pack_manifest_with_component(component.clone());
+        let manifest_bytes = encode_pack_manifest(&pack_manifest).expect("encode manifest");
+
+        let temp = tempdir().expect("temp dir");
+        let wasm_path = temp.path().join("component.wasm");
+        write_stub_wasm(&wasm_path).expect("write stub wasm");
+        let root_asset = temp.path().join("notes.txt");
+        fs::write(&root_asset, "notes ".repeat(512)).expect("write root asset");
+
+        let build = BuildProducts {
+            manifest: pack_manifest,
+            components: vec![ComponentBinary {
+                id: component.id.to_string(),
+                source: wasm_path,
+                manifest_bytes: serde_cbor::to_vec(&component).expect("component cbor"),
+                manifest_path: format!("components/{}.manifest.cbor", component.id),
+                manifest_hash_sha256: {
+                    let mut sha = Sha256::new();
+                    sha.update(serde_cbor::to_vec(&component).expect("component cbor"));
+                    format!("sha256:{:x}", sha.finalize())
+                },
+            }],
+            lock_components: Vec::new(),
+            component_manifest_files: Vec::new(),
+            flow_files: Vec::new(),
+            assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
+            extra_files: vec![ExtraFile {
+                logical_path: "notes.txt".to_string(),
+                source: root_asset,
+            }],
+        };
+        let lock = PackLockV1::new(BTreeMap::new());
+        let bundled_paths = BTreeMap::new();
+        let package = |name: &str, compression| {
+            let out = temp.path().join(name);
+            let packaged = package_gtpack(
+                &out,
+                &manifest_bytes,
+                &build,
+                BundleMode::Cache,
+                false,
+                &SbomRequest {
+                    lock: &lock,
+                    bundled_paths: &bundled_paths,
+                    embed: &[],
+                    compression,
+                },
+            )
+            .expect("package gtpack");
+            (out, packaged.files)
+        };
+
+        let (stored, stored_files) = package("stored.gtpack", PackCompression::Stored);
+        for compression in [PackCompression::Deflate, PackCompression::Zstd] {
+            let (first, files) = package("first.gtpack", compression);
+            let first_bytes = fs::read(&first).expect("read first");
+            let (second, _) = package("second.gtpack", compression);
+            assert_eq!(
+                first_bytes,
+                fs::read(&second).expect("read second"),
+                "{} archives must be byte-for-byte reproducible",
+                compression.as_str()
+            );
+            assert!(first_bytes.len() < fs::metadata(&stored).expect("stored").len() as usize);
+            assert_eq!(files, stored_files, "inventory hashes uncompressed content");
+
+            let mut archive =
+                ZipArchive::new(fs::File::open(&first).expect("open gtpack")).expect("read gtpack");
+            let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+            let mut sorted = names.clone();
+            sorted.sort();
+            assert_eq!(names, sorted, "entries are written in path order");
+
+            let mut sbom = Vec::new();
+            archive
+                .by_name("sbom.cbor")
+                .expect("sbom entry")
+                .read_to_end(&mut sbom)
+                .expect("read sbom");
+            let sbom: serde_json::Value = serde_cbor::from_slice(&sbom).expect("sbom cbor");
+            assert_eq!(sbom["compression"], compression.as_str());
+
+            let load =
+                greentic_pack::reader::open_pack(&first, greentic_pack::SigningPolicy::DevOk)
+                    .unwrap_or_else(|err| panic!("{}: {}", compression.as_str(), err.message));
+            assert_eq!(
+                load.files["assets/notes.txt"],
+                "notes ".repeat(512).into_bytes()
+            );
+        }
+    }
+
+    fn package_for_test(
+        out: &Path,
+        manifest_bytes: &[u8],
+        build: &BuildProducts,
+    ) -> Result<Vec<String>> {
+        let lock = PackLockV1::new(BTreeMap::new());
+        let bundled_paths = BTreeMap::new();
+        package_gtpack(
+            out,
+            manifest_bytes,
+            build,
+            BundleMode::Cache,
+            false,
+            &SbomRequest {
+                lock: &lock,
+                bundled_paths: &bundled_paths,
+                embed: &[],
+                compression: PackCompression::Stored,
+            },
+        )
+        .map(|packaged| packaged.warnings)
+    }
+
+    #[test]
+    fn gtpack_embeds_standard_sboms_listed_in_inventory() {
+        let component = manifest_with_dev_flow();
+        let pack_manifest = pack_manifest_with_component(component.clone());
+        let manifest_bytes = encode_pack_manifest(&pack_manifest).expect("encode manifest");
+
+        let temp = tempdir().expect("temp dir");
+        let wasm_path = temp.path().join("component.wasm");
+        write_stub_wasm(&wasm_path).expect("write stub wasm");
+
+        let build = BuildProducts {
+            manifest: pack_manifest,
+            components: vec![ComponentBinary {
+                id: component.id.to_string(),
+                source: wasm_path,
+                manifest_bytes: serde_cbor::to_vec(&component).expect("component cbor"),
+                manifest_path: format!("components/{}.manifest.cbor", component.id),
+                manifest_hash_sha256: {
+                    let mut sha = Sha256::new();
+                    sha.update(serde_cbor::to_vec(&component).expect("component cbor"));
+                    format!("sha256:{:x}", sha.finalize())
+                },
+            }],
+            lock_components: Vec::new(),
+            component_manifest_files: Vec::new(),
+            flow_files: Vec::new(),
+            assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
+            extra_files: Vec::new(),
+        };
+
+        let mut components = BTreeMap::new();
+        components.insert(
+            component.id.to_string(),
+            sample_lock_component(
+                component.id.as_str(),
+                Some("oci://ghcr.io/demo/component@sha256:abc"),
+                'a',
+            ),
+        );
+        let lock = PackLockV1::new(components);
+        let bundled_paths = BTreeMap::new();
+        let out = temp.path().join("sbom.gtpack");
+        let packaged = package_gtpack(
+            &out,
+            &manifest_bytes,
+            &build,
+            BundleMode::Cache,
+            false,
+            &SbomRequest {
+                lock: &lock,
+                bundled_paths: &bundled_paths,
+                embed: &[SbomFormat::Cyclonedx, SbomFormat::Spdx],
+                compression: PackCompression::Stored,
+            },
+        )
+        .expect("package gtpack");
+        assert!(
+            packaged
+                .files
+                .iter()
+                .all(|entry| !entry.path.starts_with("sbom."))
+        );
+
+        let mut archive = ZipArchive::new(fs::File::open(&out).expect("open gtpack"))
+            .expect("read gtpack archive");
+        let mut cdx = Vec::new();
+        archive
+            .by_name(crate::sbom::CYCLONEDX_PATH)
+            .expect("cyclonedx entry")
+            .read_to_end(&mut cdx)
+            .expect("read cyclonedx");
+        let bom: serde_json::Value = serde_json::from_slice(&cdx).expect("cyclonedx json");
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["components"][0]["name"], component.id.as_str());
+        assert!(
+            bom["components"][0]["hashes"]
+                .as_array()
+                .expect("hashes")
+                .iter()
+                .any(|hash| hash["alg"] == "BLAKE3"),
+            "bundled component hash should be recorded"
+        );
+        assert!(archive.by_name(crate::sbom::SPDX_PATH).is_ok());
+
+        let load = greentic_pack::reader::open_pack(&out, greentic_pack::SigningPolicy::DevOk)
+            .expect("embedded sboms must be covered by sbom.cbor");
+        let listed: BTreeSet<_> = load.sbom.iter().map(|entry| entry.path.as_str()).collect();
+        assert!(listed.contains(crate::sbom::CYCLONEDX_PATH));
+        assert!(listed.contains(crate::sbom::SPDX_PATH));
+    }
+
+    #[test]
+    fn file_builds_read_component_entries_from_disk() {
+        let component = manifest_with_dev_flow();
+        let pack_manifest = pack_manifest_with_component(component.clone());
+        let manifest_bytes = encode_pack_manifest(&pack_manifest).expect("encode manifest");
+
+        let temp = tempdir().expect("temp dir");
+        let wasm_path = temp.path().join("component.wasm");
+        write_stub_wasm(&wasm_path).expect("write stub wasm");
+        let build = BuildProducts {
+            manifest: pack_manifest,
+            components: vec![ComponentBinary {
+                id: component.id.to_string(),
+                source: wasm_path.clone(),
+                manifest_bytes: serde_cbor::to_vec(&component).expect("component cbor"),
+                manifest_path: format!("components/{}.manifest.cbor", component.id),
+                manifest_hash_sha256: String::new(),
+            }],
+            lock_components: Vec::new(),
+            component_manifest_files: Vec::new(),
+            flow_files: Vec::new(),
+            assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
+            extra_files: Vec::new(),
+        };
+        let lock = PackLockV1::new(BTreeMap::new());
+        let bundled_paths = BTreeMap::new();
+        let request = SbomRequest {
+            lock: &lock,
+            bundled_paths: &bundled_paths,
+            embed: &[],
+            compression: PackCompression::Stored,
+        };
+        let logical_wasm = format!("components/{}.wasm", component.id);
+
+        let streamed = assemble_archive(
+            &manifest_bytes,
+            &build,
+            BundleMode::Cache,
+            false,
+            &request,
+            false,
+        )
+        .expect("assemble for file build");
+        let (_, entry) = streamed
+            .entries
+            .iter()
+            .find(|(path, _)| *path == logical_wasm)
+            .expect("component entry");
+        assert!(matches!(entry, EntryBytes::File(path) if *path == wasm_path));
+
+        let buffered = assemble_archive(
+            &manifest_bytes,
+            &build,
+            BundleMode::Cache,
+            false,
+            &request,
+            true,
+        )
+        .expect("assemble in memory");
+        assert!(
+            buffered
+                .entries
+                .iter()
+                .all(|(_, entry)| matches!(entry, EntryBytes::Inline(_)))
+        );
+        assert_eq!(streamed.files, buffered.files);
+    }
+
     #[test]
     fn prod_gtpack_embeds_secret_requirements_cbor_only() {
         let component = manifest_with_dev_flow();
@@ -2654,6 +3431,7 @@ mod tests {
             component_manifest_files: Vec::new(),
             flow_files: Vec::new(),
             assets: Vec::new(),
+            distribution_artifacts: Vec::new(),
             extra_files: vec![ExtraFile {
                 logical_path: "secret-requirements.json".to_string(),
                 source: secret_file,
@@ -2661,8 +3439,7 @@ mod tests {
         };
 
         let out = temp.path().join("secrets.gtpack");
-        package_gtpack(&out, &manifest_bytes, &build, BundleMode::Cache, false)
-            .expect("package gtpack");
+        package_for_test(&out, &manifest_bytes, &build).expect("package gtpack");
 
         let mut archive = ZipArchive::new(fs::File::open(&out).expect("open gtpack"))
             .expect("read gtpack archive");
@@ -2925,6 +3702,8 @@ flows:
                 component_out: None,
                 manifest_out: pack_dir.join("dist/manifest.cbor"),
                 sbom_out: None,
+                spdx_out: None,
+                embed_sbom: Vec::new(),
                 gtpack_out: Some(pack_dir.join("dist/pack.gtpack")),
                 lock_path: pack_dir.join("pack.lock.cbor"),
                 bundle: BundleMode::Cache,
@@ -2938,6 +3717,9 @@ flows:
                 runtime,
                 skip_update: false,
                 allow_pack_schema: true,
+                compression: PackCompression::Stored,
+                incremental: false,
+                explain: false,
             };
 
             run(&opts).await.expect("build");
@@ -3048,6 +3830,8 @@ flows:
                 component_out: None,
                 manifest_out: pack_dir.join("dist/manifest.cbor"),
                 sbom_out: None,
+                spdx_out: None,
+                embed_sbom: Vec::new(),
                 gtpack_out: Some(pack_dir.join("dist/pack.gtpack")),
                 lock_path: pack_dir.join("pack.lock.cbor"),
                 bundle: BundleMode::Cache,
@@ -3061,6 +3845,9 @@ flows:
                 runtime,
                 skip_update: false,
                 allow_pack_schema: true,
+                compression: PackCompression::Stored,
+                incremental: false,
+                explain: false,
             };
 
             run(&opts).await.expect("build");
@@ -3157,7 +3944,9 @@ flows:
             dependencies: Vec::new(),
             flows: Vec::new(),
             assets: Vec::new(),
+            mcp_components: Vec::new(),
             extensions: None,
+            distribution: None,
         }
     }
 
@@ -0,0 +1,512 @@
+#![forbid(unsafe_code)]
+
+//! Fingerprinted cache that lets `build` skip stages whose inputs are unchanged.
+//!
+//! The cache lives in `<pack_dir>/.packc/build-cache.json`. Each stage
+//! (`update`, `resolve`, `package`) records a blake3 hash of every input it
+//! reads (files, option values, tool version) and of every output it wrote. A
+//! stage is skipped only when all inputs match and every output is still on
+//! disk with the recorded hash, so a skipped build leaves exactly the bytes a
+//! clean build would have produced.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use greentic_pack::pack_lock::PackLockV1;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::build::BuildOptions;
+
+pub const CACHE_DIR: &str = ".packc";
+const CACHE_FILE: &str = "build-cache.json";
+const CACHE_VERSION: u32 = 1;
+/// Directories under the pack root that never feed a build.
+const IGNORED_DIRS: [&str; 3] = ["dist", "target", "node_modules"];
+/// Changed inputs listed by `--explain` before the remainder is summarised.
+const MAX_EXPLAINED_INPUTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuildStage {
+    Update,
+    Resolve,
+    Package,
+}
+
+impl fmt::Display for BuildStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BuildStage::Update => "update",
+            BuildStage::Resolve => "resolve",
+            BuildStage::Package => "package",
+        })
+    }
+}
+
+/// Named input hashes for one stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    inputs: BTreeMap<String, String>,
+}
+
+impl Fingerprint {
+    pub fn add_bytes(&mut self, name: impl Into<String>, bytes: &[u8]) {
+        self.inputs
+            .insert(name.into(), blake3::hash(bytes).to_hex().to_string());
+    }
+
+    /// Record an option or other scalar input by its debug representation.
+    pub fn add_value(&mut self, name: impl Into<String>, value: impl fmt::Debug) {
+        self.add_bytes(name, format!("{value:?}").as_bytes());
+    }
+
+    /// Hash a file's content; a missing file is an input of its own.
+    pub fn add_file(&mut self, name: impl Into<String>, path: &Path) -> Result<()> {
+        let name = name.into();
+        if !path.is_file() {
+            self.inputs.insert(name, "missing".to_string());
+            return Ok(());
+        }
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        self.add_bytes(name, &bytes);
+        Ok(())
+    }
+
+    /// Hash every regular file under `dir`, keyed by its path relative to `root`.
+    ///
+    /// Hidden entries, [`IGNORED_DIRS`] and anything in `exclude` are skipped.
+    pub fn add_tree(&mut self, root: &Path, dir: &Path, exclude: &BTreeSet<PathBuf>) -> Result<()> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let walker = WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                let ignored = entry.depth() > 0
+                    && (name.starts_with('.')
+                        || (entry.file_type().is_dir()
+                            && entry.path().parent() == Some(root)
+                            && IGNORED_DIRS.contains(&name.as_ref())));
+                !ignored && !exclude.contains(entry.path())
+            });
+        for entry in walker {
+            let entry = entry.with_context(|| format!("failed to walk {}", dir.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let rel = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            let name = rel.to_string_lossy().replace('\\', "/");
+            self.add_file(name, entry.path())?;
+        }
+        Ok(())
+    }
+
+    /// Why this fingerprint differs from `previous`, one entry per changed input.
+    fn changes_since(&self, previous: &Fingerprint) -> Vec<String> {
+        let mut changes = Vec::new();
+        for (name, hash) in &self.inputs {
+            match previous.inputs.get(name) {
+                None => changes.push(format!("`{name}` added")),
+                Some(old) if old != hash => changes.push(format!("`{name}` changed")),
+                Some(_) => {}
+            }
+        }
+        for name in previous.inputs.keys() {
+            if !self.inputs.contains_key(name) {
+                changes.push(format!("`{name}` removed"));
+            }
+        }
+        changes
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StageRecord {
+    fingerprint: Fingerprint,
+    /// Output path -> blake3 of the bytes written.
+    outputs: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    stages: BTreeMap<BuildStage, StageRecord>,
+}
+
+/// Whether a stage must run, and why.
+#[derive(Debug, Clone)]
+pub struct StageDecision {
+    pub stage: BuildStage,
+    pub rerun: bool,
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for StageDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.rerun {
+            return write!(f, "{}: up to date", self.stage);
+        }
+        write!(f, "{}: re-run", self.stage)?;
+        let shown = self.reasons.len().min(MAX_EXPLAINED_INPUTS);
+        for reason in &self.reasons[..shown] {
+            write!(f, "\n  - {reason}")?;
+        }
+        if self.reasons.len() > shown {
+            write!(f, "\n  - and {} more", self.reasons.len() - shown)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct BuildCache {
+    path: PathBuf,
+    file: CacheFile,
+    enabled: bool,
+    explain: bool,
+}
+
+impl BuildCache {
+    /// Open the cache for `pack_dir`. A missing, unreadable or outdated cache
+    /// file starts empty, which re-runs every stage.
+    pub fn open(pack_dir: &Path, enabled: bool, explain: bool) -> Self {
+        let path = pack_dir.join(CACHE_DIR).join(CACHE_FILE);
+        let file = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == CACHE_VERSION)
+            .unwrap_or_default();
+        Self {
+            path,
+            file,
+            enabled,
+            explain,
+        }
+    }
+
+    /// Decide whether `stage` must run; prints the decision with `--explain`.
+    pub fn check(&self, stage: BuildStage, fingerprint: &Fingerprint) -> StageDecision {
+        let reasons = if !self.enabled {
+            vec!["build cache disabled (--no-cache)".to_string()]
+        } else if let Some(record) = self.file.stages.get(&stage) {
+            let mut reasons = fingerprint.changes_since(&record.fingerprint);
+            for (path, hash) in &record.outputs {
+                match fs::read(path) {
+                    Ok(bytes) if blake3::hash(&bytes).to_hex().as_str() == hash => {}
+                    Ok(_) => reasons.push(format!("output {} was modified", path.display())),
+                    Err(_) => reasons.push(format!("output {} is missing", path.display())),
+                }
+            }
+            reasons
+        } else {
+            vec!["no previous build recorded".to_string()]
+        };
+        let decision = StageDecision {
+            stage,
+            rerun: !reasons.is_empty(),
+            reasons,
+        };
+        if self.explain {
+            eprintln!("{decision}");
+        }
+        decision
+    }
+
+    /// Record a completed stage and persist the cache.
+    ///
+    /// `fingerprint` should be taken after the stage ran so that files it
+    /// rewrites (pack.yaml, the lock) match on the next build.
+    pub fn record(
+        &mut self,
+        stage: BuildStage,
+        fingerprint: Fingerprint,
+        outputs: &[&Path],
+    ) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut record = StageRecord {
+            fingerprint,
+            outputs: BTreeMap::new(),
+        };
+        for path in outputs {
+            let bytes =
+                fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+            record.outputs.insert(
+                path.to_path_buf(),
+                blake3::hash(&bytes).to_hex().to_string(),
+            );
+        }
+        self.file.version = CACHE_VERSION;
+        self.file.stages.insert(stage, record);
+        self.save()
+    }
+
+    /// Hash `stage` last recorded for the input `name`, e.g. `pack.yaml` or a
+    /// path under `flows/`; `"missing"` when the file did not exist.
+    pub fn recorded_input(&self, stage: BuildStage, name: &str) -> Option<&str> {
+        self.file
+            .stages
+            .get(&stage)
+            .and_then(|record| record.fingerprint.inputs.get(name))
+            .map(String::as_str)
+    }
+
+    /// Forget `stage`, e.g. after it failed half-way.
+    pub fn invalidate(&mut self, stage: BuildStage) -> Result<()> {
+        if self.enabled && self.file.stages.remove(&stage).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let bytes = serde_json::to_vec_pretty(&self.file)?;
+        fs::write(&self.path, bytes)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+fn tool_fingerprint(opts: &BuildOptions) -> Fingerprint {
+    let mut fingerprint = Fingerprint::default();
+    fingerprint.add_value("greentic-pack version", env!("CARGO_PKG_VERSION"));
+    fingerprint.add_value("network policy", opts.runtime.network_policy());
+    fingerprint.add_value("cache dir", opts.runtime.cache_dir());
+    fingerprint.add_value(
+        "GREENTIC_PACK_USE_DESCRIBE_CACHE",
+        std::env::var_os("GREENTIC_PACK_USE_DESCRIBE_CACHE"),
+    );
+    fingerprint
+}
+
+/// Inputs of `update`: pack.yaml and the `components/` and `flows/` trees it syncs.
+pub fn update_fingerprint(opts: &BuildOptions) -> Result<Fingerprint> {
+    let root = &opts.pack_dir;
+    let mut fingerprint = tool_fingerprint(opts);
+    fingerprint.add_file("pack.yaml", &root.join("pack.yaml"))?;
+    for dir in ["components", "flows"] {
+        fingerprint.add_tree(root, &root.join(dir), &BTreeSet::new())?;
+    }
+    Ok(fingerprint)
+}
+
+/// Inputs of `resolve`: the update inputs plus any local component outside the
+/// pack that the current lock points at and the MCP routers it composes.
+pub fn resolve_fingerprint(opts: &BuildOptions) -> Result<Fingerprint> {
+    let mut fingerprint = update_fingerprint(opts)?;
+    add_external_components(&mut fingerprint, opts)?;
+    add_mcp_routers(&mut fingerprint, opts)?;
+    Ok(fingerprint)
+}
+
+/// Files written by `resolve`: the lock and the composed MCP components.
+pub fn resolve_outputs(opts: &BuildOptions) -> Vec<PathBuf> {
+    let mut outputs = vec![opts.lock_path.clone()];
+    if let Ok(config) = crate::config::load_pack_config(&opts.pack_dir) {
+        outputs.extend(
+            config
+                .mcp_components
+                .iter()
+                .map(|mcp| crate::mcp::composed_artifact_path(&opts.pack_dir, &mcp.id)),
+        );
+    }
+    outputs
+}
+
+/// Inputs of `package`: every file under the pack root (minus outputs and
+/// hidden/build directories), the lock, external components and build options.
+pub fn package_fingerprint(opts: &BuildOptions) -> Result<Fingerprint> {
+    let root = &opts.pack_dir;
+    let mut fingerprint = tool_fingerprint(opts);
+    let exclude: BTreeSet<PathBuf> = package_outputs(opts)
+        .into_iter()
+        .map(Path::to_path_buf)
+        .collect();
+    fingerprint.add_tree(root, root, &exclude)?;
+    if !opts.lock_path.starts_with(root) {
+        fingerprint.add_file("lock", &opts.lock_path)?;
+    }
+    add_external_components(&mut fingerprint, opts)?;
+    if let Some(path) = opts.secrets_req.as_ref() {
+        fingerprint.add_file("--secrets-req", path)?;
+    }
+
+    fingerprint.add_value("--out", &opts.component_out);
+    fingerprint.add_value("--manifest", &opts.manifest_out);
+    fingerprint.add_value("--sbom", &opts.sbom_out);
+    fingerprint.add_value("--spdx", &opts.spdx_out);
+    fingerprint.add_value("--embed-sbom", &opts.embed_sbom);
+    fingerprint.add_value("--gtpack-out", &opts.gtpack_out);
+    fingerprint.add_value("--bundle", opts.bundle);
+    fingerprint.add_value("--compression", opts.compression);
+    fingerprint.add_value("--default-secret-scope", &opts.default_secret_scope);
+    fingerprint.add_value("--allow-oci-tags", opts.allow_oci_tags);
+    fingerprint.add_value(
+        "--require-component-manifests",
+        opts.require_component_manifests,
+    );
+    fingerprint.add_value("--no-extra-dirs", opts.no_extra_dirs);
+    fingerprint.add_value("--dev", opts.dev);
+    fingerprint.add_value("--allow-pack-schema", opts.allow_pack_schema);
+    Ok(fingerprint)
+}
+
+/// Files written by the `package` stage.
+pub fn package_outputs(opts: &BuildOptions) -> Vec<&Path> {
+    [
+        Some(&opts.manifest_out),
+        opts.gtpack_out.as_ref(),
+        opts.component_out.as_ref(),
+        opts.sbom_out.as_ref(),
+        opts.spdx_out.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(PathBuf::as_path)
+    .collect()
+}
+
+/// Hash `file://` components referenced by the current lock that live outside
+/// the pack root (those inside it are already covered by the tree hash).
+fn add_external_components(fingerprint: &mut Fingerprint, opts: &BuildOptions) -> Result<()> {
+    let Ok(lock) = greentic_pack::pack_lock::read_pack_lock(&opts.lock_path) else {
+        return Ok(());
+    };
+    for (reference, path) in external_component_paths(&lock, &opts.pack_dir) {
+        fingerprint.add_file(reference, &path)?;
+    }
+    Ok(())
+}
+
+/// Hash the router (and adapter override) of every `mcp_components` entry; the
+/// routers may live anywhere under the pack root.
+fn add_mcp_routers(fingerprint: &mut Fingerprint, opts: &BuildOptions) -> Result<()> {
+    let Ok(config) = crate::config::load_pack_config(&opts.pack_dir) else {
+        return Ok(());
+    };
+    if config.mcp_components.is_empty() {
+        return Ok(());
+    }
+    for mcp in &config.mcp_components {
+        fingerprint.add_file(format!("mcp router {}", mcp.id), &mcp.router_ref)?;
+    }
+    if let Some(adapter) = std::env::var_os("GREENTIC_PACK_ADAPTER_25_06_18") {
+        fingerprint.add_file("GREENTIC_PACK_ADAPTER_25_06_18", Path::new(&adapter))?;
+    }
+    Ok(())
+}
+
+/// Local `file://` components in `lock` that live outside `pack_dir`, keyed by reference.
+pub(crate) fn external_component_paths(
+    lock: &PackLockV1,
+    pack_dir: &Path,
+) -> Vec<(String, PathBuf)> {
+    lock.components
+        .values()
+        .filter_map(|component| component.r#ref.as_deref())
+        .filter_map(|reference| {
+            let path = PathBuf::from(reference.strip_prefix("file://")?);
+            (path.is_absolute() && !path.starts_with(pack_dir))
+                .then(|| (reference.to_string(), path))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn tree_fingerprint_skips_hidden_and_build_dirs() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("flows")).expect("flows");
+        fs::create_dir_all(root.join("dist")).expect("dist");
+        fs::create_dir_all(root.join(CACHE_DIR)).expect("cache dir");
+        fs::create_dir_all(root.join("assets/dist")).expect("nested dist");
+        fs::write(root.join("pack.yaml"), "pack_id: demo\n").expect("pack.yaml");
+        fs::write(root.join("flows/main.ygtc"), "id: main\n").expect("flow");
+        fs::write(root.join("dist/demo.gtpack"), "archive").expect("archive");
+        fs::write(root.join(CACHE_DIR).join(CACHE_FILE), "{}").expect("cache");
+        fs::write(root.join("assets/dist/app.js"), "app").expect("asset");
+        fs::write(root.join("out.gtpack"), "archive").expect("custom output");
+
+        let mut fingerprint = Fingerprint::default();
+        let exclude = BTreeSet::from([root.join("out.gtpack")]);
+        fingerprint
+            .add_tree(root, root, &exclude)
+            .expect("tree fingerprint");
+        let names: Vec<&str> = fingerprint.inputs.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            ["assets/dist/app.js", "flows/main.ygtc", "pack.yaml"]
+        );
+    }
+
+    #[test]
+    fn cache_skips_only_when_inputs_and_outputs_match() {
+        let temp = tempdir().expect("temp dir");
+        let output = temp.path().join("dist/pack.gtpack");
+        fs::create_dir_all(output.parent().unwrap()).expect("dist");
+        fs::write(&output, "archive").expect("output");
+
+        let mut fingerprint = Fingerprint::default();
+        fingerprint.add_bytes("flows/main.ygtc", b"id: main");
+        fingerprint.add_value("--bundle", "cache");
+
+        let mut cache = BuildCache::open(temp.path(), true, false);
+        let first = cache.check(BuildStage::Package, &fingerprint);
+        assert!(first.rerun);
+        assert_eq!(first.reasons, ["no previous build recorded"]);
+        cache
+            .record(
+                BuildStage::Package,
+                fingerprint.clone(),
+                &[output.as_path()],
+            )
+            .expect("record");
+
+        let cache = BuildCache::open(temp.path(), true, false);
+        assert!(!cache.check(BuildStage::Package, &fingerprint).rerun);
+
+        let mut changed = fingerprint.clone();
+        changed.add_bytes("flows/main.ygtc", b"id: other");
+        changed.add_bytes("assets/logo.svg", b"<svg/>");
+        let decision = cache.check(BuildStage::Package, &changed);
+        assert_eq!(
+            decision.reasons,
+            ["`assets/logo.svg` added", "`flows/main.ygtc` changed"]
+        );
+
+        fs::write(&output, "tampered").expect("tamper output");
+        let decision = cache.check(BuildStage::Package, &fingerprint);
+        assert!(decision.reasons[0].contains("was modified"), "{decision}");
+
+        let disabled = BuildCache::open(temp.path(), false, false);
+        assert!(disabled.check(BuildStage::Package, &fingerprint).rerun);
+    }
+
+    #[test]
+    fn explain_lists_a_bounded_number_of_reasons() {
+        let decision = StageDecision {
+            stage: BuildStage::Resolve,
+            rerun: true,
+            reasons: (0..7)
+                .map(|idx| format!("`flows/{idx}.ygtc` changed"))
+                .collect(),
+        };
+        let text = decision.to_string();
+        assert!(text.starts_with("resolve: re-run\n  - `flows/0.ygtc` changed"));
+        assert!(text.ends_with("  - and 2 more"), "{text}");
+    }
+}
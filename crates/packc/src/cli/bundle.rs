@@ -0,0 +1,710 @@
+#![forbid(unsafe_code)]
+
+//! `bundle` / `unbundle`: ship a set of built packs to an air-gapped site as one
+//! signed `distribution-bundle` archive.
+//!
+//! Each source pack is stored under `packs/<pack_id>/` without its component
+//! binaries. Every `components/*.wasm` is stored once per digest under
+//! `components/sha256/<hex>.wasm` and listed in the distribution section.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Parser;
+use greentic_pack::builder::{
+    ComponentDescriptor, DistributionSection, PackCompression, validate_distribution,
+};
+use greentic_pack::signing::{SignOptions, sign_archive_with_options, verify_archive};
+use greentic_pack::{PackLoad, SigningPolicy, open_pack_with_limits};
+use greentic_types::pack_manifest::{ExtensionInline, ExtensionRef};
+use greentic_types::{PackId, PackKind, PackManifest, PackSignatures, encode_pack_manifest};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, json};
+use sha2::{Digest, Sha256};
+
+use crate::build::{package_entries, write_archive};
+use crate::distribution::{
+    self, EXT_DISTRIBUTION_BUNDLE_V1, run_distribution_doctor, section_from_manifest, sha256_digest,
+};
+use crate::runtime::RuntimeContext;
+use crate::signer::{SignerSpec, open_signer};
+
+/// Manifest extension listing the packs stored in a bundle.
+pub const EXT_BUNDLE_PACKS_V1: &str = "greentic.bundle-packs.v1";
+
+const PACKS_DIR: &str = "packs";
+const BLOBS_DIR: &str = "components/sha256";
+
+#[derive(Debug, Parser)]
+pub struct BundleArgs {
+    /// Built .gtpack archives to include
+    #[arg(value_name = "GTPACK", required_unless_present = "spec")]
+    pub packs: Vec<PathBuf>,
+
+    /// Bundle spec (YAML) listing packs and bundle metadata; pack paths are relative to it
+    #[arg(long = "spec", value_name = "FILE")]
+    pub spec: Option<PathBuf>,
+
+    /// Where to write the bundle archive
+    #[arg(long = "out", value_name = "FILE")]
+    pub out: PathBuf,
+
+    /// Pack id of the bundle (default: spec bundle_id, else `distribution.bundle`)
+    #[arg(long = "bundle-id", value_name = "ID")]
+    pub bundle_id: Option<String>,
+
+    /// Semver version of the bundle (default: spec version, else 0.1.0)
+    #[arg(long = "bundle-version", value_name = "VERSION")]
+    pub bundle_version: Option<String>,
+
+    /// Environment the bundle is rolled out to (default: spec environment_ref, else `default`)
+    #[arg(long = "environment-ref", value_name = "REF")]
+    pub environment_ref: Option<String>,
+
+    /// Desired state version (default: spec value, else derived from the bundled digests)
+    #[arg(long = "desired-state-version", value_name = "VERSION")]
+    pub desired_state_version: Option<String>,
+
+    /// Compression for bundle entries (stored, deflate, zstd)
+    #[arg(long, value_enum, default_value = "stored")]
+    pub compression: PackCompression,
+
+    /// Ed25519 private key in PKCS#8 PEM format
+    #[arg(
+        long = "key",
+        value_name = "FILE",
+        required_unless_present = "signer",
+        conflicts_with = "signer"
+    )]
+    pub key: Option<PathBuf>,
+
+    /// External signer: agent://<socket>, exec:<cmd> or file:<path>
+    #[arg(long = "signer", value_name = "SPEC")]
+    pub signer: Option<SignerSpec>,
+
+    /// Key identifier passed to the signer
+    #[arg(long = "key-id", value_name = "ID", default_value = "default")]
+    pub key_id: String,
+
+    /// PEM certificate chain for the key (default: self-signed certificate)
+    #[arg(long = "chain", value_name = "FILE")]
+    pub chain: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct UnbundleArgs {
+    /// Distribution bundle produced by `greentic-pack bundle`
+    #[arg(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    /// Directory that receives one .gtpack per bundled pack
+    #[arg(long = "into", value_name = "DIR")]
+    pub into: PathBuf,
+
+    /// Ed25519 public key in PKCS#8 PEM format the bundle must be signed with (repeatable)
+    #[arg(long = "key", value_name = "FILE")]
+    pub key: Vec<PathBuf>,
+
+    /// CA certificate(s) in PEM format the signing certificate must chain to (repeatable)
+    #[arg(long = "trust-root", value_name = "FILE")]
+    pub trust_root: Vec<PathBuf>,
+
+    /// Trust policy directory (roots/, keys/, policy.yaml); defaults to <greentic_root>/trust
+    #[arg(long = "trust-dir", value_name = "DIR")]
+    pub trust_dir: Option<PathBuf>,
+
+    /// Development only: accept a bundle signed by any key, e.g. a self-signed dev key
+    #[arg(long = "allow-untrusted", default_value_t = false)]
+    pub allow_untrusted: bool,
+}
+
+/// `--spec` file contents.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BundleSpec {
+    #[serde(default)]
+    bundle_id: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    publisher: Option<String>,
+    #[serde(default)]
+    environment_ref: Option<String>,
+    #[serde(default)]
+    desired_state_version: Option<String>,
+    #[serde(default)]
+    tenant: JsonMap<String, serde_json::Value>,
+    #[serde(default)]
+    packs: Vec<PathBuf>,
+}
+
+/// Entry of the [`EXT_BUNDLE_PACKS_V1`] extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundledPack {
+    pack_id: String,
+    version: String,
+    /// Archive prefix holding the pack's entries, minus its component binaries.
+    path: String,
+    /// Component binaries of the original pack, by archive path, mapped to their digest.
+    components: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BundledPacks {
+    packs: Vec<BundledPack>,
+}
+
+/// A component binary shared by one or more bundled packs.
+struct BundledComponent {
+    component_id: String,
+    version: String,
+    packs: BTreeSet<String>,
+    platform: bool,
+    bytes: Vec<u8>,
+}
+
+impl BundleArgs {
+    fn signer_spec(&self) -> Result<SignerSpec> {
+        match (&self.signer, &self.key) {
+            (Some(spec), _) => Ok(spec.clone()),
+            (None, Some(key)) => Ok(SignerSpec::KeyFile(key.clone())),
+            (None, None) => {
+                bail!("{}", crate::cli_i18n::t("cli.sign.error.missing_signer"))
+            }
+        }
+    }
+}
+
+pub fn handle_bundle(args: BundleArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let limits = &runtime.reader_limits;
+    let spec = match args.spec.as_deref() {
+        Some(path) => load_spec(path)?,
+        None => BundleSpec::default(),
+    };
+    let mut pack_paths = spec.packs.clone();
+    pack_paths.extend(args.packs.iter().cloned());
+    if pack_paths.is_empty() {
+        bail!("no packs to bundle; pass .gtpack files or list them under `packs` in the spec");
+    }
+
+    let mut bundled = Vec::new();
+    let mut components: BTreeMap<String, BundledComponent> = BTreeMap::new();
+    let mut entries = Vec::new();
+    let mut first_publisher = None;
+    for path in &pack_paths {
+        let load = open_pack_with_limits(path, SigningPolicy::DevOk, limits)
+            .map_err(|err| anyhow!(err.message))
+            .with_context(|| format!("failed to open pack {}", path.display()))?;
+        let manifest = load
+            .gpack_manifest
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} has no pack manifest", path.display()))?;
+        if section_from_manifest(manifest).is_some() {
+            bail!(
+                "{} is already a distribution bundle and cannot be nested",
+                path.display()
+            );
+        }
+        let pack_id = manifest.pack_id.to_string();
+        if bundled
+            .iter()
+            .any(|pack: &BundledPack| pack.pack_id == pack_id)
+        {
+            bail!("pack {pack_id} is listed more than once");
+        }
+        first_publisher.get_or_insert_with(|| manifest.publisher.clone());
+
+        let prefix = format!("{PACKS_DIR}/{pack_id}");
+        let mut pack_components = BTreeMap::new();
+        for (name, bytes) in sorted_files(&load) {
+            if !is_component_binary(name) {
+                entries.push((
+                    format!("{prefix}/{name}"),
+                    bytes.clone(),
+                    "application/octet-stream",
+                ));
+                continue;
+            }
+            let digest = sha256_digest(bytes);
+            pack_components.insert(name.to_string(), digest.clone());
+            let component = components
+                .entry(digest)
+                .or_insert_with(|| BundledComponent {
+                    component_id: component_id(name),
+                    version: component_version(manifest, &component_id(name)),
+                    packs: BTreeSet::new(),
+                    platform: false,
+                    bytes: bytes.clone(),
+                });
+            component.packs.insert(pack_id.clone());
+            component.platform |= matches!(manifest.kind, PackKind::Provider);
+        }
+        bundled.push(BundledPack {
+            pack_id,
+            version: manifest.version.to_string(),
+            path: prefix,
+            components: pack_components,
+        });
+    }
+
+    let section = distribution_section(&args, &spec, &bundled, &components)?;
+    let bundle_id = args
+        .bundle_id
+        .clone()
+        .or_else(|| spec.bundle_id.clone())
+        .unwrap_or_else(|| "distribution.bundle".to_string());
+    let version = args
+        .bundle_version
+        .clone()
+        .or_else(|| spec.version.clone())
+        .unwrap_or_else(|| "0.1.0".to_string());
+
+    let mut extensions = BTreeMap::new();
+    extensions.insert(
+        EXT_DISTRIBUTION_BUNDLE_V1.to_string(),
+        distribution::manifest_extension(&section, &bundle_id)?,
+    );
+    extensions.insert(
+        EXT_BUNDLE_PACKS_V1.to_string(),
+        ExtensionRef {
+            kind: EXT_BUNDLE_PACKS_V1.to_string(),
+            version: "1".to_string(),
+            digest: None,
+            location: None,
+            inline: Some(ExtensionInline::Other(serde_json::to_value(
+                BundledPacks {
+                    packs: bundled.clone(),
+                },
+            )?)),
+        },
+    );
+    let manifest = PackManifest {
+        schema_version: "pack-v1".to_string(),
+        pack_id: PackId::new(bundle_id.clone()).context("invalid bundle id")?,
+        name: None,
+        version: Version::parse(&version).context("invalid bundle version (expected semver)")?,
+        kind: PackKind::Infrastructure,
+        publisher: spec
+            .publisher
+            .clone()
+            .or(first_publisher)
+            .unwrap_or_default(),
+        components: Vec::new(),
+        flows: Vec::new(),
+        dependencies: Vec::new(),
+        capabilities: Vec::new(),
+        secret_requirements: Vec::new(),
+        signatures: PackSignatures::default(),
+        bootstrap: None,
+        extensions: Some(extensions),
+    };
+    let manifest_bytes = encode_pack_manifest(&manifest).context("failed to encode manifest")?;
+    entries.push((
+        "manifest.cbor".to_string(),
+        manifest_bytes,
+        "application/cbor",
+    ));
+    for (digest, component) in &components {
+        entries.push((
+            blob_path(digest),
+            component.bytes.clone(),
+            "application/wasm",
+        ));
+    }
+    package_entries(&args.out, &entries, args.compression)?;
+
+    let chain = match args.chain.as_ref() {
+        Some(chain) => Some(
+            fs::read(chain)
+                .with_context(|| format!("failed to read certificate chain {}", chain.display()))?,
+        ),
+        None => None,
+    };
+    let signer = open_signer(&args.signer_spec()?, Some(&args.key_id), chain)?;
+    let signed = sign_archive_with_options(
+        &args.out,
+        &args.out,
+        &*signer,
+        limits,
+        &SignOptions::default(),
+    )
+    .with_context(|| format!("failed to sign {}", args.out.display()));
+    let signed = match signed {
+        Ok(signed) => signed,
+        Err(err) => {
+            let _ = fs::remove_file(&args.out);
+            return Err(err);
+        }
+    };
+
+    let references: usize = bundled.iter().map(|pack| pack.components.len()).sum();
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "bundle": args.out,
+                "bundle_id": bundle_id,
+                "desired_state_version": section.desired_state_version,
+                "packs": bundled.iter().map(|pack| &pack.pack_id).collect::<Vec<_>>(),
+                "components": components.len(),
+                "component_references": references,
+                "digest": signed.digest,
+            }))?
+        );
+    } else {
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.bundle.done",
+                &[
+                    &bundled.len().to_string(),
+                    &components.len().to_string(),
+                    &references.to_string(),
+                    &args.out.display().to_string(),
+                ],
+            )
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.bundle.desired_state_version",
+                &[&section.desired_state_version.to_string()],
+            )
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_unbundle(args: UnbundleArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let trust = super::verify::archive_trust(
+        runtime,
+        args.trust_dir.as_deref(),
+        &args.key,
+        &args.trust_root,
+    )?;
+    let limits = &runtime.reader_limits;
+    let report = verify_archive(&args.bundle, &trust, limits)
+        .with_context(|| format!("failed to verify {}", args.bundle.display()))?;
+    if report.signatures.is_empty() {
+        bail!("bundle {} is not signed", args.bundle.display());
+    }
+    if let Some(check) = report.signatures.iter().find(|check| !check.valid) {
+        bail!(
+            "bundle signature {} is invalid: {}",
+            check.path,
+            check.error.as_deref().unwrap_or("verification failed")
+        );
+    }
+    if !report.is_trusted() && !args.allow_untrusted {
+        bail!(
+            "bundle {} is not signed by a trusted key (pass --key, --trust-root or --trust-dir, \
+             or --allow-untrusted to accept any valid signature)",
+            args.bundle.display()
+        );
+    }
+
+    let load = open_pack_with_limits(&args.bundle, SigningPolicy::DevOk, limits)
+        .map_err(|err| anyhow!(err.message))
+        .with_context(|| format!("failed to open bundle {}", args.bundle.display()))?;
+    let manifest = load
+        .gpack_manifest
+        .as_ref()
+        .ok_or_else(|| anyhow!("{} has no pack manifest", args.bundle.display()))?;
+    if section_from_manifest(manifest).is_none() {
+        bail!("{} is not a distribution bundle", args.bundle.display());
+    }
+    let doctor = run_distribution_doctor(&load, None);
+    if doctor.has_errors {
+        let messages: Vec<_> = doctor
+            .diagnostics
+            .iter()
+            .map(|diag| diag.message.as_str())
+            .collect();
+        bail!("bundle verification failed: {}", messages.join("; "));
+    }
+    let bundled = bundled_packs(manifest)?;
+
+    fs::create_dir_all(&args.into)
+        .with_context(|| format!("failed to create {}", args.into.display()))?;
+    let mut written = Vec::new();
+    for pack in &bundled.packs {
+        let out = args.into.join(format!("{}.gtpack", pack.pack_id));
+        let entries = pack_entries(&load, pack)?;
+        let compression = pack_compression(&entries)
+            .with_context(|| format!("pack {} has an unreadable sbom.cbor", pack.pack_id))?;
+        write_archive(&out, &entries, compression)?;
+        // The reassembled pack must pass its own SBOM and signature checks.
+        if let Err(err) = open_pack_with_limits(&out, SigningPolicy::DevOk, limits) {
+            let _ = fs::remove_file(&out);
+            bail!(
+                "pack {} failed verification after unbundling: {}",
+                pack.pack_id,
+                err.message
+            );
+        }
+        written.push(out);
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "bundle": args.bundle,
+                "signatures": report.signatures.len(),
+                "trusted": report.is_trusted(),
+                "packs": written,
+            }))?
+        );
+    } else {
+        for path in &written {
+            println!(
+                "{}",
+                crate::cli_i18n::tf("cli.common.wrote_path", &[&path.display().to_string()])
+            );
+        }
+    }
+    Ok(())
+}
+
+fn load_spec(path: &Path) -> Result<BundleSpec> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut spec: BundleSpec = serde_yaml_bw::from_str(&contents)
+        .with_context(|| format!("{} is not a valid bundle spec", path.display()))?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    for pack in &mut spec.packs {
+        if pack.is_relative() {
+            *pack = base.join(&*pack);
+        }
+    }
+    Ok(spec)
+}
+
+fn distribution_section(
+    args: &BundleArgs,
+    spec: &BundleSpec,
+    bundled: &[BundledPack],
+    components: &BTreeMap<String, BundledComponent>,
+) -> Result<DistributionSection> {
+    let mut section = DistributionSection {
+        bundle_id: args.bundle_id.clone().or_else(|| spec.bundle_id.clone()),
+        tenant: spec.tenant.clone(),
+        environment_ref: args
+            .environment_ref
+            .clone()
+            .or_else(|| spec.environment_ref.clone())
+            .unwrap_or_else(|| "default".to_string()),
+        desired_state_version: args
+            .desired_state_version
+            .clone()
+            .or_else(|| spec.desired_state_version.clone())
+            .unwrap_or_else(|| derived_state_version(bundled)),
+        components: Vec::new(),
+        platform_components: Vec::new(),
+    };
+    for (digest, component) in components {
+        let descriptor = ComponentDescriptor {
+            component_id: component.component_id.clone(),
+            version: component.version.clone(),
+            digest: digest.clone(),
+            artifact_path: blob_path(digest),
+            kind: Some("component".to_string()),
+            artifact_type: Some("wasm".to_string()),
+            tags: component.packs.iter().cloned().collect(),
+            platform: None,
+            entrypoint: None,
+        };
+        if component.platform {
+            section.platform_components.push(descriptor);
+        } else {
+            section.components.push(descriptor);
+        }
+    }
+    validate_distribution(
+        Some(&greentic_pack::PackKind::DistributionBundle),
+        Some(&section),
+    )
+    .context("packs lock different binaries for the same component version")?;
+    Ok(section)
+}
+
+/// Content-derived version: changes whenever a bundled pack or binary does.
+fn derived_state_version(bundled: &[BundledPack]) -> String {
+    let mut hasher = Sha256::new();
+    let mut packs: Vec<_> = bundled.iter().collect();
+    packs.sort_by(|a, b| a.pack_id.cmp(&b.pack_id));
+    for pack in packs {
+        hasher.update(format!("{}@{}\n", pack.pack_id, pack.version));
+        for (path, digest) in &pack.components {
+            hasher.update(format!("{path}={digest}\n"));
+        }
+    }
+    format!("sha256:{:x}", hasher.finalize())
+}
+
+fn bundled_packs(manifest: &PackManifest) -> Result<BundledPacks> {
+    let entry = manifest
+        .extensions
+        .as_ref()
+        .and_then(|exts| exts.get(EXT_BUNDLE_PACKS_V1))
+        .ok_or_else(|| anyhow!("bundle manifest has no {EXT_BUNDLE_PACKS_V1} extension"))?;
+    match entry.inline.as_ref() {
+        Some(ExtensionInline::Other(value)) => parse_bundled_packs(value),
+        _ => bail!("{EXT_BUNDLE_PACKS_V1} extension has no inline payload"),
+    }
+}
+
+fn parse_bundled_packs(value: &serde_json::Value) -> Result<BundledPacks> {
+    let bundled: BundledPacks = serde_json::from_value(value.clone())
+        .with_context(|| format!("{EXT_BUNDLE_PACKS_V1} extension is malformed"))?;
+    // Pack ids name the files `unbundle` writes.
+    for pack in &bundled.packs {
+        PackId::new(pack.pack_id.clone()).with_context(|| {
+            format!(
+                "{EXT_BUNDLE_PACKS_V1} lists an invalid pack id `{}`",
+                pack.pack_id
+            )
+        })?;
+    }
+    Ok(bundled)
+}
+
+/// Entries of one bundled pack, with its component binaries restored.
+fn pack_entries(load: &PackLoad, pack: &BundledPack) -> Result<Vec<(String, Vec<u8>)>> {
+    let prefix = format!("{}/", pack.path);
+    let mut entries: Vec<_> = load
+        .files
+        .iter()
+        .filter_map(|(name, bytes)| {
+            name.strip_prefix(&prefix)
+                .map(|name| (name.to_string(), bytes.clone()))
+        })
+        .collect();
+    if entries.is_empty() {
+        bail!("pack {} has no entries in the bundle", pack.pack_id);
+    }
+    for (name, digest) in &pack.components {
+        let bytes = load.files.get(&blob_path(digest)).ok_or_else(|| {
+            anyhow!(
+                "component {name} of pack {} ({digest}) is missing from the bundle",
+                pack.pack_id
+            )
+        })?;
+        if !sha256_digest(bytes).eq_ignore_ascii_case(digest) {
+            bail!(
+                "component {name} of pack {} does not match digest {digest}",
+                pack.pack_id
+            );
+        }
+        entries.push((name.clone(), bytes.clone()));
+    }
+    Ok(entries)
+}
+
+/// Compression recorded in a pack's `sbom.cbor`.
+fn pack_compression(entries: &[(String, Vec<u8>)]) -> Result<PackCompression> {
+    #[derive(Deserialize)]
+    struct SbomCompression {
+        #[serde(default)]
+        compression: Option<String>,
+    }
+
+    let Some((_, bytes)) = entries.iter().find(|(name, _)| name == "sbom.cbor") else {
+        return Ok(PackCompression::Stored);
+    };
+    let sbom: SbomCompression = serde_cbor::from_slice(bytes)?;
+    PackCompression::from_sbom(sbom.compression.as_deref())
+}
+
+fn sorted_files(load: &PackLoad) -> Vec<(&str, &Vec<u8>)> {
+    let mut files: Vec<_> = load
+        .files
+        .iter()
+        .map(|(name, bytes)| (name.as_str(), bytes))
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(b.0));
+    files
+}
+
+fn is_component_binary(name: &str) -> bool {
+    name.strip_prefix("components/")
+        .is_some_and(|rest| rest.ends_with(".wasm"))
+}
+
+/// `components/<id>.wasm` -> `<id>`.
+fn component_id(name: &str) -> String {
+    let file = name.rsplit('/').next().unwrap_or(name);
+    file.strip_suffix(".wasm").unwrap_or(file).to_string()
+}
+
+/// Version of `component_id` in the pack manifest, else the pack version.
+fn component_version(manifest: &PackManifest, component_id: &str) -> String {
+    manifest
+        .components
+        .iter()
+        .find(|component| component.id.to_string() == component_id)
+        .map(|component| component.version.to_string())
+        .unwrap_or_else(|| manifest.version.to_string())
+}
+
+fn blob_path(digest: &str) -> String {
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    format!("{BLOBS_DIR}/{hex}.wasm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(pack_id: &str, components: &[(&str, &str)]) -> BundledPack {
+        BundledPack {
+            pack_id: pack_id.to_string(),
+            version: "1.0.0".to_string(),
+            path: format!("{PACKS_DIR}/{pack_id}"),
+            components: components
+                .iter()
+                .map(|(path, digest)| (path.to_string(), digest.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn derived_state_version_ignores_pack_order() {
+        let app = pack("demo.app", &[("components/a.wasm", "sha256:aa")]);
+        let provider = pack("demo.provider", &[("components/b.wasm", "sha256:bb")]);
+
+        let forward = derived_state_version(&[app.clone(), provider.clone()]);
+        assert_eq!(forward, derived_state_version(&[provider, app.clone()]));
+        assert_ne!(forward, derived_state_version(&[app]));
+    }
+
+    #[test]
+    fn only_component_binaries_are_deduplicated() {
+        assert!(is_component_binary("components/demo.app.wasm"));
+        assert!(!is_component_binary(
+            "components/demo.app.wasm.describe.cbor"
+        ));
+        assert!(!is_component_binary("blobs/sha256/00.wasm"));
+        assert_eq!(component_id("components/demo.app.wasm"), "demo.app");
+        assert_eq!(
+            blob_path("sha256:abc"),
+            "components/sha256/abc.wasm".to_string()
+        );
+    }
+
+    #[test]
+    fn bundled_pack_ids_must_be_valid() {
+        let listed = |pack_id: &str| {
+            serde_json::to_value(BundledPacks {
+                packs: vec![pack(pack_id, &[])],
+            })
+            .expect("serialize")
+        };
+        assert!(parse_bundled_packs(&listed("demo.app")).is_ok());
+        let err = parse_bundled_packs(&listed("../../escape")).expect_err("path-like id");
+        assert!(format!("{err:#}").contains("invalid pack id"));
+    }
+}
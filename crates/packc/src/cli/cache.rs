@@ -0,0 +1,92 @@
+#![forbid(unsafe_code)]
+
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use crate::describe_cache::{DescribeCache, PruneOptions};
+use crate::runtime::RuntimeContext;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    /// Show describe cache location, entry count and size.
+    Stats,
+    /// Remove stale describe cache entries.
+    Prune(PruneArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PruneArgs {
+    /// Also remove entries not used in the last N days.
+    #[arg(long = "older-than", value_name = "DAYS")]
+    pub older_than: Option<u64>,
+
+    /// Remove every entry.
+    #[arg(long, conflicts_with = "older_than")]
+    pub all: bool,
+}
+
+pub fn handle(cmd: CacheCommand, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let cache = DescribeCache::for_runtime(runtime);
+    match cmd {
+        CacheCommand::Stats => {
+            let stats = cache.stats()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf(
+                        "cli.cache.stats",
+                        &[
+                            &stats.root.display().to_string(),
+                            &stats.entries.to_string(),
+                            &stats.bytes.to_string(),
+                            &stats.stale.to_string(),
+                            &stats.abi,
+                        ]
+                    )
+                );
+            }
+        }
+        CacheCommand::Prune(args) => {
+            let older_than = match args.older_than {
+                Some(days) => match days.checked_mul(SECONDS_PER_DAY) {
+                    Some(secs) => Some(Duration::from_secs(secs)),
+                    None => bail!("{}", crate::cli_i18n::t("cli.cache.error.older_than")),
+                },
+                None => None,
+            };
+            let report = cache.prune(PruneOptions {
+                older_than,
+                all: args.all,
+            })?;
+            if json {
+                let payload = json!({
+                    "root": cache.root(),
+                    "removed": report.removed,
+                    "bytes": report.bytes,
+                    "kept": report.kept,
+                });
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else {
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf(
+                        "cli.cache.pruned",
+                        &[
+                            &report.removed.to_string(),
+                            &report.bytes.to_string(),
+                            &report.kept.to_string(),
+                        ]
+                    )
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,476 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use greentic_pack::builder::PackCompression;
+use walkdir::WalkDir;
+
+use crate::build::{self, BuildOptions, BundleMode};
+use crate::build_cache::{BuildCache, BuildStage, external_component_paths};
+use crate::cli::lint::lint_pack;
+use crate::diagnostics::render_human;
+use crate::runtime::RuntimeContext;
+
+/// Directories under the pack root whose files trigger a rebuild. Flow
+/// sidecars (`*.resolve.json`, `*.resolve.summary.json`) live under `flows/`.
+const WATCHED_DIRS: &[&str] = &["flows", "components", "assets"];
+/// How often the watched files are re-scanned.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Changed paths printed per rebuild before collapsing into a count.
+const MAX_LISTED_CHANGES: usize = 5;
+/// Suffixes of the flow sidecars the build's update step writes.
+const SIDECAR_SUFFIXES: &[&str] = &[".resolve.json", ".resolve.summary.json"];
+
+#[derive(Debug, Args)]
+pub struct DevArgs {
+    /// Root directory of the pack (must contain pack.yaml)
+    #[arg(long = "in", value_name = "DIR", default_value = ".")]
+    pub input: PathBuf,
+
+    /// Keep running and rebuild whenever pack sources change
+    #[arg(long, default_value_t = false)]
+    pub watch: bool,
+
+    /// Output path for the dev .gtpack (default: dist/dev/<pack_dir>.gtpack)
+    #[arg(long = "gtpack-out", value_name = "FILE")]
+    pub gtpack_out: Option<PathBuf>,
+
+    /// Quiet period after the last change before rebuilding, in milliseconds
+    #[arg(long = "debounce-ms", value_name = "MS", default_value_t = 300)]
+    pub debounce_ms: u64,
+
+    /// Allow OCI component refs in extensions to be tag-based (default requires sha256 digest)
+    #[arg(long = "allow-oci-tags", default_value_t = false)]
+    pub allow_oci_tags: bool,
+
+    /// Migration-only escape hatch: allow deriving component manifest/schema from pack.yaml.
+    #[arg(long = "allow-pack-schema", default_value_t = false)]
+    pub allow_pack_schema: bool,
+
+    /// Print which build stages were skipped or re-run, and why
+    #[arg(long, default_value_t = false)]
+    pub explain: bool,
+}
+
+pub async fn handle(args: DevArgs, runtime: &RuntimeContext) -> Result<()> {
+    let opts = dev_build_options(&args, runtime)?;
+    let pack_dir = opts.pack_dir.clone();
+
+    let ok = rebuild(&opts, args.allow_oci_tags).await;
+    if !args.watch {
+        if !ok {
+            bail!("{}", crate::cli_i18n::t("cli.dev.error.build_failed"));
+        }
+        return Ok(());
+    }
+
+    let mut watcher = Watcher::new(&pack_dir, Duration::from_millis(args.debounce_ms));
+    eprintln!(
+        "{}",
+        crate::cli_i18n::tf("cli.dev.watching", &[&pack_dir.display().to_string()])
+    );
+    loop {
+        let changed = watcher.wait_for_change().await;
+        eprintln!(
+            "{}",
+            crate::cli_i18n::tf("cli.dev.changed", &[&describe_changes(&changed)])
+        );
+        rebuild(&opts, args.allow_oci_tags).await;
+        // Edits made while building stay pending for the next round.
+        if let Err(err) = watcher.absorb_build_outputs() {
+            eprintln!(
+                "{}",
+                crate::cli_i18n::tf("cli.dev.error", &[&format!("{err:#}")])
+            );
+        }
+    }
+}
+
+/// Options for the dev-layout build that `dev` keeps refreshed: incremental,
+/// with flow sources included, written under `dist/dev/` by default.
+fn dev_build_options(args: &DevArgs, runtime: &RuntimeContext) -> Result<BuildOptions> {
+    let pack_dir = args
+        .input
+        .canonicalize()
+        .with_context(|| format!("failed to canonicalize pack dir {}", args.input.display()))?;
+    let name = pack_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("pack");
+    let gtpack_out = args
+        .gtpack_out
+        .clone()
+        .map(|p| if p.is_absolute() { p } else { pack_dir.join(p) })
+        .unwrap_or_else(|| {
+            pack_dir
+                .join("dist")
+                .join("dev")
+                .join(format!("{name}.gtpack"))
+        });
+    Ok(BuildOptions {
+        component_out: None,
+        manifest_out: pack_dir.join("dist").join("dev").join("manifest.cbor"),
+        sbom_out: None,
+        spdx_out: None,
+        embed_sbom: Vec::new(),
+        gtpack_out: Some(gtpack_out),
+        lock_path: pack_dir.join("pack.lock.cbor"),
+        bundle: BundleMode::Cache,
+        dry_run: false,
+        secrets_req: None,
+        default_secret_scope: None,
+        allow_oci_tags: args.allow_oci_tags,
+        require_component_manifests: false,
+        no_extra_dirs: false,
+        dev: true,
+        runtime: runtime.clone(),
+        skip_update: false,
+        allow_pack_schema: args.allow_pack_schema,
+        compression: PackCompression::Stored,
+        incremental: true,
+        explain: args.explain,
+        pack_dir,
+    })
+}
+
+/// Lint, then run the stages whose inputs changed. Problems are printed rather
+/// than returned so the watch loop keeps going; returns whether the dev pack
+/// is up to date.
+async fn rebuild(opts: &BuildOptions, allow_oci_tags: bool) -> bool {
+    let report = lint_pack(&opts.pack_dir, allow_oci_tags);
+    if !report.diagnostics.is_empty() {
+        eprint!("{}", render_human(&report.diagnostics, &opts.pack_dir));
+    }
+    let errors = report.error_count();
+    if errors > 0 {
+        eprintln!(
+            "{}",
+            crate::cli_i18n::tf("cli.dev.lint_errors", &[&errors.to_string()])
+        );
+        return false;
+    }
+
+    match build::run(opts).await {
+        Ok(()) => {
+            if let Some(gtpack_out) = opts.gtpack_out.as_ref() {
+                eprintln!(
+                    "{}",
+                    crate::cli_i18n::tf("cli.dev.ready", &[&gtpack_out.display().to_string()])
+                );
+            }
+            true
+        }
+        Err(err) => {
+            eprintln!(
+                "{}",
+                crate::cli_i18n::tf("cli.dev.error", &[&format!("{err:#}")])
+            );
+            false
+        }
+    }
+}
+
+fn describe_changes(changed: &[PathBuf]) -> String {
+    let mut names: Vec<String> = changed
+        .iter()
+        .take(MAX_LISTED_CHANGES)
+        .map(|path| path.display().to_string())
+        .collect();
+    if changed.len() > MAX_LISTED_CHANGES {
+        names.push(crate::cli_i18n::tf(
+            "cli.dev.more_changes",
+            &[&(changed.len() - MAX_LISTED_CHANGES).to_string()],
+        ));
+    }
+    names.join(", ")
+}
+
+/// Size and modification time of every watched file.
+type Snapshot = BTreeMap<PathBuf, (u64, Option<SystemTime>)>;
+
+/// Polls the pack sources and reports changes once a burst of edits settles.
+struct Watcher {
+    pack_dir: PathBuf,
+    debounce: Duration,
+    snapshot: Snapshot,
+}
+
+impl Watcher {
+    fn new(pack_dir: &Path, debounce: Duration) -> Self {
+        Self {
+            pack_dir: pack_dir.to_path_buf(),
+            debounce,
+            snapshot: scan(pack_dir),
+        }
+    }
+
+    /// Accept the changes the build itself made since the last snapshot:
+    /// `pack.yaml` and flow sidecars whose bytes are what the update step
+    /// wrote, and external components the rewritten lock starts or stops
+    /// pointing at. Anything else was edited while building and stays pending;
+    /// the build may have read those edits part-way, so its cache records are
+    /// dropped and the next round runs every stage.
+    fn absorb_build_outputs(&mut self) -> Result<()> {
+        let mut cache = BuildCache::open(&self.pack_dir, true, false);
+        let current = scan(&self.pack_dir);
+        let mut pending = false;
+        for path in changed_paths(&self.snapshot, &current) {
+            let tracking_changed = self.snapshot.contains_key(&path) != current.contains_key(&path);
+            let written = match path.strip_prefix(&self.pack_dir) {
+                Ok(rel) => is_build_output(rel) && written_by_update(&cache, rel, &path),
+                Err(_) => tracking_changed,
+            };
+            if !written {
+                pending = true;
+                continue;
+            }
+            match current.get(&path) {
+                Some(state) => self.snapshot.insert(path, *state),
+                None => self.snapshot.remove(&path),
+            };
+        }
+        if pending {
+            for stage in [BuildStage::Update, BuildStage::Resolve, BuildStage::Package] {
+                cache.invalidate(stage)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait until watched files change and then stay quiet for the debounce
+    /// period; returns the changed paths relative to the pack root.
+    async fn wait_for_change(&mut self) -> Vec<PathBuf> {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let mut current = scan(&self.pack_dir);
+            if current == self.snapshot {
+                continue;
+            }
+            let mut settled_at = Instant::now();
+            while settled_at.elapsed() < self.debounce {
+                tokio::time::sleep(POLL_INTERVAL.min(self.debounce)).await;
+                let next = scan(&self.pack_dir);
+                if next != current {
+                    current = next;
+                    settled_at = Instant::now();
+                }
+            }
+            let changed = changed_paths(&self.snapshot, &current);
+            self.snapshot = current;
+            if !changed.is_empty() {
+                return changed
+                    .into_iter()
+                    .map(|path| {
+                        path.strip_prefix(&self.pack_dir)
+                            .map(Path::to_path_buf)
+                            .unwrap_or(path)
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+/// Snapshot `pack.yaml`, the watched directories and any local `file://`
+/// component outside the pack that the current lock points at.
+fn scan(pack_dir: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let mut record = |path: &Path| {
+        if let Ok(meta) = fs::metadata(path)
+            && meta.is_file()
+        {
+            snapshot.insert(path.to_path_buf(), (meta.len(), meta.modified().ok()));
+        }
+    };
+    record(&pack_dir.join("pack.yaml"));
+    for dir in WATCHED_DIRS {
+        let walker = WalkDir::new(pack_dir.join(dir))
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            });
+        for entry in walker.filter_map(Result::ok) {
+            if entry.file_type().is_file() {
+                record(entry.path());
+            }
+        }
+    }
+    if let Ok(lock) = greentic_pack::pack_lock::read_pack_lock(&pack_dir.join("pack.lock.cbor")) {
+        for (_, path) in external_component_paths(&lock, pack_dir) {
+            record(&path);
+        }
+    }
+    snapshot
+}
+
+fn is_build_output(rel: &Path) -> bool {
+    if rel == Path::new("pack.yaml") {
+        return true;
+    }
+    let name = rel.to_string_lossy();
+    rel.starts_with("flows") && SIDECAR_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// Whether `path` holds the bytes the last recorded update step left there.
+fn written_by_update(cache: &BuildCache, rel: &Path, path: &Path) -> bool {
+    let name = rel.to_string_lossy().replace('\\', "/");
+    let recorded = cache.recorded_input(BuildStage::Update, &name);
+    match fs::read(path) {
+        Ok(bytes) => recorded == Some(blake3::hash(&bytes).to_hex().as_str()),
+        Err(_) => matches!(recorded, None | Some("missing")),
+    }
+}
+
+fn changed_paths(old: &Snapshot, new: &Snapshot) -> Vec<PathBuf> {
+    let keys: BTreeSet<&PathBuf> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|path| old.get(*path) != new.get(*path))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_cache::Fingerprint;
+    use tempfile::tempdir;
+
+    #[test]
+    fn scan_covers_sources_and_skips_outputs() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path();
+        for dir in ["flows", "components", "assets", "dist", ".packc"] {
+            fs::create_dir_all(root.join(dir)).expect("dir");
+        }
+        fs::write(root.join("pack.yaml"), "pack_id: demo\n").expect("pack.yaml");
+        fs::write(root.join("flows/main.ygtc"), "id: main\n").expect("flow");
+        fs::write(root.join("flows/main.ygtc.resolve.summary.json"), "{}").expect("sidecar");
+        fs::write(root.join("components/demo.wasm"), "wasm").expect("component");
+        fs::write(root.join("assets/logo.svg"), "<svg/>").expect("asset");
+        fs::write(root.join("dist/demo.gtpack"), "archive").expect("archive");
+        fs::write(root.join(".packc/build-cache.json"), "{}").expect("cache");
+
+        let names: Vec<PathBuf> = scan(root)
+            .into_keys()
+            .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "assets/logo.svg",
+                "components/demo.wasm",
+                "flows/main.ygtc",
+                "flows/main.ygtc.resolve.summary.json",
+                "pack.yaml",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[tokio::test]
+    async fn burst_of_edits_is_reported_once() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path().to_path_buf();
+        fs::create_dir_all(root.join("flows")).expect("flows");
+        fs::write(root.join("pack.yaml"), "pack_id: demo\n").expect("pack.yaml");
+        let mut watcher = Watcher::new(&root, Duration::from_millis(400));
+
+        let writer = {
+            let root = root.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                fs::write(root.join("flows/main.ygtc"), "id: main\n").expect("flow");
+                std::thread::sleep(Duration::from_millis(150));
+                fs::write(root.join("pack.yaml"), "pack_id: demo2\n").expect("pack.yaml");
+            })
+        };
+        let changed = watcher.wait_for_change().await;
+        writer.join().expect("writer");
+
+        assert_eq!(
+            changed,
+            [PathBuf::from("flows/main.ygtc"), PathBuf::from("pack.yaml")]
+        );
+        assert_eq!(watcher.snapshot, scan(&root));
+    }
+
+    /// Record an update stage for `root` as the build does once update ran.
+    fn record_update(root: &Path) {
+        let mut fingerprint = Fingerprint::default();
+        fingerprint
+            .add_file("pack.yaml", &root.join("pack.yaml"))
+            .expect("hash pack.yaml");
+        fingerprint
+            .add_tree(root, &root.join("flows"), &BTreeSet::new())
+            .expect("hash flows");
+        BuildCache::open(root, true, false)
+            .record(BuildStage::Update, fingerprint, &[])
+            .expect("record update");
+    }
+
+    #[test]
+    fn only_build_outputs_are_absorbed() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path().to_path_buf();
+        fs::create_dir_all(root.join("flows")).expect("flows");
+        fs::write(root.join("pack.yaml"), "pack_id: demo\n").expect("pack.yaml");
+        fs::write(root.join("flows/main.ygtc"), "id: main\n").expect("flow");
+        let mut watcher = Watcher::new(&root, Duration::from_millis(10));
+
+        // What a build writes, plus a user edit landing mid-build.
+        fs::write(root.join("pack.yaml"), "pack_id: demo\ncomponents: []\n").expect("pack.yaml");
+        fs::write(root.join("flows/main.ygtc.resolve.summary.json"), "{}").expect("sidecar");
+        record_update(&root);
+        fs::write(root.join("flows/main.ygtc"), "id: main\nnodes: {}\n").expect("flow");
+        watcher.absorb_build_outputs().expect("absorb");
+
+        assert_eq!(
+            changed_paths(&watcher.snapshot, &scan(&root)),
+            [root.join("flows/main.ygtc")]
+        );
+    }
+
+    #[test]
+    fn pack_yaml_edited_mid_build_stays_pending() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path().to_path_buf();
+        fs::create_dir_all(root.join("flows")).expect("flows");
+        fs::write(root.join("pack.yaml"), "pack_id: demo\n").expect("pack.yaml");
+        let mut watcher = Watcher::new(&root, Duration::from_millis(10));
+
+        // The update step rewrites pack.yaml, then the user edits it before
+        // the build finishes.
+        fs::write(root.join("pack.yaml"), "pack_id: demo\ncomponents: []\n").expect("pack.yaml");
+        record_update(&root);
+        fs::write(
+            root.join("pack.yaml"),
+            "pack_id: demo\nversion: 0.2.0\ncomponents: []\n",
+        )
+        .expect("user edit");
+        watcher.absorb_build_outputs().expect("absorb");
+
+        assert_eq!(
+            changed_paths(&watcher.snapshot, &scan(&root)),
+            [root.join("pack.yaml")]
+        );
+        let cache = BuildCache::open(&root, true, false);
+        assert_eq!(cache.recorded_input(BuildStage::Update, "pack.yaml"), None);
+    }
+
+    #[test]
+    fn long_change_lists_are_truncated() {
+        let changed: Vec<PathBuf> = (0..7)
+            .map(|idx| PathBuf::from(format!("flows/{idx}.ygtc")))
+            .collect();
+        assert_eq!(
+            describe_changes(&changed),
+            "flows/0.ygtc, flows/1.ygtc, flows/2.ygtc, flows/3.ygtc, flows/4.ygtc, and 2 more"
+        );
+    }
+}
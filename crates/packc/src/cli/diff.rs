@@ -0,0 +1,219 @@
+#![forbid(unsafe_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use clap::Args;
+use greentic_pack::diff::{
+    Change, ComponentChange, ComponentSummary, EntryDiff, OperationChangeKind, PackDiff,
+    PackSnapshot, SetDiff, diff_packs,
+};
+use greentic_pack::pack_lock::read_pack_lock;
+use greentic_pack::{SigningPolicy, open_pack_with_limits};
+
+use crate::runtime::RuntimeContext;
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Old side: a .gtpack archive, pack source directory or pack.lock.cbor file.
+    #[arg(value_name = "OLD")]
+    pub old: PathBuf,
+
+    /// New side: a .gtpack archive, pack source directory or pack.lock.cbor file.
+    #[arg(value_name = "NEW")]
+    pub new: PathBuf,
+
+    /// Allow OCI component refs to be tag-based when building source directories.
+    #[arg(long = "allow-oci-tags", default_value_t = false)]
+    pub allow_oci_tags: bool,
+}
+
+pub async fn handle(args: DiffArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let old = load_snapshot(&args.old, runtime, args.allow_oci_tags).await?;
+    let new = load_snapshot(&args.new, runtime, args.allow_oci_tags).await?;
+    let diff = diff_packs(&old, &new);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print_human(&diff);
+    }
+    Ok(())
+}
+
+async fn load_snapshot(
+    path: &Path,
+    runtime: &RuntimeContext,
+    allow_oci_tags: bool,
+) -> Result<PackSnapshot> {
+    let meta = fs::metadata(path).with_context(|| format!("failed to stat {}", path.display()))?;
+    if meta.is_dir() {
+        let load = super::inspect::inspect_source_dir(path, runtime, allow_oci_tags).await?;
+        return PackSnapshot::from_load(&load);
+    }
+    if path.extension().and_then(|ext| ext.to_str()) == Some("cbor") {
+        let lock = read_pack_lock(path)?;
+        return Ok(PackSnapshot::from_lock(path.display().to_string(), &lock));
+    }
+    let load = open_pack_with_limits(path, SigningPolicy::DevOk, &runtime.reader_limits)
+        .map_err(|err| anyhow!(err.message))
+        .with_context(|| format!("failed to open pack {}", path.display()))?;
+    PackSnapshot::from_load(&load)
+}
+
+fn print_human(diff: &PackDiff) {
+    println!(
+        "{}",
+        crate::cli_i18n::tf("cli.diff.header", &[&diff.old, &diff.new])
+    );
+    if diff.is_empty() {
+        println!("{}", crate::cli_i18n::t("cli.diff.no_changes"));
+        return;
+    }
+    if let Some(version) = &diff.version {
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.diff.version", &[&format_change(version)])
+        );
+    }
+
+    let components = &diff.components;
+    if !components.added.is_empty()
+        || !components.removed.is_empty()
+        || !components.changed.is_empty()
+    {
+        println!("{}", crate::cli_i18n::t("cli.diff.section.components"));
+        for component in &components.added {
+            println!("  + {}", format_summary(component));
+        }
+        for component in &components.removed {
+            println!(
+                "  - {}{}",
+                format_summary(component),
+                crate::cli_i18n::t("cli.diff.breaking_marker")
+            );
+        }
+        for change in &components.changed {
+            print_component_change(change);
+        }
+    }
+
+    print_entries("cli.diff.section.dependencies", &diff.dependencies);
+    if let Some(contents) = &diff.contents {
+        print_entries("cli.diff.section.flows", &contents.flows);
+        print_entries("cli.diff.section.assets", &contents.assets);
+        print_entries("cli.diff.section.extensions", &contents.extensions);
+        print_entries(
+            "cli.diff.section.secret_requirements",
+            &contents.secret_requirements,
+        );
+        print_set("cli.diff.section.capabilities", &contents.capabilities);
+    } else {
+        println!("{}", crate::cli_i18n::t("cli.diff.contents_not_compared"));
+    }
+    let breaking = crate::cli_i18n::t(if diff.breaking {
+        "cli.common.yes"
+    } else {
+        "cli.common.no"
+    });
+    println!(
+        "{}",
+        crate::cli_i18n::tf("cli.diff.breaking_changes", &[&breaking])
+    );
+}
+
+fn breaking_marker(breaking: bool) -> String {
+    if breaking {
+        crate::cli_i18n::t("cli.diff.breaking_marker")
+    } else {
+        String::new()
+    }
+}
+
+fn print_component_change(change: &ComponentChange) {
+    println!("  ~ {}{}", change.id, breaking_marker(change.breaking));
+    for (label, value) in [
+        ("version", &change.version),
+        ("digest", &change.digest),
+        ("world", &change.world),
+        ("abi", &change.abi_version),
+        ("describe_hash", &change.describe_hash),
+    ] {
+        if let Some(value) = value {
+            println!("      {label}: {}", format_change(value));
+        }
+    }
+    for op in &change.operations {
+        let marker = breaking_marker(op.breaking);
+        let line = match op.kind {
+            OperationChangeKind::Added => {
+                crate::cli_i18n::tf("cli.diff.operation_added", &[&op.operation_id])
+            }
+            OperationChangeKind::Removed => {
+                crate::cli_i18n::tf("cli.diff.operation_removed", &[&op.operation_id, &marker])
+            }
+            OperationChangeKind::SchemaChanged => crate::cli_i18n::tf(
+                "cli.diff.operation_schema_changed",
+                &[
+                    &op.operation_id,
+                    op.old_schema_hash.as_deref().unwrap_or("-"),
+                    op.new_schema_hash.as_deref().unwrap_or("-"),
+                    &marker,
+                ],
+            ),
+        };
+        println!("{line}");
+    }
+    if change.capabilities_changed {
+        println!("{}", crate::cli_i18n::t("cli.diff.capabilities_changed"));
+    }
+}
+
+/// Print one section; `title_key` names its i18n heading.
+fn print_entries(title_key: &str, entries: &EntryDiff) {
+    if entries.is_empty() {
+        return;
+    }
+    println!("{}", crate::cli_i18n::t(title_key));
+    for id in &entries.added {
+        println!("  + {id}");
+    }
+    for id in &entries.removed {
+        println!("  - {id}");
+    }
+    for change in &entries.changed {
+        println!("  ~ {}: {} -> {}", change.id, change.old, change.new);
+    }
+}
+
+fn print_set(title_key: &str, set: &SetDiff) {
+    if set.is_empty() {
+        return;
+    }
+    println!("{}", crate::cli_i18n::t(title_key));
+    for id in &set.added {
+        println!("  + {id}");
+    }
+    for id in &set.removed {
+        println!("  - {id}");
+    }
+}
+
+fn format_summary(component: &ComponentSummary) -> String {
+    let no_digest = crate::cli_i18n::t("cli.diff.no_digest");
+    format!(
+        "{} {} ({})",
+        component.id,
+        component.version.as_deref().unwrap_or("-"),
+        component.digest.as_deref().unwrap_or(&no_digest),
+    )
+}
+
+fn format_change(change: &Change) -> String {
+    format!(
+        "{} -> {}",
+        change.old.as_deref().unwrap_or("-"),
+        change.new.as_deref().unwrap_or("-")
+    )
+}
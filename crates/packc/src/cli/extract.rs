@@ -0,0 +1,677 @@
+#![forbid(unsafe_code)]
+
+//! `extract`: rebuild a pack source tree (`pack.yaml`, `flows/`, `components/`)
+//! from a built `.gtpack`.
+//!
+//! Flows come from the `--dev` sources when the archive carries them and are
+//! decompiled from `manifest.cbor` otherwise. Each component is restored as
+//! `components/<id>/component.wasm` next to its manifest; the flow resolve
+//! sidecars and `pack.lock.cbor` point at those files, so rebuilding the tree
+//! reproduces the archived component digests.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Parser;
+use greentic_flow::flow_ir::{FlowIr, NodeIr, Route};
+use greentic_pack::pack_lock::{PackLockV1, decode_pack_lock, write_pack_lock};
+use greentic_pack::{PackLoad, SigningPolicy, open_pack_with_limits};
+use greentic_types::cbor::canonical;
+use greentic_types::flow_resolve::{
+    ComponentSourceRefV1, FlowResolveV1, NodeResolveV1, sidecar_path_for_flow, write_flow_resolve,
+};
+use greentic_types::flow_resolve_summary::{
+    FLOW_RESOLVE_SUMMARY_SCHEMA_VERSION, FlowResolveSummaryManifestV1,
+    FlowResolveSummarySourceRefV1, FlowResolveSummaryV1, NodeResolveSummaryV1,
+    resolve_summary_path_for_flow, write_flow_resolve_summary,
+};
+use greentic_types::pack::extensions::component_manifests::EXT_COMPONENT_MANIFEST_INDEX_V1;
+use greentic_types::pack::extensions::component_sources::EXT_COMPONENT_SOURCES_V1;
+use greentic_types::{ComponentManifest, Flow, FlowKind, NodeId, PackManifest, Routing};
+use indexmap::IndexMap;
+use serde_json::json;
+
+use crate::build::EXT_BUILD_MODE_ID;
+use crate::cli::bundle::EXT_BUNDLE_PACKS_V1;
+use crate::config::{
+    BootstrapConfig, ComponentConfig, ComponentConfiguratorConfig, ComponentOperationConfig,
+    DependencyConfig, FlowConfig, FlowKindLabel, PackConfig,
+};
+use crate::distribution::{
+    DISTRIBUTION_BUNDLE_KIND, EXT_DISTRIBUTION_BUNDLE_V1, section_from_manifest, sha256_digest,
+};
+use crate::path_safety::normalize_under_root;
+use crate::runtime::RuntimeContext;
+
+/// Manifest extensions written by `build`; a rebuild adds them again.
+const BUILD_EXTENSIONS: [&str; 4] = [
+    EXT_BUILD_MODE_ID,
+    EXT_COMPONENT_SOURCES_V1,
+    EXT_COMPONENT_MANIFEST_INDEX_V1,
+    EXT_DISTRIBUTION_BUNDLE_V1,
+];
+
+#[derive(Debug, Parser)]
+pub struct ExtractArgs {
+    /// Built .gtpack archive to extract
+    #[arg(value_name = "GTPACK")]
+    pub archive: PathBuf,
+
+    /// Directory that receives the pack sources
+    #[arg(long = "out", value_name = "DIR")]
+    pub out: PathBuf,
+
+    /// Write into an output directory that is not empty
+    #[arg(long = "force", default_value_t = false)]
+    pub force: bool,
+}
+
+/// A component binary written under `components/<id>/`.
+struct RestoredComponent {
+    /// Wasm path relative to the pack root.
+    wasm: String,
+    /// Absolute wasm path, used for `file://` lock refs.
+    wasm_abs: PathBuf,
+    digest: String,
+    manifest: ComponentManifest,
+}
+
+pub fn handle(args: ExtractArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let load = open_pack_with_limits(&args.archive, SigningPolicy::DevOk, &runtime.reader_limits)
+        .map_err(|err| anyhow!(err.message))
+        .with_context(|| format!("failed to open pack {}", args.archive.display()))?;
+    let manifest = load
+        .gpack_manifest
+        .as_ref()
+        .ok_or_else(|| anyhow!("{} has no pack manifest", args.archive.display()))?;
+    if manifest
+        .extensions
+        .as_ref()
+        .is_some_and(|exts| exts.contains_key(EXT_BUNDLE_PACKS_V1))
+    {
+        bail!(
+            "{} is a distribution bundle; use `greentic-pack unbundle` to recover its packs",
+            args.archive.display()
+        );
+    }
+    let lock = load
+        .files
+        .get("pack.lock.cbor")
+        .map(|bytes| decode_pack_lock(bytes))
+        .transpose()
+        .context("archive pack.lock.cbor is invalid")?;
+
+    prepare_out_dir(&args.out, args.force)?;
+    let out = args
+        .out
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", args.out.display()))?;
+
+    let mut warnings = Vec::new();
+    let components = restore_components(&load, manifest, lock.as_ref(), &out, &mut warnings)?;
+    for entry in &manifest.flows {
+        let flow_id = entry.id.to_string();
+        let source = match load.files.get(&format!("flows/{flow_id}/flow.ygtc")) {
+            Some(bytes) => bytes.clone(),
+            None => decompile_flow(&entry.flow)
+                .with_context(|| format!("failed to decompile flow {flow_id}"))?
+                .into_bytes(),
+        };
+        let flow_path = write_file(&out, &format!("flows/{flow_id}.ygtc"), &source)?;
+        write_sidecars(&flow_path, &entry.flow, &components, &mut warnings)?;
+    }
+
+    let config = pack_config(manifest, &components)?;
+    let pack_yaml = serde_yaml_bw::to_string(&config).context("failed to render pack.yaml")?;
+    write_file(&out, "pack.yaml", pack_yaml.as_bytes())?;
+
+    let mut restored = restore_files(&load, &out)?;
+    if !manifest.secret_requirements.is_empty()
+        && !restored
+            .iter()
+            .any(|path| path == "secret-requirements.json" || path == "secrets_requirements.json")
+    {
+        let bytes = serde_json::to_vec_pretty(&manifest.secret_requirements)
+            .context("failed to serialise secret requirements")?;
+        write_file(&out, "secret-requirements.json", &bytes)?;
+        restored.push("secret-requirements.json".to_string());
+    }
+
+    match lock {
+        Some(mut lock) => {
+            relocate_lock(&mut lock, &components);
+            write_pack_lock(&out.join("pack.lock.cbor"), &lock)?;
+        }
+        None => warnings.push(
+            "archive has no pack.lock.cbor; run `greentic-pack resolve` before building"
+                .to_string(),
+        ),
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "archive": args.archive,
+                "out": out,
+                "pack_id": manifest.pack_id.to_string(),
+                "version": manifest.version.to_string(),
+                "components": components.keys().collect::<Vec<_>>(),
+                "flows": manifest.flows.iter().map(|entry| entry.id.to_string()).collect::<Vec<_>>(),
+                "files": restored,
+                "warnings": warnings,
+            }))?
+        );
+    } else {
+        for warning in &warnings {
+            eprintln!("{}", crate::cli_i18n::tf("cli.extract.warning", &[warning]));
+        }
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.extract.done",
+                &[
+                    manifest.pack_id.as_str(),
+                    &manifest.version.to_string(),
+                    &out.display().to_string(),
+                ],
+            )
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.extract.summary",
+                &[
+                    &components.len().to_string(),
+                    &manifest.flows.len().to_string(),
+                    &restored.len().to_string(),
+                ],
+            )
+        );
+    }
+    Ok(())
+}
+
+fn prepare_out_dir(out: &Path, force: bool) -> Result<()> {
+    if out.exists() {
+        let mut entries =
+            fs::read_dir(out).with_context(|| format!("failed to list {}", out.display()))?;
+        if entries.next().is_some() && !force {
+            bail!(
+                "{} is not empty (pass --force to extract into it)",
+                out.display()
+            );
+        }
+    }
+    fs::create_dir_all(out).with_context(|| format!("failed to create {}", out.display()))
+}
+
+fn write_file(root: &Path, logical: &str, bytes: &[u8]) -> Result<PathBuf> {
+    let path = normalize_under_root(root, Path::new(logical))
+        .with_context(|| format!("archive entry {logical} escapes the output directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Write each manifest component's Wasm, manifest and describe cache under
+/// `components/<id>/`.
+fn restore_components(
+    load: &PackLoad,
+    manifest: &PackManifest,
+    lock: Option<&PackLockV1>,
+    out: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<BTreeMap<String, RestoredComponent>> {
+    let mut restored = BTreeMap::new();
+    for component in &manifest.components {
+        let id = component.id.to_string();
+        let locked = lock.and_then(|lock| lock.components.get(&id));
+        // Components pulled in through tag refs are stored by digest.
+        let blob = locked.and_then(|entry| {
+            entry
+                .resolved_digest
+                .strip_prefix("sha256:")
+                .map(|hex| format!("blobs/sha256/{hex}.wasm"))
+        });
+        let source = std::iter::once(format!("components/{id}.wasm"))
+            .chain(blob)
+            .find(|path| load.files.contains_key(path));
+        let Some(source) = source else {
+            warnings.push(format!(
+                "component {id} is not bundled in the archive; add components/{id}/component.wasm before building"
+            ));
+            continue;
+        };
+
+        let bytes = &load.files[&source];
+        let digest = sha256_digest(bytes);
+        if let Some(entry) = locked
+            && entry.resolved_digest != digest
+        {
+            bail!(
+                "component {id} in the archive has digest {digest}, but pack.lock.cbor records {}",
+                entry.resolved_digest
+            );
+        }
+
+        let wasm = format!("components/{id}/component.wasm");
+        let wasm_abs = write_file(out, &wasm, bytes)?;
+        let manifest_bytes = match load.files.get(&format!("components/{id}.manifest.cbor")) {
+            Some(bytes) => bytes.clone(),
+            None => canonical::to_canonical_cbor_allow_floats(component)
+                .context("encode component manifest to canonical cbor")?,
+        };
+        write_file(
+            out,
+            &format!("components/{id}/component.manifest.cbor"),
+            &manifest_bytes,
+        )?;
+        if let Some(describe) = load.files.get(&format!("{source}.describe.cbor")) {
+            write_file(out, &format!("{wasm}.describe.cbor"), describe)?;
+        }
+
+        restored.insert(
+            id,
+            RestoredComponent {
+                wasm,
+                wasm_abs,
+                digest,
+                manifest: component.clone(),
+            },
+        );
+    }
+    Ok(restored)
+}
+
+/// Write `<flow>.ygtc.resolve.json` and `<flow>.ygtc.resolve.summary.json`
+/// pinning every node to its restored component.
+fn write_sidecars(
+    flow_path: &Path,
+    flow: &Flow,
+    components: &BTreeMap<String, RestoredComponent>,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    let mut resolve_nodes = BTreeMap::new();
+    let mut summary_nodes = BTreeMap::new();
+    for (node_id, node) in &flow.nodes {
+        let component_id = node.component.id.as_str();
+        let restored = components
+            .get(component_id)
+            .filter(|_| node.component.pack_alias.is_none());
+        let Some(restored) = restored else {
+            warnings.push(format!(
+                "flow {} node {node_id} uses component {component_id}, which the archive does not bundle; add its resolve entry before building",
+                flow.id
+            ));
+            continue;
+        };
+        let path = format!("../{}", restored.wasm);
+        resolve_nodes.insert(
+            node_id.to_string(),
+            NodeResolveV1 {
+                source: ComponentSourceRefV1::Local {
+                    path: path.clone(),
+                    digest: Some(restored.digest.clone()),
+                },
+                mode: None,
+            },
+        );
+        summary_nodes.insert(
+            node_id.to_string(),
+            NodeResolveSummaryV1 {
+                component_id: node.component.id.clone(),
+                source: FlowResolveSummarySourceRefV1::Local { path },
+                digest: restored.digest.clone(),
+                manifest: Some(FlowResolveSummaryManifestV1 {
+                    world: restored.manifest.world.clone(),
+                    version: restored.manifest.version.clone(),
+                }),
+            },
+        );
+    }
+
+    let file_name = flow_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let sidecar_path = sidecar_path_for_flow(flow_path);
+    let sidecar = FlowResolveV1 {
+        schema_version: 1,
+        flow: file_name.clone(),
+        nodes: resolve_nodes,
+    };
+    write_flow_resolve(&sidecar_path, &sidecar)
+        .with_context(|| format!("failed to write {}", sidecar_path.display()))?;
+    let summary = FlowResolveSummaryV1 {
+        schema_version: FLOW_RESOLVE_SUMMARY_SCHEMA_VERSION,
+        flow: file_name,
+        nodes: summary_nodes,
+    };
+    write_flow_resolve_summary(&resolve_summary_path_for_flow(flow_path), &summary)
+        .map_err(|e| anyhow!(e.to_string()))?;
+    Ok(())
+}
+
+/// Render a compiled flow back to YGTC source.
+///
+/// Nodes are written in the v2 shape: the operation is the node key, or the
+/// component id for nodes without one. The resolve summary supplies component
+/// ids again on rebuild.
+fn decompile_flow(flow: &Flow) -> Result<String> {
+    let mut nodes = IndexMap::new();
+    for (node_id, node) in &flow.nodes {
+        let telemetry = &node.telemetry;
+        let has_telemetry = telemetry.span_name.is_some()
+            || !telemetry.attributes.is_empty()
+            || telemetry.sampling.is_some();
+        nodes.insert(
+            node_id.to_string(),
+            NodeIr {
+                id: node_id.to_string(),
+                operation: node
+                    .component
+                    .operation
+                    .clone()
+                    .unwrap_or_else(|| node.component.id.to_string()),
+                payload: node.input.mapping.clone(),
+                output: node.output.mapping.clone(),
+                routing: routes(&node.routing)
+                    .with_context(|| format!("node {node_id} has unsupported routing"))?,
+                telemetry: has_telemetry.then(|| {
+                    json!({
+                        "span_name": telemetry.span_name,
+                        "attributes": telemetry.attributes,
+                        "sampling": telemetry.sampling,
+                    })
+                }),
+            },
+        );
+    }
+
+    let ir = FlowIr {
+        id: flow.id.to_string(),
+        title: flow.metadata.title.clone(),
+        description: flow.metadata.description.clone(),
+        kind: flow_type(flow.kind).to_string(),
+        start: None,
+        parameters: flow.metadata.extra.clone(),
+        tags: flow.metadata.tags.iter().cloned().collect(),
+        schema_version: Some(2),
+        entrypoints: flow
+            .entrypoints
+            .iter()
+            .filter_map(|(name, target)| Some((name.clone(), target.as_str()?.to_string())))
+            .collect(),
+        meta: None,
+        nodes,
+    };
+    let doc = ir.to_doc().context("failed to rebuild flow document")?;
+    serde_yaml_bw::to_string(&doc).context("failed to render flow YAML")
+}
+
+fn routes(routing: &Routing) -> Result<Vec<Route>> {
+    Ok(match routing {
+        Routing::Next { node_id } => vec![route_to(node_id)],
+        Routing::Branch { on_status, default } => on_status
+            .iter()
+            .map(|(status, node_id)| Route {
+                status: Some(status.clone()),
+                ..route_to(node_id)
+            })
+            .chain(default.iter().map(route_to))
+            .collect(),
+        Routing::End => vec![Route {
+            out: true,
+            ..Route::default()
+        }],
+        Routing::Reply => vec![Route {
+            reply: true,
+            ..Route::default()
+        }],
+        Routing::Custom(raw) => serde_json::from_value(raw.clone())?,
+    })
+}
+
+fn route_to(node_id: &NodeId) -> Route {
+    Route {
+        to: Some(node_id.to_string()),
+        ..Route::default()
+    }
+}
+
+/// The YGTC `type` of a flow kind.
+fn flow_type(kind: FlowKind) -> &'static str {
+    match kind {
+        FlowKind::Messaging => "messaging",
+        FlowKind::Event => "event",
+        FlowKind::ComponentConfig => "component-config",
+        FlowKind::Job => "job",
+        FlowKind::Http => "http",
+    }
+}
+
+/// Regenerate pack.yaml from the archived manifest.
+fn pack_config(
+    manifest: &PackManifest,
+    components: &BTreeMap<String, RestoredComponent>,
+) -> Result<PackConfig> {
+    let distribution = section_from_manifest(manifest).transpose()?;
+    let kind = if distribution.is_some() {
+        DISTRIBUTION_BUNDLE_KIND.to_string()
+    } else {
+        serde_json::to_value(manifest.kind)?
+            .as_str()
+            .map(str::to_ascii_lowercase)
+            .ok_or_else(|| anyhow!("pack kind {:?} has no pack.yaml label", manifest.kind))?
+    };
+    let extensions = manifest
+        .extensions
+        .as_ref()
+        .map(|exts| {
+            exts.iter()
+                .filter(|(key, _)| !BUILD_EXTENSIONS.contains(&key.as_str()))
+                .map(|(key, ext)| (key.clone(), ext.clone()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .filter(|exts| !exts.is_empty());
+
+    Ok(PackConfig {
+        pack_id: manifest.pack_id.to_string(),
+        version: manifest.version.to_string(),
+        kind,
+        publisher: manifest.publisher.clone(),
+        name: manifest.name.clone(),
+        bootstrap: manifest.bootstrap.as_ref().map(|spec| BootstrapConfig {
+            install_flow: spec.install_flow.clone(),
+            upgrade_flow: spec.upgrade_flow.clone(),
+            installer_component: spec.installer_component.clone(),
+        }),
+        components: manifest
+            .components
+            .iter()
+            .map(|component| component_config(component, components))
+            .collect(),
+        dependencies: manifest
+            .dependencies
+            .iter()
+            .map(|dep| DependencyConfig {
+                alias: dep.alias.clone(),
+                pack_id: dep.pack_id.to_string(),
+                version_req: dep.version_req.to_string(),
+                required_capabilities: dep.required_capabilities.clone(),
+            })
+            .collect(),
+        flows: manifest
+            .flows
+            .iter()
+            .map(|entry| FlowConfig {
+                id: entry.id.to_string(),
+                file: PathBuf::from(format!("flows/{}.ygtc", entry.id)),
+                tags: entry.tags.clone(),
+                entrypoints: entry.entrypoints.clone(),
+            })
+            .collect(),
+        // Archived assets are restored as files and picked up again as extra dirs.
+        assets: Vec::new(),
+        // Composed MCP components are restored as plain components.
+        mcp_components: Vec::new(),
+        extensions,
+        distribution,
+    })
+}
+
+fn component_config(
+    manifest: &ComponentManifest,
+    components: &BTreeMap<String, RestoredComponent>,
+) -> ComponentConfig {
+    let id = manifest.id.to_string();
+    let wasm = components
+        .get(&id)
+        .map(|restored| restored.wasm.clone())
+        .unwrap_or_else(|| format!("components/{id}/component.wasm"));
+    ComponentConfig {
+        id,
+        version: manifest.version.to_string(),
+        world: manifest.world.clone(),
+        supports: manifest
+            .supports
+            .iter()
+            .copied()
+            .map(FlowKindLabel::from_kind)
+            .collect(),
+        profiles: manifest.profiles.clone(),
+        capabilities: manifest.capabilities.clone(),
+        wasm: PathBuf::from(wasm),
+        operations: manifest
+            .operations
+            .iter()
+            .map(|op| ComponentOperationConfig {
+                name: op.name.clone(),
+                input_schema: op.input_schema.clone(),
+                output_schema: op.output_schema.clone(),
+            })
+            .collect(),
+        config_schema: manifest.config_schema.clone(),
+        resources: Some(manifest.resources.clone()),
+        configurators: manifest.configurators.as_ref().map(|configurators| {
+            ComponentConfiguratorConfig {
+                basic: configurators.basic.as_ref().map(ToString::to_string),
+                full: configurators.full.as_ref().map(ToString::to_string),
+            }
+        }),
+    }
+}
+
+/// Whether an archive entry is produced by `build` rather than copied from
+/// the pack sources.
+fn is_build_output(path: &str) -> bool {
+    matches!(
+        path,
+        "manifest.cbor"
+            | "pack.yaml"
+            | "pack.lock.cbor"
+            | "sbom.cbor"
+            | "sbom.json"
+            | crate::sbom::CYCLONEDX_PATH
+            | crate::sbom::SPDX_PATH
+    ) || ["signatures/", "components/", "blobs/", "flows/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+/// Copy assets, distribution artifacts and other source files verbatim.
+fn restore_files(load: &PackLoad, out: &Path) -> Result<Vec<String>> {
+    let mut paths: Vec<_> = load
+        .files
+        .keys()
+        .filter(|path| !is_build_output(path))
+        .cloned()
+        .collect();
+    paths.sort();
+    for path in &paths {
+        write_file(out, path, &load.files[path])?;
+    }
+    Ok(paths)
+}
+
+/// Point restored components at their extracted Wasm; other entries are kept.
+fn relocate_lock(lock: &mut PackLockV1, components: &BTreeMap<String, RestoredComponent>) {
+    for entry in lock.components.values_mut() {
+        if let Some(restored) = components.get(&entry.component_id) {
+            entry.r#ref = Some(format!("file://{}", restored.wasm_abs.to_string_lossy()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_flow::compile_ygtc_str;
+
+    fn roundtrip(src: &str) -> (Flow, Flow) {
+        let flow = compile_ygtc_str(src).expect("compile source");
+        let yaml = decompile_flow(&flow).expect("decompile");
+        let again = compile_ygtc_str(&yaml).unwrap_or_else(|err| panic!("{err}\n{yaml}"));
+        (flow, again)
+    }
+
+    #[test]
+    fn decompiled_flow_compiles_to_the_same_flow() {
+        let (flow, again) = roundtrip(
+            r#"id: main
+type: messaging
+title: Main
+tags: [support]
+start: ask
+nodes:
+  ask:
+    handle_message:
+      input: hi
+    routing:
+      - status: ok
+        to: done
+      - to: retry
+  retry:
+    handle_message:
+      input: again
+    routing:
+      - to: done
+  done:
+    reply_message: {}
+    routing: reply
+"#,
+        );
+        assert_eq!(
+            serde_json::to_value(&flow).unwrap(),
+            serde_json::to_value(&again).unwrap()
+        );
+    }
+
+    #[test]
+    fn decompiled_flow_keeps_end_routing_and_entrypoints() {
+        let (flow, again) = roundtrip(
+            r#"id: setup
+type: component-config
+entrypoints:
+  configure: step
+nodes:
+  step:
+    dev.local.component:
+      mode: basic
+    routing: out
+"#,
+        );
+        assert!(matches!(
+            again.nodes.values().next().unwrap().routing,
+            Routing::End
+        ));
+        assert_eq!(again.entrypoints, flow.entrypoints);
+        assert!(matches!(again.kind, FlowKind::ComponentConfig));
+    }
+}
@@ -203,6 +203,8 @@ pub async fn handle(
         component_out: None,
         manifest_out: pack_root.join("dist").join("manifest.cbor"),
         sbom_out: None,
+        spdx_out: None,
+        embed_sbom: Vec::new(),
         gtpack_out: Some(opts.out.clone()),
         lock_path: pack_root.join("pack.lock.cbor"),
         bundle: build::BundleMode::Cache,
@@ -216,6 +218,9 @@ pub async fn handle(
         runtime: runtime.clone(),
         skip_update: false,
         allow_pack_schema: false,
+        compression: greentic_pack::builder::PackCompression::Stored,
+        incremental: false,
+        explain: false,
     };
     build::run(&build_opts).await?;
 
@@ -1,25 +1,21 @@
 #![forbid(unsafe_code)]
 
-use std::io::Write;
 use std::{
-    collections::HashMap,
-    fs, io,
+    collections::{BTreeMap, HashMap},
+    fs,
     path::{Path, PathBuf},
-    process::{Command, Stdio},
 };
 
 use anyhow::{Context, Result, anyhow, bail};
 use clap::Parser;
+use greentic_pack::trust::TrustPolicy;
 use greentic_pack::validate::{
     ComponentReferencesExistValidator, ProviderReferencesExistValidator,
     ReferencedFilesExistValidator, SbomConsistencyValidator, SecretRequirementsValidator,
     ValidateCtx, run_validators,
 };
-use greentic_pack::{PackLoad, SigningPolicy, open_pack};
+use greentic_pack::{PackLoad, ReaderLimits, SigningPolicy};
 use greentic_types::component_source::ComponentSourceRef;
-use greentic_types::pack::extensions::component_manifests::{
-    ComponentManifestIndexV1, EXT_COMPONENT_MANIFEST_INDEX_V1,
-};
 use greentic_types::pack::extensions::component_sources::{
     ArtifactLocationV1, ComponentSourcesV1, EXT_COMPONENT_SOURCES_V1,
 };
@@ -27,15 +23,22 @@ use greentic_types::pack_manifest::{ExtensionInline as PackManifestExtensionInli
 use greentic_types::provider::ProviderDecl;
 use greentic_types::validate::{Diagnostic, Severity, ValidationReport};
 use serde::Serialize;
-use serde_cbor;
 use serde_json::Value;
-use tempfile::TempDir;
 
 use crate::build;
-use crate::pack_lock_doctor::{PackLockDoctorInput, run_pack_lock_doctor};
+use crate::component_doctor::run_component_doctor;
+pub use crate::diagnostics::ReportFormat as InspectFormat;
+use crate::diagnostics::{SpannedDiagnostic, to_junit, to_sarif};
+use crate::distribution::run_distribution_doctor;
+use crate::flow_doctor::{FlowDoctorInput, run_flow_doctor};
+use crate::lazy_entries::LazyEntries;
+use crate::pack_lock_doctor::{
+    PackLockDoctorInput, load_pack_lock, run_pack_lock_doctor_with_archive,
+};
 use crate::runtime::RuntimeContext;
 use crate::validator::{
-    DEFAULT_VALIDATOR_ALLOW, LocalValidator, ValidatorConfig, ValidatorPolicy, run_wasm_validators,
+    DEFAULT_VALIDATOR_ALLOW, LocalValidator, ValidatorConfig, ValidatorLimitOverrides,
+    ValidatorLimitsConfig, ValidatorPolicy, run_wasm_validators,
 };
 
 const EXT_BUILD_MODE_ID: &str = "greentic.pack-mode.v1";
@@ -116,6 +119,27 @@ pub struct InspectArgs {
     #[arg(long, value_enum, default_value = "optional")]
     pub validator_policy: ValidatorPolicy,
 
+    /// Fuel budget per validator component
+    #[arg(long, value_name = "UNITS")]
+    pub validator_fuel: Option<u64>,
+
+    /// Maximum linear memory per validator component, in bytes
+    #[arg(long, value_name = "BYTES")]
+    pub validator_max_memory: Option<usize>,
+
+    /// Wall-clock timeout per validator component, in milliseconds
+    #[arg(long, value_name = "MS")]
+    pub validator_timeout_ms: Option<u64>,
+
+    /// Maximum table elements per validator component
+    #[arg(long, value_name = "COUNT")]
+    pub validator_max_table_elements: Option<usize>,
+
+    /// Limits for one validator component, over the global --validator-* flags
+    /// (format: <COMPONENT_ID>=fuel:N,memory:BYTES,timeout-ms:MS,table-elements:N)
+    #[arg(long, value_name = "COMPONENT=LIMITS")]
+    pub validator_limit: Vec<String>,
+
     /// Allow online resolution of component refs during pack lock checks (default: offline)
     #[arg(long, default_value_t = false)]
     pub online: bool,
@@ -123,6 +147,10 @@ pub struct InspectArgs {
     /// Allow describe cache fallback when components cannot execute describe()
     #[arg(long = "use-describe-cache", default_value_t = false)]
     pub use_describe_cache: bool,
+
+    /// Trust policy directory (roots/, keys/, policy.yaml); defaults to <greentic_root>/trust
+    #[arg(long = "trust-dir", value_name = "DIR")]
+    pub trust_dir: Option<PathBuf>,
 }
 
 pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
@@ -134,9 +162,17 @@ pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) ->
         args.validate
     };
 
-    let load = match &mode {
-        InspectMode::Archive(path) => inspect_pack_file(path)?,
-        InspectMode::Source(path) => inspect_source_dir(path, runtime, args.allow_oci_tags).await?,
+    // Archives keep component Wasm on disk until a check reads it.
+    let (load, archive) = match &mode {
+        InspectMode::Archive(path) => {
+            let trust = runtime.trust_policy(args.trust_dir.as_deref())?;
+            let (load, archive) = inspect_pack_file(path, &trust, &runtime.reader_limits)?;
+            (load, Some(archive))
+        }
+        InspectMode::Source(path) => (
+            inspect_source_dir(path, runtime, args.allow_oci_tags).await?,
+            None,
+        ),
     };
     let build_mode = detect_pack_build_mode(&load);
     if matches!(mode, InspectMode::Archive(_)) && build_mode == PackBuildMode::Prod {
@@ -149,7 +185,7 @@ pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) ->
         }
     }
     let validation = if validate_enabled {
-        let mut output = run_pack_validation(&load, &args, runtime).await?;
+        let mut output = run_pack_validation(&load, archive.as_ref(), &args, runtime).await?;
         let mut doctor_diagnostics = Vec::new();
         let mut doctor_errors = false;
         if args.component_doctor {
@@ -160,23 +196,43 @@ pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) ->
                 InspectMode::Source(path) => Some(path.as_path()),
                 InspectMode::Archive(_) => None,
             };
-            let pack_lock_output = run_pack_lock_doctor(PackLockDoctorInput {
-                load: &load,
-                pack_dir,
-                runtime,
-                allow_oci_tags: args.allow_oci_tags,
-                use_describe_cache,
-                online: args.online,
-            })?;
+            let pack_lock_output = run_pack_lock_doctor_with_archive(
+                PackLockDoctorInput {
+                    load: &load,
+                    pack_dir,
+                    runtime,
+                    allow_oci_tags: args.allow_oci_tags,
+                    use_describe_cache,
+                    online: args.online,
+                },
+                archive.as_ref(),
+            )?;
             doctor_errors |= pack_lock_output.has_errors;
             doctor_diagnostics.extend(pack_lock_output.diagnostics);
         }
         if args.flow_doctor {
-            doctor_errors |= run_flow_doctors(&load, &mut doctor_diagnostics, build_mode)?;
+            let pack_dir = match &mode {
+                InspectMode::Source(path) => Some(path.as_path()),
+                InspectMode::Archive(_) => None,
+            };
+            // A broken lock is reported by the pack lock doctor; flows are still checked.
+            let lock = load_pack_lock(&load, pack_dir).ok().flatten();
+            let flow_output = run_flow_doctor(FlowDoctorInput {
+                load: &load,
+                lock: lock.as_ref(),
+                require_sources: build_mode == PackBuildMode::Dev,
+            });
+            doctor_errors |= flow_output.has_errors;
+            doctor_diagnostics.extend(flow_output.diagnostics);
         }
         if args.component_doctor {
-            doctor_errors |= run_component_doctors(&load, &mut doctor_diagnostics)?;
+            let component_output = run_component_doctor(&load, archive.as_ref());
+            doctor_errors |= component_output.has_errors;
+            doctor_diagnostics.extend(component_output.diagnostics);
         }
+        let distribution_output = run_distribution_doctor(&load, archive.as_ref());
+        doctor_errors |= distribution_output.has_errors;
+        doctor_diagnostics.extend(distribution_output.diagnostics);
         output.report.diagnostics.extend(doctor_diagnostics);
         output.has_errors |= doctor_errors;
         Some(output)
@@ -191,6 +247,8 @@ pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) ->
                 "report": {
                     "signature_ok": load.report.signature_ok,
                     "sbom_ok": load.report.sbom_ok,
+                    "signatures": load.report.signatures,
+                    "trust": load.report.trust,
                     "warnings": load.report.warnings,
                 },
                 "sbom": load.sbom,
@@ -203,6 +261,26 @@ pub async fn handle(args: InspectArgs, json: bool, runtime: &RuntimeContext) ->
         InspectFormat::Human => {
             print_human(&load, validation.as_ref());
         }
+        InspectFormat::Sarif | InspectFormat::Junit => {
+            let diagnostics: Vec<SpannedDiagnostic> = validation
+                .iter()
+                .flat_map(|output| output.report.diagnostics.iter().cloned())
+                .map(SpannedDiagnostic::from)
+                .collect();
+            let base = match &mode {
+                InspectMode::Source(path) => path.as_path(),
+                InspectMode::Archive(_) => Path::new(""),
+            };
+            if format == InspectFormat::Sarif {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&to_sarif(&diagnostics, base))?
+                );
+            } else {
+                let suite = format!("greentic-pack doctor {}", load.manifest.meta.pack_id);
+                print!("{}", to_junit(&suite, &diagnostics, base));
+            }
+        }
     }
 
     if validate_enabled
@@ -238,276 +316,13 @@ fn sort_json(value: Value) -> Value {
     }
 }
 
-fn run_flow_doctors(
-    load: &PackLoad,
-    diagnostics: &mut Vec<Diagnostic>,
-    build_mode: PackBuildMode,
-) -> Result<bool> {
-    if load.manifest.flows.is_empty() {
-        return Ok(false);
-    }
-
-    let mut has_errors = false;
-
-    for flow in &load.manifest.flows {
-        let Some(bytes) = load.files.get(&flow.file_yaml) else {
-            if build_mode == PackBuildMode::Prod {
-                continue;
-            }
-            diagnostics.push(Diagnostic {
-                severity: Severity::Error,
-                code: "PACK_FLOW_DOCTOR_MISSING_FLOW".to_string(),
-                message: "flow file missing from pack".to_string(),
-                path: Some(flow.file_yaml.clone()),
-                hint: Some("rebuild the pack to include flow sources".to_string()),
-                data: Value::Null,
-            });
-            has_errors = true;
-            continue;
-        };
-
-        let mut command = Command::new("greentic-flow");
-        command
-            .args(["doctor", "--json", "--stdin"])
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        let mut child = match command.spawn() {
-            Ok(child) => child,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                diagnostics.push(Diagnostic {
-                    severity: Severity::Warn,
-                    code: "PACK_FLOW_DOCTOR_UNAVAILABLE".to_string(),
-                    message: "greentic-flow not available; skipping flow doctor checks".to_string(),
-                    path: None,
-                    hint: Some("install greentic-flow or pass --no-flow-doctor".to_string()),
-                    data: Value::Null,
-                });
-                return Ok(false);
-            }
-            Err(err) => return Err(err).context("run greentic-flow doctor"),
-        };
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin
-                .write_all(bytes)
-                .context("write flow content to greentic-flow stdin")?;
-        }
-        let output = child
-            .wait_with_output()
-            .context("wait for greentic-flow doctor")?;
-
-        if !output.status.success() {
-            if flow_doctor_unsupported(&output) {
-                diagnostics.push(Diagnostic {
-                    severity: Severity::Warn,
-                    code: "PACK_FLOW_DOCTOR_UNAVAILABLE".to_string(),
-                    message: "greentic-flow does not support --stdin; skipping flow doctor checks"
-                        .to_string(),
-                    path: None,
-                    hint: Some("update greentic-flow or pass --no-flow-doctor".to_string()),
-                    data: json_diagnostic_data(&output),
-                });
-                return Ok(false);
-            }
-            has_errors = true;
-            diagnostics.push(Diagnostic {
-                severity: Severity::Error,
-                code: "PACK_FLOW_DOCTOR_FAILED".to_string(),
-                message: "flow doctor failed".to_string(),
-                path: Some(flow.file_yaml.clone()),
-                hint: Some("run `greentic-flow doctor` for details".to_string()),
-                data: json_diagnostic_data(&output),
-            });
-        }
-    }
-
-    Ok(has_errors)
-}
-
-fn flow_doctor_unsupported(output: &std::process::Output) -> bool {
-    let mut combined = String::new();
-    combined.push_str(&String::from_utf8_lossy(&output.stdout));
-    combined.push_str(&String::from_utf8_lossy(&output.stderr));
-    let combined = combined.to_lowercase();
-    combined.contains("--stdin") && combined.contains("unknown")
-        || combined.contains("found argument '--stdin'")
-        || combined.contains("unexpected argument '--stdin'")
-        || combined.contains("unrecognized option '--stdin'")
-}
-
-fn run_component_doctors(load: &PackLoad, diagnostics: &mut Vec<Diagnostic>) -> Result<bool> {
-    if load.manifest.components.is_empty() {
-        return Ok(false);
-    }
-
-    let temp = TempDir::new().context("allocate temp dir for component doctor")?;
-    let mut has_errors = false;
-
-    let mut manifest_paths = std::collections::HashMap::new();
-    if let Some(gpack_manifest) = load.gpack_manifest.as_ref()
-        && let Some(manifest_extension) = gpack_manifest
-            .extensions
-            .as_ref()
-            .and_then(|map| map.get(EXT_COMPONENT_MANIFEST_INDEX_V1))
-            .and_then(|entry| entry.inline.as_ref())
-            .and_then(|inline| match inline {
-                PackManifestExtensionInline::Other(value) => Some(value),
-                _ => None,
-            })
-            .and_then(|value| ComponentManifestIndexV1::from_extension_value(value).ok())
-    {
-        for entry in manifest_extension.entries {
-            manifest_paths.insert(entry.component_id, entry.manifest_file);
-        }
-    }
-
-    for component in &load.manifest.components {
-        let Some(wasm_bytes) = load.files.get(&component.file_wasm) else {
-            diagnostics.push(Diagnostic {
-                severity: Severity::Warn,
-                code: "PACK_COMPONENT_DOCTOR_MISSING_WASM".to_string(),
-                message: "component wasm missing from pack; skipping component doctor".to_string(),
-                path: Some(component.file_wasm.clone()),
-                hint: Some("rebuild with --bundle=cache or supply cached artifacts".to_string()),
-                data: Value::Null,
-            });
-            continue;
-        };
-
-        if component.manifest_file.is_none() {
-            if manifest_paths.contains_key(&component.name) {
-                continue;
-            }
-            diagnostics.push(component_manifest_missing_diag(&component.manifest_file));
-            continue;
-        }
-
-        let manifest_bytes = if let Some(path) = component.manifest_file.as_deref()
-            && let Some(bytes) = load.files.get(path)
-        {
-            bytes.clone()
-        } else {
-            diagnostics.push(component_manifest_missing_diag(&component.manifest_file));
-            continue;
-        };
-
-        let component_dir = temp.path().join(sanitize_component_id(&component.name));
-        fs::create_dir_all(&component_dir)
-            .with_context(|| format!("create temp dir for {}", component.name))?;
-        let wasm_path = component_dir.join("component.wasm");
-        let manifest_value = match serde_json::from_slice::<Value>(&manifest_bytes) {
-            Ok(value) => value,
-            Err(_) => match serde_cbor::from_slice::<Value>(&manifest_bytes) {
-                Ok(value) => value,
-                Err(err) => {
-                    diagnostics.push(component_manifest_missing_diag(&component.manifest_file));
-                    tracing::debug!(
-                        manifest = %component.name,
-                        "failed to parse component manifest for doctor: {err}"
-                    );
-                    continue;
-                }
-            },
-        };
-
-        if !component_manifest_has_required_fields(&manifest_value) {
-            diagnostics.push(component_manifest_missing_diag(&component.manifest_file));
-            continue;
-        }
-
-        let manifest_bytes =
-            serde_json::to_vec_pretty(&manifest_value).context("serialize component manifest")?;
-
-        let manifest_path = component_dir.join("component.manifest.json");
-        fs::write(&wasm_path, wasm_bytes)?;
-        fs::write(&manifest_path, manifest_bytes)?;
-
-        let output = match Command::new("greentic-component")
-            .args(["doctor"])
-            .arg(&wasm_path)
-            .args(["--manifest"])
-            .arg(&manifest_path)
-            .output()
-        {
-            Ok(output) => output,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                diagnostics.push(Diagnostic {
-                    severity: Severity::Warn,
-                    code: "PACK_COMPONENT_DOCTOR_UNAVAILABLE".to_string(),
-                    message: "greentic-component not available; skipping component doctor checks"
-                        .to_string(),
-                    path: None,
-                    hint: Some(
-                        "install greentic-component or pass --no-component-doctor".to_string(),
-                    ),
-                    data: Value::Null,
-                });
-                return Ok(false);
-            }
-            Err(err) => return Err(err).context("run greentic-component doctor"),
-        };
-
-        if !output.status.success() {
-            has_errors = true;
-            diagnostics.push(Diagnostic {
-                severity: Severity::Error,
-                code: "PACK_COMPONENT_DOCTOR_FAILED".to_string(),
-                message: "component doctor failed".to_string(),
-                path: Some(component.name.clone()),
-                hint: Some("run `greentic-component doctor` for details".to_string()),
-                data: json_diagnostic_data(&output),
-            });
-        }
-    }
-
-    Ok(has_errors)
-}
-
-fn json_diagnostic_data(output: &std::process::Output) -> Value {
-    serde_json::json!({
-        "status": output.status.code(),
-        "stdout": String::from_utf8_lossy(&output.stdout).trim_end(),
-        "stderr": String::from_utf8_lossy(&output.stderr).trim_end(),
-    })
-}
-
-fn component_manifest_missing_diag(manifest_file: &Option<String>) -> Diagnostic {
-    Diagnostic {
-        severity: Severity::Warn,
-        code: "PACK_COMPONENT_DOCTOR_MISSING_MANIFEST".to_string(),
-        message: "component manifest missing or incomplete; skipping component doctor".to_string(),
-        path: manifest_file.clone(),
-        hint: Some("rebuild the pack to include component manifests".to_string()),
-        data: Value::Null,
-    }
-}
-
-fn component_manifest_has_required_fields(manifest: &Value) -> bool {
-    manifest.get("name").is_some()
-        && manifest.get("artifacts").is_some()
-        && manifest.get("hashes").is_some()
-        && manifest.get("describe_export").is_some()
-        && manifest.get("config_schema").is_some()
-}
-
-fn sanitize_component_id(value: &str) -> String {
-    value
-        .chars()
-        .map(|ch| {
-            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
-                ch
-            } else {
-                '_'
-            }
-        })
-        .collect()
-}
-
-fn inspect_pack_file(path: &Path) -> Result<PackLoad> {
-    let load = open_pack(path, SigningPolicy::DevOk)
-        .map_err(|err| anyhow!(err.message))
-        .with_context(|| format!("failed to open pack {}", path.display()))?;
-    Ok(load)
+fn inspect_pack_file(
+    path: &Path,
+    trust: &TrustPolicy,
+    limits: &ReaderLimits,
+) -> Result<(PackLoad, LazyEntries)> {
+    LazyEntries::open(path, SigningPolicy::DevOk, limits, trust)
+        .with_context(|| format!("failed to open pack {}", path.display()))
 }
 
 fn detect_pack_build_mode(load: &PackLoad) -> PackBuildMode {
@@ -604,42 +419,17 @@ fn resolve_mode(args: &InspectArgs) -> Result<InspectMode> {
     ))
 }
 
-async fn inspect_source_dir(
+pub(crate) async fn inspect_source_dir(
     dir: &Path,
     runtime: &RuntimeContext,
     allow_oci_tags: bool,
 ) -> Result<PackLoad> {
-    let pack_dir = dir
-        .canonicalize()
-        .with_context(|| format!("failed to resolve pack dir {}", dir.display()))?;
-
-    let temp = TempDir::new().context("failed to allocate temp dir for inspect")?;
-    let manifest_out = temp.path().join("manifest.cbor");
-    let gtpack_out = temp.path().join("pack.gtpack");
-
-    let opts = build::BuildOptions {
-        pack_dir,
-        component_out: None,
-        manifest_out,
-        sbom_out: None,
-        gtpack_out: Some(gtpack_out.clone()),
-        lock_path: gtpack_out.with_extension("lock.json"), // use temp lock path under temp dir
-        bundle: build::BundleMode::Cache,
-        dry_run: false,
-        secrets_req: None,
-        default_secret_scope: None,
-        allow_oci_tags,
-        require_component_manifests: false,
-        no_extra_dirs: false,
-        dev: true,
-        runtime: runtime.clone(),
-        skip_update: false,
-        allow_pack_schema: false,
-    };
-
-    build::run(&opts).await?;
-
-    inspect_pack_file(&gtpack_out)
+    let opts = build::BuildOptions::for_source_dir(dir, runtime, allow_oci_tags)?;
+    let built = build::build_in_memory(&opts).await?;
+    for warning in &built.warnings {
+        tracing::warn!(warning);
+    }
+    built.into_pack_load()
 }
 
 fn print_human(load: &PackLoad, validation: Option<&ValidationOutput>) {
@@ -747,6 +537,44 @@ fn print_human(load: &PackLoad, validation: Option<&ValidationOutput>) {
         println!("Providers: none");
     }
 
+    if report.signatures.is_empty() {
+        println!("Signatures: none");
+    } else {
+        println!("Signatures:");
+        for check in &report.signatures {
+            let status = match (check.valid, check.trust.as_ref()) {
+                (false, _) => format!(
+                    "invalid: {}",
+                    check.error.as_deref().unwrap_or("verification failed")
+                ),
+                (true, Some(decision)) if decision.accepted => {
+                    format!("verified, trusted by {}", decision.rule)
+                }
+                (true, Some(decision)) => format!("verified, untrusted: {}", decision.detail),
+                (true, None) => "verified".to_string(),
+            };
+            println!(
+                "  - {} [{}] key {}: {}",
+                check.path,
+                check.role.map_or("-", |role| role.as_str()),
+                check.key_fingerprint.as_deref().unwrap_or("unknown"),
+                status
+            );
+        }
+    }
+
+    if let Some(decision) = report.trust.as_ref() {
+        let verdict = if decision.accepted {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        println!(
+            "Trust: {} by {} ({})",
+            verdict, decision.rule, decision.detail
+        );
+    }
+
     if !report.warnings.is_empty() {
         println!("Warnings:");
         for warning in &report.warnings {
@@ -775,10 +603,14 @@ fn has_error_diagnostics(diagnostics: &[Diagnostic]) -> bool {
 
 async fn run_pack_validation(
     load: &PackLoad,
+    archive: Option<&LazyEntries>,
     args: &InspectArgs,
     runtime: &RuntimeContext,
 ) -> Result<ValidationOutput> {
-    let ctx = ValidateCtx::from_pack_load(load);
+    let mut ctx = ValidateCtx::from_pack_load(load);
+    if let Some(archive) = archive {
+        ctx.pack_paths.extend(archive.names().map(str::to_string));
+    }
     let validators: Vec<Box<dyn greentic_types::validate::PackValidator>> = vec![
         Box::new(ReferencedFilesExistValidator::new(ctx.clone())),
         Box::new(SbomConsistencyValidator::new(ctx.clone())),
@@ -807,6 +639,15 @@ async fn run_pack_validation(
         }
     };
 
+    let limits = ValidatorLimitsConfig {
+        cli: ValidatorLimitOverrides {
+            fuel: args.validator_fuel,
+            max_memory_bytes: args.validator_max_memory,
+            timeout_ms: args.validator_timeout_ms,
+            max_table_elements: args.validator_max_table_elements,
+        },
+        per_validator: parse_validator_limit_args(&args.validator_limit)?,
+    };
     let config = ValidatorConfig {
         validators_root: args.validators_root.clone(),
         validator_packs: args.validator_pack.clone(),
@@ -814,9 +655,10 @@ async fn run_pack_validation(
         validator_cache_dir: args.validator_cache_dir.clone(),
         policy: args.validator_policy,
         local_validators: parse_validator_wasm_args(&args.validator_wasm)?,
+        limits,
     };
 
-    let wasm_result = run_wasm_validators(load, &config, runtime).await?;
+    let wasm_result = run_wasm_validators(load, archive, &config, runtime).await?;
     report.diagnostics.extend(wasm_result.diagnostics);
 
     let has_errors = has_error_diagnostics(&report.diagnostics) || wasm_result.missing_required;
@@ -834,7 +676,6 @@ fn print_validation(report: &ValidationOutput) {
     println!("  Info: {info} Warn: {warn} Error: {error}");
     if report.report.diagnostics.is_empty() {
         println!("  - none");
-        return;
     }
     for diag in &report.report.diagnostics {
         let sev = match diag.severity {
@@ -857,6 +698,23 @@ fn print_validation(report: &ValidationOutput) {
             println!("    hint: {hint}");
         }
     }
+    for usage in report.sources.iter().flat_map(|source| &source.validators) {
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.doctor.validator_usage",
+                &[
+                    &usage.component_id,
+                    &usage.outcome,
+                    &usage.fuel_consumed.to_string(),
+                    &usage.limits.fuel.to_string(),
+                    &usage.peak_memory_bytes.to_string(),
+                    &usage.limits.max_memory_bytes.to_string(),
+                    &usage.elapsed_ms.to_string(),
+                ],
+            )
+        );
+    }
 }
 
 fn parse_validator_wasm_args(args: &[String]) -> Result<Vec<LocalValidator>> {
@@ -888,6 +746,65 @@ fn parse_validator_wasm_args(args: &[String]) -> Result<Vec<LocalValidator>> {
     Ok(local_validators)
 }
 
+fn parse_validator_limit_args(
+    args: &[String],
+) -> Result<BTreeMap<String, ValidatorLimitOverrides>> {
+    let mut per_validator: BTreeMap<String, ValidatorLimitOverrides> = BTreeMap::new();
+    for entry in args {
+        let (component_id, spec) = entry
+            .split_once('=')
+            .map(|(id, spec)| (id.trim(), spec.trim()))
+            .filter(|(_, spec)| !spec.is_empty())
+            .ok_or_else(|| {
+                anyhow!(
+                    "invalid --validator-limit argument `{}` (expected format COMPONENT_ID=KEY:VALUE,...)",
+                    entry
+                )
+            })?;
+        if component_id.is_empty() {
+            return Err(anyhow!(
+                "validator component id must not be empty in `{}`",
+                entry
+            ));
+        }
+        let overrides = per_validator.entry(component_id.to_string()).or_default();
+        for pair in spec.split(',') {
+            let (key, value) = pair
+                .split_once(':')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| anyhow!("invalid limit `{}` in `{}`", pair, entry))?;
+            match key {
+                "fuel" => overrides.fuel = Some(parse_limit_value(key, value, entry)?),
+                "memory" => {
+                    overrides.max_memory_bytes = Some(parse_limit_value(key, value, entry)?)
+                }
+                "timeout-ms" => overrides.timeout_ms = Some(parse_limit_value(key, value, entry)?),
+                "table-elements" => {
+                    overrides.max_table_elements = Some(parse_limit_value(key, value, entry)?)
+                }
+                other => {
+                    return Err(anyhow!(
+                        "unknown limit `{}` in `{}` (expected fuel, memory, timeout-ms or table-elements)",
+                        other,
+                        entry
+                    ));
+                }
+            }
+        }
+    }
+    Ok(per_validator)
+}
+
+fn parse_limit_value<T>(key: &str, value: &str, entry: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|err| anyhow!("invalid value for `{}` in `{}`: {}", key, entry, err))
+}
+
 fn print_doctor_failure_details(data: &Value) {
     let Some(obj) = data.as_object() else {
         return;
@@ -926,12 +843,6 @@ fn validation_counts(report: &ValidationReport) -> (usize, usize, usize) {
     (info, warn, error)
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
-pub enum InspectFormat {
-    Human,
-    Json,
-}
-
 fn resolve_format(args: &InspectArgs, json: bool) -> InspectFormat {
     if json {
         InspectFormat::Json
@@ -1,13 +1,32 @@
 #![forbid(unsafe_code)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::Parser;
 use greentic_flow::compile_ygtc_str;
+use greentic_flow::json_output::{JsonDiagnostic, flow_error_to_reports};
+use greentic_types::ExtensionRef;
+use greentic_types::error::ErrorCode;
+use greentic_types::flow_resolve::{read_flow_resolve, sidecar_path_for_flow};
+use serde::de::DeserializeOwned;
+use serde_yaml_bw::Value as YamlValue;
 use tracing::info;
 
-use crate::config::load_pack_config;
+use crate::config::{
+    AssetConfig, ComponentConfig, DependencyConfig, FlowConfig, PackConfig, strict_extensions,
+    validate_extension, validate_mcp_components, validate_pack_distribution,
+};
+use crate::diagnostics::{
+    ReportFormat, SpannedDiagnostic, YamlSegment, json_pointer_segments, locate_yaml, render_human,
+    to_junit, to_sarif,
+};
+use crate::extensions::{
+    CAPABILITIES_EXTENSION_KEY, COMPONENTS_EXTENSION_KEY, validate_capabilities_extension,
+    validate_components_extension,
+};
+use crate::flow_resolve::missing_node_mappings;
+use crate::path_safety::normalize_under_root;
 
 #[derive(Debug, Parser)]
 pub struct LintArgs {
@@ -18,58 +37,507 @@ pub struct LintArgs {
     /// Allow OCI component refs in extensions to be tag-based (default requires sha256 digest)
     #[arg(long = "allow-oci-tags", default_value_t = false)]
     pub allow_oci_tags: bool,
+
+    /// Output format (`--json` is shorthand for `--format json`)
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: ReportFormat,
+}
+
+/// Everything `lint` found, in the order it was checked.
+pub struct LintReport {
+    /// Parsed pack.yaml; `None` when it could not be read or deserialized.
+    pub config: Option<PackConfig>,
+    pub flows_compiled: usize,
+    pub diagnostics: Vec<SpannedDiagnostic>,
+}
+
+impl LintReport {
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diag| diag.is_error())
+            .count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diag| diag.is_warning())
+            .count()
+    }
 }
 
 pub fn handle(args: LintArgs, json: bool) -> Result<()> {
     let pack_dir = normalize(args.input);
     info!(path = %pack_dir.display(), "linting pack");
 
-    let cfg = load_pack_config(&pack_dir)?;
-    crate::extensions::validate_components_extension(&cfg.extensions, args.allow_oci_tags)?;
+    let report = lint_pack(&pack_dir, args.allow_oci_tags);
+    let errors = report.error_count();
+    let warnings = report.warning_count();
 
-    let mut compiled = 0usize;
-    for flow in &cfg.flows {
-        let src = std::fs::read_to_string(&flow.file)?;
-        compile_ygtc_str(&src)?;
-        compiled += 1;
+    let format = if json {
+        ReportFormat::Json
+    } else {
+        args.format
+    };
+    let base = pack_dir.canonicalize().unwrap_or_else(|_| pack_dir.clone());
+    match format {
+        ReportFormat::Json => {
+            let status = if errors == 0 {
+                crate::cli_i18n::t("cli.status.ok")
+            } else {
+                crate::cli_i18n::t("cli.status.failed")
+            };
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "status": status,
+                    "pack_id": report.config.as_ref().map(|cfg| cfg.pack_id.as_str()),
+                    "version": report.config.as_ref().map(|cfg| cfg.version.as_str()),
+                    "flows": report.flows_compiled,
+                    "components": report.config.as_ref().map(|cfg| cfg.components.len()),
+                    "dependencies": report.config.as_ref().map(|cfg| cfg.dependencies.len()),
+                    "errors": errors,
+                    "warnings": warnings,
+                    "diagnostics": report.diagnostics,
+                }))?
+            );
+        }
+        ReportFormat::Sarif => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&to_sarif(&report.diagnostics, &base))?
+            );
+        }
+        ReportFormat::Junit => {
+            print!(
+                "{}",
+                to_junit("greentic-pack lint", &report.diagnostics, &base)
+            );
+        }
+        ReportFormat::Human => {
+            if !report.diagnostics.is_empty() {
+                eprint!("{}", render_human(&report.diagnostics, &base));
+            }
+            if errors == 0
+                && let Some(cfg) = report.config.as_ref()
+            {
+                println!("{}", crate::cli_i18n::t("cli.lint.ok_header"));
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf("cli.lint.pack", &[&cfg.pack_id, &cfg.version.to_string()])
+                );
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf("cli.lint.flows", &[&report.flows_compiled.to_string()])
+                );
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf(
+                        "cli.lint.components",
+                        &[&cfg.components.len().to_string()]
+                    )
+                );
+                println!(
+                    "{}",
+                    crate::cli_i18n::tf(
+                        "cli.lint.dependencies",
+                        &[&cfg.dependencies.len().to_string()]
+                    )
+                );
+            }
+        }
     }
 
-    if json {
-        println!(
+    if errors > 0 {
+        bail!(
             "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "status": crate::cli_i18n::t("cli.status.ok"),
-                "pack_id": cfg.pack_id,
-                "version": cfg.version,
-                "flows": compiled,
-                "components": cfg.components.len(),
-                "dependencies": cfg.dependencies.len(),
-            }))?
-        );
-    } else {
-        println!("{}", crate::cli_i18n::t("cli.lint.ok_header"));
-        println!(
-            "{}",
-            crate::cli_i18n::tf("cli.lint.pack", &[&cfg.pack_id, &cfg.version.to_string()])
+            crate::cli_i18n::tf(
+                "cli.lint.failed",
+                &[&errors.to_string(), &warnings.to_string()]
+            )
         );
-        println!(
-            "{}",
-            crate::cli_i18n::tf("cli.lint.flows", &[&compiled.to_string()])
+    }
+    Ok(())
+}
+
+/// Check pack.yaml, its extensions, referenced files, flows and resolve
+/// sidecars, collecting every problem instead of stopping at the first.
+pub fn lint_pack(pack_dir: &Path, allow_oci_tags: bool) -> LintReport {
+    let mut report = LintReport {
+        config: None,
+        flows_compiled: 0,
+        diagnostics: Vec::new(),
+    };
+    let root = pack_dir
+        .canonicalize()
+        .unwrap_or_else(|_| pack_dir.to_path_buf());
+    let manifest_path = root.join("pack.yaml");
+
+    let source = match std::fs::read_to_string(&manifest_path) {
+        Ok(source) => source,
+        Err(err) => {
+            report.diagnostics.push(
+                SpannedDiagnostic::error(
+                    "PACK_LINT_PACK_YAML_UNREADABLE",
+                    format!("failed to read pack.yaml: {err}"),
+                )
+                .at(&manifest_path, None),
+            );
+            return report;
+        }
+    };
+    let Some(cfg) = parse_pack_yaml(&manifest_path, &source, &mut report.diagnostics) else {
+        return report;
+    };
+    let pack_yaml = PackYaml {
+        path: &manifest_path,
+        source: &source,
+    };
+
+    for (idx, component) in cfg.components.iter().enumerate() {
+        pack_yaml.check_file(
+            &root,
+            &component.wasm,
+            &["components", &idx.to_string(), "wasm"],
+            "PACK_LINT_COMPONENT_WASM_MISSING",
+            "build the component or run `greentic-pack components` to resync pack.yaml",
+            &mut report.diagnostics,
         );
-        println!(
-            "{}",
-            crate::cli_i18n::tf("cli.lint.components", &[&cfg.components.len().to_string()])
+    }
+    for (idx, asset) in cfg.assets.iter().enumerate() {
+        pack_yaml.check_file(
+            &root,
+            &asset.path,
+            &["assets", &idx.to_string(), "path"],
+            "PACK_LINT_ASSET_MISSING",
+            "add the file or remove the asset entry",
+            &mut report.diagnostics,
         );
-        println!(
-            "{}",
-            crate::cli_i18n::tf(
-                "cli.lint.dependencies",
-                &[&cfg.dependencies.len().to_string()]
+    }
+
+    lint_extensions(
+        &pack_yaml,
+        &root,
+        &cfg,
+        allow_oci_tags,
+        &mut report.diagnostics,
+    );
+    lint_sections(&pack_yaml, &cfg, &mut report.diagnostics);
+
+    for (idx, flow) in cfg.flows.iter().enumerate() {
+        let Some(flow_path) = pack_yaml.check_file(
+            &root,
+            &flow.file,
+            &["flows", &idx.to_string(), "file"],
+            "PACK_LINT_FLOW_MISSING",
+            "add the flow file or run `greentic-pack update` to resync pack.yaml",
+            &mut report.diagnostics,
+        ) else {
+            continue;
+        };
+        if lint_flow(flow, &flow_path, &mut report.diagnostics) {
+            report.flows_compiled += 1;
+        }
+    }
+
+    report.config = Some(cfg);
+    report
+}
+
+/// Deserialize pack.yaml, reporting syntax errors and every schema error in
+/// list entries and extensions rather than only the first one serde hits.
+fn parse_pack_yaml(
+    path: &Path,
+    source: &str,
+    diagnostics: &mut Vec<SpannedDiagnostic>,
+) -> Option<PackConfig> {
+    let value: YamlValue = match serde_yaml_bw::from_str(source) {
+        Ok(value) => value,
+        Err(err) => {
+            let position = err.location().map(|loc| (loc.line(), loc.column()));
+            diagnostics.push(
+                SpannedDiagnostic::error(
+                    "PACK_LINT_PACK_YAML_SYNTAX",
+                    format!("pack.yaml is not valid YAML: {err}"),
+                )
+                .at(path, position),
+            );
+            return None;
+        }
+    };
+    let err = match serde_yaml_bw::from_str::<PackConfig>(source) {
+        Ok(cfg) => return Some(cfg),
+        Err(err) => err,
+    };
+
+    let mut errors = Vec::new();
+    for field in ["pack_id", "version", "kind", "publisher"] {
+        if value.get(field).is_none() {
+            errors.push((Vec::new(), format!("missing field `{field}`")));
+        }
+    }
+    schema_errors::<ComponentConfig>(&value, "components", &mut errors);
+    schema_errors::<DependencyConfig>(&value, "dependencies", &mut errors);
+    schema_errors::<FlowConfig>(&value, "flows", &mut errors);
+    schema_errors::<AssetConfig>(&value, "assets", &mut errors);
+    if let Some(extensions) = value.get("extensions").and_then(YamlValue::as_mapping) {
+        for (key, ext) in extensions.iter() {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            if let Err(err) = serde_yaml_bw::from_value::<ExtensionRef>(ext.clone()) {
+                errors.push((
+                    vec![YamlSegment::key("extensions"), YamlSegment::key(key)],
+                    format!("extensions[{key}]: {err}"),
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        let position = err.location().map(|loc| (loc.line(), loc.column()));
+        diagnostics.push(
+            SpannedDiagnostic::error(
+                "PACK_LINT_PACK_YAML_SCHEMA",
+                format!("pack.yaml does not match the schema: {err}"),
             )
+            .at(path, position),
+        );
+    }
+    for (segments, message) in errors {
+        let position = if segments.is_empty() {
+            Some((1, 1))
+        } else {
+            locate_yaml(source, &segments)
+        };
+        diagnostics.push(
+            SpannedDiagnostic::error("PACK_LINT_PACK_YAML_SCHEMA", message).at(path, position),
         );
     }
+    None
+}
 
-    Ok(())
+fn schema_errors<T: DeserializeOwned>(
+    value: &YamlValue,
+    section: &str,
+    errors: &mut Vec<(Vec<YamlSegment>, String)>,
+) {
+    let Some(entries) = value.get(section).and_then(YamlValue::as_sequence) else {
+        return;
+    };
+    for (idx, entry) in entries.iter().enumerate() {
+        if let Err(err) = serde_yaml_bw::from_value::<T>(entry.clone()) {
+            errors.push((
+                vec![YamlSegment::key(section), YamlSegment::Index(idx)],
+                format!("{section}[{idx}]: {err}"),
+            ));
+        }
+    }
+}
+
+struct PackYaml<'a> {
+    path: &'a Path,
+    source: &'a str,
+}
+
+impl PackYaml<'_> {
+    fn diagnostic(&self, diag: SpannedDiagnostic, segments: &[&str]) -> SpannedDiagnostic {
+        let segments: Vec<YamlSegment> = segments.iter().map(|seg| parse_segment(seg)).collect();
+        diag.at(self.path, locate_yaml(self.source, &segments))
+    }
+
+    /// Resolve a path from pack.yaml under the pack root and require it to exist.
+    fn check_file(
+        &self,
+        root: &Path,
+        relative: &Path,
+        segments: &[&str],
+        missing_code: &str,
+        hint: &str,
+        diagnostics: &mut Vec<SpannedDiagnostic>,
+    ) -> Option<PathBuf> {
+        let resolved = match normalize_under_root(root, relative) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                diagnostics.push(self.diagnostic(
+                    SpannedDiagnostic::error("PACK_LINT_PATH_INVALID", format!("{err:#}")),
+                    segments,
+                ));
+                return None;
+            }
+        };
+        if !resolved.exists() {
+            diagnostics.push(
+                self.diagnostic(
+                    SpannedDiagnostic::error(
+                        missing_code,
+                        format!("{} does not exist", relative.display()),
+                    )
+                    .with_hint(hint),
+                    segments,
+                ),
+            );
+            return None;
+        }
+        Some(resolved)
+    }
+}
+
+fn parse_segment(segment: &str) -> YamlSegment {
+    match segment.parse::<usize>() {
+        Ok(idx) => YamlSegment::Index(idx),
+        Err(_) => YamlSegment::key(segment),
+    }
+}
+
+fn lint_extensions(
+    pack_yaml: &PackYaml<'_>,
+    root: &Path,
+    cfg: &PackConfig,
+    allow_oci_tags: bool,
+    diagnostics: &mut Vec<SpannedDiagnostic>,
+) {
+    let Some(extensions) = cfg.extensions.as_ref() else {
+        return;
+    };
+    let strict = strict_extensions();
+    let invalid = |key: &str, err: anyhow::Error| {
+        pack_yaml.diagnostic(
+            SpannedDiagnostic::error("PACK_LINT_EXTENSION_INVALID", format!("{err:#}")),
+            &["extensions", key],
+        )
+    };
+
+    for (key, ext) in extensions {
+        if let Err(err) = validate_extension(key, ext, strict) {
+            diagnostics.push(invalid(key, err));
+        }
+    }
+    if let Err(err) = validate_components_extension(&cfg.extensions, allow_oci_tags) {
+        diagnostics.push(invalid(COMPONENTS_EXTENSION_KEY, err));
+    }
+    let component_ids: Vec<String> = cfg.components.iter().map(|c| c.id.clone()).collect();
+    if let Err(err) = validate_capabilities_extension(&cfg.extensions, root, &component_ids) {
+        diagnostics.push(invalid(CAPABILITIES_EXTENSION_KEY, err));
+    }
+}
+
+/// The section checks `load_pack_config` applies after deserializing, so
+/// lint and `dev` reject what a build would.
+fn lint_sections(
+    pack_yaml: &PackYaml<'_>,
+    cfg: &PackConfig,
+    diagnostics: &mut Vec<SpannedDiagnostic>,
+) {
+    if let Err(err) = validate_mcp_components(cfg) {
+        diagnostics.push(pack_yaml.diagnostic(
+            SpannedDiagnostic::error("PACK_LINT_MCP_COMPONENT_INVALID", format!("{err:#}")),
+            &["mcp_components"],
+        ));
+    }
+    if let Err(err) = validate_pack_distribution(cfg) {
+        let section = if cfg.distribution.is_some() {
+            "distribution"
+        } else {
+            "kind"
+        };
+        diagnostics.push(pack_yaml.diagnostic(
+            SpannedDiagnostic::error("PACK_LINT_DISTRIBUTION_INVALID", format!("{err:#}")),
+            &[section],
+        ));
+    }
+}
+
+/// Compile one flow and compare its nodes with the resolve sidecar.
+/// Returns whether the flow compiled.
+fn lint_flow(
+    flow: &FlowConfig,
+    flow_path: &Path,
+    diagnostics: &mut Vec<SpannedDiagnostic>,
+) -> bool {
+    let source = match std::fs::read_to_string(flow_path) {
+        Ok(source) => source,
+        Err(err) => {
+            diagnostics.push(
+                SpannedDiagnostic::error(
+                    "PACK_LINT_FLOW_COMPILE",
+                    format!("failed to read flow {}: {err}", flow.id),
+                )
+                .at(flow_path, None),
+            );
+            return false;
+        }
+    };
+    let compiled = match compile_ygtc_str(&source) {
+        Ok(compiled) => compiled,
+        Err(err) => {
+            for report in flow_error_to_reports(err) {
+                let position = flow_report_position(&source, &report);
+                diagnostics.push(
+                    SpannedDiagnostic::error("PACK_LINT_FLOW_COMPILE", report.message)
+                        .at(flow_path, position),
+                );
+            }
+            return false;
+        }
+    };
+
+    let sidecar_path = sidecar_path_for_flow(flow_path);
+    match read_flow_resolve(&sidecar_path) {
+        Ok(doc) => {
+            for node in missing_node_mappings(&compiled, &doc) {
+                let position = locate_yaml(
+                    &source,
+                    &[YamlSegment::key("nodes"), YamlSegment::key(node.as_str())],
+                );
+                diagnostics.push(
+                    SpannedDiagnostic::error(
+                        "PACK_LINT_SIDECAR_GAP",
+                        format!("node `{node}` has no entry in {}", sidecar_path.display()),
+                    )
+                    .with_hint("add a mapping to the sidecar, then rerun `greentic-pack resolve`")
+                    .at(flow_path, position),
+                );
+            }
+        }
+        Err(err) if err.code == ErrorCode::NotFound => {
+            if !compiled.nodes.is_empty() {
+                diagnostics.push(
+                    SpannedDiagnostic::warning(
+                        "PACK_LINT_SIDECAR_MISSING",
+                        format!("flow resolve sidecar missing for {}", flow.id),
+                    )
+                    .with_hint("run `greentic-pack update` to create it")
+                    .at(&sidecar_path, None),
+                );
+            }
+        }
+        Err(err) => {
+            diagnostics.push(
+                SpannedDiagnostic::error(
+                    "PACK_LINT_SIDECAR_INVALID",
+                    format!("failed to read flow resolve sidecar for {}: {err}", flow.id),
+                )
+                .at(&sidecar_path, None),
+            );
+        }
+    }
+    true
+}
+
+/// Position of a greentic-flow error: its own line/column, else its JSON
+/// pointer, else the dotted node path in its label (`flow::nodes.start`).
+fn flow_report_position(source: &str, report: &JsonDiagnostic) -> Option<(usize, usize)> {
+    if let Some(line) = report.line {
+        return Some((line, report.col.unwrap_or(1)));
+    }
+    if let Some(pointer) = report.json_pointer.as_deref() {
+        return locate_yaml(source, &json_pointer_segments(pointer));
+    }
+    let (_, dotted) = report.source_path.as_deref()?.split_once("::")?;
+    let segments: Vec<YamlSegment> = dotted.split('.').map(YamlSegment::key).collect();
+    locate_yaml(source, &segments)
 }
 
 fn normalize(path: PathBuf) -> PathBuf {
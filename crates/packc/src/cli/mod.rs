@@ -4,14 +4,19 @@ use std::{convert::TryFrom, path::PathBuf};
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use greentic_pack::reader::ReaderLimits;
 use greentic_types::{EnvId, TenantCtx, TenantId};
 use tokio::runtime::Runtime;
 
 pub mod add_extension;
+pub mod bundle;
+pub mod cache;
 pub mod components;
 pub mod config;
+pub mod dev;
+pub mod diff;
+pub mod extract;
 pub mod gui;
-pub mod input;
 pub mod inspect;
 pub mod inspect_lock;
 pub mod lint;
@@ -49,6 +54,14 @@ pub struct Cli {
     #[arg(long = "config-override", value_name = "FILE", global = true)]
     pub config_override: Option<PathBuf>,
 
+    /// Maximum uncompressed size of a .gtpack archive read by any command, in bytes
+    #[arg(long = "max-archive-bytes", value_name = "BYTES", global = true)]
+    pub max_archive_bytes: Option<u64>,
+
+    /// Maximum uncompressed size of a single .gtpack entry, in bytes
+    #[arg(long = "max-file-bytes", value_name = "BYTES", global = true)]
+    pub max_file_bytes: Option<u64>,
+
     /// Emit machine-readable JSON output where applicable
     #[arg(long, global = true)]
     pub json: bool,
@@ -74,9 +87,9 @@ pub enum Command {
     Update(self::update::UpdateArgs),
     /// Scaffold a new pack directory
     New(new::NewArgs),
-    /// Sign a pack manifest using an Ed25519 private key
+    /// Sign a pack manifest or .gtpack archive using an Ed25519 private key or external signer
     Sign(self::sign::SignArgs),
-    /// Verify a pack's manifest signature
+    /// Verify a pack manifest or .gtpack archive signature
     Verify(self::verify::VerifyArgs),
     /// GUI-related tooling
     #[command(subcommand)]
@@ -103,6 +116,19 @@ pub enum Command {
     Wizard(self::wizard::WizardArgs),
     /// Resolve component references and write pack.lock.cbor
     Resolve(self::resolve::ResolveArgs),
+    /// Inspect or prune the persistent describe cache
+    #[command(subcommand)]
+    Cache(self::cache::CacheCommand),
+    /// Compare two packs, source directories or lockfiles
+    Diff(self::diff::DiffArgs),
+    /// Build a dev-layout pack and optionally rebuild it as sources change
+    Dev(self::dev::DevArgs),
+    /// Assemble packs and their components into one signed distribution bundle
+    Bundle(self::bundle::BundleArgs),
+    /// Verify a distribution bundle and explode it back into individual packs
+    Unbundle(self::bundle::UnbundleArgs),
+    /// Rebuild a pack source tree (pack.yaml, flows, components) from a built .gtpack
+    Extract(self::extract::ExtractArgs),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -123,10 +149,24 @@ pub struct BuildArgs {
     #[arg(long, value_name = "FILE")]
     pub manifest: Option<PathBuf>,
 
-    /// Output path for the generated SBOM (legacy; writes a stub JSON)
+    /// Output path for a CycloneDX 1.5 SBOM describing the pack
     #[arg(long, value_name = "FILE")]
     pub sbom: Option<PathBuf>,
 
+    /// Output path for an SPDX 2.3 SBOM describing the pack
+    #[arg(long, value_name = "FILE")]
+    pub spdx: Option<PathBuf>,
+
+    /// Embed SBOM documents into the .gtpack (cyclonedx, spdx; default: cyclonedx)
+    #[arg(
+        long = "embed-sbom",
+        value_enum,
+        value_delimiter = ',',
+        num_args = 0..,
+        default_missing_value = "cyclonedx"
+    )]
+    pub embed_sbom: Vec<crate::sbom::SbomFormat>,
+
     /// Output path for the generated & canonical .gtpack archive (default: dist/<pack_dir>.gtpack)
     #[arg(long = "gtpack-out", value_name = "FILE")]
     pub gtpack_out: Option<PathBuf>,
@@ -139,6 +179,10 @@ pub struct BuildArgs {
     #[arg(long = "bundle", value_enum, default_value = "cache")]
     pub bundle: crate::build::BundleMode,
 
+    /// Compression for .gtpack entries (stored, deflate, zstd); output stays reproducible
+    #[arg(long, value_enum, default_value = "stored")]
+    pub compression: greentic_pack::builder::PackCompression,
+
     /// When set, the command validates input without writing artifacts
     #[arg(long)]
     pub dry_run: bool,
@@ -170,6 +214,14 @@ pub struct BuildArgs {
     /// Migration-only escape hatch: allow deriving component manifest/schema from pack.yaml.
     #[arg(long = "allow-pack-schema", default_value_t = false)]
     pub allow_pack_schema: bool,
+
+    /// Ignore the .packc/ build cache and run every stage
+    #[arg(long = "no-cache", default_value_t = false)]
+    pub no_cache: bool,
+
+    /// Print which build stages were skipped or re-run, and why
+    #[arg(long, default_value_t = false)]
+    pub explain: bool,
 }
 
 pub fn run() -> Result<()> {
@@ -200,6 +252,12 @@ pub fn print_top_level_help() {
     println!("{}", crate::cli_i18n::t("cli.help.command.add_extension"));
     println!("{}", crate::cli_i18n::t("cli.help.command.wizard"));
     println!("{}", crate::cli_i18n::t("cli.help.command.resolve"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.cache"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.diff"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.dev"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.bundle"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.unbundle"));
+    println!("{}", crate::cli_i18n::t("cli.help.command.extract"));
     println!("{}", crate::cli_i18n::t("cli.help.command.help"));
     println!();
     println!("{}", crate::cli_i18n::t("cli.help.options_header"));
@@ -207,6 +265,11 @@ pub fn print_top_level_help() {
     println!("{}", crate::cli_i18n::t("cli.help.option.offline"));
     println!("{}", crate::cli_i18n::t("cli.help.option.cache_dir"));
     println!("{}", crate::cli_i18n::t("cli.help.option.config_override"));
+    println!(
+        "{}",
+        crate::cli_i18n::t("cli.help.option.max_archive_bytes")
+    );
+    println!("{}", crate::cli_i18n::t("cli.help.option.max_file_bytes"));
     println!("{}", crate::cli_i18n::t("cli.help.option.json"));
     println!("{}", crate::cli_i18n::t("cli.help.option.locale"));
     println!("{}", crate::cli_i18n::t("cli.help.option.help"));
@@ -234,10 +297,18 @@ pub fn print_help_for_path(path: &[String]) -> bool {
         [a] if a == "add-extension" => "cli.help.page.add_extension",
         [a] if a == "wizard" => "cli.help.page.wizard",
         [a] if a == "resolve" => "cli.help.page.resolve",
+        [a] if a == "cache" => "cli.help.page.cache",
+        [a] if a == "diff" => "cli.help.page.diff",
+        [a] if a == "dev" => "cli.help.page.dev",
+        [a] if a == "bundle" => "cli.help.page.bundle",
+        [a] if a == "unbundle" => "cli.help.page.unbundle",
+        [a] if a == "extract" => "cli.help.page.extract",
         [a, b] if a == "gui" && b == "loveable-convert" => "cli.help.page.gui_loveable_convert",
         [a, b] if a == "providers" && b == "list" => "cli.help.page.providers_list",
         [a, b] if a == "providers" && b == "info" => "cli.help.page.providers_info",
         [a, b] if a == "providers" && b == "validate" => "cli.help.page.providers_validate",
+        [a, b] if a == "cache" && b == "stats" => "cli.help.page.cache_stats",
+        [a, b] if a == "cache" && b == "prune" => "cli.help.page.cache_prune",
         [a, b] if a == "add-extension" && b == "provider" => "cli.help.page.add_extension_provider",
         [a, b] if a == "add-extension" && b == "capability" => {
             "cli.help.page.add_extension_capability"
@@ -262,11 +333,18 @@ pub async fn run_with_cli(cli: Cli, warn_inspect_alias: bool) -> Result<()> {
     let wizard_locale = cli.locale.clone();
     crate::cli_i18n::init_locale(cli.locale.as_deref());
 
-    let runtime = runtime::resolve_runtime(
+    let reader_limits = ReaderLimits::new(
+        cli.max_archive_bytes
+            .unwrap_or(ReaderLimits::DEFAULT_MAX_ARCHIVE_BYTES),
+        cli.max_file_bytes
+            .unwrap_or(ReaderLimits::DEFAULT_MAX_FILE_BYTES),
+    );
+    let runtime = runtime::resolve_runtime_with_limits(
         Some(std::env::current_dir()?.as_path()),
         cli.cache_dir.as_deref(),
         cli.offline,
         cli.config_override.as_deref(),
+        reader_limits,
     )?;
 
     // Install telemetry according to resolved config.
@@ -285,8 +363,8 @@ pub async fn run_with_cli(cli: Cli, warn_inspect_alias: bool) -> Result<()> {
         Command::Components(args) => self::components::handle(args, cli.json)?,
         Command::Update(args) => self::update::handle(args, cli.json)?,
         Command::New(args) => new::handle(args, cli.json, &runtime).await?,
-        Command::Sign(args) => self::sign::handle(args, cli.json)?,
-        Command::Verify(args) => self::verify::handle(args, cli.json)?,
+        Command::Sign(args) => self::sign::handle(args, cli.json, &runtime)?,
+        Command::Verify(args) => self::verify::handle(args, cli.json, &runtime)?,
         Command::Gui(cmd) => self::gui::handle(cmd, cli.json, &runtime).await?,
         Command::Inspect(args) | Command::Doctor(args) => {
             if warn_inspect_alias {
@@ -297,11 +375,17 @@ pub async fn run_with_cli(cli: Cli, warn_inspect_alias: bool) -> Result<()> {
         Command::InspectLock(args) => self::inspect_lock::handle(args)?,
         Command::Qa(args) => self::qa::handle(args, &runtime)?,
         Command::Config(args) => self::config::handle(args, cli.json, &runtime)?,
-        Command::Plan(args) => self::plan::handle(&args)?,
-        Command::Providers(cmd) => self::providers::run(cmd)?,
+        Command::Plan(args) => self::plan::handle(&args, &runtime).await?,
+        Command::Providers(cmd) => self::providers::run(cmd, &runtime).await?,
         Command::AddExtension(cmd) => self::add_extension::handle(cmd)?,
         Command::Wizard(args) => self::wizard::handle(args, &runtime, wizard_locale.as_deref())?,
         Command::Resolve(args) => self::resolve::handle(args, &runtime, true).await?,
+        Command::Cache(cmd) => self::cache::handle(cmd, cli.json, &runtime)?,
+        Command::Diff(args) => self::diff::handle(args, cli.json, &runtime).await?,
+        Command::Dev(args) => self::dev::handle(args, &runtime).await?,
+        Command::Bundle(args) => self::bundle::handle_bundle(args, cli.json, &runtime)?,
+        Command::Unbundle(args) => self::bundle::handle_unbundle(args, cli.json, &runtime)?,
+        Command::Extract(args) => self::extract::handle(args, cli.json, &runtime)?,
     }
 
     Ok(())
@@ -1,16 +1,22 @@
 #![forbid(unsafe_code)]
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::builder::PackManifest;
 use greentic_pack::plan::infer_base_deployment_plan;
-use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use greentic_pack::plan_overlay::{PlanOverlay, apply_overlays};
+use greentic_pack::plan_render::{RenderTarget, render_plan};
+use greentic_pack::reader::{PackLoad, PackReader, SigningPolicy};
 use greentic_types::component::ComponentManifest;
+use greentic_types::pack_manifest::PackManifest as GpackManifest;
 use greentic_types::{EnvId, SecretRequirement, TenantCtx, TenantId};
 
-use crate::cli::input::materialize_pack_path;
+use crate::build;
+use crate::runtime::RuntimeContext;
 
 #[derive(Debug, clap::Args)]
 pub struct PlanArgs {
@@ -33,12 +39,40 @@ pub struct PlanArgs {
     /// When set, print additional diagnostics (for directory builds).
     #[arg(long)]
     pub verbose: bool,
+
+    /// Overlay applied to the inferred plan (YAML/JSON merge patch or JSON Patch list).
+    /// Repeat to layer several files; later overlays win.
+    #[arg(long = "overlay", value_name = "FILE")]
+    pub overlays: Vec<std::path::PathBuf>,
+
+    /// Render deployable manifests for the plan instead of printing it.
+    #[arg(long, value_enum, value_name = "TARGET", requires = "out")]
+    pub render: Option<RenderTarget>,
+
+    /// Directory that receives the rendered manifests.
+    #[arg(long, value_name = "DIR", requires = "render")]
+    pub out: Option<std::path::PathBuf>,
 }
 
-pub fn handle(args: &PlanArgs) -> Result<()> {
-    let (temp, pack_path) = materialize_pack_path(&args.input, args.verbose)?;
+pub async fn handle(args: &PlanArgs, runtime: &RuntimeContext) -> Result<()> {
     let tenant_ctx = build_tenant_ctx(&args.environment, &args.tenant)?;
-    let plan = plan_for_pack(&pack_path, &tenant_ctx, &args.environment)?;
+    let inputs = load_pack(&args.input, runtime, args.verbose).await?;
+    let mut plan = plan_for_pack(&inputs, &tenant_ctx, &args.environment)?;
+
+    if !args.overlays.is_empty() {
+        let overlays = args
+            .overlays
+            .iter()
+            .map(|path| PlanOverlay::load(path))
+            .collect::<Result<Vec<_>>>()?;
+        let layered = apply_overlays(&plan, &overlays)?;
+        print_sources(&layered.sources);
+        plan = layered.plan;
+    }
+
+    if let (Some(target), Some(out)) = (args.render, args.out.as_deref()) {
+        return write_rendered(&plan, target, out);
+    }
 
     if args.json {
         println!("{}", serde_json::to_string(&plan)?);
@@ -46,26 +80,136 @@ pub fn handle(args: &PlanArgs) -> Result<()> {
         println!("{}", serde_json::to_string_pretty(&plan)?);
     }
 
-    drop(temp);
     Ok(())
 }
 
+/// Print which layer set each plan field, on stderr so stdout stays valid JSON.
+fn print_sources(sources: &BTreeMap<String, String>) {
+    let width = sources.keys().map(String::len).max().unwrap_or(0);
+    eprintln!("Field sources:");
+    for (pointer, source) in sources {
+        eprintln!("  {pointer:<width$}  {source}");
+    }
+}
+
+fn write_rendered(
+    plan: &greentic_types::deployment::DeploymentPlan,
+    target: RenderTarget,
+    out: &Path,
+) -> Result<()> {
+    let files = render_plan(plan, target)?;
+    fs::create_dir_all(out)
+        .with_context(|| format!("failed to create output directory {}", out.display()))?;
+    for (name, contents) in files {
+        let path = out.join(name);
+        fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        eprintln!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// What the plan is inferred from, gathered from a source build or an archive.
+struct PlanInputs {
+    manifest: PackManifest,
+    components: HashMap<String, ComponentManifest>,
+    secret_requirements: Option<Vec<SecretRequirement>>,
+}
+
+/// Stream the needed entries out of a `.gtpack`, or build a source directory in process.
+async fn load_pack(input: &Path, runtime: &RuntimeContext, verbose: bool) -> Result<PlanInputs> {
+    let metadata =
+        fs::metadata(input).with_context(|| format!("unable to read input {}", input.display()))?;
+    if metadata.is_dir() {
+        let opts = build::BuildOptions::for_source_dir(input, runtime, false)?;
+        let built = build::build_in_memory(&opts).await?;
+        if verbose {
+            for warning in &built.warnings {
+                eprintln!("warning: {warning}");
+            }
+        }
+        return PlanInputs::from_load(built.into_pack_load()?);
+    }
+    if !metadata.is_file() {
+        bail!(
+            "input {} is neither a file nor a directory",
+            input.display()
+        );
+    }
+    let reader = PackReader::open(input, SigningPolicy::DevOk, &runtime.reader_limits)
+        .map_err(|err| anyhow!(err.message))?;
+    PlanInputs::from_reader(reader)
+}
+
+impl PlanInputs {
+    fn from_load(load: PackLoad) -> Result<Self> {
+        let mut components = HashMap::new();
+        for component in &load.manifest.components {
+            let id = component.name.clone();
+            if let Some(manifest) = load
+                .get_component_manifest_prefer_file(&id)
+                .with_context(|| format!("failed to load manifest for component `{id}`"))?
+            {
+                components.insert(id, manifest);
+            }
+        }
+        let secret_requirements = secret_requirements(load.gpack_manifest.as_ref(), |name| {
+            Ok(load.files.get(name).cloned())
+        })
+        .unwrap_or(None);
+        Ok(Self {
+            manifest: load.manifest,
+            components,
+            secret_requirements,
+        })
+    }
+
+    /// Only the component manifests and secret requirements are read from the archive.
+    fn from_reader(mut reader: PackReader) -> Result<Self> {
+        let ids: Vec<String> = reader
+            .manifest
+            .components
+            .iter()
+            .map(|component| component.name.clone())
+            .collect();
+        let mut components = HashMap::new();
+        for id in ids {
+            if let Some(manifest) = reader
+                .get_component_manifest_prefer_file(&id)
+                .with_context(|| format!("failed to load manifest for component `{id}`"))?
+            {
+                components.insert(id, manifest);
+            }
+        }
+        let gpack_manifest = reader.gpack_manifest.clone();
+        let secret_requirements = secret_requirements(gpack_manifest.as_ref(), |name| {
+            if !reader.contains(name) {
+                return Ok(None);
+            }
+            reader.read_entry(name).map(Some)
+        })
+        .unwrap_or(None);
+        Ok(Self {
+            manifest: reader.manifest,
+            components,
+            secret_requirements,
+        })
+    }
+}
+
 fn plan_for_pack(
-    path: &Path,
+    inputs: &PlanInputs,
     tenant: &TenantCtx,
     environment: &str,
 ) -> Result<greentic_types::deployment::DeploymentPlan> {
-    let load = open_pack(path, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
-    let connectors = load.manifest.meta.annotations.get("connectors");
-    let components = load_component_manifests(&load)?;
-    let secret_requirements = load_secret_requirements(&load).unwrap_or(None);
+    let connectors = inputs.manifest.meta.annotations.get("connectors");
 
     Ok(infer_base_deployment_plan(
-        &load.manifest.meta,
-        &load.manifest.flows,
+        &inputs.manifest.meta,
+        &inputs.manifest.flows,
         connectors,
-        &components,
-        secret_requirements,
+        &inputs.components,
+        inputs.secret_requirements.clone(),
         tenant,
         environment,
     ))
@@ -79,22 +223,11 @@ fn build_tenant_ctx(environment: &str, tenant: &str) -> Result<TenantCtx> {
     Ok(TenantCtx::new(env_id, tenant_id))
 }
 
-fn load_component_manifests(load: &PackLoad) -> Result<HashMap<String, ComponentManifest>> {
-    let mut manifests = HashMap::new();
-    for component in &load.manifest.components {
-        let id = &component.name;
-        if let Some(manifest) = load
-            .get_component_manifest_prefer_file(id)
-            .with_context(|| format!("failed to load manifest for component `{id}`"))?
-        {
-            manifests.insert(component.name.clone(), manifest);
-        }
-    }
-    Ok(manifests)
-}
-
-fn load_secret_requirements(load: &PackLoad) -> Result<Option<Vec<SecretRequirement>>> {
-    if let Some(manifest) = load.gpack_manifest.as_ref()
+fn secret_requirements(
+    gpack_manifest: Option<&GpackManifest>,
+    mut read: impl FnMut(&str) -> Result<Option<Vec<u8>>>,
+) -> Result<Option<Vec<SecretRequirement>>> {
+    if let Some(manifest) = gpack_manifest
         && !manifest.secret_requirements.is_empty()
     {
         return Ok(Some(manifest.secret_requirements.clone()));
@@ -104,8 +237,8 @@ fn load_secret_requirements(load: &PackLoad) -> Result<Option<Vec<SecretRequirem
         "assets/secret-requirements.json",
         "secret-requirements.json",
     ] {
-        if let Some(bytes) = load.files.get(name) {
-            let reqs: Vec<SecretRequirement> = serde_json::from_slice(bytes)
+        if let Some(bytes) = read(name)? {
+            let reqs: Vec<SecretRequirement> = serde_json::from_slice(&bytes)
                 .context("secret requirements file is invalid JSON")?;
             return Ok(Some(reqs));
         }
@@ -10,10 +10,10 @@ use clap::{Args, Subcommand};
 use greentic_types::pack_manifest::{PackManifest, PackSignatures};
 use greentic_types::provider::{ProviderDecl, ProviderExtensionInline};
 use greentic_types::{PackId, PackKind, decode_pack_manifest};
-use tempfile::TempDir;
 use zip::ZipArchive;
 
-use crate::cli::input::materialize_pack_path;
+use crate::build;
+use crate::runtime::RuntimeContext;
 
 #[derive(Debug, Subcommand)]
 pub enum ProvidersCommand {
@@ -66,16 +66,16 @@ pub struct ValidateArgs {
     pub json: bool,
 }
 
-pub fn run(cmd: ProvidersCommand) -> Result<()> {
+pub async fn run(cmd: ProvidersCommand, runtime: &RuntimeContext) -> Result<()> {
     match cmd {
-        ProvidersCommand::List(args) => list(&args),
-        ProvidersCommand::Info(args) => info(&args),
-        ProvidersCommand::Validate(args) => validate(&args),
+        ProvidersCommand::List(args) => list(&args, runtime).await,
+        ProvidersCommand::Info(args) => info(&args, runtime).await,
+        ProvidersCommand::Validate(args) => validate(&args, runtime).await,
     }
 }
 
-pub fn list(args: &ListArgs) -> Result<()> {
-    let pack = load_pack(args.pack.as_deref())?;
+pub async fn list(args: &ListArgs, runtime: &RuntimeContext) -> Result<()> {
+    let pack = load_pack(args.pack.as_deref(), runtime).await?;
     let providers = providers_from_manifest(&pack.manifest);
 
     if args.json {
@@ -108,8 +108,8 @@ pub fn list(args: &ListArgs) -> Result<()> {
     Ok(())
 }
 
-pub fn info(args: &InfoArgs) -> Result<()> {
-    let pack = load_pack(args.pack.as_deref())?;
+pub async fn info(args: &InfoArgs, runtime: &RuntimeContext) -> Result<()> {
+    let pack = load_pack(args.pack.as_deref(), runtime).await?;
     let inline = match pack.manifest.provider_extension_inline() {
         Some(value) => value,
         None => bail!(
@@ -141,8 +141,8 @@ pub fn info(args: &InfoArgs) -> Result<()> {
     Ok(())
 }
 
-pub fn validate(args: &ValidateArgs) -> Result<()> {
-    let pack = load_pack(args.pack.as_deref())?;
+pub async fn validate(args: &ValidateArgs, runtime: &RuntimeContext) -> Result<()> {
+    let pack = load_pack(args.pack.as_deref(), runtime).await?;
     let Some(inline) = pack.manifest.provider_extension_inline() else {
         if args.json {
             println!(
@@ -204,27 +204,27 @@ struct LoadedPack {
     manifest: PackManifest,
     root_dir: Option<PathBuf>,
     entries: HashSet<String>,
-    _temp: Option<TempDir>,
 }
 
-fn load_pack(pack: Option<&Path>) -> Result<LoadedPack> {
+async fn load_pack(pack: Option<&Path>, runtime: &RuntimeContext) -> Result<LoadedPack> {
     let input = pack.unwrap_or_else(|| Path::new("."));
-    let root_dir = if input.is_dir() {
-        Some(
-            input
-                .canonicalize()
-                .with_context(|| format!("failed to canonicalize {}", input.display()))?,
-        )
-    } else {
-        None
-    };
-    let (temp, pack_path) = materialize_pack_path(input, false)?;
-    let (manifest, entries) = read_manifest(&pack_path)?;
+    if input.is_dir() {
+        let root_dir = input
+            .canonicalize()
+            .with_context(|| format!("failed to canonicalize {}", input.display()))?;
+        let opts = build::BuildOptions::for_source_dir(&root_dir, runtime, false)?;
+        let built = build::build_in_memory(&opts).await?;
+        return Ok(LoadedPack {
+            manifest: built.manifest,
+            root_dir: Some(root_dir),
+            entries: built.files.into_keys().collect(),
+        });
+    }
+    let (manifest, entries) = read_manifest(input)?;
     Ok(LoadedPack {
         manifest,
-        root_dir,
+        root_dir: None,
         entries,
-        _temp: temp,
     })
 }
 
@@ -1117,7 +1117,9 @@ mod tests {
             dependencies: Vec::new(),
             flows: Vec::new(),
             assets: Vec::new(),
+            mcp_components: Vec::new(),
             extensions: None,
+            distribution: None,
         };
         let lock = PackLockV1::new(BTreeMap::new());
         let args = QaArgs {
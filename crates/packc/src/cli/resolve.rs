@@ -3,22 +3,28 @@
 use std::collections::{BTreeMap, btree_map::Entry};
 use std::fs;
 use std::future::Future;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
 
 use crate::config::load_pack_config;
+use crate::describe_cache::{CachedDescribe, DescribeCache};
 use crate::flow_resolve::{read_flow_resolve_summary_for_flow, strip_file_uri_prefix};
 use crate::runtime::RuntimeContext;
 use anyhow::{Context, Result, anyhow, bail};
 use clap::Args;
 use greentic_distributor_client::{DistClient, DistOptions};
-use greentic_pack::pack_lock::{LockedComponent, PackLockV1, write_pack_lock};
+use greentic_pack::dependencies::{DependencyRequest, PackIndex, RootPack, resolve_dependencies};
+use greentic_pack::pack_lock::{
+    LockedComponent, LockedDependency, PackLockV1, read_pack_lock, write_pack_lock,
+};
 use greentic_pack::resolver::{ComponentResolver, ResolveReq, ResolvedComponent};
 use greentic_types::cbor::canonical;
 use greentic_types::flow_resolve_summary::{FlowResolveSummarySourceRefV1, FlowResolveSummaryV1};
-use greentic_types::schemas::component::v0_6_0::{ComponentDescribe, schema_hash};
-use hex;
+use greentic_types::schemas::component::v0_6_0::ComponentDescribe;
 use sha2::{Digest, Sha256};
-use tokio::runtime::Handle;
+use tokio::runtime::{Handle, RuntimeFlavor};
 use wasmtime::Engine;
 use wasmtime::component::{Component as WasmtimeComponent, Linker};
 
@@ -33,9 +39,38 @@ pub struct ResolveArgs {
     /// Output path for pack.lock.cbor (default: pack.lock.cbor under pack root).
     #[arg(long = "lock", value_name = "FILE")]
     pub lock: Option<PathBuf>,
+
+    /// Directory or registry mirror searched for dependency .gtpack files (repeatable).
+    /// Without it, previously locked dependencies are re-checked against pack.yaml and kept.
+    #[arg(long = "packs-dir", value_name = "DIR")]
+    pub packs_dir: Vec<PathBuf>,
+
+    /// Components to resolve and describe concurrently (default: available CPUs).
+    #[arg(long = "jobs", short = 'j', value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
 }
 
 pub async fn handle(args: ResolveArgs, runtime: &RuntimeContext, emit_path: bool) -> Result<()> {
+    let (lock_path, lock) = resolve_pack_lock(&args, runtime, emit_path)?;
+    write_pack_lock(&lock_path, &lock)?;
+    if emit_path {
+        eprintln!(
+            "{}",
+            crate::cli_i18n::tf("cli.common.wrote_path", &[&lock_path.display().to_string()])
+        );
+    }
+
+    Ok(())
+}
+
+/// Resolve the pack's components and dependencies without writing the lock.
+///
+/// Returns the lock path `handle` would write to along with the lock itself.
+pub fn resolve_pack_lock(
+    args: &ResolveArgs,
+    runtime: &RuntimeContext,
+    verbose: bool,
+) -> Result<(PathBuf, PackLockV1)> {
     let pack_dir = args
         .input
         .canonicalize()
@@ -52,21 +87,108 @@ pub async fn handle(args: ResolveArgs, runtime: &RuntimeContext, emit_path: bool
     if !entries.is_empty() {
         let resolver = PackResolver::new(runtime)?;
         let engine = Engine::default();
-        for component in entries.values_mut() {
-            populate_component_contract(&engine, &resolver, component).await?;
+        let jobs = args
+            .jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get);
+        let cache = DescribeCache::for_runtime(runtime);
+        populate_components(&engine, &resolver, &cache, &mut entries, jobs)?;
+    }
+
+    if !config.mcp_components.is_empty() {
+        let version = semver::Version::parse(&config.version)
+            .with_context(|| format!("pack version {} is not valid semver", config.version))?;
+        for composed in crate::mcp::compose_all(&pack_dir, &config.mcp_components)? {
+            match entries.entry(composed.id.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(composed.locked_component(&version));
+                }
+                Entry::Occupied(_) => bail!(
+                    "MCP component {} is also resolved by flow nodes; use a distinct id",
+                    composed.id
+                ),
+            }
         }
     }
 
-    let lock = PackLockV1::new(entries);
-    write_pack_lock(&lock_path, &lock)?;
-    if emit_path {
+    let mut lock = PackLockV1::new(entries);
+    lock.dependencies =
+        resolve_pack_dependencies(&pack_dir, &config, &args.packs_dir, &lock_path, verbose)?;
+    Ok((lock_path, lock))
+}
+
+fn resolve_pack_dependencies(
+    pack_dir: &Path,
+    config: &crate::config::PackConfig,
+    packs_dirs: &[PathBuf],
+    lock_path: &Path,
+    verbose: bool,
+) -> Result<BTreeMap<String, LockedDependency>> {
+    if config.dependencies.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let requests = config
+        .dependencies
+        .iter()
+        .map(|dep| {
+            DependencyRequest::new(
+                dep.alias.clone(),
+                dep.pack_id.clone(),
+                &dep.version_req,
+                dep.required_capabilities.clone(),
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let version = semver::Version::parse(&config.version)
+        .with_context(|| format!("pack version {} is not valid semver", config.version))?;
+    let root = RootPack {
+        pack_id: &config.pack_id,
+        version: &version,
+        dependencies: &requests,
+    };
+
+    if packs_dirs.is_empty() {
+        // Nothing to search: re-check what an earlier `resolve --packs-dir` selected
+        // against the current requirements. Packs never resolved that way stay unpinned.
+        let locked = read_pack_lock(lock_path)
+            .map(|lock| lock.dependencies)
+            .unwrap_or_default();
+        if locked.is_empty() {
+            return Ok(locked);
+        }
+        return PackIndex::from_locked(&locked)
+            .and_then(|index| resolve_dependencies(root, &index))
+            .map(|resolution| resolution.locked())
+            .map_err(|err| {
+                anyhow!(
+                    "{err:#}\nlocked dependencies no longer satisfy pack.yaml; \
+                     re-run `greentic-pack resolve --packs-dir <DIR>`"
+                )
+            });
+    }
+
+    let roots: Vec<PathBuf> = packs_dirs
+        .iter()
+        .map(|dir| {
+            if dir.is_absolute() {
+                dir.clone()
+            } else {
+                pack_dir.join(dir)
+            }
+        })
+        .collect();
+    let index = PackIndex::scan(&roots)?;
+    for skipped in &index.skipped {
         eprintln!(
             "{}",
-            crate::cli_i18n::tf("cli.common.wrote_path", &[&lock_path.display().to_string()])
+            crate::cli_i18n::tf("cli.resolve.dependency_skipped", &[skipped])
         );
     }
-
-    Ok(())
+    let resolution = resolve_dependencies(root, &index)?;
+    if verbose {
+        eprint!("{}", resolution.tree);
+    }
+    Ok(resolution.locked())
 }
 
 fn resolve_lock_path(pack_dir: &Path, override_path: Option<&Path>) -> PathBuf {
@@ -147,9 +269,75 @@ fn collect_from_summary(
     Ok(())
 }
 
-async fn populate_component_contract(
+/// Resolve and describe `entries` on up to `jobs` worker threads.
+///
+/// Each worker fills in the entries it takes in place, so the lock contents do
+/// not depend on scheduling. Every component is attempted; failures are reported
+/// together in component id order.
+fn populate_components(
+    engine: &Engine,
+    resolver: &(dyn ComponentResolver + Sync),
+    cache: &DescribeCache,
+    entries: &mut BTreeMap<String, LockedComponent>,
+    jobs: usize,
+) -> Result<()> {
+    let workers = jobs.clamp(1, entries.len().max(1));
+    let handle = Handle::try_current().ok();
+    let queue = Mutex::new(entries.values_mut());
+    let failures = Mutex::new(Vec::new());
+
+    let fan_out = || {
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    // Remote refs are fetched through the caller's Tokio runtime.
+                    let _guard = handle.as_ref().map(Handle::enter);
+                    loop {
+                        let next = queue.lock().expect("resolve queue lock").next();
+                        let Some(component) = next else {
+                            break;
+                        };
+                        if let Err(err) =
+                            populate_component_contract(engine, resolver, cache, component)
+                        {
+                            failures
+                                .lock()
+                                .expect("resolve failures lock")
+                                .push((component.component_id.clone(), err));
+                        }
+                    }
+                });
+            }
+        })
+    };
+    // The fan-out blocks until every worker finishes; on a multi-threaded
+    // runtime move it off the async worker that called `resolve` so the
+    // runtime keeps driving other tasks. `block_in_place` panics on a
+    // current-thread runtime, so there the fan-out runs in place.
+    match handle.as_ref().map(Handle::runtime_flavor) {
+        Some(RuntimeFlavor::MultiThread) => tokio::task::block_in_place(fan_out),
+        _ => fan_out(),
+    }
+
+    let mut failures = failures.into_inner().expect("resolve failures lock");
+    if failures.is_empty() {
+        return Ok(());
+    }
+    failures.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut message = crate::cli_i18n::tf(
+        "cli.resolve.error.components_failed",
+        &[&failures.len().to_string()],
+    );
+    for (component_id, err) in &failures {
+        message.push_str(&format!("\n  - {component_id}: {err:#}"));
+    }
+    bail!("{message}")
+}
+
+fn populate_component_contract(
     engine: &Engine,
-    resolver: &dyn ComponentResolver,
+    resolver: &(dyn ComponentResolver + Sync),
+    cache: &DescribeCache,
     component: &mut LockedComponent,
 ) -> Result<()> {
     if is_builtin_component(component.component_id.as_str()) {
@@ -175,14 +363,29 @@ async fn populate_component_contract(
         component_version: component.component_version.clone(),
     })?;
     let bytes = resolved.bytes;
+    let digest = format!("sha256:{:x}", Sha256::digest(&bytes));
+    if let Some(cached) = cache.get(&digest) {
+        return apply_describe(component, &cached);
+    }
     let use_describe_cache =
         std::env::var("GREENTIC_PACK_USE_DESCRIBE_CACHE").is_ok() || cfg!(test);
-    let describe = match describe_component(engine, &bytes) {
-        Ok(describe) => describe,
+    let cached = match describe_component(engine, &bytes) {
+        // Only real describe() results are persisted; sidecar payloads stay per-path.
+        Ok(describe) => match cache.put(&digest, &describe) {
+            Ok(cached) => cached,
+            Err(err) => {
+                // A failed write only means the next run describes again.
+                tracing::warn!(
+                    "failed to cache describe for {}: {err:#}",
+                    component.component_id
+                );
+                describe_entry(&digest, describe)?
+            }
+        },
         Err(err) => {
             if let Some(describe) = load_describe_from_cache_path(resolved.source_path.as_deref())?
             {
-                describe
+                describe_entry(&digest, describe)?
             } else if is_state_store_tenant_ctx_abi_mismatch(&err)
                 || is_missing_descriptor_instance(&err)
             {
@@ -207,6 +410,21 @@ async fn populate_component_contract(
         }
     };
 
+    apply_describe(component, &cached)
+}
+
+fn describe_entry(digest: &str, describe: ComponentDescribe) -> Result<CachedDescribe> {
+    Ok(CachedDescribe {
+        digest: digest.to_string(),
+        abi: crate::component_host_stubs::DESCRIBE_HOST_ABI.to_string(),
+        describe_hash: crate::describe_cache::describe_hash(&describe)?,
+        schema_hashes: crate::describe_cache::operation_schema_hashes(&describe)?,
+        describe,
+    })
+}
+
+fn apply_describe(component: &mut LockedComponent, cached: &CachedDescribe) -> Result<()> {
+    let describe = &cached.describe;
     if describe.info.id != component.component_id {
         bail!(
             "component {} describe id mismatch: {}",
@@ -215,30 +433,20 @@ async fn populate_component_contract(
         );
     }
 
-    let describe_hash = compute_describe_hash(&describe)?;
-    let mut operations: Vec<_> = describe
-        .operations
+    // BTreeMap iteration keeps operations sorted by id.
+    component.describe_hash = cached.describe_hash.clone();
+    component.operations = cached
+        .schema_hashes
         .iter()
-        .map(|op| {
-            let hash = schema_hash(&op.input.schema, &op.output.schema, &describe.config_schema)
-                .map_err(|err| anyhow!("schema_hash for {}: {}", op.id, err))?;
-            Ok((op.id.clone(), hash))
-        })
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
         .map(
             |(operation_id, schema_hash)| greentic_pack::pack_lock::LockedOperation {
-                operation_id,
-                schema_hash,
+                operation_id: operation_id.clone(),
+                schema_hash: schema_hash.clone(),
             },
         )
         .collect();
-    operations.sort_by(|a, b| a.operation_id.cmp(&b.operation_id));
-
-    component.describe_hash = describe_hash;
-    component.operations = operations;
-    component.role = Some(describe.info.role);
-    component.component_version = Some(describe.info.version);
+    component.role = Some(describe.info.role.clone());
+    component.component_version = Some(describe.info.version.clone());
     Ok(())
 }
 
@@ -318,7 +526,7 @@ where
     tokio::task::block_in_place(|| handle.block_on(fut))
 }
 
-fn describe_component(engine: &Engine, bytes: &[u8]) -> Result<ComponentDescribe> {
+pub(crate) fn describe_component(engine: &Engine, bytes: &[u8]) -> Result<ComponentDescribe> {
     describe_component_untyped(engine, bytes)
 }
 
@@ -371,13 +579,6 @@ fn load_describe_from_cache_path(path: Option<&Path>) -> Result<Option<Component
     Ok(Some(describe))
 }
 
-fn compute_describe_hash(describe: &ComponentDescribe) -> Result<String> {
-    let bytes =
-        canonical::to_canonical_cbor_allow_floats(describe).context("canonicalize describe")?;
-    let digest = Sha256::digest(bytes.as_slice());
-    Ok(hex::encode(digest))
-}
-
 fn is_state_store_tenant_ctx_abi_mismatch(err: &anyhow::Error) -> bool {
     let text = format!("{:#}", err);
     text.contains("greentic:state/state-store@1.0.0")
@@ -532,4 +733,127 @@ mod tests {
         let err = collect_from_summary(&pack_dir, &flow, &summary, &mut entries).unwrap_err();
         assert!(err.to_string().contains("points to different artifacts"));
     }
+
+    struct UnreachableResolver;
+
+    impl ComponentResolver for UnreachableResolver {
+        fn resolve(&self, req: ResolveReq) -> Result<ResolvedComponent> {
+            bail!("{} is unreachable", req.reference)
+        }
+    }
+
+    fn locked(component_id: &str) -> LockedComponent {
+        LockedComponent {
+            component_id: component_id.to_string(),
+            r#ref: Some(format!("oci://example/{component_id}:1.0.0")),
+            abi_version: "0.6.0".to_string(),
+            resolved_digest: format!("sha256:{}", "a".repeat(64)),
+            describe_hash: String::new(),
+            operations: Vec::new(),
+            world: None,
+            component_version: None,
+            role: None,
+        }
+    }
+
+    #[test]
+    fn populate_components_reports_every_failure_in_order() {
+        let mut entries: BTreeMap<String, LockedComponent> =
+            ["zeta.comp", "flow.call", "alpha.comp"]
+                .into_iter()
+                .map(|id| (id.to_string(), locked(id)))
+                .collect();
+
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let cache = DescribeCache::new(temp.path());
+        let err = populate_components(
+            &Engine::default(),
+            &UnreachableResolver,
+            &cache,
+            &mut entries,
+            4,
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("failed to resolve 2 component(s)"), "{err}");
+        let alpha = err.find("alpha.comp").expect("alpha failure");
+        let zeta = err.find("zeta.comp").expect("zeta failure");
+        assert!(alpha < zeta, "{err}");
+        assert_eq!(entries["flow.call"].describe_hash, "0".repeat(64));
+        assert_eq!(entries["flow.call"].role.as_deref(), Some("builtin"));
+    }
+
+    fn sample_describe(component_id: &str) -> ComponentDescribe {
+        use greentic_types::schemas::common::schema_ir::SchemaIr;
+        use greentic_types::schemas::component::v0_6_0::{
+            ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput, schema_hash,
+        };
+
+        let schema = SchemaIr::String {
+            min_len: None,
+            max_len: None,
+            regex: None,
+            format: None,
+        };
+        ComponentDescribe {
+            info: ComponentInfo {
+                id: component_id.to_string(),
+                version: "0.1.0".to_string(),
+                role: "tool".to_string(),
+                display_name: None,
+            },
+            provided_capabilities: Vec::new(),
+            required_capabilities: Vec::new(),
+            metadata: BTreeMap::new(),
+            operations: vec![ComponentOperation {
+                id: "run".to_string(),
+                display_name: None,
+                input: ComponentRunInput {
+                    schema: schema.clone(),
+                },
+                output: ComponentRunOutput {
+                    schema: schema.clone(),
+                },
+                defaults: BTreeMap::new(),
+                redactions: Vec::new(),
+                constraints: BTreeMap::new(),
+                schema_hash: schema_hash(&schema, &schema, &schema).expect("schema hash"),
+            }],
+            config_schema: schema,
+        }
+    }
+
+    #[tokio::test]
+    async fn populate_components_runs_on_current_thread_runtime() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let wasm = temp.path().join("demo.wasm");
+        let bytes = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        fs::write(&wasm, bytes).expect("write wasm");
+        let cache = DescribeCache::new(temp.path().join("describe"));
+        let digest = format!("sha256:{:x}", Sha256::digest(bytes));
+        cache
+            .put(&digest, &sample_describe("demo.local"))
+            .expect("seed describe cache");
+
+        let mut component = locked("demo.local");
+        component.r#ref = Some(format!("file://{}", wasm.display()));
+        component.resolved_digest = digest;
+        let mut entries = BTreeMap::from([("demo.local".to_string(), component)]);
+
+        let runtime = crate::runtime::resolve_runtime(
+            Some(temp.path()),
+            Some(temp.path().join("cache").as_path()),
+            true,
+            None,
+        )
+        .expect("runtime");
+        let resolver = PackResolver::new(&runtime).expect("resolver");
+        populate_components(&Engine::default(), &resolver, &cache, &mut entries, 2)
+            .expect("resolve local component");
+
+        let resolved = &entries["demo.local"];
+        assert_eq!(resolved.role.as_deref(), Some("tool"));
+        assert_eq!(resolved.operations.len(), 1);
+    }
 }
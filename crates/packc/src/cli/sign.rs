@@ -1,18 +1,21 @@
 #![forbid(unsafe_code)]
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use ed25519_dalek::pkcs8::DecodePrivateKey;
-use ed25519_dalek::{Signer, SigningKey};
+use greentic_pack::reader::ReaderLimits;
+use greentic_pack::signing::{SignOptions, SignatureRole, sign_archive_with_options};
 use greentic_types::{PackManifest, Signature, SignatureAlgorithm, encode_pack_manifest};
 
+use crate::runtime::RuntimeContext;
+use crate::signer::{SignerSpec, open_signer};
+
 #[derive(Debug, Parser)]
 pub struct SignArgs {
-    /// Path to the pack directory containing pack.yaml
-    #[arg(long = "pack", value_name = "DIR")]
+    /// Path to the pack directory containing pack.yaml, or a built .gtpack archive
+    #[arg(long = "pack", value_name = "DIR|GTPACK")]
     pub pack: PathBuf,
 
     /// Path to manifest.cbor (defaults to <pack>/dist/manifest.cbor)
@@ -20,22 +23,76 @@ pub struct SignArgs {
     pub manifest: Option<PathBuf>,
 
     /// Ed25519 private key in PKCS#8 PEM format
-    #[arg(long = "key", value_name = "FILE")]
-    pub key: PathBuf,
+    #[arg(
+        long = "key",
+        value_name = "FILE",
+        required_unless_present = "signer",
+        conflicts_with = "signer"
+    )]
+    pub key: Option<PathBuf>,
+
+    /// External signer: agent://<socket>, exec:<cmd> or file:<path>
+    #[arg(long = "signer", value_name = "SPEC")]
+    pub signer: Option<SignerSpec>,
 
     /// Optional key identifier to embed alongside the signature
     #[arg(long = "key-id", value_name = "ID", default_value = "default")]
     pub key_id: String,
+
+    /// PEM certificate chain for the key (archives only; default: self-signed certificate)
+    #[arg(long = "chain", value_name = "FILE")]
+    pub chain: Option<PathBuf>,
+
+    /// Where to write the signed archive (archives only; default: rewrite in place)
+    #[arg(long = "out", value_name = "FILE")]
+    pub out: Option<PathBuf>,
+
+    /// Signer role recorded in the archive signature: builder, approver or publisher
+    #[arg(long = "role", value_name = "ROLE")]
+    pub role: Option<SignatureRole>,
+
+    /// Keep existing archive signatures and add this one (countersign)
+    #[arg(long = "append", default_value_t = false)]
+    pub append: bool,
 }
 
-pub fn handle(args: SignArgs, json: bool) -> Result<()> {
+impl SignArgs {
+    fn signer_spec(&self) -> Result<SignerSpec> {
+        match (&self.signer, &self.key) {
+            (Some(spec), _) => Ok(spec.clone()),
+            (None, Some(key)) => Ok(SignerSpec::KeyFile(key.clone())),
+            (None, None) => {
+                anyhow::bail!("{}", crate::cli_i18n::t("cli.sign.error.missing_signer"))
+            }
+        }
+    }
+}
+
+pub fn handle(args: SignArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    if args.pack.is_file() {
+        return sign_gtpack(&args, json, &runtime.reader_limits);
+    }
+    if args.role.is_some() {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::t("cli.sign.error.role_requires_archive")
+        );
+    }
+
     let pack_dir = args
         .pack
         .canonicalize()
         .with_context(|| format!("failed to resolve pack dir {}", args.pack.display()))?;
     let manifest_path = args
         .manifest
-        .map(|p| if p.is_relative() { pack_dir.join(p) } else { p })
+        .as_ref()
+        .map(|p| {
+            if p.is_relative() {
+                pack_dir.join(p)
+            } else {
+                p.clone()
+            }
+        })
         .unwrap_or_else(|| pack_dir.join("dist").join("manifest.cbor"));
 
     let manifest_bytes = fs::read(&manifest_path)
@@ -45,11 +102,18 @@ pub fn handle(args: SignArgs, json: bool) -> Result<()> {
 
     let unsigned_bytes = encode_unsigned(&manifest)?;
 
-    let private_pem = fs::read_to_string(&args.key)
-        .with_context(|| format!("failed to read private key {}", args.key.display()))?;
-    let signing_key =
-        SigningKey::from_pkcs8_pem(&private_pem).context("failed to parse ed25519 private key")?;
-    let signature_bytes = signing_key.sign(&unsigned_bytes).to_bytes().to_vec();
+    let spec = args.signer_spec()?;
+    let signer = open_signer(&spec, Some(&args.key_id), None)?;
+    let signature = signer
+        .sign(&unsigned_bytes)
+        .with_context(|| format!("failed to sign with {spec}"))?;
+    if !signature.alg.eq_ignore_ascii_case("ed25519") {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::tf("cli.sign.error.unsupported_alg", &[&signature.alg])
+        );
+    }
+    let signature_bytes = signature.sig;
 
     let mut signed_manifest = manifest.clone();
     signed_manifest.signatures.signatures.push(Signature::new(
@@ -100,3 +164,70 @@ fn encode_unsigned(manifest: &PackManifest) -> Result<Vec<u8>> {
     unsigned.signatures.signatures.clear();
     encode_pack_manifest(&unsigned).context("failed to encode unsigned manifest")
 }
+
+fn sign_gtpack(args: &SignArgs, json: bool, limits: &ReaderLimits) -> Result<()> {
+    let chain = match args.chain.as_ref() {
+        Some(chain) => Some(
+            fs::read(chain)
+                .with_context(|| format!("failed to read certificate chain {}", chain.display()))?,
+        ),
+        None => None,
+    };
+    let spec = args.signer_spec()?;
+    let signer = open_signer(&spec, Some(&args.key_id), chain)?;
+
+    let out: &Path = args.out.as_deref().unwrap_or(&args.pack);
+    let options = SignOptions {
+        role: args.role,
+        append: args.append,
+    };
+    let signed = sign_archive_with_options(&args.pack, out, &*signer, limits, &options)
+        .with_context(|| format!("failed to sign {}", args.pack.display()))?;
+    let fingerprint = signed.key_fingerprint.clone().unwrap_or_default();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "status": crate::cli_i18n::t("cli.sign.status.signed"),
+                "archive": signed.path,
+                "digest": signed.digest,
+                "key_fingerprint": fingerprint,
+                "role": signed.role,
+                "signature": signed.signature_path,
+                "signatures": signed.signatures,
+                "replaced": signed.replaced,
+            }))?
+        );
+    } else {
+        println!("{}", crate::cli_i18n::t("cli.sign.signed_archive"));
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.sign.archive", &[&signed.path.display().to_string()])
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.sign.digest", &[&signed.digest])
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.sign.key_fingerprint", &[&fingerprint])
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.sign.signature_slot",
+                &[
+                    &signed.signature_path,
+                    signed.role.map_or("-", |role| role.as_str())
+                ]
+            )
+        );
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.sign.signatures", &[&signed.signatures.to_string()])
+        );
+    }
+
+    Ok(())
+}
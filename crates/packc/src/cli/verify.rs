@@ -1,34 +1,75 @@
 #![forbid(unsafe_code)]
 
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use ed25519_dalek::VerifyingKey;
 use ed25519_dalek::pkcs8::DecodePublicKey;
+use greentic_pack::signing::{SignatureRole, verify_archive};
+use greentic_pack::trust::TrustPolicy;
 use greentic_types::{PackManifest, SignatureAlgorithm, encode_pack_manifest};
 
+use crate::runtime::RuntimeContext;
+
 #[derive(Debug, Parser)]
 pub struct VerifyArgs {
-    /// Path to the pack directory containing pack.yaml
-    #[arg(long = "pack", value_name = "DIR")]
-    pub pack: PathBuf,
+    /// Built .gtpack archive to verify
+    #[arg(value_name = "GTPACK", conflicts_with = "pack")]
+    pub archive: Option<PathBuf>,
+
+    /// Path to the pack directory containing pack.yaml, or a built .gtpack archive
+    #[arg(long = "pack", value_name = "DIR|GTPACK")]
+    pub pack: Option<PathBuf>,
 
     /// Path to manifest.cbor (defaults to <pack>/dist/manifest.cbor)
     #[arg(long = "manifest", value_name = "FILE")]
     pub manifest: Option<PathBuf>,
 
-    /// Ed25519 public key in PKCS#8 PEM format
+    /// Ed25519 public key in PKCS#8 PEM format (repeatable)
     #[arg(long = "key", value_name = "FILE")]
-    pub key: PathBuf,
+    pub key: Vec<PathBuf>,
+
+    /// CA certificate(s) in PEM format that archive signing certificates must chain to (repeatable)
+    #[arg(long = "trust-root", value_name = "FILE")]
+    pub trust_root: Vec<PathBuf>,
+
+    /// Trust policy directory (roots/, keys/, policy.yaml); defaults to <greentic_root>/trust
+    #[arg(long = "trust-dir", value_name = "DIR")]
+    pub trust_dir: Option<PathBuf>,
+
+    /// Require signatures from at least N distinct keys (--key keys for manifests)
+    #[arg(long = "threshold", value_name = "N")]
+    pub threshold: Option<usize>,
+
+    /// Require a trusted archive signature in this role (repeatable)
+    #[arg(long = "require-role", value_name = "ROLE")]
+    pub require_role: Vec<SignatureRole>,
 }
 
-pub fn handle(args: VerifyArgs, json: bool) -> Result<()> {
-    let pack_dir = args
-        .pack
+pub fn handle(args: VerifyArgs, json: bool, runtime: &RuntimeContext) -> Result<()> {
+    let Some(target) = args.archive.clone().or_else(|| args.pack.clone()) else {
+        anyhow::bail!("{}", crate::cli_i18n::t("cli.verify.error.missing_target"));
+    };
+    if target.is_file() {
+        return verify_gtpack(&target, &args, json, runtime);
+    }
+    if args.key.is_empty() {
+        anyhow::bail!("{}", crate::cli_i18n::t("cli.verify.error.missing_key"));
+    }
+    if !args.require_role.is_empty() {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::t("cli.verify.error.roles_require_archive")
+        );
+    }
+    let threshold = args.threshold.unwrap_or(1).max(1);
+
+    let pack_dir = target
         .canonicalize()
-        .with_context(|| format!("failed to resolve pack dir {}", args.pack.display()))?;
+        .with_context(|| format!("failed to resolve pack dir {}", target.display()))?;
     let manifest_path = args
         .manifest
         .map(|p| if p.is_relative() { pack_dir.join(p) } else { p })
@@ -46,14 +87,19 @@ pub fn handle(args: VerifyArgs, json: bool) -> Result<()> {
         );
     }
 
-    let public_pem = fs::read_to_string(&args.key)
-        .with_context(|| format!("failed to read public key {}", args.key.display()))?;
-    let verifying_key =
-        VerifyingKey::from_public_key_pem(&public_pem).context("failed to parse public key")?;
+    let mut verifying_keys = Vec::new();
+    for key in &args.key {
+        let public_pem = fs::read_to_string(key)
+            .with_context(|| format!("failed to read public key {}", key.display()))?;
+        verifying_keys.push(
+            VerifyingKey::from_public_key_pem(&public_pem).context("failed to parse public key")?,
+        );
+    }
 
     let unsigned_bytes = encode_unsigned(&manifest)?;
 
-    let mut verified = false;
+    // Distinct --key keys that verified at least one signature.
+    let mut signers = BTreeSet::new();
     let mut errors = Vec::new();
     for sig in &manifest.signatures.signatures {
         if sig.algorithm != SignatureAlgorithm::Ed25519 {
@@ -64,18 +110,18 @@ pub fn handle(args: VerifyArgs, json: bool) -> Result<()> {
             errors.push("invalid signature bytes".to_string());
             continue;
         };
-        if verifying_key
-            .verify_strict(&unsigned_bytes, &signature)
-            .is_ok()
+        match verifying_keys
+            .iter()
+            .position(|key| key.verify_strict(&unsigned_bytes, &signature).is_ok())
         {
-            verified = true;
-            break;
-        } else {
-            errors.push("signature verification failed".to_string());
+            Some(idx) => {
+                signers.insert(idx);
+            }
+            None => errors.push("signature verification failed".to_string()),
         }
     }
 
-    if !verified {
+    if signers.is_empty() {
         anyhow::bail!(
             "{}",
             crate::cli_i18n::tf(
@@ -84,6 +130,15 @@ pub fn handle(args: VerifyArgs, json: bool) -> Result<()> {
             )
         );
     }
+    if signers.len() < threshold {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.verify.error.threshold_not_met",
+                &[&signers.len().to_string(), &threshold.to_string()]
+            )
+        );
+    }
 
     if json {
         println!(
@@ -92,6 +147,7 @@ pub fn handle(args: VerifyArgs, json: bool) -> Result<()> {
                 "status": crate::cli_i18n::t("cli.verify.status.verified"),
                 "manifest": manifest_path,
                 "signatures": manifest.signatures.signatures.len(),
+                "verified_keys": signers.len(),
             }))?
         );
     } else {
@@ -120,3 +176,129 @@ fn encode_unsigned(manifest: &PackManifest) -> Result<Vec<u8>> {
     unsigned.signatures.signatures.clear();
     encode_pack_manifest(&unsigned).context("failed to encode unsigned manifest")
 }
+
+fn verify_gtpack(
+    path: &Path,
+    args: &VerifyArgs,
+    json: bool,
+    runtime: &RuntimeContext,
+) -> Result<()> {
+    let mut trust = archive_trust(
+        runtime,
+        args.trust_dir.as_deref(),
+        &args.key,
+        &args.trust_root,
+    )?;
+    if args.threshold.is_some() || !args.require_role.is_empty() {
+        let mut requirement = trust.requirement().cloned().unwrap_or_default();
+        if let Some(threshold) = args.threshold {
+            requirement.threshold = threshold;
+        }
+        for role in &args.require_role {
+            if !requirement.roles.contains(role) {
+                requirement.roles.push(*role);
+            }
+        }
+        trust.set_requirement(requirement);
+    }
+    if trust.is_empty() {
+        anyhow::bail!("{}", crate::cli_i18n::t("cli.verify.error.missing_anchor"));
+    }
+
+    let report = verify_archive(path, &trust, &runtime.reader_limits)
+        .with_context(|| format!("failed to verify {}", path.display()))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{}",
+            crate::cli_i18n::tf("cli.verify.archive", &[&path.display().to_string()])
+        );
+        for check in &report.signatures {
+            let status = if check.valid && check.trusted {
+                crate::cli_i18n::t("cli.verify.status.verified")
+            } else if check.valid {
+                crate::cli_i18n::t("cli.verify.status.untrusted")
+            } else {
+                crate::cli_i18n::t("cli.verify.status.rejected")
+            };
+            let role = check.role.map_or("-", |role| role.as_str());
+            let detail = match (&check.trust, &check.error) {
+                (Some(decision), _) => format!("{}: {}", decision.rule, decision.detail),
+                (None, Some(error)) => error.clone(),
+                (None, None) => String::new(),
+            };
+            println!(
+                "{}",
+                crate::cli_i18n::tf(
+                    "cli.verify.signature_result",
+                    &[&check.path, role, &status, &detail]
+                )
+            );
+        }
+        if let Some(decision) = report.trust.as_ref() {
+            println!(
+                "{}",
+                crate::cli_i18n::tf("cli.verify.decision", &[&decision.rule, &decision.detail])
+            );
+        }
+        for warning in &report.warnings {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    if report.signatures.is_empty() {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.verify.error.archive_unsigned",
+                &[&path.display().to_string()]
+            )
+        );
+    }
+    if !report.is_trusted() {
+        anyhow::bail!(
+            "{}",
+            crate::cli_i18n::tf(
+                "cli.verify.error.archive_untrusted",
+                &[&path.display().to_string()]
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Trust policy from the trust directory plus keys and roots pinned on the
+/// command line.
+pub(crate) fn archive_trust(
+    runtime: &RuntimeContext,
+    trust_dir: Option<&Path>,
+    keys: &[PathBuf],
+    roots: &[PathBuf],
+) -> Result<TrustPolicy> {
+    let mut trust = runtime.trust_policy(trust_dir)?;
+    for key in keys {
+        let public_pem = fs::read_to_string(key)
+            .with_context(|| format!("failed to read public key {}", key.display()))?;
+        trust
+            .pin_public_key_pem(&file_label(key), &public_pem)
+            .with_context(|| format!("invalid public key {}", key.display()))?;
+    }
+    for root in roots {
+        let root_pem = fs::read(root)
+            .with_context(|| format!("failed to read trust root {}", root.display()))?;
+        trust
+            .add_roots_pem(&file_label(root), &root_pem)
+            .with_context(|| format!("invalid trust root {}", root.display()))?;
+    }
+    Ok(trust)
+}
+
+/// Rule name for a key or root passed on the command line.
+fn file_label(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("cli")
+        .to_string()
+}
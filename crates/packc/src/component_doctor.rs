@@ -0,0 +1,299 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeSet, HashMap};
+
+use greentic_pack::PackLoad;
+use greentic_pack::builder::ComponentEntry;
+use greentic_types::ComponentManifest;
+use greentic_types::pack::extensions::component_manifests::{
+    ComponentManifestIndexV1, EXT_COMPONENT_MANIFEST_INDEX_V1,
+};
+use greentic_types::pack_manifest::ExtensionInline as PackManifestExtensionInline;
+use greentic_types::validate::{Diagnostic, Severity};
+use serde_json::{Value, json};
+
+use crate::lazy_entries::{LazyEntries, entry_bytes};
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+pub struct ComponentDoctorOutput {
+    pub diagnostics: Vec<Diagnostic>,
+    pub has_errors: bool,
+}
+
+/// Check every component's wasm and manifest in process.
+///
+/// Manifests in the `greentic-component` layout (`name`, `artifacts`, `hashes`,
+/// `describe_export`, `config_schema`) get their artifact hash verified against
+/// the bundled wasm; manifests written by `greentic-pack build` are checked
+/// against the pack manifest entry. Wasm missing from `load.files` is read
+/// from `archive` when given.
+pub fn run_component_doctor(
+    load: &PackLoad,
+    archive: Option<&LazyEntries>,
+) -> ComponentDoctorOutput {
+    let mut output = ComponentDoctorOutput {
+        diagnostics: Vec::new(),
+        has_errors: false,
+    };
+    let indexed = manifest_index_paths(load);
+
+    for component in &load.manifest.components {
+        let wasm_bytes = match entry_bytes(load, archive, &component.file_wasm) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => {
+                output.diagnostics.push(Diagnostic {
+                    severity: Severity::Warn,
+                    code: "PACK_COMPONENT_DOCTOR_MISSING_WASM".to_string(),
+                    message: "component wasm missing from pack; skipping component doctor"
+                        .to_string(),
+                    path: Some(component.file_wasm.clone()),
+                    hint: Some(
+                        "rebuild with --bundle=cache or supply cached artifacts".to_string(),
+                    ),
+                    data: Value::Null,
+                });
+                continue;
+            }
+            Err(err) => {
+                output.has_errors = true;
+                output.diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: "PACK_COMPONENT_DOCTOR_FAILED".to_string(),
+                    message: "component doctor failed".to_string(),
+                    path: Some(component.name.clone()),
+                    hint: Some("fix the component errors listed in data.errors".to_string()),
+                    data: json!({ "errors": [format!("{err:#}")] }),
+                });
+                continue;
+            }
+        };
+
+        let manifest_file = component
+            .manifest_file
+            .clone()
+            .or_else(|| indexed.get(&component.name).cloned());
+        let raw_manifest = manifest_file
+            .as_deref()
+            .and_then(|path| load.files.get(path))
+            .and_then(|bytes| decode_manifest_value(bytes));
+
+        let mut errors = check_wasm(&wasm_bytes);
+        match raw_manifest {
+            Some(value) if component_manifest_has_required_fields(&value) => {
+                errors.extend(check_component_manifest_fields(&value, &wasm_bytes));
+            }
+            _ => match load.get_component_manifest_prefer_file(&component.name) {
+                Ok(Some(manifest)) => errors.extend(check_pack_manifest(&manifest, component)),
+                Ok(None) => {
+                    output
+                        .diagnostics
+                        .push(component_manifest_missing_diag(&manifest_file));
+                    continue;
+                }
+                Err(err) => {
+                    tracing::debug!(
+                        manifest = %component.name,
+                        "failed to parse component manifest for doctor: {err}"
+                    );
+                    output
+                        .diagnostics
+                        .push(component_manifest_missing_diag(&manifest_file));
+                    continue;
+                }
+            },
+        }
+
+        if !errors.is_empty() {
+            output.has_errors = true;
+            output.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "PACK_COMPONENT_DOCTOR_FAILED".to_string(),
+                message: "component doctor failed".to_string(),
+                path: Some(component.name.clone()),
+                hint: Some("fix the component errors listed in data.errors".to_string()),
+                data: json!({ "errors": errors }),
+            });
+        }
+    }
+
+    output
+}
+
+fn manifest_index_paths(load: &PackLoad) -> HashMap<String, String> {
+    let mut paths = HashMap::new();
+    if let Some(index) = load
+        .gpack_manifest
+        .as_ref()
+        .and_then(|manifest| manifest.extensions.as_ref())
+        .and_then(|map| map.get(EXT_COMPONENT_MANIFEST_INDEX_V1))
+        .and_then(|entry| entry.inline.as_ref())
+        .and_then(|inline| match inline {
+            PackManifestExtensionInline::Other(value) => Some(value),
+            _ => None,
+        })
+        .and_then(|value| ComponentManifestIndexV1::from_extension_value(value).ok())
+    {
+        for entry in index.entries {
+            paths.insert(entry.component_id, entry.manifest_file);
+        }
+    }
+    paths
+}
+
+fn decode_manifest_value(bytes: &[u8]) -> Option<Value> {
+    serde_json::from_slice::<Value>(bytes)
+        .ok()
+        .or_else(|| serde_cbor::from_slice::<Value>(bytes).ok())
+}
+
+fn check_wasm(bytes: &[u8]) -> Vec<String> {
+    if bytes.starts_with(WASM_MAGIC) {
+        Vec::new()
+    } else {
+        vec!["component wasm is not a WebAssembly binary".to_string()]
+    }
+}
+
+fn component_manifest_has_required_fields(manifest: &Value) -> bool {
+    manifest.get("name").is_some()
+        && manifest.get("artifacts").is_some()
+        && manifest.get("hashes").is_some()
+        && manifest.get("describe_export").is_some()
+        && manifest.get("config_schema").is_some()
+}
+
+/// Field checks for a `greentic-component` manifest.
+fn check_component_manifest_fields(manifest: &Value, wasm_bytes: &[u8]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let non_empty = |key: &str| {
+        manifest
+            .get(key)
+            .and_then(Value::as_str)
+            .is_some_and(|value| !value.trim().is_empty())
+    };
+    for key in ["name", "describe_export"] {
+        if !non_empty(key) {
+            errors.push(format!("manifest field `{key}` must be a non-empty string"));
+        }
+    }
+    if !manifest
+        .pointer("/artifacts/component_wasm")
+        .is_some_and(Value::is_string)
+    {
+        errors.push("manifest field `artifacts.component_wasm` must be a string".to_string());
+    }
+    if !manifest.get("config_schema").is_some_and(Value::is_object) {
+        errors.push("manifest field `config_schema` must be an object".to_string());
+    }
+    match manifest
+        .pointer("/hashes/component_wasm")
+        .and_then(Value::as_str)
+    {
+        Some(declared) => match declared.strip_prefix("blake3:") {
+            Some(expected) => {
+                let actual = blake3::hash(wasm_bytes).to_hex().to_string();
+                if !expected.eq_ignore_ascii_case(&actual) {
+                    errors.push(format!(
+                        "hashes.component_wasm is {declared} but the bundled wasm hashes to blake3:{actual}"
+                    ));
+                }
+            }
+            None => errors.push(format!(
+                "hashes.component_wasm `{declared}` must use the blake3: prefix"
+            )),
+        },
+        None => errors.push("manifest field `hashes.component_wasm` must be a string".to_string()),
+    }
+    errors
+}
+
+/// Checks for a manifest produced by `greentic-pack build`.
+fn check_pack_manifest(manifest: &ComponentManifest, component: &ComponentEntry) -> Vec<String> {
+    let mut errors = Vec::new();
+    if manifest.id.as_str() != component.name {
+        errors.push(format!(
+            "manifest id `{}` does not match component `{}`",
+            manifest.id.as_str(),
+            component.name
+        ));
+    }
+    if manifest.version != component.version {
+        errors.push(format!(
+            "manifest version {} does not match pack entry version {}",
+            manifest.version, component.version
+        ));
+    }
+    if manifest.world.trim().is_empty() {
+        errors.push("manifest world must not be empty".to_string());
+    } else if let Some(world) = component.world.as_deref()
+        && world != manifest.world
+    {
+        errors.push(format!(
+            "manifest world `{}` does not match pack entry world `{world}`",
+            manifest.world
+        ));
+    }
+    let mut seen = BTreeSet::new();
+    for operation in &manifest.operations {
+        if operation.name.trim().is_empty() {
+            errors.push("manifest declares an operation with an empty name".to_string());
+        } else if !seen.insert(operation.name.as_str()) {
+            errors.push(format!(
+                "manifest declares operation `{}` more than once",
+                operation.name
+            ));
+        }
+    }
+    errors
+}
+
+fn component_manifest_missing_diag(manifest_file: &Option<String>) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warn,
+        code: "PACK_COMPONENT_DOCTOR_MISSING_MANIFEST".to_string(),
+        message: "component manifest missing or incomplete; skipping component doctor".to_string(),
+        path: manifest_file.clone(),
+        hint: Some("rebuild the pack to include component manifests".to_string()),
+        data: Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(hash: &str) -> Value {
+        json!({
+            "name": "demo.echo",
+            "artifacts": { "component_wasm": "component.wasm" },
+            "hashes": { "component_wasm": hash },
+            "describe_export": "describe",
+            "config_schema": { "type": "object" },
+        })
+    }
+
+    #[test]
+    fn component_manifest_hash_is_checked_against_wasm() {
+        let wasm = b"\0asm\x0d\0\x01\0".to_vec();
+        assert!(check_wasm(&wasm).is_empty());
+        assert_eq!(check_wasm(b"not wasm").len(), 1);
+
+        let hash = format!("blake3:{}", blake3::hash(&wasm).to_hex());
+        assert!(check_component_manifest_fields(&manifest(&hash), &wasm).is_empty());
+
+        let stale = format!("blake3:{}", "0".repeat(64));
+        let errors = check_component_manifest_fields(&manifest(&stale), &wasm);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("hashes.component_wasm"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn component_manifest_fields_are_required() {
+        let mut value = manifest("sha256:abc");
+        value["describe_export"] = json!("");
+        value["config_schema"] = json!([]);
+        let errors = check_component_manifest_fields(&value, b"\0asm");
+        assert_eq!(errors.len(), 3, "{errors:?}");
+    }
+}
@@ -8,6 +8,12 @@ use wasmtime::component::Linker;
 use wasmtime_wasi::p2::add_to_linker_sync;
 use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 
+/// Version tag for the import surface provided to describe-only instantiation.
+///
+/// Bump this whenever the stubs below change shape so cached describe results
+/// produced against the previous surface are no longer reused.
+pub const DESCRIBE_HOST_ABI: &str = "greentic:component@0.6.0+describe-host-stubs.1";
+
 pub struct DescribeHostState {
     table: ResourceTable,
     wasi: WasiCtx,
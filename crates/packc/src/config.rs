@@ -1,9 +1,12 @@
+use crate::distribution::DISTRIBUTION_BUNDLE_KIND;
 use crate::path_safety::normalize_under_root;
 use anyhow::{Context, Result, bail};
+use greentic_pack::PackKind as SpecPackKind;
+use greentic_pack::builder::{DistributionSection, validate_distribution};
 use greentic_types::pack_manifest::ExtensionInline;
 use greentic_types::provider::{PROVIDER_EXTENSION_ID, ProviderDecl, ProviderExtensionInline};
 use greentic_types::{
-    ComponentCapabilities, ComponentProfiles, ExtensionRef, FlowKind, ResourceHints,
+    ComponentCapabilities, ComponentId, ComponentProfiles, ExtensionRef, FlowKind, ResourceHints,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -31,8 +34,14 @@ pub struct PackConfig {
     pub flows: Vec<FlowConfig>,
     #[serde(default)]
     pub assets: Vec<AssetConfig>,
+    /// MCP routers composed with the protocol adapter into components at resolve time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_components: Vec<McpComponentConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub extensions: Option<BTreeMap<String, ExtensionRef>>,
+    /// Artifacts shipped by a `kind: distribution-bundle` pack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<DistributionSection>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -81,6 +90,31 @@ pub struct DependencyConfig {
     pub required_capabilities: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpComponentConfig {
+    pub id: String,
+    /// Local router component, relative to the pack root.
+    pub router_ref: PathBuf,
+    #[serde(default = "McpComponentConfig::default_protocol")]
+    pub protocol: String,
+    #[serde(default = "McpComponentConfig::default_adapter_template")]
+    pub adapter_template: String,
+}
+
+impl McpComponentConfig {
+    pub const PROTOCOL_25_06_18: &'static str = "25.06.18";
+    pub const PROTOCOL_LATEST: &'static str = "latest";
+    pub const ADAPTER_DEFAULT: &'static str = "default";
+
+    fn default_protocol() -> String {
+        Self::PROTOCOL_25_06_18.to_string()
+    }
+
+    fn default_adapter_template() -> String {
+        Self::ADAPTER_DEFAULT.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AssetConfig {
     pub path: PathBuf,
@@ -129,6 +163,16 @@ impl FlowKindLabel {
             FlowKindLabel::Http => FlowKind::Http,
         }
     }
+
+    pub fn from_kind(kind: FlowKind) -> Self {
+        match kind {
+            FlowKind::Messaging => FlowKindLabel::Messaging,
+            FlowKind::Event => FlowKindLabel::Event,
+            FlowKind::ComponentConfig => FlowKindLabel::ComponentConfig,
+            FlowKind::Job => FlowKindLabel::Job,
+            FlowKind::Http => FlowKindLabel::Http,
+        }
+    }
 }
 
 pub fn load_pack_config(root: &Path) -> Result<PackConfig> {
@@ -148,13 +192,78 @@ pub fn load_pack_config(root: &Path) -> Result<PackConfig> {
     for asset in cfg.assets.iter_mut() {
         asset.path = normalize_under_root(root, &asset.path)?;
     }
+    validate_mcp_components(&cfg)
+        .with_context(|| format!("{} has invalid mcp_components", manifest_path.display()))?;
+    for mcp in cfg.mcp_components.iter_mut() {
+        mcp.router_ref = normalize_under_root(root, &mcp.router_ref)?;
+    }
 
     validate_extensions(cfg.extensions.as_ref(), strict_extensions())?;
+    validate_pack_distribution(&cfg)
+        .with_context(|| format!("{} has an invalid distribution", manifest_path.display()))?;
 
     Ok(cfg)
 }
 
-fn strict_extensions() -> bool {
+/// The `distribution` section must match the pack kind.
+pub(crate) fn validate_pack_distribution(cfg: &PackConfig) -> Result<()> {
+    let kind = cfg
+        .kind
+        .eq_ignore_ascii_case(DISTRIBUTION_BUNDLE_KIND)
+        .then_some(SpecPackKind::DistributionBundle);
+    validate_distribution(kind.as_ref(), cfg.distribution.as_ref())
+}
+
+pub(crate) fn validate_mcp_components(cfg: &PackConfig) -> Result<()> {
+    let mut seen = std::collections::BTreeSet::new();
+    for mcp in &cfg.mcp_components {
+        // The id names the composed artifact's directory under `.packc/mcp/`.
+        ComponentId::new(mcp.id.clone())
+            .with_context(|| format!("invalid mcp component id `{}`", mcp.id))?;
+        if matches!(mcp.id.as_str(), "." | "..") {
+            bail!("invalid mcp component id `{}`", mcp.id);
+        }
+        if !seen.insert(mcp.id.as_str()) {
+            bail!("duplicate mcp component id `{}`", mcp.id);
+        }
+        if cfg
+            .components
+            .iter()
+            .any(|component| component.id == mcp.id)
+        {
+            bail!(
+                "mcp component `{}` clashes with a component of the same id",
+                mcp.id
+            );
+        }
+        if mcp.router_ref.to_string_lossy().contains("://") {
+            bail!(
+                "mcp component `{}` router_ref must be a local path (remote refs are not supported yet)",
+                mcp.id
+            );
+        }
+        if !matches!(
+            mcp.protocol.as_str(),
+            McpComponentConfig::PROTOCOL_25_06_18 | McpComponentConfig::PROTOCOL_LATEST
+        ) {
+            bail!(
+                "mcp component `{}` uses unsupported protocol `{}`",
+                mcp.id,
+                mcp.protocol
+            );
+        }
+        if mcp.adapter_template != McpComponentConfig::ADAPTER_DEFAULT {
+            bail!(
+                "mcp component `{}` uses unsupported adapter_template `{}` (only `default` is available)",
+                mcp.id,
+                mcp.adapter_template
+            );
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn strict_extensions() -> bool {
     matches!(
         std::env::var("GREENTIC_PACK_STRICT_EXTENSIONS")
             .unwrap_or_default()
@@ -172,40 +281,47 @@ fn validate_extensions(
     };
 
     for (key, ext) in exts {
-        if ext.kind.trim().is_empty() {
-            bail!("extensions[{key}] kind must not be empty");
-        }
-        if ext.version.trim().is_empty() {
-            bail!("extensions[{key}] version must not be empty");
+        validate_extension(key, ext, strict)?;
+    }
+
+    Ok(())
+}
+
+/// Validate a single `extensions` entry of pack.yaml.
+pub(crate) fn validate_extension(key: &str, ext: &ExtensionRef, strict: bool) -> Result<()> {
+    if ext.kind.trim().is_empty() {
+        bail!("extensions[{key}] kind must not be empty");
+    }
+    if ext.version.trim().is_empty() {
+        bail!("extensions[{key}] version must not be empty");
+    }
+    if ext.kind != key {
+        bail!(
+            "extensions[{key}] kind `{}` must match the extension key",
+            ext.kind
+        );
+    }
+    if strict && let Some(location) = ext.location.as_deref() {
+        let digest_missing = ext
+            .digest
+            .as_ref()
+            .map(|d| d.trim().is_empty())
+            .unwrap_or(true);
+        if digest_missing {
+            bail!("extensions[{key}] location requires digest in strict mode");
         }
-        if ext.kind != *key {
+        let allowed = location.starts_with("oci://")
+            || location.starts_with("file://")
+            || location.starts_with("https://");
+        if !allowed {
             bail!(
-                "extensions[{key}] kind `{}` must match the extension key",
-                ext.kind
+                "extensions[{key}] location `{location}` uses an unsupported scheme; allowed: oci://, file://, https://"
             );
         }
-        if strict && let Some(location) = ext.location.as_deref() {
-            let digest_missing = ext
-                .digest
-                .as_ref()
-                .map(|d| d.trim().is_empty())
-                .unwrap_or(true);
-            if digest_missing {
-                bail!("extensions[{key}] location requires digest in strict mode");
-            }
-            let allowed = location.starts_with("oci://")
-                || location.starts_with("file://")
-                || location.starts_with("https://");
-            if !allowed {
-                bail!(
-                    "extensions[{key}] location `{location}` uses an unsupported scheme; allowed: oci://, file://, https://"
-                );
-            }
-        }
+    }
 
-        if ext.kind == PROVIDER_EXTENSION_ID || ext.kind == LEGACY_PROVIDER_EXTENSION_KIND {
-            validate_provider_extension(key, ext)?;
-        }
+    if ext.kind == PROVIDER_EXTENSION_ID || ext.kind == LEGACY_PROVIDER_EXTENSION_KIND {
+        validate_provider_extension(key, ext)?;
     }
 
     Ok(())
@@ -387,4 +503,34 @@ mod tests {
         );
         validate_extensions(Some(&extensions), false).expect("unknown extensions should pass");
     }
+
+    #[test]
+    fn mcp_components_default_protocol_and_reject_remote_routers() {
+        let yaml = |router: &str| {
+            format!(
+                "pack_id: demo.mcp\nversion: 0.1.0\nkind: application\npublisher: demo\n\
+                 mcp_components:\n  - id: weather\n    router_ref: {router}\n"
+            )
+        };
+        let temp = tempfile::tempdir().expect("temp dir");
+        std::fs::write(temp.path().join("pack.yaml"), yaml("mcp/router.wasm")).expect("pack.yaml");
+        let cfg = load_pack_config(temp.path()).expect("load pack.yaml");
+        let mcp = &cfg.mcp_components[0];
+        assert_eq!(mcp.protocol, McpComponentConfig::PROTOCOL_25_06_18);
+        assert_eq!(mcp.adapter_template, McpComponentConfig::ADAPTER_DEFAULT);
+        assert!(mcp.router_ref.ends_with("mcp/router.wasm"));
+
+        std::fs::write(
+            temp.path().join("pack.yaml"),
+            yaml("oci://ghcr.io/demo/router:1"),
+        )
+        .expect("pack.yaml");
+        let err = load_pack_config(temp.path()).expect_err("remote router rejected");
+        assert!(format!("{err:#}").contains("must be a local path"));
+
+        let escaping = yaml("mcp/router.wasm").replace("id: weather", "id: ../../x");
+        std::fs::write(temp.path().join("pack.yaml"), escaping).expect("pack.yaml");
+        let err = load_pack_config(temp.path()).expect_err("path-like id rejected");
+        assert!(format!("{err:#}").contains("invalid mcp component id"));
+    }
 }
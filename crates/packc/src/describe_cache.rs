@@ -0,0 +1,374 @@
+#![forbid(unsafe_code)]
+
+//! Persistent, content-addressed cache of component `describe()` results.
+//!
+//! Entries live under `<cache_dir>/describe/` and are keyed by the component's
+//! resolved digest plus [`DESCRIBE_HOST_ABI`], so a host stub change never
+//! serves a describe payload produced against a different import surface.
+//! `resolve`, the pack lock doctor and `qa` share the same store.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, anyhow};
+use greentic_types::cbor::canonical;
+use greentic_types::schemas::component::v0_6_0::{ComponentDescribe, schema_hash};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::component_host_stubs::DESCRIBE_HOST_ABI;
+use crate::runtime::RuntimeContext;
+
+const ENTRY_EXTENSION: &str = "cbor";
+
+/// A cached describe result with the hashes recorded in `pack.lock.cbor`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDescribe {
+    pub digest: String,
+    pub abi: String,
+    pub describe_hash: String,
+    /// Operation id -> `schema_hash`.
+    pub schema_hashes: BTreeMap<String, String>,
+    pub describe: ComponentDescribe,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub root: PathBuf,
+    pub abi: String,
+    pub entries: usize,
+    pub bytes: u64,
+    /// Entries written against another host ABI or that no longer decode.
+    pub stale: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PruneOptions {
+    /// Also remove entries not used within this duration.
+    pub older_than: Option<Duration>,
+    /// Remove every entry.
+    pub all: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub removed: usize,
+    pub bytes: u64,
+    pub kept: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DescribeCache {
+    root: PathBuf,
+}
+
+impl DescribeCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn for_runtime(runtime: &RuntimeContext) -> Self {
+        Self::new(runtime.cache_dir().join("describe"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Look up the describe result for `digest` (`sha256:<hex>`).
+    ///
+    /// Unreadable or mismatched entries are treated as misses.
+    pub fn get(&self, digest: &str) -> Option<CachedDescribe> {
+        let path = self.entry_path(digest);
+        let bytes = fs::read(&path).ok()?;
+        let entry: CachedDescribe = canonical::from_cbor(&bytes).ok()?;
+        if entry.digest != digest || entry.abi != DESCRIBE_HOST_ABI {
+            return None;
+        }
+        // Refresh the mtime so `cache prune --older-than` keeps entries in use.
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry)
+    }
+
+    /// Store the describe result for the component whose bytes hash to `digest`.
+    pub fn put(&self, digest: &str, describe: &ComponentDescribe) -> Result<CachedDescribe> {
+        let entry = CachedDescribe {
+            digest: digest.to_string(),
+            abi: DESCRIBE_HOST_ABI.to_string(),
+            describe_hash: describe_hash(describe)?,
+            schema_hashes: operation_schema_hashes(describe)?,
+            describe: describe.clone(),
+        };
+        let bytes = canonical::to_canonical_cbor_allow_floats(&entry)
+            .context("encode describe cache entry")?;
+        let path = self.entry_path(digest);
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow!("describe cache entry has no parent directory"))?;
+        fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)
+            .with_context(|| format!("create temp file in {}", dir.display()))?;
+        tmp.write_all(&bytes)
+            .context("write describe cache entry")?;
+        tmp.persist(&path)
+            .map_err(|err| anyhow!("persist {}: {}", path.display(), err.error))?;
+        Ok(entry)
+    }
+
+    /// Return the cached entry for `digest`, or run `describe` and store its result.
+    pub fn get_or_describe(
+        &self,
+        digest: &str,
+        describe: impl FnOnce() -> Result<ComponentDescribe>,
+    ) -> Result<CachedDescribe> {
+        if let Some(entry) = self.get(digest) {
+            return Ok(entry);
+        }
+        let describe = describe()?;
+        self.put(digest, &describe)
+    }
+
+    pub fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats {
+            root: self.root.clone(),
+            abi: DESCRIBE_HOST_ABI.to_string(),
+            entries: 0,
+            bytes: 0,
+            stale: 0,
+        };
+        for path in self.entry_paths()? {
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            stats.entries += 1;
+            stats.bytes += meta.len();
+            if !entry_is_current(&path) {
+                stats.stale += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Remove stale entries, plus entries older than `opts.older_than` or all of
+    /// them when `opts.all` is set.
+    pub fn prune(&self, opts: PruneOptions) -> Result<PruneReport> {
+        let now = SystemTime::now();
+        let mut report = PruneReport::default();
+        for path in self.entry_paths()? {
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            let expired = opts.older_than.is_some_and(|max_age| {
+                meta.modified()
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age > max_age)
+            });
+            if opts.all || expired || !entry_is_current(&path) {
+                fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+                report.removed += 1;
+                report.bytes += meta.len();
+            } else {
+                report.kept += 1;
+            }
+        }
+        self.remove_empty_shards()?;
+        Ok(report)
+    }
+
+    fn entry_path(&self, digest: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(digest.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(DESCRIBE_HOST_ABI.as_bytes());
+        let key = hex::encode(hasher.finalize());
+        self.root
+            .join(&key[..2])
+            .join(format!("{key}.{ENTRY_EXTENSION}"))
+    }
+
+    fn entry_paths(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        if !self.root.is_dir() {
+            return Ok(paths);
+        }
+        for shard in
+            fs::read_dir(&self.root).with_context(|| format!("read {}", self.root.display()))?
+        {
+            let shard = shard?.path();
+            if !shard.is_dir() {
+                continue;
+            }
+            for entry in
+                fs::read_dir(&shard).with_context(|| format!("read {}", shard.display()))?
+            {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) == Some(ENTRY_EXTENSION) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn remove_empty_shards(&self) -> Result<()> {
+        if !self.root.is_dir() {
+            return Ok(());
+        }
+        for shard in
+            fs::read_dir(&self.root).with_context(|| format!("read {}", self.root.display()))?
+        {
+            let shard = shard?.path();
+            if shard.is_dir() && fs::read_dir(&shard)?.next().is_none() {
+                let _ = fs::remove_dir(&shard);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn entry_is_current(path: &Path) -> bool {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| canonical::from_cbor::<CachedDescribe>(&bytes).ok())
+        .is_some_and(|entry| entry.abi == DESCRIBE_HOST_ABI)
+}
+
+/// `describe_hash` as recorded in `pack.lock.cbor`: sha256 over the canonical CBOR payload.
+pub fn describe_hash(describe: &ComponentDescribe) -> Result<String> {
+    let bytes =
+        canonical::to_canonical_cbor_allow_floats(describe).context("canonicalize describe")?;
+    let digest = Sha256::digest(bytes.as_slice());
+    Ok(hex::encode(digest))
+}
+
+/// Per-operation `schema_hash` values keyed by operation id.
+pub fn operation_schema_hashes(describe: &ComponentDescribe) -> Result<BTreeMap<String, String>> {
+    describe
+        .operations
+        .iter()
+        .map(|op| {
+            let hash = schema_hash(&op.input.schema, &op.output.schema, &describe.config_schema)
+                .map_err(|err| anyhow!("schema_hash for {}: {}", op.id, err))?;
+            Ok((op.id.clone(), hash))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_types::schemas::common::schema_ir::SchemaIr;
+    use greentic_types::schemas::component::v0_6_0::{
+        ComponentInfo, ComponentOperation, ComponentRunInput, ComponentRunOutput,
+    };
+    use tempfile::TempDir;
+
+    fn sample_describe() -> ComponentDescribe {
+        let schema = SchemaIr::String {
+            min_len: None,
+            max_len: None,
+            regex: None,
+            format: None,
+        };
+        let mut operation = ComponentOperation {
+            id: "run".to_string(),
+            display_name: None,
+            input: ComponentRunInput {
+                schema: schema.clone(),
+            },
+            output: ComponentRunOutput {
+                schema: schema.clone(),
+            },
+            defaults: BTreeMap::new(),
+            redactions: Vec::new(),
+            constraints: BTreeMap::new(),
+            schema_hash: String::new(),
+        };
+        operation.schema_hash =
+            schema_hash(&operation.input.schema, &operation.output.schema, &schema)
+                .expect("schema hash");
+        ComponentDescribe {
+            info: ComponentInfo {
+                id: "demo.component".to_string(),
+                version: "0.1.0".to_string(),
+                role: "tool".to_string(),
+                display_name: None,
+            },
+            provided_capabilities: Vec::new(),
+            required_capabilities: Vec::new(),
+            metadata: BTreeMap::new(),
+            operations: vec![operation],
+            config_schema: schema,
+        }
+    }
+
+    #[test]
+    fn stores_and_reloads_describe_results() {
+        let temp = TempDir::new().expect("temp dir");
+        let cache = DescribeCache::new(temp.path().join("describe"));
+        let digest = format!("sha256:{}", "a".repeat(64));
+        assert!(cache.get(&digest).is_none());
+
+        let describe = sample_describe();
+        let stored = cache.put(&digest, &describe).expect("put");
+        let loaded = cache.get(&digest).expect("cache hit");
+        assert_eq!(loaded.describe_hash, stored.describe_hash);
+        assert_eq!(loaded.describe_hash, describe_hash(&describe).unwrap());
+        assert_eq!(
+            loaded.schema_hashes.get("run"),
+            Some(&describe.operations[0].schema_hash)
+        );
+
+        let other = format!("sha256:{}", "b".repeat(64));
+        let described = cache
+            .get_or_describe(&other, || Ok(sample_describe()))
+            .expect("describe on miss");
+        assert_eq!(described.digest, other);
+        cache
+            .get_or_describe(&other, || {
+                panic!("cached entries must not be described again")
+            })
+            .expect("hit");
+    }
+
+    #[test]
+    fn prune_removes_stale_and_requested_entries() {
+        let temp = TempDir::new().expect("temp dir");
+        let cache = DescribeCache::new(temp.path().join("describe"));
+        let digest = format!("sha256:{}", "c".repeat(64));
+        cache.put(&digest, &sample_describe()).expect("put");
+
+        let corrupt = cache
+            .root()
+            .join("ff")
+            .join(format!("{}.cbor", "f".repeat(64)));
+        fs::create_dir_all(corrupt.parent().unwrap()).unwrap();
+        fs::write(&corrupt, b"not cbor").unwrap();
+
+        let stats = cache.stats().expect("stats");
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.stale, 1);
+
+        let report = cache.prune(PruneOptions::default()).expect("prune");
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.kept, 1);
+        assert!(!corrupt.parent().unwrap().exists());
+        assert!(cache.get(&digest).is_some());
+
+        let report = cache
+            .prune(PruneOptions {
+                all: true,
+                ..PruneOptions::default()
+            })
+            .expect("prune all");
+        assert_eq!(report.removed, 1);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
@@ -0,0 +1,656 @@
+#![forbid(unsafe_code)]
+
+//! Diagnostics carrying file/line/column spans, plus rustc-style, SARIF and
+//! JUnit renderers shared by `lint` and `doctor`.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use greentic_types::validate::{Diagnostic, Severity};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+/// 1-based position inside a source file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SourceSpan {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpannedDiagnostic {
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<SourceSpan>,
+}
+
+impl From<Diagnostic> for SpannedDiagnostic {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self {
+            diagnostic,
+            span: None,
+        }
+    }
+}
+
+impl SpannedDiagnostic {
+    pub fn new(severity: Severity, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            diagnostic: Diagnostic {
+                severity,
+                code: code.to_string(),
+                message: message.into(),
+                path: None,
+                hint: None,
+                data: Value::Null,
+            },
+            span: None,
+        }
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warn, code, message)
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.diagnostic.hint = Some(hint.into());
+        self
+    }
+
+    /// Attach the file and, when known, the `(line, column)` inside it.
+    pub fn at(mut self, file: &Path, position: Option<(usize, usize)>) -> Self {
+        self.diagnostic.path = Some(file.display().to_string());
+        self.span = position.map(|(line, column)| SourceSpan {
+            file: file.to_path_buf(),
+            line,
+            column,
+        });
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.diagnostic.severity, Severity::Error)
+    }
+
+    pub fn is_warning(&self) -> bool {
+        matches!(self.diagnostic.severity, Severity::Warn)
+    }
+}
+
+/// Output formats for commands that report diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Human,
+    Json,
+    Sarif,
+    Junit,
+}
+
+/// Render diagnostics the way rustc does: header, `-->` location, the source
+/// line with a caret under the column, and a trailing `= help:` line.
+///
+/// Paths are shown relative to `base` when they live under it.
+pub fn render_human(diagnostics: &[SpannedDiagnostic], base: &Path) -> String {
+    let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut out = String::new();
+    for diag in diagnostics {
+        let label = match diag.diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warn => "warning",
+            Severity::Info => "info",
+        };
+        let _ = writeln!(
+            out,
+            "{label}[{}]: {}",
+            diag.diagnostic.code, diag.diagnostic.message
+        );
+
+        let source_line = diag.span.as_ref().and_then(|span| {
+            sources
+                .entry(span.file.clone())
+                .or_insert_with(|| std::fs::read_to_string(&span.file).ok())
+                .as_deref()
+                .and_then(|text| text.lines().nth(span.line.saturating_sub(1)))
+                .map(str::to_string)
+        });
+        let gutter = diag
+            .span
+            .as_ref()
+            .map(|span| span.line.to_string().len())
+            .unwrap_or(1);
+        let pad = " ".repeat(gutter);
+
+        match (&diag.span, diag.diagnostic.path.as_deref()) {
+            (Some(span), _) => {
+                let _ = writeln!(
+                    out,
+                    "{pad}--> {}:{}:{}",
+                    display_path(&span.file, base),
+                    span.line,
+                    span.column
+                );
+                if let Some(line) = source_line {
+                    let _ = writeln!(out, "{pad} |");
+                    let _ = writeln!(out, "{} | {line}", span.line);
+                    let _ = writeln!(out, "{pad} | {}^", caret_indent(&line, span.column));
+                }
+            }
+            (None, Some(path)) => {
+                let _ = writeln!(out, "{pad}--> {}", display_path(Path::new(path), base));
+            }
+            (None, None) => {}
+        }
+        if let Some(hint) = diag.diagnostic.hint.as_deref() {
+            let _ = writeln!(out, "{pad} = help: {hint}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn display_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Whitespace leading up to `column`, keeping tabs so the caret lines up.
+fn caret_indent(line: &str, column: usize) -> String {
+    line.chars()
+        .take(column.saturating_sub(1))
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect()
+}
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Build a SARIF 2.1.0 log with a single run: codes become rules, severities
+/// become levels and spans or paths become artifact locations.
+pub fn to_sarif(diagnostics: &[SpannedDiagnostic], base: &Path) -> Value {
+    let mut rules = Vec::new();
+    let mut rule_index: HashMap<&str, usize> = HashMap::new();
+    let mut results = Vec::new();
+
+    for diag in diagnostics {
+        let code = diag.diagnostic.code.as_str();
+        let level = sarif_level(&diag.diagnostic.severity);
+        let index = *rule_index.entry(code).or_insert_with(|| {
+            rules.push(json!({
+                "id": code,
+                "shortDescription": { "text": diag.diagnostic.message },
+                "defaultConfiguration": { "level": level },
+            }));
+            rules.len() - 1
+        });
+
+        let mut result = json!({
+            "ruleId": code,
+            "ruleIndex": index,
+            "level": level,
+            "message": { "text": diag.diagnostic.message },
+        });
+        if let Some(uri) = artifact_uri(diag, base) {
+            let mut physical = json!({ "artifactLocation": { "uri": uri } });
+            if let Some(span) = &diag.span {
+                physical["region"] = json!({
+                    "startLine": span.line,
+                    "startColumn": span.column,
+                });
+            }
+            result["locations"] = json!([{ "physicalLocation": physical }]);
+        }
+        let mut properties = Map::new();
+        if let Some(hint) = diag.diagnostic.hint.as_deref() {
+            properties.insert("hint".to_string(), json!(hint));
+        }
+        if !diag.diagnostic.data.is_null() {
+            properties.insert("data".to_string(), diag.diagnostic.data.clone());
+        }
+        if !properties.is_empty() {
+            result["properties"] = Value::Object(properties);
+        }
+        results.push(result);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "greentic-pack",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warn => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Render a JUnit XML report with one test case per diagnostic. Errors are
+/// failures; warnings and notes pass with their text in `system-out`. A clean
+/// run yields a single passing case so dashboards still record it.
+pub fn to_junit(suite: &str, diagnostics: &[SpannedDiagnostic], base: &Path) -> String {
+    let failures = diagnostics.iter().filter(|diag| diag.is_error()).count();
+    let tests = diagnostics.len().max(1);
+    let suite = xml_escape(suite);
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\">"
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">"
+    );
+    if diagnostics.is_empty() {
+        let _ = writeln!(
+            out,
+            "    <testcase classname=\"{suite}\" name=\"no diagnostics\"/>"
+        );
+    }
+    for diag in diagnostics {
+        let uri = artifact_uri(diag, base);
+        let classname = uri
+            .as_deref()
+            .map(xml_escape)
+            .unwrap_or_else(|| suite.clone());
+        let code = xml_escape(&diag.diagnostic.code);
+        let mut details = diag.diagnostic.message.clone();
+        match (&uri, &diag.span) {
+            (Some(uri), Some(span)) => {
+                let _ = write!(details, "\n  --> {uri}:{}:{}", span.line, span.column);
+            }
+            (Some(uri), None) => {
+                let _ = write!(details, "\n  --> {uri}");
+            }
+            _ => {}
+        }
+        if let Some(hint) = diag.diagnostic.hint.as_deref() {
+            let _ = write!(details, "\n  = help: {hint}");
+        }
+
+        let _ = writeln!(
+            out,
+            "    <testcase classname=\"{classname}\" name=\"{code}\">"
+        );
+        if diag.is_error() {
+            let _ = writeln!(
+                out,
+                "      <failure type=\"{code}\" message=\"{}\">{}</failure>",
+                xml_escape(&diag.diagnostic.message),
+                xml_escape(&details)
+            );
+        } else {
+            let _ = writeln!(
+                out,
+                "      <system-out>{}: {}</system-out>",
+                sarif_level(&diag.diagnostic.severity),
+                xml_escape(&details)
+            );
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+fn artifact_uri(diag: &SpannedDiagnostic, base: &Path) -> Option<String> {
+    let path = match &diag.span {
+        Some(span) => span.file.clone(),
+        None => PathBuf::from(diag.diagnostic.path.as_deref()?),
+    };
+    Some(display_path(&path, base).replace('\\', "/"))
+}
+
+/// Escape text for XML attributes and content, dropping characters XML 1.0
+/// cannot carry.
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(ch),
+            ch if (ch as u32) < 0x20 => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// One step of a path into a YAML document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum YamlSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl YamlSegment {
+    pub fn key(key: impl Into<String>) -> Self {
+        YamlSegment::Key(key.into())
+    }
+
+    fn matches(&self, other: &YamlSegment) -> bool {
+        match (self, other) {
+            (YamlSegment::Index(idx), YamlSegment::Key(key))
+            | (YamlSegment::Key(key), YamlSegment::Index(idx)) => *key == idx.to_string(),
+            _ => self == other,
+        }
+    }
+}
+
+/// Split a JSON pointer (`/nodes/start/routing/0`) into YAML path segments.
+pub fn json_pointer_segments(pointer: &str) -> Vec<YamlSegment> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|token| {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            match token.parse::<usize>() {
+                Ok(idx) => YamlSegment::Index(idx),
+                Err(_) => YamlSegment::Key(token),
+            }
+        })
+        .collect()
+}
+
+struct Frame {
+    indent: usize,
+    segment: YamlSegment,
+    position: (usize, usize),
+    items: usize,
+}
+
+/// Find the `(line, column)` of the node at `path` in a block-style YAML
+/// document. When the full path is absent the deepest matching ancestor is
+/// returned, so callers still land near the problem.
+///
+/// This is a line scanner, not a parser: it understands block mappings and
+/// sequences (including `- key: value` items and block scalars) which covers
+/// pack.yaml and `.ygtc` files as written by the tooling.
+pub fn locate_yaml(source: &str, path: &[YamlSegment]) -> Option<(usize, usize)> {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root_items = 0usize;
+    let mut best: Option<(usize, (usize, usize))> = None;
+    let mut block_scalar: Option<usize> = None;
+
+    for (idx, raw) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw.trim_start_matches(' ');
+        let mut indent = raw.len() - trimmed.len();
+        if let Some(owner) = block_scalar {
+            if trimmed.trim().is_empty() || indent > owner {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if trimmed.trim().is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+
+        let mut content = trimmed;
+        loop {
+            if content == "-" || content.starts_with("- ") {
+                stack.retain(|frame| {
+                    frame.indent < indent
+                        || (frame.indent == indent && matches!(frame.segment, YamlSegment::Key(_)))
+                });
+                let counter = match stack.last_mut() {
+                    Some(parent) => &mut parent.items,
+                    None => &mut root_items,
+                };
+                let index = *counter;
+                *counter += 1;
+                stack.push(Frame {
+                    indent,
+                    segment: YamlSegment::Index(index),
+                    position: (line_no, indent + 1),
+                    items: 0,
+                });
+                record_match(&stack, path, &mut best);
+
+                let rest = content[1..].trim_start_matches(' ');
+                indent += content.len() - rest.len();
+                content = rest;
+                if content.is_empty() {
+                    break;
+                }
+                continue;
+            }
+
+            if let Some((key, value)) = split_key(content) {
+                stack.retain(|frame| frame.indent < indent);
+                stack.push(Frame {
+                    indent,
+                    segment: YamlSegment::Key(key),
+                    position: (line_no, indent + 1),
+                    items: 0,
+                });
+                record_match(&stack, path, &mut best);
+                let value = value.split(" #").next().unwrap_or("").trim();
+                if value.starts_with('|') || value.starts_with('>') {
+                    block_scalar = Some(indent);
+                }
+            }
+            break;
+        }
+
+        if best.as_ref().is_some_and(|(depth, _)| *depth == path.len()) {
+            break;
+        }
+    }
+
+    best.map(|(_, position)| position)
+}
+
+fn record_match(stack: &[Frame], path: &[YamlSegment], best: &mut Option<(usize, (usize, usize))>) {
+    let depth = stack.len();
+    if depth > path.len() || best.as_ref().is_some_and(|(found, _)| *found >= depth) {
+        return;
+    }
+    if stack
+        .iter()
+        .zip(path)
+        .all(|(frame, segment)| segment.matches(&frame.segment))
+    {
+        *best = Some((depth, stack[depth - 1].position));
+    }
+}
+
+/// Split `key: value` into its key and the remainder after the colon.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    if content.starts_with(['{', '[', '&', '*', '!', '|', '>']) {
+        return None;
+    }
+    if let Some(quote) = content
+        .chars()
+        .next()
+        .filter(|ch| *ch == '"' || *ch == '\'')
+    {
+        let end = content[1..].find(quote)? + 1;
+        let rest = content[end + 1..].strip_prefix(':')?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        return Some((content[1..end].to_string(), rest));
+    }
+    let colon = content
+        .match_indices(':')
+        .map(|(idx, _)| idx)
+        .find(|idx| matches!(content.as_bytes().get(idx + 1), None | Some(b' ')))?;
+    let key = content[..colon].trim_end();
+    if key.is_empty() || key.contains(" #") {
+        return None;
+    }
+    Some((key.to_string(), &content[colon + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK: &str = "pack_id: demo\ncomponents:\n- id: a\n  wasm: a.wasm\n- id: b\n  wasm: b.wasm\nflows:\n  - id: main\n    file: flows/main.ygtc\nextensions:\n  greentic.components:\n    kind: greentic.components\n";
+
+    fn path(segments: &[&str]) -> Vec<YamlSegment> {
+        segments
+            .iter()
+            .map(|seg| match seg.parse::<usize>() {
+                Ok(idx) => YamlSegment::Index(idx),
+                Err(_) => YamlSegment::key(*seg),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn locates_keys_and_sequence_items() {
+        assert_eq!(locate_yaml(PACK, &path(&["pack_id"])), Some((1, 1)));
+        assert_eq!(locate_yaml(PACK, &path(&["components", "1"])), Some((5, 1)));
+        assert_eq!(
+            locate_yaml(PACK, &path(&["components", "1", "wasm"])),
+            Some((6, 3))
+        );
+        assert_eq!(
+            locate_yaml(PACK, &path(&["flows", "0", "file"])),
+            Some((9, 5))
+        );
+        assert_eq!(
+            locate_yaml(PACK, &path(&["extensions", "greentic.components"])),
+            Some((11, 3))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_deepest_ancestor_and_skips_block_scalars() {
+        assert_eq!(
+            locate_yaml(PACK, &path(&["components", "1", "world"])),
+            Some((5, 1))
+        );
+        assert_eq!(locate_yaml(PACK, &path(&["missing"])), None);
+
+        let flow = "nodes:\n  hello:\n    template: |\n      note: not a key\n    routing:\n      - out: true\n";
+        assert_eq!(
+            locate_yaml(flow, &path(&["nodes", "hello", "routing"])),
+            Some((5, 5))
+        );
+        assert_eq!(
+            locate_yaml(flow, &path(&["nodes", "hello", "note"])),
+            Some((2, 3))
+        );
+        assert_eq!(
+            json_pointer_segments("/nodes/hello/routing/0"),
+            path(&["nodes", "hello", "routing", "0"])
+        );
+    }
+
+    #[test]
+    fn renders_rustc_style_snippets() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let file = dir.path().join("pack.yaml");
+        std::fs::write(&file, PACK).expect("write pack.yaml");
+
+        let diag = SpannedDiagnostic::error("PACK_LINT_TEST", "wasm is missing")
+            .at(&file, Some((6, 3)))
+            .with_hint("run greentic-pack components");
+        let rendered = render_human(&[diag], dir.path());
+        assert_eq!(
+            rendered,
+            "error[PACK_LINT_TEST]: wasm is missing\n --> pack.yaml:6:3\n  |\n6 |   wasm: b.wasm\n  |   ^\n  = help: run greentic-pack components\n\n"
+        );
+    }
+
+    fn doctor_diagnostics() -> Vec<SpannedDiagnostic> {
+        vec![
+            SpannedDiagnostic::error("PACK_MISSING_FILE", "file <missing> & gone")
+                .at(Path::new("/pack/flows/main.ygtc"), Some((3, 5)))
+                .with_hint("rebuild the pack"),
+            SpannedDiagnostic::from(Diagnostic {
+                severity: Severity::Warn,
+                code: "PACK_VALIDATOR_UNAVAILABLE".to_string(),
+                message: "validator unavailable".to_string(),
+                path: Some("validators/demo.gtpack".to_string()),
+                hint: None,
+                data: json!({ "reference": "oci://ghcr.io/demo" }),
+            }),
+            SpannedDiagnostic::error("PACK_MISSING_FILE", "second"),
+        ]
+    }
+
+    #[test]
+    fn sarif_maps_codes_levels_and_locations() {
+        let sarif = to_sarif(&doctor_diagnostics(), Path::new("/pack"));
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "PACK_MISSING_FILE");
+
+        let results = run["results"].as_array().expect("results");
+        assert_eq!(results[0]["level"], "error");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "flows/main.ygtc");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(results[0]["properties"]["hint"], "rebuild the pack");
+
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "validators/demo.gtpack"
+        );
+        assert_eq!(
+            results[1]["properties"]["data"]["reference"],
+            "oci://ghcr.io/demo"
+        );
+        assert_eq!(results[2]["ruleIndex"], 0);
+        assert!(results[2].get("locations").is_none());
+    }
+
+    #[test]
+    fn junit_reports_errors_as_failures() {
+        let xml = to_junit(
+            "greentic-pack doctor",
+            &doctor_diagnostics(),
+            Path::new("/pack"),
+        );
+        assert!(xml.contains(r#"tests="3" failures="2""#), "{xml}");
+        assert!(
+            xml.contains(r#"<testcase classname="flows/main.ygtc" name="PACK_MISSING_FILE">"#),
+            "{xml}"
+        );
+        assert!(
+            xml.contains(r#"message="file &lt;missing&gt; &amp; gone""#),
+            "{xml}"
+        );
+        assert!(xml.contains("--&gt; flows/main.ygtc:3:5"), "{xml}");
+        assert!(
+            xml.contains("<system-out>warning: validator unavailable"),
+            "{xml}"
+        );
+
+        let clean = to_junit("greentic-pack lint", &[], Path::new("/pack"));
+        assert!(clean.contains(r#"tests="1" failures="0""#), "{clean}");
+        assert!(clean.contains(r#"name="no diagnostics""#), "{clean}");
+    }
+}
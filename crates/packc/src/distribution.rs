@@ -0,0 +1,276 @@
+#![forbid(unsafe_code)]
+
+//! `distribution-bundle` packs: artifacts listed in pack.yaml's `distribution`
+//! section are copied into the archive verbatim and pinned by sha256 digest.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::PackLoad;
+use greentic_pack::builder::{ComponentDescriptor, DistributionSection};
+use greentic_types::pack_manifest::{ExtensionInline, ExtensionRef, PackManifest};
+use greentic_types::validate::{Diagnostic, Severity};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::lazy_entries::{LazyEntries, entry_bytes};
+use crate::path_safety::normalize_under_root;
+
+/// Manifest extension carrying the distribution section of a bundle.
+pub const EXT_DISTRIBUTION_BUNDLE_V1: &str = "greentic.distribution-bundle.v1";
+/// pack.yaml `kind` for distribution bundles.
+pub const DISTRIBUTION_BUNDLE_KIND: &str = "distribution-bundle";
+
+/// An artifact to copy into the archive at `logical_path`.
+#[derive(Debug, Clone)]
+pub struct DistributionArtifact {
+    pub logical_path: String,
+    pub source: PathBuf,
+}
+
+/// Every descriptor of the section, components first, then platform components.
+fn descriptors(section: &DistributionSection) -> impl Iterator<Item = &ComponentDescriptor> {
+    section
+        .components
+        .iter()
+        .chain(section.platform_components.iter())
+}
+
+/// Locate each `artifact_path` under the pack root and check it against its
+/// declared digest.
+pub fn collect_artifacts(
+    section: &DistributionSection,
+    pack_root: &Path,
+) -> Result<Vec<DistributionArtifact>> {
+    let mut seen = BTreeSet::new();
+    let mut artifacts = Vec::new();
+    for descriptor in descriptors(section) {
+        let logical_path = descriptor.artifact_path.clone();
+        // The path is used verbatim as the archive entry name.
+        if logical_path
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            bail!(
+                "artifact_path `{logical_path}` must be a relative path without `.` or `..` segments"
+            );
+        }
+        let source = normalize_under_root(pack_root, Path::new(&logical_path))
+            .with_context(|| format!("invalid artifact_path `{logical_path}`"))?;
+        let bytes = fs::read(&source).with_context(|| {
+            format!(
+                "failed to read distribution artifact {} for {}@{}",
+                source.display(),
+                descriptor.component_id,
+                descriptor.version
+            )
+        })?;
+        let actual = sha256_digest(&bytes);
+        if !actual.eq_ignore_ascii_case(&descriptor.digest) {
+            bail!(
+                "distribution artifact {logical_path} for {}@{} has digest {actual}, expected {}",
+                descriptor.component_id,
+                descriptor.version,
+                descriptor.digest
+            );
+        }
+        // Descriptors may share an artifact; it is stored once.
+        if seen.insert(logical_path.clone()) {
+            artifacts.push(DistributionArtifact {
+                logical_path,
+                source,
+            });
+        }
+    }
+    artifacts.sort_by(|a, b| a.logical_path.cmp(&b.logical_path));
+    Ok(artifacts)
+}
+
+/// Manifest extension for `section`; `bundle_id` defaults to the pack id.
+pub fn manifest_extension(section: &DistributionSection, pack_id: &str) -> Result<ExtensionRef> {
+    let mut section = section.clone();
+    if section.bundle_id.is_none() {
+        section.bundle_id = Some(pack_id.to_string());
+    }
+    Ok(ExtensionRef {
+        kind: EXT_DISTRIBUTION_BUNDLE_V1.to_string(),
+        version: "1".to_string(),
+        digest: None,
+        location: None,
+        inline: Some(ExtensionInline::Other(
+            serde_json::to_value(&section).context("failed to encode distribution section")?,
+        )),
+    })
+}
+
+/// The distribution section recorded in a built manifest, if any.
+pub fn section_from_manifest(manifest: &PackManifest) -> Option<Result<DistributionSection>> {
+    let entry = manifest
+        .extensions
+        .as_ref()?
+        .get(EXT_DISTRIBUTION_BUNDLE_V1)?;
+    Some(match entry.inline.as_ref() {
+        Some(ExtensionInline::Other(value)) => serde_json::from_value(value.clone())
+            .context("distribution extension does not match the distribution schema"),
+        _ => Err(anyhow!("distribution extension has no inline payload")),
+    })
+}
+
+pub fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+pub struct DistributionDoctorOutput {
+    pub diagnostics: Vec<Diagnostic>,
+    pub has_errors: bool,
+}
+
+/// Re-verify every artifact of a distribution bundle against its digest;
+/// artifacts missing from `load.files` are read from `archive` when given.
+pub fn run_distribution_doctor(
+    load: &PackLoad,
+    archive: Option<&LazyEntries>,
+) -> DistributionDoctorOutput {
+    let mut output = DistributionDoctorOutput {
+        diagnostics: Vec::new(),
+        has_errors: false,
+    };
+    let Some(section) = load.gpack_manifest.as_ref().and_then(section_from_manifest) else {
+        return output;
+    };
+    let section = match section {
+        Ok(section) => section,
+        Err(err) => {
+            output.has_errors = true;
+            output.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "PACK_DISTRIBUTION_INVALID".to_string(),
+                message: format!("{err:#}"),
+                path: Some("manifest.cbor".to_string()),
+                hint: Some("rebuild the bundle with greentic-pack build".to_string()),
+                data: Value::Null,
+            });
+            return output;
+        }
+    };
+
+    for descriptor in descriptors(&section) {
+        let path = descriptor.artifact_path.as_str();
+        let data = json!({
+            "component_id": descriptor.component_id,
+            "version": descriptor.version,
+            "expected": descriptor.digest,
+        });
+        let bytes = match entry_bytes(load, archive, path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                output.has_errors = true;
+                output.diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: "PACK_DISTRIBUTION_INVALID".to_string(),
+                    message: format!("{err:#}"),
+                    path: Some(path.to_string()),
+                    hint: Some("rebuild the bundle with greentic-pack build".to_string()),
+                    data,
+                });
+                continue;
+            }
+        };
+        let Some(bytes) = bytes else {
+            output.has_errors = true;
+            output.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "PACK_DISTRIBUTION_ARTIFACT_MISSING".to_string(),
+                message: format!(
+                    "artifact for {}@{} is missing from the pack",
+                    descriptor.component_id, descriptor.version
+                ),
+                path: Some(path.to_string()),
+                hint: Some("rebuild the bundle so every artifact_path is packaged".to_string()),
+                data,
+            });
+            continue;
+        };
+        let actual = sha256_digest(&bytes);
+        if !actual.eq_ignore_ascii_case(&descriptor.digest) {
+            let mut data = data;
+            data["actual"] = json!(actual);
+            output.has_errors = true;
+            output.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "PACK_DISTRIBUTION_DIGEST_MISMATCH".to_string(),
+                message: format!(
+                    "artifact for {}@{} has digest {actual}, expected {}",
+                    descriptor.component_id, descriptor.version, descriptor.digest
+                ),
+                path: Some(path.to_string()),
+                hint: Some(
+                    "the bundled artifact was modified after the pack was built".to_string(),
+                ),
+                data,
+            });
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Map as JsonMap;
+    use tempfile::tempdir;
+
+    fn section(artifact_path: &str, digest: &str) -> DistributionSection {
+        DistributionSection {
+            bundle_id: None,
+            tenant: JsonMap::new(),
+            environment_ref: "env-prod".into(),
+            desired_state_version: "v1".into(),
+            components: vec![ComponentDescriptor {
+                component_id: "app.component".into(),
+                version: "1.0.0".into(),
+                digest: digest.into(),
+                artifact_path: artifact_path.into(),
+                kind: Some("software".into()),
+                artifact_type: None,
+                tags: Vec::new(),
+                platform: None,
+                entrypoint: None,
+            }],
+            platform_components: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn artifacts_are_checked_against_their_digest() {
+        let temp = tempdir().expect("temp dir");
+        fs::create_dir_all(temp.path().join("artifacts")).expect("artifacts dir");
+        fs::write(temp.path().join("artifacts/app.bin"), b"payload").expect("artifact");
+        let digest = sha256_digest(b"payload");
+
+        let artifacts = collect_artifacts(&section("artifacts/app.bin", &digest), temp.path())
+            .expect("matching digest");
+        assert_eq!(artifacts[0].logical_path, "artifacts/app.bin");
+
+        let err = collect_artifacts(
+            &section("artifacts/app.bin", &sha256_digest(b"other")),
+            temp.path(),
+        )
+        .expect_err("digest mismatch");
+        assert!(err.to_string().contains(&digest), "{err}");
+
+        assert!(collect_artifacts(&section("../app.bin", &digest), temp.path()).is_err());
+    }
+
+    #[test]
+    fn extension_defaults_bundle_id_to_pack_id() {
+        let ext = manifest_extension(&section("a.bin", "sha256:00"), "demo.bundle").expect("ext");
+        let Some(ExtensionInline::Other(value)) = ext.inline else {
+            panic!("inline payload expected");
+        };
+        assert_eq!(value["bundle_id"], "demo.bundle");
+        assert_eq!(value["components"][0]["artifact_path"], "a.bin");
+    }
+}
@@ -0,0 +1,243 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeSet, HashMap};
+
+use greentic_flow::flow_bundle::load_and_validate_bundle_with_flow;
+use greentic_flow::json_output::{JsonDiagnostic, flow_error_to_reports};
+use greentic_flow::lint::lint_builtin_rules;
+use greentic_pack::PackLoad;
+use greentic_pack::pack_lock::PackLockV1;
+use greentic_types::Flow;
+use greentic_types::validate::{Diagnostic, Severity};
+use serde_json::{Value, json};
+
+pub struct FlowDoctorInput<'a> {
+    pub load: &'a PackLoad,
+    /// Lock used to check node bindings; without it nodes are only checked against the manifest.
+    pub lock: Option<&'a PackLockV1>,
+    /// Report flows whose YAML source is not in the pack (dev builds ship it).
+    pub require_sources: bool,
+}
+
+pub struct FlowDoctorOutput {
+    pub diagnostics: Vec<Diagnostic>,
+    pub has_errors: bool,
+}
+
+/// Validate every flow in the pack in process: YAML schema and graph checks
+/// from `greentic-flow`, plus node-to-component bindings against the lock.
+pub fn run_flow_doctor(input: FlowDoctorInput<'_>) -> FlowDoctorOutput {
+    let mut output = FlowDoctorOutput {
+        diagnostics: Vec::new(),
+        has_errors: false,
+    };
+    let compiled: HashMap<&str, &Flow> = input
+        .load
+        .gpack_manifest
+        .iter()
+        .flat_map(|manifest| manifest.flows.iter())
+        .map(|entry| (entry.id.as_str(), &entry.flow))
+        .collect();
+    let known_components: BTreeSet<&str> = input
+        .load
+        .manifest
+        .components
+        .iter()
+        .map(|component| component.name.as_str())
+        .collect();
+
+    for flow in &input.load.manifest.flows {
+        let mut errors = Vec::new();
+        match input.load.files.get(&flow.file_yaml) {
+            Some(bytes) => match std::str::from_utf8(bytes) {
+                Ok(yaml) => errors.extend(check_flow_source(yaml)),
+                Err(err) => errors.push(JsonDiagnostic::from_message(
+                    format!("flow source is not valid UTF-8: {err}"),
+                    None,
+                )),
+            },
+            None if input.require_sources => {
+                output.has_errors = true;
+                output.diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: "PACK_FLOW_DOCTOR_MISSING_FLOW".to_string(),
+                    message: "flow file missing from pack".to_string(),
+                    path: Some(flow.file_yaml.clone()),
+                    hint: Some("rebuild the pack to include flow sources".to_string()),
+                    data: Value::Null,
+                });
+            }
+            None => {}
+        }
+        if let Some(compiled) = compiled.get(flow.id.as_str()) {
+            errors.extend(check_flow_bindings(compiled, input.lock, &known_components));
+        }
+
+        if !errors.is_empty() {
+            for error in &mut errors {
+                error
+                    .source_path
+                    .get_or_insert_with(|| flow.file_yaml.clone());
+            }
+            output.has_errors = true;
+            output.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "PACK_FLOW_DOCTOR_FAILED".to_string(),
+                message: "flow doctor failed".to_string(),
+                path: Some(flow.file_yaml.clone()),
+                hint: Some("fix the flow errors listed in data.errors".to_string()),
+                data: json!({ "errors": errors }),
+            });
+        }
+    }
+
+    output
+}
+
+/// Schema validation and built-in lint rules for a single `.ygtc` source.
+fn check_flow_source(yaml: &str) -> Vec<JsonDiagnostic> {
+    match load_and_validate_bundle_with_flow(yaml, None) {
+        Ok((_, flow)) => lint_builtin_rules(&flow)
+            .into_iter()
+            .map(|message| JsonDiagnostic::from_message(message, None))
+            .collect(),
+        Err(err) => flow_error_to_reports(err),
+    }
+}
+
+/// Every node must target a component shipped by the pack and, when the lock
+/// records operations for it, one of those operations.
+fn check_flow_bindings(
+    flow: &Flow,
+    lock: Option<&PackLockV1>,
+    known_components: &BTreeSet<&str>,
+) -> Vec<JsonDiagnostic> {
+    let mut errors = Vec::new();
+    for (node_id, node) in &flow.nodes {
+        if node.component.pack_alias.is_some() {
+            continue;
+        }
+        let component_id = node.component.id.as_str();
+        if component_id.is_empty()
+            || component_id == "component.exec"
+            || crate::build::is_builtin_component_id(component_id)
+        {
+            continue;
+        }
+        let pointer = Some(format!("/nodes/{node_id}"));
+        let locked = lock.and_then(|lock| lock.components.get(component_id));
+        let Some(locked) = locked else {
+            if !known_components.contains(component_id) {
+                errors.push(JsonDiagnostic {
+                    message: format!(
+                        "node `{node_id}` references component `{component_id}`, which is not in pack.lock.cbor or the pack manifest"
+                    ),
+                    source_path: None,
+                    line: None,
+                    col: None,
+                    json_pointer: pointer,
+                });
+            }
+            continue;
+        };
+        let Some(operation) = node.component.operation.as_deref() else {
+            continue;
+        };
+        if !locked.operations.is_empty()
+            && !locked
+                .operations
+                .iter()
+                .any(|op| op.operation_id == operation)
+        {
+            errors.push(JsonDiagnostic {
+                message: format!(
+                    "node `{node_id}` calls operation `{operation}`, which component `{component_id}` does not provide"
+                ),
+                source_path: None,
+                line: None,
+                col: None,
+                json_pointer: pointer,
+            });
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_pack::pack_lock::{LockedComponent, LockedOperation};
+    use greentic_types::ComponentId;
+    use std::collections::BTreeMap;
+
+    const FLOW: &str = r#"id: main
+type: messaging
+start: hello
+nodes:
+  hello:
+    handle_message:
+      input: "hi"
+    routing:
+      - out: true
+"#;
+
+    fn lock_with(component_id: &str, operations: &[&str]) -> PackLockV1 {
+        let component = LockedComponent {
+            component_id: component_id.to_string(),
+            r#ref: None,
+            abi_version: "0.6.0".to_string(),
+            resolved_digest: format!("sha256:{}", "a".repeat(64)),
+            describe_hash: "a".repeat(64),
+            operations: operations
+                .iter()
+                .map(|op| LockedOperation {
+                    operation_id: op.to_string(),
+                    schema_hash: "b".repeat(64),
+                })
+                .collect(),
+            world: None,
+            component_version: None,
+            role: None,
+        };
+        PackLockV1::new(BTreeMap::from([(component_id.to_string(), component)]))
+    }
+
+    fn bound_flow(component_id: &str, operation: &str) -> Flow {
+        let mut flow = greentic_flow::compile_ygtc_str(FLOW).expect("compile flow");
+        let node = flow.nodes.values_mut().next().expect("node");
+        node.component.id = ComponentId::new(component_id).expect("component id");
+        node.component.pack_alias = None;
+        node.component.operation = Some(operation.to_string());
+        flow
+    }
+
+    #[test]
+    fn flow_source_errors_are_reported_in_process() {
+        assert!(check_flow_source(FLOW).is_empty());
+        assert!(!check_flow_source("id: main\nnodes: [").is_empty());
+    }
+
+    #[test]
+    fn bindings_are_checked_against_lock_operations() {
+        let lock = lock_with("demo.echo", &["run"]);
+        let known = BTreeSet::new();
+
+        let ok = bound_flow("demo.echo", "run");
+        assert!(check_flow_bindings(&ok, Some(&lock), &known).is_empty());
+
+        let bad_op = bound_flow("demo.echo", "missing");
+        let errors = check_flow_bindings(&bad_op, Some(&lock), &known);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].message.contains("`missing`"),
+            "{}",
+            errors[0].message
+        );
+        assert_eq!(errors[0].json_pointer.as_deref(), Some("/nodes/hello"));
+
+        let unknown = bound_flow("demo.unknown", "run");
+        assert_eq!(check_flow_bindings(&unknown, Some(&lock), &known).len(), 1);
+        let known = BTreeSet::from(["demo.unknown"]);
+        assert!(check_flow_bindings(&unknown, Some(&lock), &known).is_empty());
+    }
+}
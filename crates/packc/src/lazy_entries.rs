@@ -0,0 +1,147 @@
+#![forbid(unsafe_code)]
+
+//! On-demand access to the archive entries `doctor` does not buffer.
+//!
+//! `doctor` reads the metadata of a `.gtpack` (manifests, flows, lock, SBOM)
+//! into a [`PackLoad`] but leaves component Wasm in the archive; checks that
+//! need the bytes read them through [`LazyEntries`] one entry at a time.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use greentic_pack::reader::PackReader;
+use greentic_pack::trust::TrustPolicy;
+use greentic_pack::{PackLoad, ReaderLimits, SigningPolicy};
+
+/// Entries of an opened archive that were left out of its [`PackLoad`].
+#[derive(Clone)]
+pub struct LazyEntries {
+    reader: Arc<Mutex<PackReader>>,
+    /// Sorted paths of the entries left in the archive, with their sizes.
+    entries: Arc<Vec<(String, u64)>>,
+}
+
+impl LazyEntries {
+    /// Open and verify `path`, buffering every entry except `*.wasm`.
+    pub fn open(
+        path: &Path,
+        policy: SigningPolicy,
+        limits: &ReaderLimits,
+        trust: &TrustPolicy,
+    ) -> Result<(PackLoad, Self)> {
+        let mut reader = PackReader::open_with_trust(path, policy, limits, trust)
+            .map_err(|err| anyhow!(err.message))?;
+        let (lazy, buffered): (Vec<String>, Vec<String>) = reader
+            .entry_names()
+            .map(str::to_string)
+            .partition(|name| name.ends_with(".wasm"));
+
+        let mut files = HashMap::with_capacity(buffered.len());
+        for name in buffered {
+            let bytes = reader.read_entry(&name)?;
+            files.insert(name, bytes);
+        }
+        let entries = lazy
+            .into_iter()
+            .map(|name| {
+                let size = reader.entry_size(&name).unwrap_or_default();
+                (name, size)
+            })
+            .collect();
+        let load = PackLoad {
+            manifest: reader.manifest.clone(),
+            report: reader.report.clone(),
+            sbom: reader.sbom.clone(),
+            files,
+            gpack_manifest: reader.gpack_manifest.clone(),
+        };
+        Ok((
+            load,
+            Self {
+                reader: Arc::new(Mutex::new(reader)),
+                entries: Arc::new(entries),
+            },
+        ))
+    }
+
+    /// Paths of the entries left in the archive, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Uncompressed size of an entry left in the archive.
+    pub fn size(&self, path: &str) -> Option<u64> {
+        self.entries
+            .binary_search_by(|(name, _)| name.as_str().cmp(path))
+            .ok()
+            .map(|index| self.entries[index].1)
+    }
+
+    /// Read `path` from the archive; `None` if it is not one of [`Self::names`].
+    pub fn read(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        if self.size(path).is_none() {
+            return Ok(None);
+        }
+        let mut reader = self
+            .reader
+            .lock()
+            .map_err(|_| anyhow!("pack reader lock poisoned"))?;
+        reader.read_entry(path).map(Some)
+    }
+}
+
+/// Bytes of `path` from `load`, or from `archive` when the entry was left there.
+pub fn entry_bytes<'a>(
+    load: &'a PackLoad,
+    archive: Option<&LazyEntries>,
+    path: &str,
+) -> Result<Option<Cow<'a, [u8]>>> {
+    if let Some(bytes) = load.files.get(path) {
+        return Ok(Some(Cow::Borrowed(bytes)));
+    }
+    match archive {
+        Some(archive) => Ok(archive.read(path)?.map(Cow::Owned)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_pack::open_pack;
+
+    #[test]
+    fn wasm_is_read_from_the_archive_on_demand() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/validators/noop-validator.gtpack");
+        let eager = open_pack(&path, SigningPolicy::DevOk).expect("open pack");
+        let (load, archive) = LazyEntries::open(
+            &path,
+            SigningPolicy::DevOk,
+            &ReaderLimits::default(),
+            &TrustPolicy::default(),
+        )
+        .expect("open lazily");
+
+        assert!(load.files.keys().all(|name| !name.ends_with(".wasm")));
+        let wasm: Vec<&str> = archive.names().collect();
+        assert!(!wasm.is_empty());
+        for name in wasm {
+            let bytes = entry_bytes(&load, Some(&archive), name)
+                .expect("read entry")
+                .expect("entry present");
+            assert_eq!(bytes.as_ref(), eager.files[name].as_slice());
+            assert_eq!(archive.size(name), Some(bytes.len() as u64));
+        }
+        assert_eq!(
+            entry_bytes(&load, Some(&archive), "manifest.cbor")
+                .expect("read manifest")
+                .map(|bytes| bytes.into_owned()),
+            eager.files.get("manifest.cbor").cloned()
+        );
+        assert!(archive.read("components/missing.wasm").unwrap().is_none());
+    }
+}
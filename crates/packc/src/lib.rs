@@ -1,16 +1,26 @@
 #![forbid(unsafe_code)]
 
 pub mod build;
+pub mod build_cache;
 pub mod cli;
 pub mod cli_i18n;
+pub mod component_doctor;
 pub mod component_host_stubs;
 pub mod config;
+pub mod describe_cache;
+pub mod diagnostics;
+pub mod distribution;
 pub mod extensions;
+pub mod flow_doctor;
 pub mod flow_resolve;
+pub mod lazy_entries;
+pub mod mcp;
 pub mod new;
 pub mod pack_lock_doctor;
 pub mod path_safety;
 pub mod runtime;
+pub mod sbom;
+pub mod signer;
 pub mod telemetry;
 pub mod validator;
 
@@ -1,18 +1,29 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, bail};
 pub mod adapter_cache;
 pub mod adapter_ref;
 use adapter_cache::ensure_adapter_local;
 use adapter_ref::MCP_ADAPTER_25_06_18;
+use greentic_pack::pack_lock::LockedComponent;
+use greentic_types::{
+    ComponentCapabilities, ComponentId, ComponentManifest, ComponentProfiles, FlowKind,
+    ResourceHints,
+};
 use semver::Version;
-#[cfg(test)]
-use serde_json::Map as JsonMap;
+use sha2::{Digest, Sha256};
+use wasm_compose::composer::ComponentComposer;
+use wasm_compose::config::Config as ComposeConfig;
+
+use crate::build_cache::CACHE_DIR;
+use crate::config::McpComponentConfig;
 
-use crate::manifest::{McpComponentSpec, SpecBundle, normalize_protocol};
-use crate::path_safety::normalize_under_root;
+/// Component ABI exported by the composed adapter + router.
+pub const MCP_COMPONENT_ABI: &str = "0.4.0";
+/// World exported by the composed adapter + router.
+pub const MCP_COMPONENT_WORLD: &str = "greentic:component/node@0.4.0";
 
 #[derive(Debug, Clone)]
 pub struct ComposedMcpComponent {
@@ -20,87 +31,119 @@ pub struct ComposedMcpComponent {
     pub protocol: String,
     pub adapter_template: String,
     pub artifact_path: PathBuf,
-    pub version: Version,
+    /// `sha256:<hex>` digest of the composed component.
+    pub digest: String,
 }
 
-pub fn compose_all(
-    pack_dir: &Path,
-    spec_bundle: &SpecBundle,
-    pack_version: &Version,
-    runtime: &crate::runtime::RuntimeContext,
-) -> Result<Vec<ComposedMcpComponent>> {
-    compose_all_with_override(
-        pack_dir,
-        spec_bundle,
-        pack_version,
-        fake_compose_enabled(),
-        runtime,
-    )
+impl ComposedMcpComponent {
+    /// Lock entry pinning the composed artifact by digest.
+    ///
+    /// Composed components export the 0.4 node world rather than `describe()`,
+    /// so the entry carries no operations and a zero describe hash.
+    pub fn locked_component(&self, version: &Version) -> LockedComponent {
+        LockedComponent {
+            component_id: self.id.clone(),
+            r#ref: Some(format!("file://{}", self.artifact_path.display())),
+            abi_version: MCP_COMPONENT_ABI.to_string(),
+            resolved_digest: self.digest.clone(),
+            describe_hash: "0".repeat(64),
+            operations: Vec::new(),
+            world: Some(MCP_COMPONENT_WORLD.to_string()),
+            component_version: Some(version.to_string()),
+            role: None,
+        }
+    }
+}
+
+/// Where the composed component for `id` is written, under the pack's build cache dir.
+pub fn composed_artifact_path(pack_dir: &Path, id: &str) -> PathBuf {
+    pack_dir
+        .join(CACHE_DIR)
+        .join("mcp")
+        .join(id)
+        .join("component.wasm")
 }
 
-pub fn compose_all_with_override(
+/// Compose every `mcp_components` entry of `pack.yaml` in process.
+///
+/// `components` come from [`crate::config::load_pack_config`], so router refs
+/// are already validated and normalized under `pack_dir`.
+pub fn compose_all(
     pack_dir: &Path,
-    spec_bundle: &SpecBundle,
-    pack_version: &Version,
-    allow_fake_compose: bool,
-    runtime: &crate::runtime::RuntimeContext,
+    components: &[McpComponentConfig],
 ) -> Result<Vec<ComposedMcpComponent>> {
-    if spec_bundle.spec.mcp_components.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let workspace = runtime.cache_dir().join("mcp");
-    fs::create_dir_all(&workspace)
-        .with_context(|| format!("failed to prepare MCP workspace {}", workspace.display()))?;
-
     let mut outputs = Vec::new();
 
-    for entry in &spec_bundle.spec.mcp_components {
+    for entry in components {
         let protocol = normalize_protocol(&entry.protocol);
         let adapter_template = entry.adapter_template.clone();
-        let router_path = resolve_router_path(pack_dir, &entry.router_ref)?;
-        let adapter_path = resolve_adapter_template(&protocol, &adapter_template, runtime)?;
-        let out_path = workspace.join(&entry.id).join("component.wasm");
+        let router_path = resolve_router_path(&entry.router_ref)?;
+        let adapter_path = resolve_adapter_template(&protocol, &adapter_template, pack_dir)?;
+        let out_path = composed_artifact_path(pack_dir, &entry.id);
 
+        let bytes = compose(&adapter_path, &router_path)
+            .with_context(|| format!("failed to compose MCP component `{}`", entry.id))?;
         if let Some(parent) = out_path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("failed to create directory {}", parent.display()))?;
         }
-
-        compose_with_wasm_tools(&adapter_path, &router_path, &out_path, allow_fake_compose)
-            .with_context(|| format!("failed to compose MCP component `{}`", entry.id))?;
+        fs::write(&out_path, &bytes)
+            .with_context(|| format!("failed to write {}", out_path.display()))?;
 
         outputs.push(ComposedMcpComponent {
             id: entry.id.clone(),
             protocol,
             adapter_template,
             artifact_path: out_path,
-            version: pack_version.clone(),
+            digest: format!("sha256:{:x}", Sha256::digest(&bytes)),
         });
     }
 
     Ok(outputs)
 }
 
-fn resolve_router_path(pack_dir: &Path, router_ref: &str) -> Result<PathBuf> {
-    let path = normalize_under_root(pack_dir, &PathBuf::from(router_ref))?;
-    if path.exists() {
-        Ok(path)
+/// Manifest bundled for a composed MCP component; it versions with the pack.
+pub fn component_manifest(id: &str, version: &Version) -> Result<ComponentManifest> {
+    Ok(ComponentManifest {
+        id: ComponentId::new(id).with_context(|| format!("invalid MCP component id {id}"))?,
+        version: version.clone(),
+        supports: vec![FlowKind::Messaging],
+        world: MCP_COMPONENT_WORLD.to_string(),
+        profiles: ComponentProfiles {
+            default: Some("default".to_string()),
+            supported: vec!["default".to_string()],
+        },
+        capabilities: ComponentCapabilities::default(),
+        configurators: None,
+        operations: Vec::new(),
+        config_schema: None,
+        resources: ResourceHints::default(),
+        dev_flows: BTreeMap::new(),
+    })
+}
+
+fn normalize_protocol(protocol: &str) -> String {
+    if protocol == McpComponentConfig::PROTOCOL_LATEST {
+        McpComponentConfig::PROTOCOL_25_06_18.to_string()
     } else {
-        bail!(
-            "router_ref `{}` does not exist (resolved to {})",
-            router_ref,
-            path.display()
-        );
+        protocol.to_string()
+    }
+}
+
+fn resolve_router_path(router_ref: &Path) -> Result<PathBuf> {
+    if router_ref.is_file() {
+        Ok(router_ref.to_path_buf())
+    } else {
+        bail!("router_ref {} does not exist", router_ref.display());
     }
 }
 
 fn resolve_adapter_template(
     protocol: &str,
     adapter_template: &str,
-    runtime: &crate::runtime::RuntimeContext,
+    pack_dir: &Path,
 ) -> Result<PathBuf> {
-    if adapter_template != McpComponentSpec::ADAPTER_DEFAULT {
+    if adapter_template != McpComponentConfig::ADAPTER_DEFAULT {
         bail!(
             "unsupported adapter_template `{}` (only `default` is available)",
             adapter_template
@@ -108,16 +151,15 @@ fn resolve_adapter_template(
     }
 
     match protocol {
-        McpComponentSpec::PROTOCOL_25_06_18 | McpComponentSpec::PROTOCOL_LATEST => {
-            // Today we rely on the vendored adapter; future work may pull from GHCR.
+        McpComponentConfig::PROTOCOL_25_06_18 => {
+            // Today we rely on the adapter embedded in packc; future work may pull from GHCR.
             if let Ok(path) = std::env::var("GREENTIC_PACK_ADAPTER_25_06_18") {
                 let adapter_path = PathBuf::from(path);
                 if adapter_path.exists() {
                     return Ok(adapter_path);
                 }
             }
-            runtime.require_online("resolve MCP adapter template")?;
-            ensure_adapter_local(&MCP_ADAPTER_25_06_18)
+            ensure_adapter_local(&MCP_ADAPTER_25_06_18, pack_dir)
         }
         other => bail!("unsupported MCP protocol `{}`", other),
     }
@@ -125,7 +167,7 @@ fn resolve_adapter_template(
 
 #[cfg(test)]
 fn write_bundled_adapter(workspace: &Path, filename: &str) -> Result<PathBuf> {
-    let bundled = include_bytes!("../assets/mcp_adapter_25_06_18.component.wasm");
+    let bundled = adapter_cache::VENDORED_ADAPTER_25_06_18;
     let target = workspace.join(filename);
     if !target.exists() {
         fs::write(&target, bundled).with_context(|| {
@@ -138,51 +180,14 @@ fn write_bundled_adapter(workspace: &Path, filename: &str) -> Result<PathBuf> {
     Ok(target)
 }
 
-fn compose_with_wasm_tools(
-    adapter: &Path,
-    router: &Path,
-    output: &Path,
-    allow_fake_compose: bool,
-) -> Result<()> {
-    if allow_fake_compose {
-        fs::copy(adapter, output).with_context(|| {
-            format!(
-                "failed to copy adapter {} to {}",
-                adapter.display(),
-                output.display()
-            )
-        })?;
-        return Ok(());
-    }
-
-    let status = Command::new("wasm-tools")
-        .arg("compose")
-        .arg(adapter)
-        .arg("-d")
-        .arg(router)
-        .arg("-o")
-        .arg(output)
-        .status()
-        .with_context(|| "failed to invoke `wasm-tools compose`")?;
-
-    if !status.success() {
-        bail!("`wasm-tools compose` failed with status {}", status);
-    }
-
-    if !output.exists() {
-        return Err(anyhow!(
-            "expected composed component at {} but it was not produced",
-            output.display()
-        ));
-    }
-
-    Ok(())
-}
-
-fn fake_compose_enabled() -> bool {
-    std::env::var("PACKC_ALLOW_FAKE_COMPOSE")
-        .map(|v| v == "1")
-        .unwrap_or(false)
+/// Instantiate `adapter` with `router` satisfying its router import, the same
+/// wiring `wasm-tools compose <adapter> -d <router>` produces.
+fn compose(adapter: &Path, router: &Path) -> Result<Vec<u8>> {
+    let config = ComposeConfig {
+        definitions: vec![router.to_path_buf()],
+        ..Default::default()
+    };
+    ComponentComposer::new(adapter, &config).compose()
 }
 
 #[cfg(test)]
@@ -199,6 +204,26 @@ mod tests {
         assert!(!contents.is_empty(), "adapter bytes should not be empty");
     }
 
+    #[test]
+    fn embedded_adapter_is_written_under_pack_cache_dir() {
+        let temp = tempdir().unwrap();
+        let path = ensure_adapter_local(&MCP_ADAPTER_25_06_18, temp.path()).unwrap();
+        assert!(path.starts_with(temp.path().join(CACHE_DIR)));
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            adapter_cache::VENDORED_ADAPTER_25_06_18
+        );
+
+        fs::write(&path, b"stale").unwrap();
+        let again = ensure_adapter_local(&MCP_ADAPTER_25_06_18, temp.path()).unwrap();
+        assert_eq!(again, path);
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            adapter_cache::VENDORED_ADAPTER_25_06_18,
+            "a modified adapter copy should be replaced"
+        );
+    }
+
     #[test]
     fn compose_all_writes_composed_component() {
         let temp = tempdir().unwrap();
@@ -206,53 +231,40 @@ mod tests {
         let router_path = pack_dir.join("router-component.wasm");
         fs::write(
             &router_path,
-            include_bytes!("../assets/mcp_adapter_25_06_18.component.wasm"),
+            include_bytes!("../tests/fixtures/router-echo-component.wasm"),
         )
         .unwrap();
+        // The embedded adapter is used; no network access happens.
 
-        let spec = crate::manifest::PackSpec {
-            pack_version: greentic_pack::builder::PACK_VERSION,
-            id: "demo.pack".into(),
-            version: "0.1.0".into(),
-            kind: None,
-            name: Some("Demo".into()),
-            description: None,
-            authors: Vec::new(),
-            license: None,
-            homepage: None,
-            support: None,
-            vendor: None,
-            flow_files: Vec::new(),
-            template_dirs: Vec::new(),
-            entry_flows: Vec::new(),
-            imports_required: Vec::new(),
-            events: None,
-            repo: None,
-            messaging: None,
-            interfaces: Vec::new(),
-            mcp_components: vec![McpComponentSpec {
-                id: "mcp-demo".into(),
-                router_ref: "router-component.wasm".into(),
-                protocol: McpComponentSpec::PROTOCOL_25_06_18.into(),
-                adapter_template: McpComponentSpec::ADAPTER_DEFAULT.into(),
-            }],
-            annotations: JsonMap::new(),
-            components: Vec::new(),
-            distribution: None,
-        };
-
-        let specs = crate::manifest::SpecBundle {
-            spec,
-            source: pack_dir.join("pack.yaml"),
-        };
+        let components = vec![McpComponentConfig {
+            id: "mcp-demo".into(),
+            router_ref: router_path,
+            protocol: McpComponentConfig::PROTOCOL_LATEST.into(),
+            adapter_template: McpComponentConfig::ADAPTER_DEFAULT.into(),
+        }];
 
-        let composed =
-            compose_all_with_override(pack_dir, &specs, &Version::parse("0.1.0").unwrap(), true)
-                .expect("composition succeeds");
+        let composed = compose_all(pack_dir, &components).expect("composition succeeds");
         assert_eq!(composed.len(), 1, "one mcp component should be produced");
-        assert!(
-            composed[0].artifact_path.exists(),
-            "composed artifact should exist"
+        let composed = &composed[0];
+        assert_eq!(composed.protocol, McpComponentConfig::PROTOCOL_25_06_18);
+        assert_eq!(
+            composed.artifact_path,
+            composed_artifact_path(pack_dir, "mcp-demo")
         );
+        let bytes = fs::read(&composed.artifact_path).expect("composed artifact");
+        assert_eq!(
+            composed.digest,
+            format!("sha256:{:x}", Sha256::digest(&bytes))
+        );
+        let adapter = write_bundled_adapter(pack_dir, "adapter.wasm").unwrap();
+        assert_ne!(
+            bytes,
+            fs::read(adapter).unwrap(),
+            "router should be composed into the adapter"
+        );
+
+        let locked = composed.locked_component(&Version::new(0, 1, 0));
+        assert_eq!(locked.abi_version, MCP_COMPONENT_ABI);
+        assert_eq!(locked.resolved_digest, composed.digest);
     }
 }
@@ -1,31 +1,46 @@
+use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use super::adapter_ref::{MCP_ADAPTER_25_06_18, McpAdapterRef};
+use crate::build_cache::CACHE_DIR;
+
+/// MCP adapter for protocol 25.06.18, embedded in the binary.
+pub(crate) const VENDORED_ADAPTER_25_06_18: &[u8] =
+    include_bytes!("../../assets/mcp_adapter_25_06_18.component.wasm");
 
 /// Return the local adapter path for the given reference.
 ///
-/// Current behaviour: use the vendored asset bundled in packc.
+/// Current behaviour: write the adapter embedded in packc under
+/// `<pack_dir>/.packc/mcp/adapters/`, so no network or source checkout is needed.
 /// Future: implement OCI pull + cache when GHCR is the source of truth.
-pub fn ensure_adapter_local(adapter: &McpAdapterRef) -> Result<PathBuf> {
+pub fn ensure_adapter_local(adapter: &McpAdapterRef, pack_dir: &Path) -> Result<PathBuf> {
     if adapter.protocol == MCP_ADAPTER_25_06_18.protocol {
-        vendored_adapter_path()
+        materialise(
+            pack_dir,
+            "mcp_adapter_25_06_18.component.wasm",
+            VENDORED_ADAPTER_25_06_18,
+        )
     } else {
         anyhow::bail!("unsupported MCP adapter protocol `{}`", adapter.protocol)
     }
 }
 
-fn vendored_adapter_path() -> Result<PathBuf> {
-    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
-        .join("assets")
-        .join("mcp_adapter_25_06_18.component.wasm");
-    if path.exists() {
-        Ok(path)
-    } else {
-        Err(anyhow::anyhow!(
-            "vendored MCP adapter missing at {}",
-            path.display()
-        ))
+fn materialise(pack_dir: &Path, filename: &str, bytes: &[u8]) -> Result<PathBuf> {
+    let path = pack_dir
+        .join(CACHE_DIR)
+        .join("mcp")
+        .join("adapters")
+        .join(filename);
+    if fs::read(&path).ok().as_deref() == Some(bytes) {
+        return Ok(path);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
     }
+    fs::write(&path, bytes)
+        .with_context(|| format!("failed to write MCP adapter {}", path.display()))?;
+    Ok(path)
 }
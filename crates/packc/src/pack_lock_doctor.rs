@@ -23,6 +23,8 @@ use wasmtime::Engine;
 use wasmtime::component::{Component as WasmtimeComponent, Linker};
 
 use crate::component_host_stubs::{DescribeHostState, add_describe_host_imports};
+use crate::describe_cache::{DescribeCache, describe_hash as compute_describe_hash};
+use crate::lazy_entries::{LazyEntries, entry_bytes};
 use crate::runtime::{NetworkPolicy, RuntimeContext};
 
 pub struct PackLockDoctorInput<'a> {
@@ -57,6 +59,14 @@ struct DescribeResolution {
 }
 
 pub fn run_pack_lock_doctor(input: PackLockDoctorInput<'_>) -> Result<PackLockDoctorOutput> {
+    run_pack_lock_doctor_with_archive(input, None)
+}
+
+/// [`run_pack_lock_doctor`] for a load whose component Wasm is left in `archive`.
+pub fn run_pack_lock_doctor_with_archive(
+    input: PackLockDoctorInput<'_>,
+    archive: Option<&LazyEntries>,
+) -> Result<PackLockDoctorOutput> {
     let mut diagnostics: Vec<ComponentDiagnostic> = Vec::new();
     let mut has_errors = false;
 
@@ -122,6 +132,7 @@ pub fn run_pack_lock_doctor(input: PackLockDoctorInput<'_>) -> Result<PackLockDo
         .collect();
 
     let engine = Engine::default();
+    let describe_cache = DescribeCache::for_runtime(input.runtime);
 
     for (component_id, locked) in &pack_lock.components {
         if locked.abi_version != "0.6.0" {
@@ -130,6 +141,7 @@ pub fn run_pack_lock_doctor(input: PackLockDoctorInput<'_>) -> Result<PackLockDo
 
         let wasm = match resolve_component_wasm(
             &input,
+            archive,
             &manifest_map,
             &component_sources_map,
             component_id,
@@ -171,6 +183,8 @@ pub fn run_pack_lock_doctor(input: PackLockDoctorInput<'_>) -> Result<PackLockDo
         let describe_resolution = match describe_component_with_cache(
             &engine,
             &wasm,
+            &describe_cache,
+            &digest,
             input.use_describe_cache,
             component_id,
         ) {
@@ -442,7 +456,10 @@ fn finish_diagnostics(mut diagnostics: Vec<ComponentDiagnostic>) -> PackLockDoct
     }
 }
 
-fn load_pack_lock(load: &PackLoad, pack_dir: Option<&Path>) -> Result<Option<PackLockV1>> {
+pub(crate) fn load_pack_lock(
+    load: &PackLoad,
+    pack_dir: Option<&Path>,
+) -> Result<Option<PackLockV1>> {
     if let Some(bytes) = load.files.get("pack.lock.cbor") {
         return read_pack_lock_from_bytes(bytes).map(Some);
     }
@@ -492,6 +509,7 @@ fn build_component_sources_map(
 
 fn resolve_component_wasm(
     input: &PackLockDoctorInput<'_>,
+    archive: Option<&LazyEntries>,
     manifest_map: &HashMap<String, &greentic_pack::builder::ComponentEntry>,
     component_sources_map: &HashMap<String, ComponentSourceEntryV1>,
     component_id: &str,
@@ -499,9 +517,9 @@ fn resolve_component_wasm(
 ) -> Result<WasmSource> {
     if let Some(entry) = manifest_map.get(component_id) {
         let logical = entry.file_wasm.clone();
-        if let Some(bytes) = input.load.files.get(&logical) {
+        if let Some(bytes) = entry_bytes(input.load, archive, &logical)? {
             return Ok(WasmSource {
-                bytes: bytes.clone(),
+                bytes: bytes.into_owned(),
                 source_path: input
                     .pack_dir
                     .map(|dir| dir.join(&entry.file_wasm))
@@ -526,9 +544,9 @@ fn resolve_component_wasm(
     if let Some(entry) = component_sources_map.get(component_id)
         && let ArtifactLocationV1::Inline { wasm_path, .. } = &entry.artifact
     {
-        if let Some(bytes) = input.load.files.get(wasm_path) {
+        if let Some(bytes) = entry_bytes(input.load, archive, wasm_path)? {
             return Ok(WasmSource {
-                bytes: bytes.clone(),
+                bytes: bytes.into_owned(),
                 source_path: input
                     .pack_dir
                     .map(|dir| dir.join(wasm_path))
@@ -609,14 +627,27 @@ where
 fn describe_component_with_cache(
     engine: &Engine,
     wasm: &WasmSource,
+    describe_cache: &DescribeCache,
+    digest: &str,
     use_cache: bool,
     component_id: &str,
 ) -> Result<DescribeResolution> {
-    match describe_component(engine, &wasm.bytes) {
-        Ok(describe) => Ok(DescribeResolution {
-            describe,
+    // Only successful describe() calls are persisted, so a hit stands in for one.
+    if let Some(cached) = describe_cache.get(digest) {
+        return Ok(DescribeResolution {
+            describe: cached.describe,
             requires_typed_instance: true,
-        }),
+        });
+    }
+    match describe_component(engine, &wasm.bytes) {
+        Ok(describe) => {
+            // A failed write only means the next run describes again.
+            let _ = describe_cache.put(digest, &describe);
+            Ok(DescribeResolution {
+                describe,
+                requires_typed_instance: true,
+            })
+        }
         Err(err) => {
             if should_fallback_to_untyped_describe(&err)
                 && let Ok(describe) = describe_component_untyped(engine, &wasm.bytes)
@@ -719,13 +750,6 @@ fn load_describe_sidecar_from_pack(load: &PackLoad, logical_path: &str) -> Optio
     load.files.get(&describe_path).cloned()
 }
 
-fn compute_describe_hash(describe: &ComponentDescribe) -> Result<String> {
-    let bytes =
-        canonical::to_canonical_cbor_allow_floats(describe).context("canonicalize describe")?;
-    let digest = Sha256::digest(bytes.as_slice());
-    Ok(hex::encode(digest))
-}
-
 fn validate_schema_ir(
     component_id: &str,
     schema: &SchemaIr,
@@ -5,11 +5,15 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use greentic_config::{ConfigLayer, ConfigResolver, ResolvedConfig};
+use greentic_pack::reader::ReaderLimits;
+use greentic_pack::trust::TrustPolicy;
 use greentic_types::ConnectionKind;
 use std::sync::Arc;
 
 pub struct RuntimeState {
     pub resolved: ResolvedConfig,
+    /// Size limits for every `.gtpack` a command reads (`--max-archive-bytes`, `--max-file-bytes`).
+    pub reader_limits: ReaderLimits,
 }
 
 pub type RuntimeContext = Arc<RuntimeState>;
@@ -49,6 +53,33 @@ impl RuntimeState {
     pub fn warnings(&self) -> &[String] {
         &self.resolved.warnings
     }
+
+    /// Trust directory contributed by the greentic-config layer.
+    pub fn trust_dir(&self) -> PathBuf {
+        self.resolved.config.paths.greentic_root.join("trust")
+    }
+
+    /// Load the signature trust policy.
+    ///
+    /// Precedence: explicit `--trust-dir`, `GREENTIC_PACK_TRUST_DIR`, then
+    /// `<greentic_root>/trust` when it exists. Without any of these the policy is
+    /// empty and no trust decision is made.
+    pub fn trust_policy(&self, cli_dir: Option<&Path>) -> Result<TrustPolicy> {
+        let explicit = cli_dir
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("GREENTIC_PACK_TRUST_DIR").map(PathBuf::from));
+        if let Some(dir) = explicit {
+            if !dir.is_dir() {
+                anyhow::bail!("trust directory {} does not exist", dir.display());
+            }
+            return TrustPolicy::load_dir(&dir);
+        }
+        let dir = self.trust_dir();
+        if dir.is_dir() {
+            return TrustPolicy::load_dir(&dir);
+        }
+        Ok(TrustPolicy::default())
+    }
 }
 
 pub fn resolve_runtime(
@@ -56,6 +87,23 @@ pub fn resolve_runtime(
     cli_cache_dir: Option<&Path>,
     cli_offline: bool,
     cli_override: Option<&Path>,
+) -> Result<RuntimeContext> {
+    resolve_runtime_with_limits(
+        project_root,
+        cli_cache_dir,
+        cli_offline,
+        cli_override,
+        ReaderLimits::default(),
+    )
+}
+
+/// Same as [`resolve_runtime`] with caller-supplied archive size limits.
+pub fn resolve_runtime_with_limits(
+    project_root: Option<&Path>,
+    cli_cache_dir: Option<&Path>,
+    cli_offline: bool,
+    cli_override: Option<&Path>,
+    reader_limits: ReaderLimits,
 ) -> Result<RuntimeContext> {
     let mut resolver = ConfigResolver::new();
     if let Some(root) = project_root {
@@ -80,7 +128,10 @@ pub fn resolve_runtime(
         resolved.config.paths.cache_dir = cache_dir.to_path_buf();
     }
 
-    Ok(Arc::new(RuntimeState { resolved }))
+    Ok(Arc::new(RuntimeState {
+        resolved,
+        reader_limits,
+    }))
 }
 
 fn load_cli_override_layer(path: &Path) -> Result<ConfigLayer> {
@@ -1,10 +1,85 @@
-use crate::flows::FlowAsset;
-use crate::manifest::SpecBundle;
-use crate::templates::TemplateAsset;
+#![forbid(unsafe_code)]
+
+//! Standard SBOM documents (CycloneDX 1.5 / SPDX 2.3) derived from the same
+//! inventory that `build` writes to `sbom.cbor`.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use greentic_pack::builder::SbomEntry;
+use greentic_pack::pack_lock::{LockedComponent, PackLockV1};
+use greentic_types::PackManifest;
 use serde::Serialize;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
+/// Archive path of an embedded CycloneDX document.
+pub const CYCLONEDX_PATH: &str = "sbom.cdx.json";
+/// Archive path of an embedded SPDX document.
+pub const SPDX_PATH: &str = "sbom.spdx.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum SbomFormat {
+    Cyclonedx,
+    Spdx,
+}
+
+impl SbomFormat {
+    pub fn archive_path(self) -> &'static str {
+        match self {
+            SbomFormat::Cyclonedx => CYCLONEDX_PATH,
+            SbomFormat::Spdx => SPDX_PATH,
+        }
+    }
+
+    pub fn render(self, inventory: &SbomInventory<'_>) -> Result<Vec<u8>> {
+        let bytes = match self {
+            SbomFormat::Cyclonedx => serde_json::to_vec_pretty(&cyclonedx(inventory)),
+            SbomFormat::Spdx => serde_json::to_vec_pretty(&spdx(inventory)),
+        };
+        bytes.context("failed to encode sbom document")
+    }
+}
+
+/// Everything an SBOM document is generated from.
+pub struct SbomInventory<'a> {
+    pub manifest: &'a PackManifest,
+    pub lock: &'a PackLockV1,
+    /// Archive inventory (the `files` list of `sbom.cbor`).
+    pub files: &'a [SbomEntry],
+    /// Archive path of each bundled lock component, keyed by component id.
+    pub bundled_paths: &'a BTreeMap<String, String>,
+}
+
+impl SbomInventory<'_> {
+    fn file(&self, path: &str) -> Option<&SbomEntry> {
+        self.files.iter().find(|entry| entry.path == path)
+    }
+
+    fn component_archive_path(&self, component_id: &str) -> Option<String> {
+        if let Some(path) = self.bundled_paths.get(component_id) {
+            return Some(path.clone());
+        }
+        let default = format!("components/{component_id}.wasm");
+        self.file(&default).map(|_| default)
+    }
+
+    fn flow_hash(&self, flow: &greentic_types::PackFlowEntry) -> String {
+        let path = format!("flows/{}/flow.json", flow.id);
+        if let Some(entry) = self.file(&path) {
+            return entry.hash_blake3.clone();
+        }
+        let bytes = serde_json::to_vec(&flow.flow).unwrap_or_default();
+        blake3::hash(&bytes).to_hex().to_string()
+    }
+
+    fn assets(&self) -> impl Iterator<Item = &SbomEntry> {
+        self.files
+            .iter()
+            .filter(|entry| entry.path.starts_with("assets/"))
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct CycloneDxBom {
     #[serde(rename = "bomFormat")]
@@ -14,31 +89,37 @@ pub struct CycloneDxBom {
     pub version: u32,
     pub metadata: Metadata,
     pub components: Vec<Component>,
+    pub dependencies: Vec<Dependency>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Metadata {
-    pub timestamp: String,
-    pub component: ComponentSummary,
+    pub tools: Tools,
+    pub component: Component,
 }
 
 #[derive(Debug, Serialize)]
-pub struct ComponentSummary {
-    pub name: String,
-    pub version: String,
-    #[serde(rename = "type")]
-    pub component_type: &'static str,
+pub struct Tools {
+    pub components: Vec<Component>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct Component {
-    pub name: String,
     #[serde(rename = "type")]
     pub component_type: &'static str,
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    pub bom_ref: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub version: Option<String>,
+    pub publisher: Option<String>,
+    pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hashes: Option<Vec<HashEntry>>,
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<HashEntry>,
+    #[serde(rename = "externalReferences", skip_serializing_if = "Vec::is_empty")]
+    pub external_references: Vec<ExternalReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<Property>,
 }
 
 #[derive(Debug, Serialize)]
@@ -47,37 +128,91 @@ pub struct HashEntry {
     pub content: String,
 }
 
-pub fn generate(
-    spec: &SpecBundle,
-    flows: &[FlowAsset],
-    templates: &[TemplateAsset],
-) -> CycloneDxBom {
-    let timestamp = OffsetDateTime::now_utc()
-        .format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string());
+#[derive(Debug, Serialize)]
+pub struct ExternalReference {
+    #[serde(rename = "type")]
+    pub reference_type: &'static str,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Property {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Dependency {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    #[serde(rename = "dependsOn")]
+    pub depends_on: Vec<String>,
+}
+
+fn property(name: &str, value: impl Into<String>) -> Property {
+    Property {
+        name: format!("greentic:{name}"),
+        value: value.into(),
+    }
+}
+
+fn pack_ref(manifest: &PackManifest) -> String {
+    format!("pack:{}@{}", manifest.pack_id, manifest.version)
+}
 
+/// Build a CycloneDX 1.5 document for the pack.
+///
+/// No timestamp or serial number is emitted so that embedding the document keeps
+/// the archive reproducible.
+pub fn cyclonedx(inventory: &SbomInventory<'_>) -> CycloneDxBom {
+    let manifest = inventory.manifest;
+    let root_ref = pack_ref(manifest);
     let mut components = Vec::new();
-    for flow in flows {
+    let mut depends_on = Vec::new();
+
+    for locked in inventory.lock.components.values() {
+        let component = cyclonedx_component(inventory, locked);
+        if let Some(bom_ref) = component.bom_ref.clone() {
+            depends_on.push(bom_ref);
+        }
+        components.push(component);
+    }
+
+    for flow in &manifest.flows {
+        let mut properties = vec![property(
+            "flow_kind",
+            format!("{:?}", flow.kind).to_lowercase(),
+        )];
+        for entrypoint in &flow.entrypoints {
+            properties.push(property("entrypoint", entrypoint.clone()));
+        }
         components.push(Component {
-            name: flow.bundle.id.clone(),
             component_type: "file",
-            version: None,
-            hashes: Some(vec![HashEntry {
-                alg: "SHA-256",
-                content: flow.sha256.clone(),
-            }]),
+            bom_ref: Some(format!("flow:{}", flow.id)),
+            name: format!("flows/{}", flow.id),
+            hashes: vec![HashEntry {
+                alg: "BLAKE3",
+                content: inventory.flow_hash(flow),
+            }],
+            properties,
+            ..Component::default()
         });
     }
 
-    for template in templates {
+    for asset in inventory.assets() {
         components.push(Component {
-            name: template.logical_path.clone(),
             component_type: "file",
-            version: None,
-            hashes: Some(vec![HashEntry {
-                alg: "SHA-256",
-                content: template.sha256.clone(),
-            }]),
+            bom_ref: Some(format!("file:{}", asset.path)),
+            name: asset.path.clone(),
+            hashes: vec![HashEntry {
+                alg: "BLAKE3",
+                content: asset.hash_blake3.clone(),
+            }],
+            properties: vec![
+                property("media_type", asset.media_type.clone()),
+                property("size", asset.size.to_string()),
+            ],
+            ..Component::default()
         });
     }
 
@@ -86,13 +221,445 @@ pub fn generate(
         spec_version: "1.5",
         version: 1,
         metadata: Metadata {
-            timestamp,
-            component: ComponentSummary {
-                name: spec.spec.id.clone(),
-                version: spec.spec.version.clone(),
+            tools: Tools {
+                components: vec![Component {
+                    component_type: "application",
+                    name: "greentic-pack".to_string(),
+                    version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                    ..Component::default()
+                }],
+            },
+            component: Component {
                 component_type: "application",
+                bom_ref: Some(root_ref.clone()),
+                publisher: Some(manifest.publisher.clone()).filter(|p| !p.trim().is_empty()),
+                name: manifest.pack_id.to_string(),
+                version: Some(manifest.version.to_string()),
+                properties: vec![property(
+                    "pack_kind",
+                    format!("{:?}", manifest.kind).to_lowercase(),
+                )],
+                ..Component::default()
             },
         },
         components,
+        dependencies: vec![Dependency {
+            reference: root_ref,
+            depends_on,
+        }],
+    }
+}
+
+fn cyclonedx_component(inventory: &SbomInventory<'_>, locked: &LockedComponent) -> Component {
+    let mut hashes = Vec::new();
+    if let Some(hex) = locked.resolved_digest.strip_prefix("sha256:") {
+        hashes.push(HashEntry {
+            alg: "SHA-256",
+            content: hex.to_string(),
+        });
+    }
+
+    let mut properties = vec![
+        property("resolved_digest", locked.resolved_digest.clone()),
+        property("abi_version", locked.abi_version.clone()),
+        property("describe_hash", locked.describe_hash.clone()),
+    ];
+    if let Some(world) = locked.world.as_ref() {
+        properties.push(property("world", world.clone()));
+    }
+    if let Some(role) = locked.role.as_ref() {
+        properties.push(property("role", role.clone()));
+    }
+    if let Some(path) = inventory.component_archive_path(&locked.component_id) {
+        if let Some(entry) = inventory.file(&path) {
+            hashes.push(HashEntry {
+                alg: "BLAKE3",
+                content: entry.hash_blake3.clone(),
+            });
+        }
+        properties.push(property("archive_path", path));
+    }
+
+    let external_references = locked
+        .r#ref
+        .as_ref()
+        .filter(|reference| !reference.starts_with("file://"))
+        .map(|reference| {
+            vec![ExternalReference {
+                reference_type: "distribution",
+                url: reference.clone(),
+            }]
+        })
+        .unwrap_or_default();
+
+    Component {
+        component_type: "library",
+        bom_ref: Some(format!("component:{}", locked.component_id)),
+        name: locked.component_id.clone(),
+        version: locked.component_version.clone(),
+        hashes,
+        external_references,
+        properties,
+        ..Component::default()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    pub spdx_version: &'static str,
+    pub data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: &'static str,
+    pub name: String,
+    pub document_namespace: String,
+    pub creation_info: CreationInfo,
+    pub packages: Vec<SpdxPackage>,
+    pub relationships: Vec<Relationship>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreationInfo {
+    pub created: String,
+    pub creators: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxPackage {
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_info: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supplier: Option<String>,
+    pub download_location: String,
+    pub files_analyzed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_package_purpose: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checksums: Vec<Checksum>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<SpdxExternalRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checksum {
+    pub algorithm: &'static str,
+    pub checksum_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxExternalRef {
+    pub reference_category: &'static str,
+    pub reference_type: &'static str,
+    pub reference_locator: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relationship {
+    pub spdx_element_id: String,
+    pub relationship_type: &'static str,
+    pub related_spdx_element: String,
+}
+
+/// Build an SPDX 2.3 document for the pack.
+///
+/// `created` honours `SOURCE_DATE_EPOCH` and otherwise falls back to the Unix
+/// epoch, keeping the output reproducible.
+pub fn spdx(inventory: &SbomInventory<'_>) -> SpdxDocument {
+    let manifest = inventory.manifest;
+    let root_id = "SPDXRef-Pack".to_string();
+    let manifest_hash = inventory
+        .file("manifest.cbor")
+        .map(|entry| entry.hash_blake3.clone())
+        .unwrap_or_default();
+
+    let mut packages = vec![SpdxPackage {
+        spdx_id: root_id.clone(),
+        name: manifest.pack_id.to_string(),
+        version_info: Some(manifest.version.to_string()),
+        supplier: Some(manifest.publisher.clone())
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| format!("Organization: {p}")),
+        download_location: "NOASSERTION".to_string(),
+        primary_package_purpose: Some("APPLICATION"),
+        ..SpdxPackage::default()
+    }];
+    let mut relationships = vec![Relationship {
+        spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+        relationship_type: "DESCRIBES",
+        related_spdx_element: root_id.clone(),
+    }];
+    let mut contains = |id: &str| {
+        relationships.push(Relationship {
+            spdx_element_id: root_id.clone(),
+            relationship_type: "CONTAINS",
+            related_spdx_element: id.to_string(),
+        });
+    };
+
+    for locked in inventory.lock.components.values() {
+        let spdx_id = format!(
+            "SPDXRef-Component-{}",
+            spdx_ref_suffix(&locked.component_id)
+        );
+        let mut checksums = Vec::new();
+        if let Some(hex) = locked.resolved_digest.strip_prefix("sha256:") {
+            checksums.push(Checksum {
+                algorithm: "SHA256",
+                checksum_value: hex.to_string(),
+            });
+        }
+        let archive_path = inventory.component_archive_path(&locked.component_id);
+        if let Some(entry) = archive_path
+            .as_deref()
+            .and_then(|path| inventory.file(path))
+        {
+            checksums.push(Checksum {
+                algorithm: "BLAKE3",
+                checksum_value: entry.hash_blake3.clone(),
+            });
+        }
+        let download_location = locked
+            .r#ref
+            .clone()
+            .filter(|reference| !reference.starts_with("file://"))
+            .unwrap_or_else(|| "NOASSERTION".to_string());
+        let mut comment = vec![format!("abi_version={}", locked.abi_version)];
+        if let Some(world) = locked.world.as_ref() {
+            comment.push(format!("world={world}"));
+        }
+        if let Some(role) = locked.role.as_ref() {
+            comment.push(format!("role={role}"));
+        }
+        if let Some(path) = archive_path.as_ref() {
+            comment.push(format!("archive_path={path}"));
+        }
+        packages.push(SpdxPackage {
+            spdx_id: spdx_id.clone(),
+            name: locked.component_id.clone(),
+            version_info: locked.component_version.clone(),
+            download_location,
+            primary_package_purpose: Some("LIBRARY"),
+            checksums,
+            comment: Some(comment.join("; ")),
+            ..SpdxPackage::default()
+        });
+        contains(&spdx_id);
+    }
+
+    for flow in &manifest.flows {
+        let spdx_id = format!("SPDXRef-Flow-{}", spdx_ref_suffix(flow.id.as_ref()));
+        packages.push(SpdxPackage {
+            spdx_id: spdx_id.clone(),
+            name: format!("flows/{}", flow.id),
+            download_location: "NOASSERTION".to_string(),
+            primary_package_purpose: Some("FILE"),
+            checksums: vec![Checksum {
+                algorithm: "BLAKE3",
+                checksum_value: inventory.flow_hash(flow),
+            }],
+            comment: Some(format!(
+                "flow_kind={}",
+                format!("{:?}", flow.kind).to_lowercase()
+            )),
+            ..SpdxPackage::default()
+        });
+        contains(&spdx_id);
+    }
+
+    for asset in inventory.assets() {
+        let spdx_id = format!("SPDXRef-File-{}", spdx_ref_suffix(&asset.path));
+        packages.push(SpdxPackage {
+            spdx_id: spdx_id.clone(),
+            name: asset.path.clone(),
+            download_location: "NOASSERTION".to_string(),
+            primary_package_purpose: Some("FILE"),
+            checksums: vec![Checksum {
+                algorithm: "BLAKE3",
+                checksum_value: asset.hash_blake3.clone(),
+            }],
+            ..SpdxPackage::default()
+        });
+        contains(&spdx_id);
+    }
+
+    SpdxDocument {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: "SPDXRef-DOCUMENT",
+        name: format!("{}-{}", manifest.pack_id, manifest.version),
+        document_namespace: format!(
+            "https://greentic.ai/spdx/{}/{}/{}",
+            manifest.pack_id, manifest.version, manifest_hash
+        ),
+        creation_info: CreationInfo {
+            created: creation_timestamp(),
+            creators: vec![format!("Tool: greentic-pack-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        packages,
+        relationships,
+    }
+}
+
+/// SPDX identifiers only allow letters, digits, `.` and `-`.
+fn spdx_ref_suffix(raw: &str) -> String {
+    raw.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn creation_timestamp() -> String {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i64>().ok())
+        .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use greentic_types::{PackId, PackKind, PackSignatures};
+    use semver::Version;
+
+    fn sample_manifest() -> PackManifest {
+        PackManifest {
+            schema_version: "pack-v1".to_string(),
+            pack_id: PackId::new("demo.sbom").unwrap(),
+            name: None,
+            version: Version::parse("1.2.3").unwrap(),
+            kind: PackKind::Application,
+            publisher: "Acme".to_string(),
+            components: Vec::new(),
+            flows: Vec::new(),
+            dependencies: Vec::new(),
+            capabilities: Vec::new(),
+            secret_requirements: Vec::new(),
+            signatures: PackSignatures::default(),
+            bootstrap: None,
+            extensions: None,
+        }
+    }
+
+    fn sample_lock() -> PackLockV1 {
+        let mut components = BTreeMap::new();
+        components.insert(
+            "demo.component".to_string(),
+            LockedComponent {
+                component_id: "demo.component".to_string(),
+                r#ref: Some(format!("oci://ghcr.io/acme/demo@sha256:{}", "a".repeat(64))),
+                abi_version: "0.6.0".to_string(),
+                resolved_digest: format!("sha256:{}", "a".repeat(64)),
+                describe_hash: "b".repeat(64),
+                operations: Vec::new(),
+                world: Some("greentic:component/component@0.6.0".to_string()),
+                component_version: Some("0.4.0".to_string()),
+                role: None,
+            },
+        );
+        PackLockV1::new(components)
+    }
+
+    fn sample_files() -> Vec<SbomEntry> {
+        vec![
+            SbomEntry {
+                path: "assets/logo.png".to_string(),
+                size: 3,
+                hash_blake3: "c".repeat(64),
+                media_type: "application/octet-stream".to_string(),
+            },
+            SbomEntry {
+                path: "components/demo.component.wasm".to_string(),
+                size: 8,
+                hash_blake3: "d".repeat(64),
+                media_type: "application/wasm".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn cyclonedx_lists_locked_components_and_assets() {
+        let manifest = sample_manifest();
+        let lock = sample_lock();
+        let files = sample_files();
+        let bundled = BTreeMap::new();
+        let inventory = SbomInventory {
+            manifest: &manifest,
+            lock: &lock,
+            files: &files,
+            bundled_paths: &bundled,
+        };
+
+        let doc = serde_json::to_value(cyclonedx(&inventory)).unwrap();
+        assert_eq!(doc["bomFormat"], "CycloneDX");
+        assert_eq!(doc["specVersion"], "1.5");
+        assert_eq!(doc["metadata"]["component"]["name"], "demo.sbom");
+
+        let component = &doc["components"][0];
+        assert_eq!(component["name"], "demo.component");
+        assert_eq!(component["version"], "0.4.0");
+        assert_eq!(component["hashes"][0]["alg"], "SHA-256");
+        assert_eq!(component["hashes"][1]["alg"], "BLAKE3");
+        assert!(
+            component["externalReferences"][0]["url"]
+                .as_str()
+                .unwrap()
+                .starts_with("oci://")
+        );
+        assert!(
+            doc["components"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|c| c["name"] == "assets/logo.png")
+        );
+        assert_eq!(
+            doc["dependencies"][0]["dependsOn"][0],
+            "component:demo.component"
+        );
+    }
+
+    #[test]
+    fn spdx_document_is_reproducible() {
+        let manifest = sample_manifest();
+        let lock = sample_lock();
+        let files = sample_files();
+        let bundled = BTreeMap::new();
+        let inventory = SbomInventory {
+            manifest: &manifest,
+            lock: &lock,
+            files: &files,
+            bundled_paths: &bundled,
+        };
+
+        let first = SbomFormat::Spdx.render(&inventory).unwrap();
+        let second = SbomFormat::Spdx.render(&inventory).unwrap();
+        assert_eq!(first, second);
+
+        let doc: serde_json::Value = serde_json::from_slice(&first).unwrap();
+        assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+        let packages = doc["packages"].as_array().unwrap();
+        assert!(
+            packages
+                .iter()
+                .any(|p| p["SPDXID"] == "SPDXRef-Component-demo.component")
+        );
+        assert_eq!(packages[1]["checksums"][0]["algorithm"], "SHA256");
     }
 }
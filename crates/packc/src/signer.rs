@@ -0,0 +1,438 @@
+#![forbid(unsafe_code)]
+
+//! Signer backends selectable from the CLI.
+//!
+//! Besides PEM key files, signatures can be produced by a local signing agent
+//! listening on a Unix socket (`agent:///run/greentic/signer.sock`) or by a
+//! command hook (`exec:pkcs11-sign --slot 0`), so private keys never need to be
+//! present on the build machine. Both speak the same protocol: one JSON request
+//! per exchange and one JSON response.
+//!
+//! ```text
+//! -> {"version":1,"op":"sign","key_id":"release","payload":"<base64>"}
+//! <- {"alg":"ed25519","signature":"<base64>"}
+//! -> {"version":1,"op":"chain","key_id":"release"}
+//! <- {"chain_pem":"-----BEGIN CERTIFICATE-----\n..."}
+//! <- {"error":"key release is locked"}
+//! ```
+//!
+//! The agent reads one newline-terminated request per connection. Command hooks
+//! are spawned once per request with the request on stdin and must print the
+//! response on stdout. The hook command line is split with shell quoting rules
+//! (`exec:"/opt/HSM Tools/sign" --label 'release key'`) but is not run through a
+//! shell. Agents and hooks that do not answer within a minute are abandoned.
+
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use greentic_pack::builder::{ExternalSignature, Signer};
+use greentic_pack::signing::KeySigner;
+use serde::{Deserialize, Serialize};
+
+/// Protocol version sent with every request.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// How long an agent or command hook may take to answer one request.
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where signatures come from, as given to `--signer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerSpec {
+    /// Ed25519 PKCS#8 PEM private key on disk (`file:<path>` or `--key`).
+    KeyFile(PathBuf),
+    /// Signing agent on a Unix socket (`agent://<socket path>`).
+    Agent(PathBuf),
+    /// Command hook speaking the JSON protocol on stdin/stdout (`exec:<cmd> [args...]`).
+    Exec(Vec<String>),
+}
+
+impl FromStr for SignerSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if let Some(socket) = value.strip_prefix("agent://") {
+            if socket.is_empty() {
+                bail!("agent signer needs a socket path, e.g. agent:///run/signer.sock");
+            }
+            return Ok(Self::Agent(PathBuf::from(socket)));
+        }
+        if let Some(command) = value.strip_prefix("exec:") {
+            let argv = shlex::split(command)
+                .ok_or_else(|| anyhow!("exec signer command has unbalanced quotes: {command}"))?;
+            if argv.is_empty() {
+                bail!("exec signer needs a command, e.g. exec:pkcs11-sign --slot 0");
+            }
+            return Ok(Self::Exec(argv));
+        }
+        if let Some(path) = value.strip_prefix("file:") {
+            return Ok(Self::KeyFile(PathBuf::from(path)));
+        }
+        bail!("unsupported signer `{value}` (expected agent://<socket>, exec:<cmd> or file:<path>)")
+    }
+}
+
+impl fmt::Display for SignerSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyFile(path) => write!(f, "file:{}", path.display()),
+            Self::Agent(socket) => write!(f, "agent://{}", socket.display()),
+            Self::Exec(argv) => write!(
+                f,
+                "exec:{}",
+                shlex::try_join(argv.iter().map(String::as_str)).map_err(|_| fmt::Error)?
+            ),
+        }
+    }
+}
+
+/// Open the signer described by `spec`.
+///
+/// `chain` overrides the certificate chain reported by the backend (for key
+/// files it replaces the default self-signed certificate).
+pub fn open_signer(
+    spec: &SignerSpec,
+    key_id: Option<&str>,
+    chain: Option<Vec<u8>>,
+) -> Result<Box<dyn Signer + Send + Sync>> {
+    match spec {
+        SignerSpec::KeyFile(path) => {
+            let private_pem = fs::read_to_string(path)
+                .with_context(|| format!("failed to read private key {}", path.display()))?;
+            let mut signer = KeySigner::from_pkcs8_pem(&private_pem)?;
+            if let Some(chain) = chain {
+                signer = signer.with_chain_pem(chain);
+            }
+            Ok(Box::new(signer))
+        }
+        SignerSpec::Agent(socket) => Ok(Box::new(ExternalSigner {
+            spec: spec.clone(),
+            transport: Transport::Agent(socket.clone()),
+            key_id: key_id.map(str::to_string),
+            chain,
+        })),
+        SignerSpec::Exec(argv) => Ok(Box::new(ExternalSigner {
+            spec: spec.clone(),
+            transport: Transport::Exec(argv.clone()),
+            key_id: key_id.map(str::to_string),
+            chain,
+        })),
+    }
+}
+
+/// [`Signer`] that forwards requests to a signing agent or command hook.
+pub struct ExternalSigner {
+    spec: SignerSpec,
+    transport: Transport,
+    key_id: Option<String>,
+    chain: Option<Vec<u8>>,
+}
+
+enum Transport {
+    Agent(PathBuf),
+    Exec(Vec<String>),
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    version: u32,
+    op: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Response {
+    #[serde(default)]
+    alg: Option<String>,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    chain_pem: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl ExternalSigner {
+    fn call(&self, op: &str, payload: Option<&[u8]>) -> Result<Response> {
+        let request = Request {
+            version: PROTOCOL_VERSION,
+            op,
+            key_id: self.key_id.as_deref(),
+            payload: payload.map(|bytes| STANDARD.encode(bytes)),
+        };
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+
+        let raw = match &self.transport {
+            Transport::Agent(socket) => agent_exchange(socket, &line)?,
+            Transport::Exec(argv) => exec_exchange(argv, &line)?,
+        };
+        let response: Response = serde_json::from_slice(&raw)
+            .with_context(|| format!("signer returned an invalid `{op}` response"))?;
+        if let Some(error) = response.error {
+            bail!("signer refused `{op}`: {error}");
+        }
+        Ok(response)
+    }
+}
+
+impl Signer for ExternalSigner {
+    fn sign(&self, message: &[u8]) -> Result<ExternalSignature> {
+        let response = self.call("sign", Some(message))?;
+        let signature = response
+            .signature
+            .ok_or_else(|| anyhow!("signer response is missing `signature`"))?;
+        let sig = STANDARD
+            .decode(signature.trim())
+            .context("signer returned a signature that is not valid base64")?;
+        Ok(ExternalSignature {
+            alg: response.alg.unwrap_or_else(|| "ed25519".to_string()),
+            sig,
+        })
+    }
+
+    fn chain_pem(&self) -> Result<Vec<u8>> {
+        if let Some(chain) = &self.chain {
+            return Ok(chain.clone());
+        }
+        let response = self.call("chain", None)?;
+        let chain = response
+            .chain_pem
+            .ok_or_else(|| anyhow!("signer `{}` returned no `chain_pem`", self.spec))?;
+        Ok(chain.into_bytes())
+    }
+}
+
+#[cfg(unix)]
+fn agent_exchange(socket: &Path, request: &[u8]) -> Result<Vec<u8>> {
+    use std::net::Shutdown;
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("failed to connect to signing agent {}", socket.display()))?;
+    stream.set_read_timeout(Some(SIGNER_TIMEOUT))?;
+    stream.set_write_timeout(Some(SIGNER_TIMEOUT))?;
+    stream
+        .write_all(request)
+        .context("failed to send request to signing agent")?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .context("failed to read signing agent response")?;
+    Ok(response)
+}
+
+#[cfg(not(unix))]
+fn agent_exchange(socket: &Path, _request: &[u8]) -> Result<Vec<u8>> {
+    bail!(
+        "signing agents over Unix sockets are not supported on this platform ({})",
+        socket.display()
+    )
+}
+
+fn exec_exchange(argv: &[String], request: &[u8]) -> Result<Vec<u8>> {
+    exec_exchange_within(argv, request, SIGNER_TIMEOUT)
+}
+
+fn exec_exchange_within(argv: &[String], request: &[u8], timeout: Duration) -> Result<Vec<u8>> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| anyhow!("exec signer needs a command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run signer command `{program}`"))?;
+
+    // Pipes are serviced on their own threads so a hook that stops reading or
+    // writing cannot stall the deadline check below.
+    let stdin = child.stdin.take().map(|mut stdin| {
+        let request = request.to_vec();
+        thread::spawn(move || match stdin.write_all(&request) {
+            // Hooks that answer without reading the request close stdin early.
+            Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            other => other,
+        })
+    });
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("failed to wait for signer command `{program}`"))?
+        {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "signer command `{program}` did not answer within {}s",
+                timeout.as_secs_f32()
+            );
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    if let Some(stdin) = stdin {
+        stdin
+            .join()
+            .map_err(|_| anyhow!("signer request writer panicked"))?
+            .context("failed to send request to signer command")?;
+    }
+    let stdout = collect(stdout)?;
+    let stderr = collect(stderr)?;
+    if !status.success() {
+        bail!(
+            "signer command `{program}` failed ({status}): {}",
+            String::from_utf8_lossy(&stderr).trim()
+        );
+    }
+    Ok(stdout)
+}
+
+fn drain<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<std::io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        pipe.read_to_end(&mut buf)?;
+        Ok(buf)
+    })
+}
+
+fn collect(reader: Option<thread::JoinHandle<std::io::Result<Vec<u8>>>>) -> Result<Vec<u8>> {
+    let Some(reader) = reader else {
+        return Ok(Vec::new());
+    };
+    reader
+        .join()
+        .map_err(|_| anyhow!("signer output reader panicked"))?
+        .context("failed to read signer command output")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::EncodePrivateKey;
+    use ed25519_dalek::{Signer as _, SigningKey};
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_signer_specs() {
+        assert_eq!(
+            "agent:///run/signer.sock".parse::<SignerSpec>().unwrap(),
+            SignerSpec::Agent(PathBuf::from("/run/signer.sock"))
+        );
+        assert_eq!(
+            "exec:pkcs11-sign --slot 0".parse::<SignerSpec>().unwrap(),
+            SignerSpec::Exec(vec!["pkcs11-sign".into(), "--slot".into(), "0".into()])
+        );
+        assert_eq!(
+            "file:keys/release.pem".parse::<SignerSpec>().unwrap(),
+            SignerSpec::KeyFile(PathBuf::from("keys/release.pem"))
+        );
+        assert!("agent://".parse::<SignerSpec>().is_err());
+        assert_eq!(
+            r#"exec:"/opt/HSM Tools/sign" --label 'release key'"#
+                .parse::<SignerSpec>()
+                .unwrap(),
+            SignerSpec::Exec(vec![
+                "/opt/HSM Tools/sign".into(),
+                "--label".into(),
+                "release key".into()
+            ])
+        );
+        assert!("exec:".parse::<SignerSpec>().is_err());
+        assert!("exec:sign --label 'release".parse::<SignerSpec>().is_err());
+        assert!("vault:release".parse::<SignerSpec>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_hook_response_is_decoded() {
+        let temp = tempdir().unwrap();
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let signature = key.sign(b"payload").to_bytes();
+        let response = temp.path().join("response.json");
+        fs::write(
+            &response,
+            serde_json::json!({ "alg": "ed25519", "signature": STANDARD.encode(signature) })
+                .to_string(),
+        )
+        .unwrap();
+
+        let spec: SignerSpec = format!("exec:cat {}", response.display()).parse().unwrap();
+        let signer = open_signer(&spec, Some("release"), None).unwrap();
+        let signed = signer.sign(b"payload").unwrap();
+        assert_eq!(signed.alg, "ed25519");
+        assert_eq!(signed.sig, signature.to_vec());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_hook_errors_are_reported() {
+        let temp = tempdir().unwrap();
+        let response = temp.path().join("response.json");
+        fs::write(&response, r#"{"error":"token not present"}"#).unwrap();
+
+        let spec: SignerSpec = format!("exec:cat {}", response.display()).parse().unwrap();
+        let signer = open_signer(&spec, None, None).unwrap();
+        let err = signer.sign(b"payload").unwrap_err();
+        assert!(err.to_string().contains("token not present"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_hook_without_chain_is_an_error() {
+        let temp = tempdir().unwrap();
+        let response = temp.path().join("response.json");
+        fs::write(&response, r#"{"alg":"ed25519"}"#).unwrap();
+
+        let spec: SignerSpec = format!("exec:cat {}", response.display()).parse().unwrap();
+        let signer = open_signer(&spec, None, None).unwrap();
+        let err = signer.chain_pem().unwrap_err();
+        assert!(err.to_string().contains(&spec.to_string()), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_hook_that_hangs_times_out() {
+        let argv = vec!["sleep".to_string(), "5".to_string()];
+        let started = Instant::now();
+        let err = exec_exchange_within(&argv, b"{}\n", Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("did not answer"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn key_file_signer_uses_chain_override() {
+        let temp = tempdir().unwrap();
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let pem = key.to_pkcs8_pem(pkcs8::LineEnding::LF).unwrap();
+        let path = temp.path().join("release.pem");
+        fs::write(&path, pem.as_bytes()).unwrap();
+
+        let signer =
+            open_signer(&SignerSpec::KeyFile(path), None, Some(b"chain".to_vec())).unwrap();
+        assert_eq!(signer.chain_pem().unwrap(), b"chain".to_vec());
+        assert_eq!(
+            signer.sign(b"payload").unwrap().sig,
+            key.sign(b"payload").to_bytes().to_vec()
+        );
+    }
+}
@@ -1,69 +1,155 @@
 #![forbid(unsafe_code)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow};
 use clap::ValueEnum;
 use greentic_distributor_client::{DistClient, DistOptions};
-use greentic_pack::{PackLoad, SigningPolicy, open_pack};
+use greentic_pack::pack_lock::{PackLockV1, decode_pack_lock};
+use greentic_pack::reader::PackReader;
+use greentic_pack::{PackLoad, ReaderLimits, SigningPolicy};
 use greentic_types::pack_manifest::{ExtensionInline, PackManifest};
 use greentic_types::provider::PROVIDER_EXTENSION_ID;
 use greentic_types::validate::{Diagnostic, Severity};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use wasmtime::component::{Component, Linker};
-use wasmtime::{Config, Engine, Store};
+use wasmtime::component::{Component, HasSelf, Linker};
+use wasmtime::{Config, Engine, ResourceLimiter, Store, Trap};
 use wasmtime_wasi::p2::add_to_linker_sync;
 use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 
+use crate::lazy_entries::LazyEntries;
 use crate::runtime::{NetworkPolicy, RuntimeContext};
 
-const PACK_VALIDATOR_WORLDS: [&str; 2] = [
+const PACK_VALIDATOR_WORLDS: [&str; 4] = [
     "greentic:pack-validate@0.1.0/pack-validator",
     "greentic:pack-validate/pack-validator@0.1.0",
+    "greentic:pack-validate@0.2.0/pack-validator",
+    "greentic:pack-validate/pack-validator@0.2.0",
 ];
+const VALIDATOR_EXPORT_V0_1: &str = "greentic:pack-validate/validator@0.1.0";
+const VALIDATOR_EXPORT_V0_2: &str = "greentic:pack-validate/validator@0.2.0";
+/// Largest single file a validator may read through `host.read-file`.
+const MAX_READ_FILE_BYTES: u64 = 8 * 1024 * 1024;
+/// Total bytes a validator may read through `host.read-file` in one run.
+const MAX_READ_TOTAL_BYTES: u64 = 64 * 1024 * 1024;
 pub const DEFAULT_VALIDATOR_ALLOW: &str = "oci://ghcr.io/greentic-ai/validators/";
-const DEFAULT_TIMEOUT_SECS: u64 = 2;
+const DEFAULT_TIMEOUT_MS: u64 = 2_000;
 const DEFAULT_MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+const DEFAULT_FUEL: u64 = 2_000_000_000;
+const DEFAULT_MAX_TABLE_ELEMENTS: usize = 10_000;
+const EPOCH_TICK: Duration = Duration::from_millis(10);
 
 mod bindings {
-    wasmtime::component::bindgen!({
-        inline: r#"
-        package greentic:pack-validate@0.1.0;
-
-        interface validator {
-          record diagnostic {
-            severity: string,
-            code: string,
-            message: string,
-            path: option<string>,
-            hint: option<string>,
-          }
-
-          record pack-inputs {
-            manifest-cbor: list<u8>,
-            sbom-json: string,
-            file-index: list<string>,
-          }
-
-          applies: func(inputs: pack-inputs) -> bool;
-          validate: func(inputs: pack-inputs) -> list<diagnostic>;
-        }
+    pub mod v0_1 {
+        wasmtime::component::bindgen!({
+            inline: r#"
+            package greentic:pack-validate@0.1.0;
+
+            interface validator {
+              record diagnostic {
+                severity: string,
+                code: string,
+                message: string,
+                path: option<string>,
+                hint: option<string>,
+              }
+
+              record pack-inputs {
+                manifest-cbor: list<u8>,
+                sbom-json: string,
+                file-index: list<string>,
+              }
+
+              applies: func(inputs: pack-inputs) -> bool;
+              validate: func(inputs: pack-inputs) -> list<diagnostic>;
+            }
 
-        world pack-validator {
-          export validator;
-        }
-        "#,
-    });
+            world pack-validator {
+              export validator;
+            }
+            "#,
+        });
+    }
+
+    pub mod v0_2 {
+        wasmtime::component::bindgen!({
+            inline: r#"
+            package greentic:pack-validate@0.2.0;
+
+            /// Read-only access to the pack under validation.
+            interface host {
+              variant read-error {
+                /// No file with this path exists in the pack.
+                not-found,
+                /// The file is larger than the per-file read limit (its size is returned).
+                too-large(u64),
+                /// The validator has used up its total read budget.
+                budget-exhausted,
+              }
+
+              /// Read a file by its pack-relative path (as listed in `file-index`).
+              read-file: func(path: string) -> result<list<u8>, read-error>;
+            }
+
+            interface validator {
+              record diagnostic {
+                severity: string,
+                code: string,
+                message: string,
+                path: option<string>,
+                hint: option<string>,
+                /// Structured payload encoded as a JSON document.
+                data: option<string>,
+              }
+
+              record locked-component {
+                component-id: string,
+                reference: option<string>,
+                digest: string,
+                abi-version: string,
+                %world: option<string>,
+                version: option<string>,
+                role: option<string>,
+              }
+
+              record locked-dependency {
+                pack-id: string,
+                version: string,
+                digest: string,
+                reference: option<string>,
+              }
+
+              record pack-inputs {
+                manifest-cbor: list<u8>,
+                sbom-json: string,
+                file-index: list<string>,
+                lock-components: list<locked-component>,
+                lock-dependencies: list<locked-dependency>,
+              }
+
+              applies: func(inputs: pack-inputs) -> bool;
+              validate: func(inputs: pack-inputs) -> list<diagnostic>;
+            }
+
+            world pack-validator {
+              import host;
+              export validator;
+            }
+            "#,
+        });
+    }
 }
 
-use bindings::PackValidator;
-use bindings::exports::greentic::pack_validate::validator::{
-    Diagnostic as WasmDiagnostic, PackInputs,
-};
+use bindings::v0_1::exports::greentic::pack_validate::validator as validator_v0_1;
+use bindings::v0_2::exports::greentic::pack_validate::validator as validator_v0_2;
+use bindings::v0_2::greentic::pack_validate::host::{self as host_v0_2, ReadError};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 pub enum ValidatorPolicy {
@@ -85,6 +171,74 @@ pub struct ValidatorConfig {
     pub validator_cache_dir: PathBuf,
     pub policy: ValidatorPolicy,
     pub local_validators: Vec<LocalValidator>,
+    pub limits: ValidatorLimitsConfig,
+}
+
+/// Resources a single validator component may use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ValidatorLimits {
+    pub fuel: u64,
+    pub max_memory_bytes: usize,
+    pub timeout_ms: u64,
+    pub max_table_elements: usize,
+}
+
+impl Default for ValidatorLimits {
+    fn default() -> Self {
+        Self {
+            fuel: DEFAULT_FUEL,
+            max_memory_bytes: DEFAULT_MAX_MEMORY_BYTES,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            max_table_elements: DEFAULT_MAX_TABLE_ELEMENTS,
+        }
+    }
+}
+
+/// A partial set of limits; unset fields fall through to the next layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidatorLimitOverrides {
+    pub fuel: Option<u64>,
+    pub max_memory_bytes: Option<usize>,
+    pub timeout_ms: Option<u64>,
+    pub max_table_elements: Option<usize>,
+}
+
+impl ValidatorLimitOverrides {
+    fn apply(&self, limits: &mut ValidatorLimits) {
+        if let Some(fuel) = self.fuel {
+            limits.fuel = fuel;
+        }
+        if let Some(bytes) = self.max_memory_bytes {
+            limits.max_memory_bytes = bytes;
+        }
+        if let Some(timeout_ms) = self.timeout_ms {
+            limits.timeout_ms = timeout_ms;
+        }
+        if let Some(elements) = self.max_table_elements {
+            limits.max_table_elements = elements;
+        }
+    }
+}
+
+/// Validator limits from the `doctor` flags.
+///
+/// Precedence (highest first): `--validator-limit <ID>=...` for the matching
+/// component id, the global `--validator-*` flags, built-in defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorLimitsConfig {
+    pub cli: ValidatorLimitOverrides,
+    pub per_validator: BTreeMap<String, ValidatorLimitOverrides>,
+}
+
+impl ValidatorLimitsConfig {
+    pub fn limits_for(&self, component_id: &str) -> ValidatorLimits {
+        let mut limits = ValidatorLimits::default();
+        self.cli.apply(&mut limits);
+        if let Some(overrides) = self.per_validator.get(component_id) {
+            overrides.apply(&mut limits);
+        }
+        limits
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -100,6 +254,31 @@ pub struct ValidatorSourceReport {
     pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Resource use of each validator component loaded from this source.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<ValidatorUsage>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidatorUsage {
+    pub component_id: String,
+    /// `ok`, `not-applicable`, `timeout`, `out-of-fuel`, `memory-limit`,
+    /// `table-limit` or `failed`.
+    pub outcome: String,
+    pub limits: ValidatorLimits,
+    pub fuel_consumed: u64,
+    pub peak_memory_bytes: usize,
+    pub peak_table_elements: usize,
+    pub elapsed_ms: u64,
+}
+
+impl ValidatorUsage {
+    fn exceeded_limit(&self) -> bool {
+        matches!(
+            self.outcome.as_str(),
+            "timeout" | "out-of-fuel" | "memory-limit" | "table-limit"
+        )
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -115,6 +294,12 @@ struct ValidatorComponent {
     wasm: Vec<u8>,
 }
 
+/// A component queued to run, with the index of the source it came from.
+struct QueuedValidator {
+    component: ValidatorComponent,
+    source: usize,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ValidatorRunResult {
     pub diagnostics: Vec<Diagnostic>,
@@ -122,12 +307,15 @@ pub struct ValidatorRunResult {
     pub missing_required: bool,
 }
 
+/// Run the configured validators over `load`; entries left in `archive` are
+/// listed in the file index and read from the archive on request.
 pub async fn run_wasm_validators(
     load: &PackLoad,
+    archive: Option<&LazyEntries>,
     config: &ValidatorConfig,
     runtime: &RuntimeContext,
 ) -> Result<ValidatorRunResult> {
-    let inputs = build_pack_inputs(load)?;
+    let inputs = build_pack_inputs(load, archive)?;
 
     let mut result = ValidatorRunResult::default();
     let mut components = Vec::new();
@@ -136,15 +324,19 @@ pub async fn run_wasm_validators(
         let reference = format!("local:{}", local.path.display());
         match fs::read(&local.path) {
             Ok(bytes) => {
-                components.push(ValidatorComponent {
-                    component_id: local.component_id.clone(),
-                    wasm: bytes,
+                components.push(QueuedValidator {
+                    component: ValidatorComponent {
+                        component_id: local.component_id.clone(),
+                        wasm: bytes,
+                    },
+                    source: result.sources.len(),
                 });
                 result.sources.push(ValidatorSourceReport {
                     reference: reference.clone(),
                     origin: "local".to_string(),
                     status: "loaded".to_string(),
                     message: None,
+                    validators: Vec::new(),
                 });
             }
             Err(err) => {
@@ -177,6 +369,7 @@ pub async fn run_wasm_validators(
                     origin: "local".to_string(),
                     status: "failed".to_string(),
                     message: Some(err.to_string()),
+                    validators: Vec::new(),
                 });
             }
         }
@@ -189,13 +382,19 @@ pub async fn run_wasm_validators(
 
     for validator_ref in refs {
         match load_validator_components(&validator_ref, config, runtime).await {
-            Ok(mut loaded) => {
-                components.append(&mut loaded);
+            Ok(loaded) => {
+                let source = result.sources.len();
+                components.extend(
+                    loaded
+                        .into_iter()
+                        .map(|component| QueuedValidator { component, source }),
+                );
                 result.sources.push(ValidatorSourceReport {
                     reference: validator_ref.reference.clone(),
                     origin: validator_ref.origin.clone(),
                     status: "loaded".to_string(),
                     message: None,
+                    validators: Vec::new(),
                 });
             }
             Err(err) => {
@@ -219,6 +418,7 @@ pub async fn run_wasm_validators(
                     origin: validator_ref.origin.clone(),
                     status: "failed".to_string(),
                     message: Some(err.to_string()),
+                    validators: Vec::new(),
                 });
                 if !is_required {
                     result.diagnostics.push(Diagnostic {
@@ -242,13 +442,32 @@ pub async fn run_wasm_validators(
     }
 
     let engine = build_engine()?;
-    let mut linker = Linker::new(&engine);
-    add_to_linker_sync(&mut linker)?;
-
-    for component in components {
-        let validator_result = run_component_validator(&engine, &mut linker, &component, &inputs);
+    let mut linker = build_linker(&engine)?;
+    let _ticker = EpochTicker::start(&engine)?;
+
+    for queued in components {
+        let component = &queued.component;
+        let limits = config.limits.limits_for(&component.component_id);
+        let (usage, validator_result) =
+            run_component_validator(&engine, &mut linker, component, &inputs, limits);
         match validator_result {
             Ok(mut diags) => result.diagnostics.append(&mut diags),
+            Err(err) if usage.exceeded_limit() => {
+                result.diagnostics.push(Diagnostic {
+                    severity: Severity::Warn,
+                    code: "PACK_VALIDATOR_LIMIT_EXCEEDED".to_string(),
+                    message: format!(
+                        "Validator component {} stopped: {}.",
+                        component.component_id, usage.outcome
+                    ),
+                    path: None,
+                    hint: Some(format!(
+                        "{err}; raise the limit with the doctor --validator-* flags or --validator-limit {}=...",
+                        component.component_id
+                    )),
+                    data: serde_json::to_value(&usage).unwrap_or(Value::Null),
+                });
+            }
             Err(err) => {
                 result.diagnostics.push(Diagnostic {
                     severity: Severity::Warn,
@@ -263,6 +482,9 @@ pub async fn run_wasm_validators(
                 });
             }
         }
+        if let Some(source) = result.sources.get_mut(queued.source) {
+            source.validators.push(usage);
+        }
     }
 
     Ok(result)
@@ -272,66 +494,283 @@ fn build_engine() -> Result<Engine> {
     let mut config = Config::new();
     config.wasm_component_model(true);
     config.epoch_interruption(true);
+    config.consume_fuel(true);
     Ok(Engine::new(&config)?)
 }
 
+fn build_linker(engine: &Engine) -> Result<Linker<ValidatorCtx>> {
+    let mut linker = Linker::new(engine);
+    add_to_linker_sync(&mut linker)?;
+    bindings::v0_2::PackValidator::add_to_linker::<ValidatorCtx, HasSelf<ValidatorCtx>>(
+        &mut linker,
+        |ctx| ctx,
+    )?;
+    Ok(linker)
+}
+
+/// Number of epoch ticks that cover `timeout_ms`, never less than one.
+fn epoch_deadline_ticks(timeout_ms: u64) -> u64 {
+    let tick_ms = EPOCH_TICK.as_millis() as u64;
+    timeout_ms.div_ceil(tick_ms).max(1)
+}
+
+/// Advances the engine epoch on a fixed tick for as long as it is alive.
+///
+/// One ticker serves every validator in a run; each store converts its
+/// timeout into a deadline measured in ticks.
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    fn start(engine: &Engine) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let engine = engine.clone();
+        let flag = Arc::clone(&stop);
+        let handle = std::thread::Builder::new()
+            .name("validator-epoch".to_string())
+            .spawn(move || {
+                while !flag.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            })
+            .context("failed to start validator epoch ticker")?;
+        Ok(Self {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
 fn run_component_validator(
     engine: &Engine,
     linker: &mut Linker<ValidatorCtx>,
     component: &ValidatorComponent,
-    inputs: &PackInputs,
-) -> Result<Vec<Diagnostic>> {
+    inputs: &ValidatorInputs,
+    limits: ValidatorLimits,
+) -> (ValidatorUsage, Result<Vec<Diagnostic>>) {
+    let ctx =
+        ValidatorCtx::new(limits, Arc::clone(&inputs.files)).with_archive(inputs.archive.clone());
+    let mut store = Store::new(engine, ctx);
+    store.limiter(|ctx| &mut ctx.limiter);
+
+    let started = Instant::now();
+    // Fuel is in place before anything can fail, so the fuel accounting below
+    // is meaningful for load errors too.
+    let outcome = match store.set_fuel(limits.fuel) {
+        Ok(()) => invoke_validator(&mut store, engine, linker, component, inputs, limits),
+        Err(err) => Err(anyhow!("failed to set validator fuel: {err}")),
+    };
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let fuel_left = store.get_fuel().unwrap_or(limits.fuel);
+    let limiter = &store.data().limiter;
+
+    let status = match &outcome {
+        Ok(Some(_)) => "ok",
+        Ok(None) => "not-applicable",
+        Err(_) if limiter.memory_exceeded => "memory-limit",
+        Err(_) if limiter.table_exceeded => "table-limit",
+        Err(err) => match err.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => "out-of-fuel",
+            Some(Trap::Interrupt) => "timeout",
+            _ => "failed",
+        },
+    };
+    let usage = ValidatorUsage {
+        component_id: component.component_id.clone(),
+        outcome: status.to_string(),
+        limits,
+        fuel_consumed: limits.fuel.saturating_sub(fuel_left),
+        peak_memory_bytes: limiter.peak_memory_bytes,
+        peak_table_elements: limiter.peak_table_elements,
+        elapsed_ms,
+    };
+    (usage, outcome.map(Option::unwrap_or_default))
+}
+
+/// Maps a wasmtime error to `anyhow`, keeping any [`Trap`] so the outcome
+/// can be classified from it.
+fn guest_error(context: &'static str) -> impl FnOnce(wasmtime::Error) -> anyhow::Error {
+    move |err| {
+        let message = format!("{context}: {err}");
+        match err.downcast_ref::<Trap>() {
+            Some(trap) => anyhow::Error::new(*trap).context(message),
+            None => anyhow!(message),
+        }
+    }
+}
+
+/// Runs `applies` and, when it returns true, `validate`. `None` means the
+/// validator does not apply to this pack.
+fn invoke_validator(
+    store: &mut Store<ValidatorCtx>,
+    engine: &Engine,
+    linker: &mut Linker<ValidatorCtx>,
+    component: &ValidatorComponent,
+    inputs: &ValidatorInputs,
+    limits: ValidatorLimits,
+) -> Result<Option<Vec<Diagnostic>>> {
     let component = Component::from_binary(engine, &component.wasm)
         .map_err(|err| anyhow!("failed to load validator component: {err}"))?;
+    let abi = validator_abi(engine, &component)?;
+    // Compilation is not guest time; the deadline starts with instantiation.
+    store.set_epoch_deadline(epoch_deadline_ticks(limits.timeout_ms));
+
+    // The ABI versions share one call sequence; only the generated bindings,
+    // the input record and the diagnostic mapping differ.
+    macro_rules! applies_then_validate {
+        ($abi:ident, $to_inputs:ident, $convert:ident) => {{
+            let validator =
+                bindings::$abi::PackValidator::instantiate(&mut *store, &component, linker)
+                    .map_err(guest_error("failed to instantiate validator component"))?;
+            let guest = validator.greentic_pack_validate_validator();
+            let inputs = inputs.$to_inputs();
+
+            let applies = guest
+                .call_applies(&mut *store, &inputs)
+                .map_err(guest_error("validator applies call failed"))?;
+            if !applies {
+                return Ok(None);
+            }
+            let diags = guest
+                .call_validate(&mut *store, &inputs)
+                .map_err(guest_error("validator validate call failed"))?;
+            Ok(Some(diags.into_iter().map($convert).collect()))
+        }};
+    }
 
-    let mut store = Store::new(engine, ValidatorCtx::new());
-    store.limiter(|ctx| &mut ctx.limits);
-    store.set_epoch_deadline(1);
+    match abi {
+        ValidatorAbi::V0_1 => applies_then_validate!(v0_1, to_v0_1, convert_diagnostic_v0_1),
+        ValidatorAbi::V0_2 => applies_then_validate!(v0_2, to_v0_2, convert_diagnostic_v0_2),
+    }
+}
 
-    let validator = PackValidator::instantiate(&mut store, &component, linker)
-        .map_err(|err| anyhow!("failed to instantiate validator component: {err}"))?;
-    let guest = validator.greentic_pack_validate_validator();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ValidatorAbi {
+    V0_1,
+    V0_2,
+}
 
-    let engine = engine.clone();
-    let timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
-    std::thread::spawn(move || {
-        std::thread::sleep(timeout);
-        engine.increment_epoch();
-    });
+/// Pick the `greentic:pack-validate` version from the component's exports, so
+/// raw `.wasm` validators work without a manifest declaring their world.
+fn validator_abi(engine: &Engine, component: &Component) -> Result<ValidatorAbi> {
+    let ty = component.component_type();
+    let exports: Vec<&str> = ty.exports(engine).map(|(name, _)| name).collect();
+    if exports.contains(&VALIDATOR_EXPORT_V0_2) {
+        Ok(ValidatorAbi::V0_2)
+    } else if exports.contains(&VALIDATOR_EXPORT_V0_1) {
+        Ok(ValidatorAbi::V0_1)
+    } else {
+        Err(anyhow!(
+            "component exports neither {VALIDATOR_EXPORT_V0_2} nor {VALIDATOR_EXPORT_V0_1}"
+        ))
+    }
+}
 
-    let applies = guest
-        .call_applies(&mut store, inputs)
-        .map_err(|err| anyhow!("validator applies call failed: {err}"))?;
-    if !applies {
-        return Ok(Vec::new());
+fn parse_severity(severity: &str) -> Severity {
+    match severity {
+        "info" => Severity::Info,
+        "warn" => Severity::Warn,
+        "error" => Severity::Error,
+        _ => Severity::Warn,
     }
+}
 
-    let diags = guest
-        .call_validate(&mut store, inputs)
-        .map_err(|err| anyhow!("validator validate call failed: {err}"))?;
-    Ok(convert_diagnostics(diags))
+fn convert_diagnostic_v0_1(diag: validator_v0_1::Diagnostic) -> Diagnostic {
+    Diagnostic {
+        severity: parse_severity(&diag.severity),
+        code: diag.code,
+        message: diag.message,
+        path: diag.path,
+        hint: diag.hint,
+        data: Value::Null,
+    }
 }
 
-fn convert_diagnostics(diags: Vec<WasmDiagnostic>) -> Vec<Diagnostic> {
-    diags
-        .into_iter()
-        .map(|diag| Diagnostic {
-            severity: match diag.severity.as_str() {
-                "info" => Severity::Info,
-                "warn" => Severity::Warn,
-                "error" => Severity::Error,
-                _ => Severity::Warn,
-            },
-            code: diag.code,
-            message: diag.message,
-            path: diag.path,
-            hint: diag.hint,
-            data: Value::Null,
-        })
-        .collect()
+/// `data` that is not valid JSON is kept as a string rather than dropped.
+fn convert_diagnostic_v0_2(diag: validator_v0_2::Diagnostic) -> Diagnostic {
+    let data = match diag.data {
+        Some(raw) => serde_json::from_str(&raw).unwrap_or(Value::String(raw)),
+        None => Value::Null,
+    };
+    Diagnostic {
+        severity: parse_severity(&diag.severity),
+        code: diag.code,
+        message: diag.message,
+        path: diag.path,
+        hint: diag.hint,
+        data,
+    }
 }
 
-fn build_pack_inputs(load: &PackLoad) -> Result<PackInputs> {
+/// Pack contents handed to every validator; converted per ABI version.
+struct ValidatorInputs {
+    manifest_cbor: Vec<u8>,
+    sbom_json: String,
+    file_index: Vec<String>,
+    lock: Option<PackLockV1>,
+    files: Arc<HashMap<String, Vec<u8>>>,
+    archive: Option<LazyEntries>,
+}
+
+impl ValidatorInputs {
+    fn to_v0_1(&self) -> validator_v0_1::PackInputs {
+        validator_v0_1::PackInputs {
+            manifest_cbor: self.manifest_cbor.clone(),
+            sbom_json: self.sbom_json.clone(),
+            file_index: self.file_index.clone(),
+        }
+    }
+
+    fn to_v0_2(&self) -> validator_v0_2::PackInputs {
+        let (lock_components, lock_dependencies) = match self.lock.as_ref() {
+            Some(lock) => (
+                lock.components
+                    .values()
+                    .map(|component| validator_v0_2::LockedComponent {
+                        component_id: component.component_id.clone(),
+                        reference: component.r#ref.clone(),
+                        digest: component.resolved_digest.clone(),
+                        abi_version: component.abi_version.clone(),
+                        world: component.world.clone(),
+                        version: component.component_version.clone(),
+                        role: component.role.clone(),
+                    })
+                    .collect(),
+                lock.dependencies
+                    .values()
+                    .map(|dependency| validator_v0_2::LockedDependency {
+                        pack_id: dependency.pack_id.clone(),
+                        version: dependency.version.clone(),
+                        digest: dependency.digest.clone(),
+                        reference: dependency.r#ref.clone(),
+                    })
+                    .collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        validator_v0_2::PackInputs {
+            manifest_cbor: self.manifest_cbor.clone(),
+            sbom_json: self.sbom_json.clone(),
+            file_index: self.file_index.clone(),
+            lock_components,
+            lock_dependencies,
+        }
+    }
+}
+
+fn build_pack_inputs(load: &PackLoad, archive: Option<&LazyEntries>) -> Result<ValidatorInputs> {
     let manifest_bytes = load.files.get("manifest.cbor").cloned().unwrap_or_default();
 
     let sbom_json = if let Some(bytes) = load.files.get("sbom.json") {
@@ -344,12 +783,27 @@ fn build_pack_inputs(load: &PackLoad) -> Result<PackInputs> {
             .context("failed to serialize sbom json")?
     };
 
-    let file_index = load.files.keys().cloned().collect();
+    let mut file_index: Vec<String> = load.files.keys().cloned().collect();
+    if let Some(archive) = archive {
+        file_index.extend(archive.names().map(str::to_string));
+    }
+    file_index.sort();
+
+    // An unreadable lock is reported by the pack-lock doctor; validators just
+    // see no entries.
+    let lock = load
+        .files
+        .get("pack.lock.cbor")
+        .and_then(|bytes| decode_pack_lock(bytes).ok());
 
-    Ok(PackInputs {
+    Ok(ValidatorInputs {
         manifest_cbor: manifest_bytes,
         sbom_json,
         file_index,
+        lock,
+        // Copied once per run; every validator store shares this map.
+        files: Arc::new(load.files.clone()),
+        archive: archive.cloned(),
     })
 }
 
@@ -498,10 +952,10 @@ async fn load_validator_components(
     let path = Path::new(reference);
     if path.exists() {
         if path.is_dir() {
-            return load_validator_components_from_dir(path);
+            return load_validator_components_from_dir(path, &runtime.reader_limits);
         }
         if path.extension().and_then(|ext| ext.to_str()) == Some("gtpack") {
-            return load_validator_components_from_pack(path);
+            return load_validator_components_from_pack(path, &runtime.reader_limits);
         }
         if path.extension().and_then(|ext| ext.to_str()) == Some("wasm") {
             let wasm = std::fs::read(path).with_context(|| {
@@ -524,7 +978,10 @@ async fn load_validator_components(
     ))
 }
 
-fn load_validator_components_from_dir(path: &Path) -> Result<Vec<ValidatorComponent>> {
+fn load_validator_components_from_dir(
+    path: &Path,
+    limits: &ReaderLimits,
+) -> Result<Vec<ValidatorComponent>> {
     let mut components = Vec::new();
     for entry in std::fs::read_dir(path)
         .with_context(|| format!("failed to read validators root {}", path.display()))?
@@ -532,19 +989,22 @@ fn load_validator_components_from_dir(path: &Path) -> Result<Vec<ValidatorCompon
         let entry = entry?;
         let path = entry.path();
         if path.extension().and_then(|ext| ext.to_str()) == Some("gtpack") {
-            components.extend(load_validator_components_from_pack(&path)?);
+            components.extend(load_validator_components_from_pack(&path, limits)?);
         }
     }
     Ok(components)
 }
 
-fn load_validator_components_from_pack(path: &Path) -> Result<Vec<ValidatorComponent>> {
-    let load = open_pack(path, SigningPolicy::DevOk)
+fn load_validator_components_from_pack(
+    path: &Path,
+    limits: &ReaderLimits,
+) -> Result<Vec<ValidatorComponent>> {
+    let mut reader = PackReader::open(path, SigningPolicy::DevOk, limits)
         .map_err(|err| anyhow!(err.message))
         .with_context(|| format!("failed to open validator pack {}", path.display()))?;
     let mut components = Vec::new();
 
-    if let Some(manifest) = load.gpack_manifest.as_ref() {
+    if let Some(manifest) = reader.gpack_manifest.clone() {
         for component in &manifest.components {
             if !PACK_VALIDATOR_WORLDS
                 .iter()
@@ -560,9 +1020,9 @@ fn load_validator_components_from_pack(path: &Path) -> Result<Vec<ValidatorCompo
                 ),
                 format!("components/{}.wasm", component.id.as_str()),
             ];
-            let wasm = wasm_paths
+            let wasm_path = wasm_paths
                 .iter()
-                .find_map(|path| load.files.get(path).cloned())
+                .find(|path| reader.contains(path))
                 .ok_or_else(|| {
                     anyhow!(
                         "validator pack missing {} for component {}",
@@ -572,27 +1032,27 @@ fn load_validator_components_from_pack(path: &Path) -> Result<Vec<ValidatorCompo
                 })?;
             components.push(ValidatorComponent {
                 component_id: component.id.as_str().to_string(),
-                wasm,
+                wasm: reader.read_entry(wasm_path)?,
             });
         }
     } else {
-        for component in &load.manifest.components {
+        for component in &reader.manifest.components.clone() {
             let Some(world) = component.world.as_deref() else {
                 continue;
             };
             if !PACK_VALIDATOR_WORLDS.iter().any(|item| item == &world) {
                 continue;
             }
-            let Some(wasm) = load.files.get(&component.file_wasm).cloned() else {
+            if !reader.contains(&component.file_wasm) {
                 return Err(anyhow!(
                     "validator pack missing {} for component {}",
                     component.file_wasm,
                     component.name
                 ));
-            };
+            }
             components.push(ValidatorComponent {
                 component_id: component.name.clone(),
-                wasm,
+                wasm: reader.read_entry(&component.file_wasm)?,
             });
         }
     }
@@ -665,7 +1125,7 @@ async fn load_validator_components_from_oci(
     if is_zip_archive(&bytes) {
         let temp = tempfile::NamedTempFile::new()?;
         std::fs::write(temp.path(), &bytes)?;
-        return load_validator_components_from_pack(temp.path());
+        return load_validator_components_from_pack(temp.path(), &runtime.reader_limits);
     }
 
     Ok(vec![ValidatorComponent {
@@ -681,14 +1141,15 @@ fn is_zip_archive(bytes: &[u8]) -> bool {
 struct ValidatorCtx {
     table: ResourceTable,
     wasi: WasiCtx,
-    limits: wasmtime::StoreLimits,
+    limiter: ValidatorLimiter,
+    files: Arc<HashMap<String, Vec<u8>>>,
+    /// Entries not in `files`, read from the archive on request.
+    archive: Option<LazyEntries>,
+    read_budget: u64,
 }
 
 impl ValidatorCtx {
-    fn new() -> Self {
-        let limits = wasmtime::StoreLimitsBuilder::new()
-            .memory_size(DEFAULT_MAX_MEMORY_BYTES)
-            .build();
+    fn new(limits: ValidatorLimits, files: Arc<HashMap<String, Vec<u8>>>) -> Self {
         let wasi = WasiCtxBuilder::new()
             .inherit_stdout()
             .inherit_stderr()
@@ -696,8 +1157,102 @@ impl ValidatorCtx {
         Self {
             table: ResourceTable::new(),
             wasi,
-            limits,
+            limiter: ValidatorLimiter::new(limits),
+            files,
+            archive: None,
+            read_budget: MAX_READ_TOTAL_BYTES,
+        }
+    }
+
+    fn with_archive(mut self, archive: Option<LazyEntries>) -> Self {
+        self.archive = archive;
+        self
+    }
+}
+
+impl host_v0_2::Host for ValidatorCtx {
+    fn read_file(&mut self, path: String) -> std::result::Result<Vec<u8>, ReadError> {
+        let buffered = self.files.get(&path);
+        let size = match (buffered, self.archive.as_ref()) {
+            (Some(bytes), _) => bytes.len() as u64,
+            (None, Some(archive)) => archive.size(&path).ok_or(ReadError::NotFound)?,
+            (None, None) => return Err(ReadError::NotFound),
+        };
+        if size > MAX_READ_FILE_BYTES {
+            return Err(ReadError::TooLarge(size));
+        }
+        if size > self.read_budget {
+            return Err(ReadError::BudgetExhausted);
+        }
+        let bytes = match buffered {
+            Some(bytes) => bytes.clone(),
+            None => {
+                let archive = self.archive.as_ref().ok_or(ReadError::NotFound)?;
+                match archive.read(&path) {
+                    Ok(Some(bytes)) => bytes,
+                    Ok(None) => return Err(ReadError::NotFound),
+                    Err(err) => {
+                        tracing::warn!("validator read of {path} failed: {err:#}");
+                        return Err(ReadError::NotFound);
+                    }
+                }
+            }
+        };
+        self.read_budget -= size;
+        Ok(bytes)
+    }
+}
+
+/// Enforces memory and table limits and records the peak sizes requested.
+struct ValidatorLimiter {
+    max_memory_bytes: usize,
+    max_table_elements: usize,
+    peak_memory_bytes: usize,
+    peak_table_elements: usize,
+    memory_exceeded: bool,
+    table_exceeded: bool,
+}
+
+impl ValidatorLimiter {
+    fn new(limits: ValidatorLimits) -> Self {
+        Self {
+            max_memory_bytes: limits.max_memory_bytes,
+            max_table_elements: limits.max_table_elements,
+            peak_memory_bytes: 0,
+            peak_table_elements: 0,
+            memory_exceeded: false,
+            table_exceeded: false,
+        }
+    }
+}
+
+impl ResourceLimiter for ValidatorLimiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        if desired > self.max_memory_bytes {
+            self.memory_exceeded = true;
+            return Ok(false);
         }
+        self.peak_memory_bytes = self.peak_memory_bytes.max(desired);
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        if desired > self.max_table_elements {
+            self.table_exceeded = true;
+            return Ok(false);
+        }
+        self.peak_table_elements = self.peak_table_elements.max(desired);
+        Ok(true)
     }
 }
 
@@ -733,6 +1288,165 @@ mod tests {
         files: Vec<SbomEntry>,
     }
 
+    #[test]
+    fn limits_layer_defaults_cli_and_per_validator() {
+        let mut config = ValidatorLimitsConfig::default();
+        assert_eq!(config.limits_for("any"), ValidatorLimits::default());
+
+        config.cli.fuel = Some(42);
+        config.cli.timeout_ms = Some(900);
+        config.per_validator.insert(
+            "strict".to_string(),
+            ValidatorLimitOverrides {
+                fuel: Some(7),
+                max_memory_bytes: Some(1024),
+                ..ValidatorLimitOverrides::default()
+            },
+        );
+
+        let limits = config.limits_for("other");
+        assert_eq!(limits.fuel, 42);
+        assert_eq!(limits.timeout_ms, 900);
+        assert_eq!(limits.max_memory_bytes, DEFAULT_MAX_MEMORY_BYTES);
+        assert_eq!(limits.max_table_elements, DEFAULT_MAX_TABLE_ELEMENTS);
+
+        let limits = config.limits_for("strict");
+        assert_eq!(limits.fuel, 7);
+        assert_eq!(limits.max_memory_bytes, 1024);
+        assert_eq!(limits.timeout_ms, 900);
+        assert_eq!(limits.max_table_elements, DEFAULT_MAX_TABLE_ELEMENTS);
+    }
+
+    #[test]
+    fn epoch_deadline_covers_timeout() {
+        assert_eq!(epoch_deadline_ticks(0), 1);
+        assert_eq!(epoch_deadline_ticks(1), 1);
+        assert_eq!(epoch_deadline_ticks(DEFAULT_TIMEOUT_MS), 200);
+        assert_eq!(epoch_deadline_ticks(2_005), 201);
+    }
+
+    #[test]
+    fn v0_2_diagnostics_keep_json_data() {
+        let diag = |data: Option<&str>| validator_v0_2::Diagnostic {
+            severity: "error".to_string(),
+            code: "DEMO".to_string(),
+            message: "demo".to_string(),
+            path: Some("flows/main.ygtc".to_string()),
+            hint: None,
+            data: data.map(str::to_string),
+        };
+
+        let converted = convert_diagnostic_v0_2(diag(Some(r#"{"node":"start","line":3}"#)));
+        assert!(matches!(converted.severity, Severity::Error));
+        assert_eq!(converted.data["node"], "start");
+        assert_eq!(converted.data["line"], 3);
+
+        let raw = convert_diagnostic_v0_2(diag(Some("not json")));
+        assert_eq!(raw.data, Value::String("not json".to_string()));
+        assert_eq!(convert_diagnostic_v0_2(diag(None)).data, Value::Null);
+    }
+
+    #[test]
+    fn read_file_is_bounded_to_pack_contents() {
+        use host_v0_2::Host;
+
+        let big = vec![0u8; (MAX_READ_FILE_BYTES + 1) as usize];
+        let files = HashMap::from([
+            ("flows/main.ygtc".to_string(), b"id: main".to_vec()),
+            ("assets/big.bin".to_string(), big),
+        ]);
+        let mut ctx = ValidatorCtx::new(ValidatorLimits::default(), Arc::new(files));
+
+        assert_eq!(
+            ctx.read_file("flows/main.ygtc".to_string()).expect("read"),
+            b"id: main"
+        );
+        assert!(matches!(
+            ctx.read_file("../pack.yaml".to_string()),
+            Err(ReadError::NotFound)
+        ));
+        assert!(matches!(
+            ctx.read_file("assets/big.bin".to_string()),
+            Err(ReadError::TooLarge(size)) if size == MAX_READ_FILE_BYTES + 1
+        ));
+
+        ctx.read_budget = 4;
+        assert!(matches!(
+            ctx.read_file("flows/main.ygtc".to_string()),
+            Err(ReadError::BudgetExhausted)
+        ));
+    }
+
+    #[test]
+    fn v0_2_inputs_expose_lock_entries() {
+        let mut components = BTreeMap::new();
+        components.insert(
+            "demo.echo".to_string(),
+            greentic_pack::pack_lock::LockedComponent {
+                component_id: "demo.echo".to_string(),
+                r#ref: Some("oci://ghcr.io/greentic-ai/demo-echo:0.1.0".to_string()),
+                abi_version: "0.6.0".to_string(),
+                resolved_digest: format!("sha256:{}", "a".repeat(64)),
+                describe_hash: "b".repeat(64),
+                operations: Vec::new(),
+                world: None,
+                component_version: Some("0.1.0".to_string()),
+                role: None,
+            },
+        );
+        let inputs = ValidatorInputs {
+            manifest_cbor: Vec::new(),
+            sbom_json: "{}".to_string(),
+            file_index: vec!["pack.lock.cbor".to_string()],
+            lock: Some(PackLockV1::new(components)),
+            files: Arc::new(HashMap::new()),
+            archive: None,
+        };
+
+        let v2 = inputs.to_v0_2();
+        assert_eq!(v2.lock_components.len(), 1);
+        assert_eq!(v2.lock_components[0].component_id, "demo.echo");
+        assert_eq!(v2.lock_components[0].version.as_deref(), Some("0.1.0"));
+        assert!(v2.lock_dependencies.is_empty());
+        assert_eq!(inputs.to_v0_1().file_index, v2.file_index);
+    }
+
+    #[test]
+    fn validator_abi_requires_a_validator_export() {
+        let engine = build_engine().expect("engine");
+        let component = Component::new(&engine, "(component)").expect("empty component");
+        assert!(validator_abi(&engine, &component).is_err());
+    }
+
+    #[test]
+    fn malformed_validator_is_a_failure_not_a_limit() {
+        let engine = build_engine().expect("engine");
+        let mut linker = build_linker(&engine).expect("linker");
+        let component = ValidatorComponent {
+            component_id: "broken".to_string(),
+            wasm: b"not wasm".to_vec(),
+        };
+        let inputs = ValidatorInputs {
+            manifest_cbor: Vec::new(),
+            sbom_json: String::new(),
+            file_index: Vec::new(),
+            lock: None,
+            files: Arc::new(HashMap::new()),
+            archive: None,
+        };
+        let limits = ValidatorLimits {
+            fuel: 1,
+            ..ValidatorLimits::default()
+        };
+
+        let (usage, outcome) =
+            run_component_validator(&engine, &mut linker, &component, &inputs, limits);
+        assert!(outcome.is_err());
+        assert_eq!(usage.outcome, "failed");
+        assert!(!usage.exceeded_limit());
+        assert_eq!(usage.fuel_consumed, 0);
+    }
+
     #[test]
     fn validator_pack_accepts_id_wasm_from_pack_manifest() {
         let temp = tempdir().expect("temp dir");
@@ -804,10 +1518,14 @@ mod tests {
         writer.write_all(&sbom_cbor).expect("write sbom");
         writer.finish().expect("finish pack");
 
-        let components =
-            load_validator_components_from_pack(&pack_path).expect("load validator components");
+        let components = load_validator_components_from_pack(&pack_path, &ReaderLimits::default())
+            .expect("load validator components");
         assert_eq!(components.len(), 1);
         assert_eq!(components[0].component_id, "messaging-validator");
         assert_eq!(components[0].wasm, wasm_bytes);
+
+        let tight = ReaderLimits::new(ReaderLimits::DEFAULT_MAX_ARCHIVE_BYTES, 4);
+        let err = load_validator_components_from_pack(&pack_path, &tight).unwrap_err();
+        assert!(format!("{err:#}").contains("exceeds maximum"), "{err:#}");
     }
 }
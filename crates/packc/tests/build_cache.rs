@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use assert_cmd::prelude::*;
+use walkdir::WalkDir;
+
+fn fixture_dir(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("packs")
+        .join(name)
+}
+
+fn copy_fixture(name: &str, dest: &Path) {
+    let src = fixture_dir(name);
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
+    }
+}
+
+struct Workspace {
+    _temp: tempfile::TempDir,
+    pack_dir: PathBuf,
+    out_dir: PathBuf,
+}
+
+impl Workspace {
+    fn new() -> Self {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let pack_dir = temp.path().join("pack");
+        let out_dir = temp.path().join("out");
+        copy_fixture("valid-minimal", &pack_dir);
+        fs::create_dir_all(pack_dir.join("assets")).expect("assets dir");
+        fs::write(pack_dir.join("assets/readme.txt"), "v1").expect("write asset");
+        fs::create_dir_all(&out_dir).expect("out dir");
+        Self {
+            _temp: temp,
+            pack_dir,
+            out_dir,
+        }
+    }
+
+    fn gtpack(&self) -> PathBuf {
+        self.out_dir.join("pack.gtpack")
+    }
+
+    fn build(&self, extra: &[&str]) -> Output {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+        cmd.args([
+            "build",
+            "--in",
+            self.pack_dir.to_str().unwrap(),
+            "--allow-pack-schema",
+            "--manifest",
+            self.out_dir.join("manifest.cbor").to_str().unwrap(),
+            "--gtpack-out",
+            self.gtpack().to_str().unwrap(),
+            "--cache-dir",
+            self.out_dir.join("cache").to_str().unwrap(),
+            "--offline",
+            "--log",
+            "warn",
+        ])
+        .args(extra)
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+        cmd.assert().success().get_output().clone()
+    }
+}
+
+#[test]
+fn unchanged_build_is_skipped_and_explained() {
+    let workspace = Workspace::new();
+    workspace.build(&[]);
+    assert!(workspace.pack_dir.join(".packc/build-cache.json").exists());
+    let first = fs::read(workspace.gtpack()).expect("read gtpack");
+
+    let output = workspace.build(&["--explain"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("update: up to date"), "{stderr}");
+    assert!(stderr.contains("resolve: up to date"), "{stderr}");
+    assert!(stderr.contains("package: up to date"), "{stderr}");
+    assert_eq!(fs::read(workspace.gtpack()).expect("read gtpack"), first);
+}
+
+#[test]
+fn changed_input_reruns_package_with_reason() {
+    let workspace = Workspace::new();
+    workspace.build(&[]);
+    fs::write(workspace.pack_dir.join("assets/readme.txt"), "v2").expect("write asset");
+
+    let output = workspace.build(&["--explain"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("update: up to date"), "{stderr}");
+    assert!(stderr.contains("package: re-run"), "{stderr}");
+    assert!(stderr.contains("`assets/readme.txt` changed"), "{stderr}");
+    let incremental = fs::read(workspace.gtpack()).expect("read gtpack");
+
+    let output = workspace.build(&["--no-cache", "--explain"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("build cache disabled (--no-cache)"),
+        "{stderr}"
+    );
+    assert_eq!(
+        fs::read(workspace.gtpack()).expect("read gtpack"),
+        incremental,
+        "incremental build must match a clean build"
+    );
+}
+
+#[test]
+fn modified_output_is_rebuilt() {
+    let workspace = Workspace::new();
+    workspace.build(&[]);
+    let first = fs::read(workspace.gtpack()).expect("read gtpack");
+    fs::write(workspace.gtpack(), b"tampered").expect("tamper gtpack");
+
+    let output = workspace.build(&["--explain"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("was modified"), "{stderr}");
+    assert_eq!(fs::read(workspace.gtpack()).expect("read gtpack"), first);
+}
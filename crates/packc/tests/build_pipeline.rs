@@ -331,6 +331,95 @@ flows: []
     );
 }
 
+#[test]
+fn sbom_without_gtpack_out_describes_archive_files() {
+    let temp = tempdir().unwrap();
+    let pack_dir = temp.path();
+    fs::create_dir_all(pack_dir.join("components")).unwrap();
+    let wasm_path = pack_dir.join("components/auto.wasm");
+    write_stub_wasm(&wasm_path);
+    write_describe_sidecar(&wasm_path, "dev.auto", "0.1.0");
+    fs::create_dir_all(pack_dir.join("assets")).unwrap();
+    fs::write(pack_dir.join("assets/notes.txt"), "hello").unwrap();
+    fs::create_dir_all(pack_dir.join("flows")).unwrap();
+    fs::write(
+        pack_dir.join("flows/main.ygtc"),
+        r#"id: main
+type: messaging
+start: call
+nodes:
+  call:
+    component.exec:
+      component: dev.auto
+      operation: handle_message
+      input:
+        text: "hi"
+    routing:
+      - out: true
+"#,
+    )
+    .unwrap();
+    let digest = format!("sha256:{:x}", Sha256::digest(fs::read(&wasm_path).unwrap()));
+    let summary = json!({
+        "schema_version": 1,
+        "flow": "main.ygtc",
+        "nodes": {
+            "call": {
+                "component_id": "dev.auto",
+                "source": { "kind": "local", "path": "../components/auto.wasm" },
+                "digest": digest
+            }
+        }
+    });
+    fs::write(
+        pack_dir.join("flows/main.ygtc.resolve.summary.json"),
+        serde_json::to_vec_pretty(&summary).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        pack_dir.join("pack.yaml"),
+        "pack_id: dev.local.auto\nversion: 0.1.0\nkind: application\npublisher: Test\ncomponents: []\nflows: []\n",
+    )
+    .unwrap();
+
+    let sbom_out = pack_dir.join("dist/sbom.cdx.json");
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(pack_dir)
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .args([
+            "build",
+            "--in",
+            pack_dir.to_str().unwrap(),
+            "--allow-pack-schema",
+            "--sbom",
+            sbom_out.to_str().unwrap(),
+            "--log",
+            "warn",
+        ])
+        .output()
+        .expect("run packc build");
+    assert!(
+        output.status.success(),
+        "packc build failed:\nstdout={}\nstderr={}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let bom: serde_json::Value =
+        serde_json::from_slice(&fs::read(&sbom_out).expect("read sbom")).expect("sbom json");
+    let asset = bom["components"]
+        .as_array()
+        .expect("components")
+        .iter()
+        .find(|component| component["name"] == "assets/notes.txt")
+        .unwrap_or_else(|| panic!("asset should be inventoried without --gtpack-out: {bom:#}"));
+    assert_eq!(
+        asset["hashes"][0]["content"],
+        blake3::hash(b"hello").to_hex().as_str()
+    );
+    assert!(!pack_dir.join("dist/pack.gtpack").exists());
+}
+
 #[test]
 fn build_generates_summary_with_cached_oci_components() {
     let temp = tempdir().unwrap();
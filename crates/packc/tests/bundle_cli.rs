@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use ed25519_dalek::SigningKey;
+use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+use greentic_types::decode_pack_manifest;
+use greentic_types::pack_manifest::ExtensionInline;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+use zip::write::SimpleFileOptions;
+
+fn copy_fixture(name: &str, dest: &Path) {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/packs")
+        .join(name);
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
+    }
+}
+
+fn greentic_pack(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.args(args).env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+    cmd
+}
+
+/// Build the valid-minimal fixture as `pack_id` and return the archive path.
+fn build_pack(root: &Path, pack_id: &str) -> PathBuf {
+    let pack_dir = root.join(pack_id);
+    copy_fixture("valid-minimal", &pack_dir);
+    let pack_yaml = fs::read_to_string(pack_dir.join("pack.yaml")).expect("read pack.yaml");
+    fs::write(
+        pack_dir.join("pack.yaml"),
+        pack_yaml.replace("dev.local.valid-minimal", pack_id),
+    )
+    .expect("write pack.yaml");
+
+    let out = root.join(format!("{pack_id}.gtpack"));
+    greentic_pack(&[
+        "build",
+        "--in",
+        pack_dir.to_str().unwrap(),
+        "--allow-pack-schema",
+        "--no-cache",
+        "--offline",
+        "--log",
+        "warn",
+    ])
+    .arg("--manifest")
+    .arg(pack_dir.join("dist/manifest.cbor"))
+    .arg("--gtpack-out")
+    .arg(&out)
+    .arg("--cache-dir")
+    .arg(root.join("cache"))
+    .assert()
+    .success();
+    out
+}
+
+/// Write an Ed25519 key pair and return `(private, public)` PEM paths.
+fn write_keys(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    let mut secret = [0u8; 32];
+    getrandom::fill(&mut secret).expect("generate random signing key bytes");
+    let signing_key = SigningKey::from_bytes(&secret);
+    let priv_pem = signing_key
+        .to_pkcs8_pem(pkcs8::LineEnding::LF)
+        .expect("priv pem");
+    let pub_pem = signing_key
+        .verifying_key()
+        .to_public_key_pem(pkcs8::LineEnding::LF)
+        .expect("pub pem");
+    let priv_path = dir.join(format!("{name}.sk.pem"));
+    let pub_path = dir.join(format!("{name}.pk.pem"));
+    fs::write(&priv_path, priv_pem.as_bytes()).expect("write sk");
+    fs::write(&pub_path, pub_pem.as_bytes()).expect("write pk");
+    (priv_path, pub_path)
+}
+
+fn read_entries(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut archive = ZipArchive::new(fs::File::open(path).expect("open archive")).expect("zip");
+    let mut entries = BTreeMap::new();
+    for idx in 0..archive.len() {
+        let mut file = archive.by_index(idx).expect("entry");
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).expect("read entry");
+        entries.insert(file.name().to_string(), bytes);
+    }
+    entries
+}
+
+/// Two packs that lock the same component, bundled and signed with `key`.
+fn bundle(root: &Path, key: &Path) -> (Vec<PathBuf>, PathBuf) {
+    let packs = vec![
+        build_pack(root, "dev.local.app-a"),
+        build_pack(root, "dev.local.app-b"),
+    ];
+    let out = root.join("offline.gtpack");
+    greentic_pack(&["bundle"])
+        .args(&packs)
+        .arg("--out")
+        .arg(&out)
+        .arg("--key")
+        .arg(key)
+        .args(["--environment-ref", "env-offline"])
+        .assert()
+        .success();
+    (packs, out)
+}
+
+#[test]
+fn bundle_deduplicates_components_and_unbundle_restores_packs() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let (private, public) = write_keys(temp.path(), "publisher");
+    let (packs, bundle_path) = bundle(temp.path(), &private);
+
+    let entries = read_entries(&bundle_path);
+    let blobs: Vec<_> = entries
+        .keys()
+        .filter(|name| name.starts_with("components/sha256/"))
+        .collect();
+    assert_eq!(blobs.len(), 1, "{:?}", entries.keys());
+    assert!(entries.contains_key("packs/dev.local.app-a/manifest.cbor"));
+    assert!(entries.contains_key("signatures/pack.sig"));
+    assert!(
+        !entries
+            .keys()
+            .any(|name| name.starts_with("packs/") && name.ends_with(".wasm")),
+        "component binaries are stored once at the bundle root"
+    );
+
+    let manifest = decode_pack_manifest(&entries["manifest.cbor"]).expect("decode manifest");
+    let extension = manifest
+        .extensions
+        .as_ref()
+        .and_then(|exts| exts.get("greentic.distribution-bundle.v1"))
+        .expect("distribution extension");
+    let Some(ExtensionInline::Other(section)) = extension.inline.as_ref() else {
+        panic!("distribution extension should carry the section inline");
+    };
+    assert_eq!(section["environment_ref"], "env-offline");
+    assert!(
+        section["desired_state_version"]
+            .as_str()
+            .is_some_and(|version| version.starts_with("sha256:"))
+    );
+    let components = section["components"].as_array().expect("components");
+    assert_eq!(components.len(), 1);
+    assert_eq!(
+        components[0]["tags"],
+        serde_json::json!(["dev.local.app-a", "dev.local.app-b"])
+    );
+
+    let into = temp.path().join("unbundled");
+    greentic_pack(&["unbundle", bundle_path.to_str().unwrap(), "--into"])
+        .arg(&into)
+        .arg("--key")
+        .arg(&public)
+        .assert()
+        .success();
+    for pack in &packs {
+        let restored = into.join(pack.file_name().expect("pack file name"));
+        assert_eq!(read_entries(&restored), read_entries(pack));
+    }
+}
+
+#[test]
+fn unbundle_rejects_untrusted_or_tampered_bundles() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let (private, _) = write_keys(temp.path(), "publisher");
+    let (_, other_public) = write_keys(temp.path(), "other");
+    let (_, bundle_path) = bundle(temp.path(), &private);
+
+    greentic_pack(&["unbundle", bundle_path.to_str().unwrap(), "--into"])
+        .arg(temp.path().join("untrusted"))
+        .arg("--key")
+        .arg(&other_public)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("not signed by a trusted key"));
+
+    // Without a key or trust root, only an explicit opt-in accepts the signature.
+    greentic_pack(&["unbundle", bundle_path.to_str().unwrap(), "--into"])
+        .arg(temp.path().join("no-trust"))
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--allow-untrusted"));
+    greentic_pack(&["unbundle", bundle_path.to_str().unwrap(), "--into"])
+        .arg(temp.path().join("dev"))
+        .arg("--allow-untrusted")
+        .assert()
+        .success();
+
+    // Swap the shared component binary for different bytes.
+    let tampered = temp.path().join("tampered.gtpack");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&tampered).expect("create"));
+    for (name, mut bytes) in read_entries(&bundle_path) {
+        if name.starts_with("components/sha256/") {
+            bytes.extend_from_slice(b"tampered");
+        }
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .expect("start entry");
+        writer.write_all(&bytes).expect("write entry");
+    }
+    writer.finish().expect("finish zip");
+
+    let into = temp.path().join("tampered-out");
+    greentic_pack(&["unbundle", tampered.to_str().unwrap(), "--into"])
+        .arg(&into)
+        .arg("--allow-untrusted")
+        .assert()
+        .failure();
+    assert!(!into.exists());
+}
@@ -165,6 +165,12 @@ fn build_writes_all_outputs_offline_with_cache_dir() {
     assert!(out_manifest.exists(), "manifest output missing");
     assert!(out_sbom.exists(), "sbom output missing");
     assert!(out_gtpack.exists(), "gtpack output missing");
+
+    let bom: JsonValue =
+        serde_json::from_slice(&std::fs::read(&out_sbom).expect("read sbom")).expect("sbom json");
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["specVersion"], "1.5");
+    assert_eq!(bom["metadata"]["component"]["type"], "application");
 }
 
 #[test]
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+fn copy_fixture(name: &str, dest: &Path) {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/packs")
+        .join(name);
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
+    }
+}
+
+fn dev_cmd(pack_dir: &Path, cache_dir: &Path) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.args([
+        "dev",
+        "--in",
+        pack_dir.to_str().unwrap(),
+        "--allow-pack-schema",
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+        "--offline",
+        "--log",
+        "warn",
+    ])
+    .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+    cmd
+}
+
+#[test]
+fn dev_writes_dev_layout_pack() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    copy_fixture("valid-minimal", &pack_dir);
+
+    let output = dev_cmd(&pack_dir, &temp.path().join("cache"))
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("dev: ready"), "{stderr}");
+
+    let gtpack = pack_dir.join("dist/dev/pack.gtpack");
+    let mut archive = ZipArchive::new(fs::File::open(&gtpack).expect("open gtpack")).expect("zip");
+    assert!(
+        archive.by_name("flows/main/flow.ygtc").is_ok(),
+        "dev layout should include flow sources"
+    );
+}
+
+#[test]
+fn dev_reports_lint_errors_without_building() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    copy_fixture("valid-minimal", &pack_dir);
+    fs::write(pack_dir.join("flows/main.ygtc"), "id: main\nnodes: [\n").expect("break flow");
+
+    let output = dev_cmd(&pack_dir, &temp.path().join("cache"))
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("PACK_LINT_FLOW_COMPILE"), "{stderr}");
+    assert!(!pack_dir.join("dist/dev/pack.gtpack").exists());
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use greentic_pack::pack_lock::{LockedComponent, LockedOperation, PackLockV1, write_pack_lock};
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn locked(id: &str, version: &str, digest: char, ops: &[(&str, char)]) -> LockedComponent {
+    LockedComponent {
+        component_id: id.to_string(),
+        r#ref: Some(format!("oci://example/{id}:{version}")),
+        abi_version: "0.6.0".to_string(),
+        resolved_digest: format!("sha256:{}", digest.to_string().repeat(64)),
+        describe_hash: digest.to_string().repeat(64),
+        operations: ops
+            .iter()
+            .map(|(op, hash)| LockedOperation {
+                operation_id: op.to_string(),
+                schema_hash: hash.to_string().repeat(64),
+            })
+            .collect(),
+        world: Some("greentic:component/component@0.6.0".to_string()),
+        component_version: Some(version.to_string()),
+        role: Some("tool".to_string()),
+    }
+}
+
+fn write_lock(path: &Path, components: Vec<LockedComponent>) {
+    let components: BTreeMap<_, _> = components
+        .into_iter()
+        .map(|component| (component.component_id.clone(), component))
+        .collect();
+    write_pack_lock(path, &PackLockV1::new(components)).expect("write lock");
+}
+
+fn run_diff(temp: &TempDir, json: bool) -> Vec<u8> {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.current_dir(temp.path())
+        .args(["diff", "old.lock.cbor", "new.lock.cbor"]);
+    if json {
+        cmd.arg("--json");
+    }
+    cmd.assert().success().get_output().stdout.clone()
+}
+
+#[test]
+fn diff_reports_lockfile_changes() {
+    let temp = TempDir::new().expect("temp dir");
+    write_lock(
+        &temp.path().join("old.lock.cbor"),
+        vec![
+            locked("demo.echo", "1.4.2", 'a', &[("legacy", '2'), ("run", '1')]),
+            locked("demo.retired", "0.1.0", 'c', &[]),
+        ],
+    );
+    write_lock(
+        &temp.path().join("new.lock.cbor"),
+        vec![
+            locked("demo.echo", "1.4.3", 'b', &[("run", '9')]),
+            locked("demo.fresh", "0.1.0", 'd', &[]),
+        ],
+    );
+
+    let payload: Value = serde_json::from_slice(&run_diff(&temp, true)).expect("json");
+    assert_eq!(payload["breaking"], true);
+    assert_eq!(payload["components"]["added"][0]["id"], "demo.fresh");
+    assert_eq!(payload["components"]["removed"][0]["id"], "demo.retired");
+    let changed = &payload["components"]["changed"][0];
+    assert_eq!(changed["id"], "demo.echo");
+    assert_eq!(changed["version"]["old"], "1.4.2");
+    assert_eq!(changed["version"]["new"], "1.4.3");
+    let ops = changed["operations"].as_array().expect("operations");
+    assert_eq!(ops[0]["operation_id"], "legacy");
+    assert_eq!(ops[0]["kind"], "removed");
+    assert_eq!(ops[1]["operation_id"], "run");
+    assert_eq!(ops[1]["kind"], "schema_changed");
+    assert_eq!(ops[1]["breaking"], true);
+    assert!(payload.get("contents").is_none());
+
+    let human = String::from_utf8(run_diff(&temp, false)).expect("utf8");
+    assert!(human.contains("~ demo.echo [breaking]"), "{human}");
+    assert!(human.contains("version: 1.4.2 -> 1.4.3"), "{human}");
+    assert!(human.contains("Breaking changes: yes"), "{human}");
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use greentic_types::decode_pack_manifest;
+use greentic_types::pack_manifest::ExtensionInline;
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+const ARTIFACT: &[u8] = b"#!/bin/sh\necho installing\n";
+
+fn copy_fixture(name: &str, dest: &Path) {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/packs")
+        .join(name);
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
+    }
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+/// The valid-minimal fixture turned into a distribution bundle whose single
+/// component points at `artifacts/install.sh` with `digest`.
+fn bundle_pack(dest: &Path, digest: &str) {
+    copy_fixture("valid-minimal", dest);
+    fs::create_dir_all(dest.join("artifacts")).expect("artifacts dir");
+    fs::write(dest.join("artifacts/install.sh"), ARTIFACT).expect("write artifact");
+
+    let pack_yaml = fs::read_to_string(dest.join("pack.yaml")).expect("read pack.yaml");
+    let pack_yaml = pack_yaml.replace("kind: application", "kind: distribution-bundle");
+    let distribution = format!(
+        "distribution:\n  environment_ref: env-prod\n  desired_state_version: v1\n  components:\n  - component_id: demo.installer\n    version: 1.0.0\n    digest: {digest}\n    artifact_path: artifacts/install.sh\n    kind: software\n    entrypoint: install.sh\n"
+    );
+    fs::write(dest.join("pack.yaml"), pack_yaml + &distribution).expect("write pack.yaml");
+}
+
+fn greentic_pack(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.args(args).env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+    cmd
+}
+
+fn build(pack_dir: &Path, out_dir: &Path) -> Command {
+    let mut cmd = greentic_pack(&[
+        "build",
+        "--in",
+        pack_dir.to_str().unwrap(),
+        "--allow-pack-schema",
+        "--no-cache",
+        "--offline",
+        "--log",
+        "warn",
+    ]);
+    cmd.arg("--manifest")
+        .arg(out_dir.join("manifest.cbor"))
+        .arg("--gtpack-out")
+        .arg(out_dir.join("bundle.gtpack"))
+        .arg("--cache-dir")
+        .arg(out_dir.join("cache"));
+    cmd
+}
+
+fn doctor(gtpack: &Path) -> std::process::Output {
+    greentic_pack(&[
+        "doctor",
+        gtpack.to_str().unwrap(),
+        "--json",
+        "--no-flow-doctor",
+        "--no-component-doctor",
+    ])
+    .output()
+    .expect("run doctor")
+}
+
+#[test]
+fn build_packages_distribution_artifacts() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    bundle_pack(&pack_dir, &sha256(ARTIFACT));
+
+    build(&pack_dir, temp.path()).assert().success();
+
+    let file = fs::File::open(temp.path().join("bundle.gtpack")).expect("open gtpack");
+    let mut archive = ZipArchive::new(file).expect("zip");
+    let mut bundled = Vec::new();
+    archive
+        .by_name("artifacts/install.sh")
+        .expect("artifact packaged at its artifact_path")
+        .read_to_end(&mut bundled)
+        .expect("read artifact");
+    assert_eq!(bundled, ARTIFACT);
+
+    let mut manifest_bytes = Vec::new();
+    archive
+        .by_name("manifest.cbor")
+        .expect("manifest")
+        .read_to_end(&mut manifest_bytes)
+        .expect("read manifest");
+    let manifest = decode_pack_manifest(&manifest_bytes).expect("decode manifest");
+    let extension = manifest
+        .extensions
+        .as_ref()
+        .and_then(|exts| exts.get("greentic.distribution-bundle.v1"))
+        .expect("distribution extension");
+    let Some(ExtensionInline::Other(section)) = extension.inline.as_ref() else {
+        panic!("distribution extension should carry the section inline");
+    };
+    assert_eq!(section["bundle_id"], "dev.local.valid-minimal");
+    assert_eq!(section["components"][0]["digest"], sha256(ARTIFACT));
+
+    let output = doctor(&temp.path().join("bundle.gtpack"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("PACK_DISTRIBUTION_"), "{stdout}");
+}
+
+#[test]
+fn build_rejects_artifact_digest_mismatch() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    bundle_pack(&pack_dir, &sha256(b"something else"));
+
+    build(&pack_dir, temp.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "has digest {}",
+            sha256(ARTIFACT)
+        )));
+    assert!(!temp.path().join("bundle.gtpack").exists());
+}
+
+#[test]
+fn build_rejects_distribution_bundle_without_section() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    copy_fixture("valid-minimal", &pack_dir);
+    let pack_yaml = fs::read_to_string(pack_dir.join("pack.yaml")).expect("read pack.yaml");
+    fs::write(
+        pack_dir.join("pack.yaml"),
+        pack_yaml.replace("kind: application", "kind: distribution-bundle"),
+    )
+    .expect("write pack.yaml");
+
+    build(&pack_dir, temp.path())
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "distribution section is required",
+        ));
+}
+
+#[test]
+fn doctor_reports_missing_distribution_artifact() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let pack_dir = temp.path().join("pack");
+    copy_fixture("valid-minimal", &pack_dir);
+    // Hand-written metadata pointing at an artifact the pack never ships.
+    let mut pack_yaml = fs::read_to_string(pack_dir.join("pack.yaml")).expect("read pack.yaml");
+    pack_yaml.push_str(&format!(
+        "extensions:\n  greentic.distribution-bundle.v1:\n    kind: greentic.distribution-bundle.v1\n    version: \"1\"\n    inline:\n      environment_ref: env-prod\n      desired_state_version: v1\n      components:\n      - component_id: demo.installer\n        version: 1.0.0\n        digest: {}\n        artifact_path: artifacts/install.sh\n",
+        sha256(ARTIFACT)
+    ));
+    fs::write(pack_dir.join("pack.yaml"), pack_yaml).expect("write pack.yaml");
+    build(&pack_dir, temp.path()).assert().success();
+
+    let output = doctor(&temp.path().join("bundle.gtpack"));
+    assert!(!output.status.success(), "doctor should fail");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("PACK_DISTRIBUTION_ARTIFACT_MISSING"),
+        "{stdout}"
+    );
+}
@@ -226,6 +226,45 @@ fn doctor_fails_on_missing_provider_schema() {
     );
 }
 
+#[test]
+fn doctor_sarif_maps_validation_diagnostics() {
+    let (_pack_temp, pack_dir) = copy_fixture_to_temp("missing-provider-schema");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .args([
+            "doctor",
+            pack_dir.to_str().unwrap(),
+            "--format",
+            "sarif",
+            "--no-flow-doctor",
+            "--no-component-doctor",
+        ])
+        .output()
+        .expect("run doctor");
+    assert!(
+        !output.status.success(),
+        "doctor should still fail when validation errors exist"
+    );
+
+    let sarif: Value = serde_json::from_slice(&output.stdout).expect("valid sarif");
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+    assert!(rules.iter().any(|rule| rule["id"] == "PACK_MISSING_FILE"));
+    let results = run["results"].as_array().expect("results");
+    assert!(
+        results.iter().any(|result| {
+            result["ruleId"] == "PACK_MISSING_FILE"
+                && result["level"] == "error"
+                && result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                    == "schemas/messaging/demo/config.schema.json"
+        }),
+        "expected missing provider schema result: {sarif}"
+    );
+}
+
 #[test]
 fn doctor_reports_sbom_dangling_path() {
     let temp = tempfile::tempdir().expect("temp dir");
@@ -432,6 +471,125 @@ fn doctor_loads_validator_pack_from_root() {
     );
 }
 
+#[test]
+fn doctor_reports_validator_fuel_exhaustion() {
+    let (_pack_temp, pack_dir) = copy_fixture_to_temp("valid-minimal");
+    let validators_dir = validators_fixture_dir();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .args([
+            "doctor",
+            pack_dir.to_str().unwrap(),
+            "--validators-root",
+            validators_dir.to_str().unwrap(),
+            "--validator-fuel",
+            "1",
+            "--json",
+            "--no-flow-doctor",
+            "--no-component-doctor",
+        ])
+        .output()
+        .expect("run doctor");
+    assert!(
+        output.status.success(),
+        "limit breaches are warnings: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    let validation = &payload["validation"];
+    let exceeded = validation["diagnostics"]
+        .as_array()
+        .expect("validation diagnostics present")
+        .iter()
+        .find(|diag| diag["code"] == "PACK_VALIDATOR_LIMIT_EXCEEDED")
+        .expect("limit diagnostic");
+    assert_eq!(exceeded["data"]["outcome"], "out-of-fuel");
+
+    let usage = validation["sources"]
+        .as_array()
+        .expect("sources")
+        .iter()
+        .flat_map(|source| source["validators"].as_array().cloned().unwrap_or_default())
+        .next()
+        .expect("validator usage");
+    assert_eq!(usage["outcome"], "out-of-fuel");
+    assert_eq!(usage["limits"]["fuel"], 1);
+    assert_eq!(usage["fuel_consumed"], 1);
+}
+
+#[test]
+fn doctor_applies_per_validator_limits() {
+    let (_pack_temp, pack_dir) = copy_fixture_to_temp("valid-minimal");
+    let validators_dir = validators_fixture_dir();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .args([
+            "doctor",
+            pack_dir.to_str().unwrap(),
+            "--validators-root",
+            validators_dir.to_str().unwrap(),
+            "--validator-timeout-ms",
+            "5000",
+            "--validator-limit",
+            "other-validator=fuel:2",
+            "--validator-limit",
+            "noop-validator=fuel:1,table-elements:500",
+            "--json",
+            "--no-flow-doctor",
+            "--no-component-doctor",
+        ])
+        .output()
+        .expect("run doctor");
+    assert!(
+        output.status.success(),
+        "limit breaches are warnings: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    let usage = payload["validation"]["sources"]
+        .as_array()
+        .expect("sources")
+        .iter()
+        .flat_map(|source| source["validators"].as_array().cloned().unwrap_or_default())
+        .find(|usage| usage["component_id"] == "noop-validator")
+        .expect("noop validator usage");
+    assert_eq!(usage["outcome"], "out-of-fuel");
+    assert_eq!(usage["limits"]["fuel"], 1);
+    assert_eq!(usage["limits"]["max_table_elements"], 500);
+    assert_eq!(usage["limits"]["timeout_ms"], 5000);
+}
+
+#[test]
+fn doctor_rejects_malformed_validator_limit() {
+    let (_pack_temp, pack_dir) = copy_fixture_to_temp("valid-minimal");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args([
+            "doctor",
+            pack_dir.to_str().unwrap(),
+            "--validator-limit",
+            "noop-validator=gas:1",
+            "--json",
+            "--no-flow-doctor",
+            "--no-component-doctor",
+        ])
+        .output()
+        .expect("run doctor");
+    assert!(!output.status.success(), "unknown limit key must fail");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown limit `gas`"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 #[test]
 fn doctor_blocks_unlisted_validator_oci_ref() {
     let (_pack_temp, pack_dir) = copy_fixture_to_temp("valid-minimal");
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use greentic_pack::pack_lock::read_pack_lock;
+use greentic_types::{PackManifest, decode_pack_manifest};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+const FIXTURE: &str = "valid-minimal";
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/packs")
+        .join(FIXTURE)
+}
+
+fn copy_fixture(dest: &Path) {
+    let src = fixture_dir();
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
+    }
+}
+
+fn greentic_pack(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.args(args).env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+    cmd
+}
+
+/// Build the pack in `pack_dir` into `out` with the test build flags.
+fn build(root: &Path, pack_dir: &Path, out: &Path, extra: &[&str]) {
+    greentic_pack(&[
+        "build",
+        "--in",
+        pack_dir.to_str().unwrap(),
+        "--no-cache",
+        "--offline",
+        "--log",
+        "warn",
+    ])
+    .args(extra)
+    .arg("--manifest")
+    .arg(pack_dir.join("dist/manifest.cbor"))
+    .arg("--gtpack-out")
+    .arg(out)
+    .arg("--cache-dir")
+    .arg(root.join("cache"))
+    .assert()
+    .success();
+}
+
+fn build_fixture(root: &Path, extra: &[&str]) -> PathBuf {
+    let pack_dir = root.join("pack");
+    copy_fixture(&pack_dir);
+    let out = root.join("original.gtpack");
+    let mut args = vec!["--allow-pack-schema"];
+    args.extend_from_slice(extra);
+    build(root, &pack_dir, &out, &args);
+    out
+}
+
+fn extract(archive: &Path, out: &Path) -> Command {
+    let mut cmd = greentic_pack(&["extract", archive.to_str().unwrap(), "--out"]);
+    cmd.arg(out);
+    cmd
+}
+
+fn read_entries(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut archive = ZipArchive::new(fs::File::open(path).expect("open archive")).expect("zip");
+    let mut entries = BTreeMap::new();
+    for idx in 0..archive.len() {
+        let mut file = archive.by_index(idx).expect("entry");
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).expect("read entry");
+        entries.insert(file.name().to_string(), bytes);
+    }
+    entries
+}
+
+fn manifest(entries: &BTreeMap<String, Vec<u8>>) -> PackManifest {
+    decode_pack_manifest(&entries["manifest.cbor"]).expect("decode manifest")
+}
+
+#[test]
+fn extracted_tree_rebuilds_to_the_same_pack() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let original = build_fixture(temp.path(), &[]);
+    let src = temp.path().join("extracted");
+    extract(&original, &src).assert().success();
+
+    let wasm = src.join("components/dev.local.component/component.wasm");
+    assert_eq!(
+        fs::read(&wasm).expect("restored wasm"),
+        fs::read(fixture_dir().join("components/fixture.wasm")).expect("fixture wasm")
+    );
+    assert!(
+        src.join("components/dev.local.component/component.manifest.cbor")
+            .is_file()
+    );
+    assert!(src.join("flows/main.ygtc.resolve.json").is_file());
+    assert!(src.join("flows/main.ygtc.resolve.summary.json").is_file());
+    let lock = read_pack_lock(&src.join("pack.lock.cbor")).expect("extracted lock");
+    let locked = &lock.components["dev.local.component"];
+    assert_eq!(
+        locked.r#ref.as_deref(),
+        Some(format!("file://{}", wasm.canonicalize().unwrap().display()).as_str())
+    );
+
+    let rebuilt = temp.path().join("rebuilt.gtpack");
+    build(temp.path(), &src, &rebuilt, &[]);
+
+    let before = read_entries(&original);
+    let after = read_entries(&rebuilt);
+    assert_eq!(
+        after["components/dev.local.component.wasm"],
+        before["components/dev.local.component.wasm"]
+    );
+    let (before, after) = (manifest(&before), manifest(&after));
+    assert_eq!(
+        serde_json::to_value(&after.flows).unwrap(),
+        serde_json::to_value(&before.flows).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(&after.components).unwrap(),
+        serde_json::to_value(&before.components).unwrap()
+    );
+    let rebuilt_lock = read_pack_lock(&src.join("pack.lock.cbor")).expect("rebuilt lock");
+    assert_eq!(
+        rebuilt_lock.components["dev.local.component"].resolved_digest,
+        locked.resolved_digest
+    );
+}
+
+#[test]
+fn extract_keeps_dev_flow_sources() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let original = build_fixture(temp.path(), &["--dev"]);
+    let src = temp.path().join("extracted");
+    extract(&original, &src).assert().success();
+
+    assert_eq!(
+        fs::read_to_string(src.join("flows/main.ygtc")).expect("flow source"),
+        fs::read_to_string(fixture_dir().join("flows/main.ygtc")).expect("fixture flow")
+    );
+    let pack_yaml = fs::read_to_string(src.join("pack.yaml")).expect("pack.yaml");
+    assert!(pack_yaml.contains("wasm: components/dev.local.component/component.wasm"));
+    assert!(!pack_yaml.contains("greentic.pack-mode.v1"));
+}
+
+#[test]
+fn extract_refuses_non_empty_output_without_force() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let original = build_fixture(temp.path(), &[]);
+    let src = temp.path().join("extracted");
+    fs::create_dir_all(&src).expect("create out dir");
+    fs::write(src.join("notes.txt"), "keep me").expect("write marker");
+
+    extract(&original, &src)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("is not empty"));
+    assert!(!src.join("pack.yaml").exists());
+
+    extract(&original, &src).arg("--force").assert().success();
+    assert!(src.join("pack.yaml").is_file());
+    assert!(src.join("notes.txt").is_file());
+}
@@ -38,8 +38,14 @@ fn offline_fixture_e2e_new_doctor() {
         validator_allow: vec!["oci://".to_string()],
         validator_cache_dir: PathBuf::from(".greentic/cache/validators"),
         validator_policy: packc::validator::ValidatorPolicy::Optional,
+        validator_fuel: None,
+        validator_max_memory: None,
+        validator_timeout_ms: None,
+        validator_max_table_elements: None,
+        validator_limit: Vec::new(),
         online: false,
         use_describe_cache: true,
+        trust_dir: None,
     };
 
     let result = rt.block_on(inspect_handle(inspect_args, true, &runtime));
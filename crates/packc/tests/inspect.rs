@@ -179,6 +179,32 @@ fn doctor_warns_on_unsupported_manifest() {
     drop(temp_dir);
 }
 
+#[test]
+fn doctor_honours_reader_limit_flags() {
+    let (temp_dir, pack_path, _adapter_name) = build_pack_with_messaging();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args([
+            "--max-file-bytes",
+            "16",
+            "doctor",
+            pack_path.to_str().unwrap(),
+            "--no-flow-doctor",
+            "--no-component-doctor",
+        ])
+        .output()
+        .expect("run doctor");
+    assert!(!output.status.success(), "doctor should reject the archive");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("exceeds maximum allowed size of 16 bytes"),
+        "{stderr}"
+    );
+
+    drop(temp_dir);
+}
+
 fn build_pack_with_messaging() -> (TempDir, PathBuf, String) {
     let adapter_name = "demo-adapter".to_string();
     let temp = TempDir::new().expect("temp dir");
@@ -0,0 +1,183 @@
+use std::fs;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use tempfile::TempDir;
+
+const PACK_YAML: &str = r#"pack_id: dev.local.broken
+version: 0.1.0
+kind: application
+publisher: Greentic
+components:
+- id: demo.echo
+  version: 0.1.0
+  world: greentic:component/component@0.6.0
+  profiles:
+    default: default
+    supported:
+    - default
+  capabilities:
+    wasi:
+      random: false
+      clocks: false
+    host: {}
+  wasm: components/demo.echo/component.wasm
+flows:
+- id: main
+  file: flows/main.ygtc
+extensions:
+  greentic.demo:
+    kind: greentic.other
+    version: 1.0.0
+"#;
+
+fn broken_pack() -> TempDir {
+    let temp = TempDir::new().expect("temp dir");
+    fs::write(temp.path().join("pack.yaml"), PACK_YAML).expect("write pack.yaml");
+    fs::create_dir_all(temp.path().join("flows")).expect("flows dir");
+    fs::write(
+        temp.path().join("flows/main.ygtc"),
+        "id: main\ntype: messaging\nnodes: [\n",
+    )
+    .expect("write flow");
+    temp
+}
+
+fn lint(temp: &TempDir, json: bool) -> std::process::Output {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    cmd.current_dir(temp.path())
+        .args(["lint", "--in", ".", "--log", "warn"]);
+    if json {
+        cmd.arg("--json");
+    }
+    cmd.assert().failure().get_output().clone()
+}
+
+#[test]
+fn lint_collects_every_error_with_spans() {
+    let temp = broken_pack();
+
+    let output = lint(&temp, true);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("json");
+    let diagnostics = payload["diagnostics"].as_array().expect("diagnostics");
+    let find = |code: &str| {
+        diagnostics
+            .iter()
+            .find(|diag| diag["code"] == code)
+            .unwrap_or_else(|| panic!("missing {code} in {diagnostics:#?}"))
+    };
+
+    let wasm = find("PACK_LINT_COMPONENT_WASM_MISSING");
+    assert_eq!(wasm["span"]["line"], 18);
+    assert_eq!(wasm["span"]["column"], 3);
+
+    let extension = find("PACK_LINT_EXTENSION_INVALID");
+    assert_eq!(extension["span"]["line"], 23);
+
+    let flow = find("PACK_LINT_FLOW_COMPILE");
+    assert!(
+        flow["span"]["file"]
+            .as_str()
+            .is_some_and(|file| file.ends_with("main.ygtc")),
+        "{flow}"
+    );
+    assert!(flow["span"]["line"].as_u64().is_some(), "{flow}");
+    assert_eq!(payload["errors"], 3);
+
+    let output = lint(&temp, false);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("error[PACK_LINT_COMPONENT_WASM_MISSING]"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("--> pack.yaml:18:3"), "{stderr}");
+    assert!(
+        stderr.contains("18 |   wasm: components/demo.echo/component.wasm"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("3 error(s)"), "{stderr}");
+}
+
+#[test]
+fn lint_warnings_do_not_fail() {
+    let temp = broken_pack();
+    let wasm = temp.path().join("components/demo.echo/component.wasm");
+    fs::create_dir_all(wasm.parent().unwrap()).expect("component dir");
+    fs::write(&wasm, b"\0asm\x01\0\0\0").expect("write wasm");
+    fs::write(
+        temp.path().join("flows/main.ygtc"),
+        "id: main\ntype: messaging\nstart: hello\nnodes:\n  hello:\n    handle_message:\n      input: \"hi\"\n    routing:\n      - out: true\n",
+    )
+    .expect("write flow");
+    let pack_yaml = PACK_YAML.replace("kind: greentic.other", "kind: greentic.demo");
+    fs::write(temp.path().join("pack.yaml"), pack_yaml).expect("write pack.yaml");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(temp.path())
+        .args(["lint", "--in", ".", "--json", "--log", "warn"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let payload: Value = serde_json::from_slice(&output).expect("json");
+    assert_eq!(payload["errors"], 0);
+    assert_eq!(
+        payload["diagnostics"][0]["code"],
+        "PACK_LINT_SIDECAR_MISSING"
+    );
+}
+
+#[test]
+fn lint_applies_pack_config_section_checks() {
+    let temp = broken_pack();
+    let pack_yaml = format!(
+        "{PACK_YAML}mcp_components:\n- id: ../escape\n  router_ref: mcp/router.wasm\ndistribution:\n  environment_ref: env\n  desired_state_version: \"1\"\n"
+    );
+    fs::write(temp.path().join("pack.yaml"), pack_yaml).expect("write pack.yaml");
+
+    let output = lint(&temp, true);
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("json");
+    let diagnostics = payload["diagnostics"].as_array().expect("diagnostics");
+    let find = |code: &str| {
+        diagnostics
+            .iter()
+            .find(|diag| diag["code"] == code)
+            .unwrap_or_else(|| panic!("missing {code} in {diagnostics:#?}"))
+    };
+
+    let mcp = find("PACK_LINT_MCP_COMPONENT_INVALID");
+    assert!(
+        mcp["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("invalid mcp component id")),
+        "{mcp}"
+    );
+    assert_eq!(mcp["span"]["line"], 26);
+    let distribution = find("PACK_LINT_DISTRIBUTION_INVALID");
+    assert_eq!(distribution["span"]["line"], 29);
+    assert_eq!(payload["errors"], 5);
+    assert_eq!(payload["warnings"], 0);
+}
+
+#[test]
+fn lint_junit_lists_errors_as_failures() {
+    let temp = broken_pack();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(temp.path())
+        .args(["lint", "--in", ".", "--format", "junit", "--log", "warn"])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let xml = String::from_utf8(output).expect("utf8");
+    assert!(xml.starts_with("<?xml"), "{xml}");
+    assert!(xml.contains(r#"tests="3" failures="3""#), "{xml}");
+    assert!(
+        xml.contains(r#"<testcase classname="pack.yaml" name="PACK_LINT_COMPONENT_WASM_MISSING">"#),
+        "{xml}"
+    );
+}
@@ -1,11 +1,19 @@
-use std::path::PathBuf;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use assert_cmd::prelude::*;
+use greentic_pack::pack_lock::read_pack_lock;
+use greentic_types::decode_pack_manifest;
+use sha2::{Digest, Sha256};
 use tempfile::TempDir;
+use walkdir::WalkDir;
 use wasmtime::component::{Component, Linker};
 use wasmtime::{Config, Engine, Store};
 use wasmtime_wasi::p2::add_to_linker_sync;
 use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
+use zip::ZipArchive;
 
 mod component_bindings {
     wasmtime::component::bindgen!({
@@ -86,99 +94,144 @@ impl WasiView for Ctx {
     }
 }
 
-fn compose_adapter_and_router(
-    adapter: &std::path::Path,
-    router: &std::path::Path,
-    out: &std::path::Path,
-) {
-    // Prefer wasm-tools if available; otherwise skip to avoid noisy failures in minimal environments.
-    let has_wasm_tools = Command::new("wasm-tools")
-        .arg("--version")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
-    if !has_wasm_tools {
-        eprintln!("skipping mcp composition test: wasm-tools not installed");
-        return;
+const MCP_ID: &str = "mcp.echo";
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/packs/valid-minimal")
+}
+
+fn copy_fixture(dest: &Path) {
+    let src = fixture_dir();
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create fixture dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture file");
+        }
     }
+}
+
+/// Build a pack declaring an `mcp_components` entry for the echo router and
+/// return the pack dir and the composed component bundled in the archive.
+fn build_mcp_pack(temp: &TempDir) -> (PathBuf, Vec<u8>) {
+    let pack_dir = temp.path().join("pack");
+    copy_fixture(&pack_dir);
+    fs::create_dir_all(pack_dir.join("mcp")).expect("mcp dir");
+    fs::copy(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("router-echo-component.wasm"),
+        pack_dir.join("mcp/router.wasm"),
+    )
+    .expect("copy router");
+    let mut pack_yaml = fs::read_to_string(pack_dir.join("pack.yaml")).expect("pack.yaml");
+    pack_yaml.push_str(&format!(
+        "mcp_components:\n- id: {MCP_ID}\n  router_ref: mcp/router.wasm\n"
+    ));
+    fs::write(pack_dir.join("pack.yaml"), pack_yaml).expect("write pack.yaml");
 
-    let output = Command::new("wasm-tools")
+    let gtpack = temp.path().join("mcp.gtpack");
+    Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .args(["build", "--in"])
+        .arg(&pack_dir)
         .args([
-            "compose",
-            adapter.to_str().unwrap(),
-            "-d",
-            router.to_str().unwrap(),
-            "-o",
-            out.to_str().unwrap(),
+            "--no-cache",
+            "--offline",
+            "--allow-pack-schema",
+            "--log",
+            "warn",
         ])
-        .output()
-        .expect("invoke wasm-tools compose");
-    assert!(
-        output.status.success(),
-        "composition should succeed:\nstdout={}\nstderr={}",
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
-    );
-    assert!(out.exists(), "composed artifact should exist");
+        .arg("--manifest")
+        .arg(pack_dir.join("dist/manifest.cbor"))
+        .arg("--gtpack-out")
+        .arg(&gtpack)
+        .arg("--cache-dir")
+        .arg(temp.path().join("cache"))
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .env_remove("GREENTIC_PACK_ADAPTER_25_06_18")
+        // Composition happens in process with the embedded adapter; make sure no
+        // external tool is picked up.
+        .env("PATH", "")
+        .assert()
+        .success();
+
+    let mut archive = ZipArchive::new(fs::File::open(&gtpack).expect("open gtpack")).expect("zip");
+    let mut composed = Vec::new();
+    archive
+        .by_name(&format!("components/{MCP_ID}.wasm"))
+        .expect("composed component bundled")
+        .read_to_end(&mut composed)
+        .expect("read composed component");
+    (pack_dir, composed)
 }
 
-fn inspect_exports(component: &std::path::Path) -> String {
-    let output = Command::new("wasm-tools")
-        .args(["component", "wit", component.to_str().unwrap()])
-        .output()
-        .expect("invoke wasm-tools component wit");
-    assert!(
-        output.status.success(),
-        "wasm-tools component wit failed: {}",
-        String::from_utf8_lossy(&output.stderr)
-    );
-    String::from_utf8(output.stdout).expect("wit output utf8")
+fn engine() -> Engine {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    Engine::new(&config).expect("engine")
 }
 
 #[test]
 fn composed_component_exports_greentic_world_and_imports_router() {
     let temp = TempDir::new().unwrap();
-    let out = temp.path().join("composed.component.wasm");
-    let adapter = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("assets")
-        .join("mcp_adapter_25_06_18.component.wasm");
-    let router = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("router-echo-component.wasm");
-
-    compose_adapter_and_router(&adapter, &router, &out);
-    let wit = inspect_exports(&out);
+    let (_, composed) = build_mcp_pack(&temp);
+
+    let engine = engine();
+    let component = Component::new(&engine, &composed).expect("load composed component");
+    let ty = component.component_type();
+    let exports: Vec<&str> = ty.exports(&engine).map(|(name, _)| name).collect();
+    let imports: Vec<&str> = ty.imports(&engine).map(|(name, _)| name).collect();
     assert!(
-        wit.contains("export greentic:component/node@0.4.0"),
-        "composed component should export greentic component world:\n{}",
-        wit
+        exports.contains(&"greentic:component/node@0.4.0"),
+        "composed component should export greentic component world: {exports:?}"
     );
     assert!(
-        !wit.contains("import wasix:mcp/router@25.6.18"),
-        "router dependency should be satisfied by composition:\n{}",
-        wit
+        !imports.contains(&"wasix:mcp/router@25.6.18"),
+        "router dependency should be satisfied by composition: {imports:?}"
     );
 }
 
 #[test]
-fn composed_component_invokes_router_echo() {
+fn composed_component_is_locked_and_listed_in_manifest() {
     let temp = TempDir::new().unwrap();
-    let out = temp.path().join("composed.component.wasm");
-    let adapter = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("assets")
-        .join("mcp_adapter_25_06_18.component.wasm");
-    let router = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("router-echo-component.wasm");
+    let (pack_dir, composed) = build_mcp_pack(&temp);
 
-    compose_adapter_and_router(&adapter, &router, &out);
+    let lock = read_pack_lock(&pack_dir.join("pack.lock.cbor")).expect("pack lock");
+    let locked = &lock.components[MCP_ID];
+    assert_eq!(
+        locked.resolved_digest,
+        format!("sha256:{:x}", Sha256::digest(&composed))
+    );
+    assert_eq!(locked.abi_version, "0.4.0");
+    assert!(
+        locked
+            .r#ref
+            .as_deref()
+            .is_some_and(|r| r.ends_with(&format!(".packc/mcp/{MCP_ID}/component.wasm")))
+    );
 
-    let mut config = Config::new();
-    config.wasm_component_model(true);
-    let engine = Engine::new(&config).expect("engine");
-    let component = Component::from_file(&engine, &out).expect("load composed component");
+    let manifest = decode_pack_manifest(
+        &fs::read(pack_dir.join("dist/manifest.cbor")).expect("manifest.cbor"),
+    )
+    .expect("decode manifest");
+    let component = manifest
+        .components
+        .iter()
+        .find(|component| component.id.as_str() == MCP_ID)
+        .expect("composed component listed in manifest");
+    assert_eq!(component.world, "greentic:component/node@0.4.0");
+    assert_eq!(component.version.to_string(), manifest.version.to_string());
+}
+
+#[test]
+fn composed_component_invokes_router_echo() {
+    let temp = TempDir::new().unwrap();
+    let (_, composed) = build_mcp_pack(&temp);
+
+    let engine = engine();
+    let component = Component::new(&engine, &composed).expect("load composed component");
 
     let mut linker: Linker<Ctx> = Linker::new(&engine);
     add_to_linker_sync(&mut linker).expect("add wasi to linker");
@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
 
 use assert_cmd::prelude::*;
 use greentic_types::cbor::canonical;
@@ -123,9 +124,11 @@ fn write_weather_summary(pack_dir: &Path, _cache_dir: &Path) {
     std::fs::write(summary_path, serde_json::to_vec_pretty(&doc).unwrap()).expect("write summary");
 }
 
-#[test]
-fn plan_outputs_json_for_gtpack() {
-    let temp = TempDir::new().expect("temp dir");
+/// Builds share `examples/weather-demo` (lockfile, build cache), so run them one at a time.
+static WEATHER_BUILD: Mutex<()> = Mutex::new(());
+
+fn build_weather_gtpack(temp: &TempDir) -> PathBuf {
+    let _guard = WEATHER_BUILD.lock().unwrap_or_else(|err| err.into_inner());
     let gtpack = temp.path().join("demo.gtpack");
     let cache_dir = temp.path().join("cache");
     let pack_dir = workspace_root().join("examples/weather-demo");
@@ -157,6 +160,13 @@ fn plan_outputs_json_for_gtpack() {
         ])
         .assert()
         .success();
+    gtpack
+}
+
+#[test]
+fn plan_outputs_json_for_gtpack() {
+    let temp = TempDir::new().expect("temp dir");
+    let gtpack = build_weather_gtpack(&temp);
 
     let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
         .current_dir(workspace_root())
@@ -180,6 +190,111 @@ fn plan_outputs_json_for_gtpack() {
     assert_eq!(parsed["environment"], "demo-env");
 }
 
+#[test]
+fn plan_applies_overlays_in_order() {
+    let temp = TempDir::new().expect("temp dir");
+    let gtpack = build_weather_gtpack(&temp);
+    let base = temp.path().join("plan.base.yaml");
+    std::fs::write(
+        &base,
+        "extra:\n  region: eu-west-1\ntelemetry:\n  required: true\n  suggested_endpoint: http://otel:4317\n  extra: {}\n",
+    )
+    .expect("write base overlay");
+    let prod = temp.path().join("plan.prod.json");
+    std::fs::write(
+        &prod,
+        r#"[{"op": "replace", "path": "/runners/0/replicas", "value": 3}]"#,
+    )
+    .expect("write prod overlay");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args(["plan", gtpack.to_str().unwrap(), "--json", "--overlay"])
+        .arg(&base)
+        .arg("--overlay")
+        .arg(&prod)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let parsed: Value = serde_json::from_slice(&output.stdout).expect("plan json");
+    assert_eq!(parsed["runners"][0]["replicas"], 3);
+    assert_eq!(parsed["extra"]["region"], "eu-west-1");
+    assert_eq!(
+        parsed["telemetry"]["suggested_endpoint"],
+        "http://otel:4317"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let source_of = |pointer: &str| {
+        stderr
+            .lines()
+            .find_map(|line| {
+                let mut parts = line.split_whitespace();
+                (parts.next() == Some(pointer)).then(|| parts.next().unwrap_or("").to_string())
+            })
+            .unwrap_or_else(|| panic!("no source for {pointer} in {stderr}"))
+    };
+    assert!(source_of("/runners/0/replicas").ends_with("plan.prod.json"));
+    assert!(source_of("/extra/region").ends_with("plan.base.yaml"));
+    assert_eq!(source_of("/pack_id"), "inferred");
+}
+
+#[test]
+fn plan_rejects_overlay_with_unknown_field() {
+    let temp = TempDir::new().expect("temp dir");
+    let gtpack = build_weather_gtpack(&temp);
+    let overlay = temp.path().join("typo.yaml");
+    std::fs::write(&overlay, "replica_count: 2\n").expect("write overlay");
+
+    Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args(["plan", gtpack.to_str().unwrap(), "--overlay"])
+        .arg(&overlay)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("DeploymentPlan schema"));
+}
+
+#[test]
+fn plan_renders_k8s_manifests() {
+    let temp = TempDir::new().expect("temp dir");
+    let gtpack = build_weather_gtpack(&temp);
+    let out = temp.path().join("k8s");
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args(["plan", gtpack.to_str().unwrap(), "--render", "k8s", "--out"])
+        .arg(&out)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    assert!(output.stdout.is_empty(), "rendering replaces the plan JSON");
+
+    let kustomization =
+        std::fs::read_to_string(out.join("kustomization.yaml")).expect("kustomization");
+    let deployment = kustomization
+        .lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .find(|name| name.starts_with("deployment-"))
+        .unwrap_or_else(|| panic!("no deployment in {kustomization}"));
+    let manifest = std::fs::read_to_string(out.join(deployment)).expect("deployment");
+    assert!(manifest.contains("kind: Deployment"), "{manifest}");
+    assert!(manifest.contains("GREENTIC_FLOWS"), "{manifest}");
+}
+
+#[test]
+fn plan_render_requires_out_dir() {
+    Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .current_dir(workspace_root())
+        .args(["plan", "missing.gtpack", "--render", "compose"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--out"));
+}
+
 #[test]
 fn plan_errors_for_missing_pack() {
     Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
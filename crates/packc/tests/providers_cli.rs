@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
+use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use assert_cmd::prelude::*;
@@ -14,6 +15,7 @@ use greentic_types::{PackId, PackKind, encode_pack_manifest};
 use semver::Version;
 use serde_json::Value;
 use tempfile::TempDir;
+use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipWriter};
 
@@ -184,3 +186,53 @@ fn validate_passes_for_missing_extension() {
         .assert()
         .success();
 }
+
+fn copy_fixture(name: &str, dest: &Path) {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("packs")
+        .join(name);
+    for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+        let target = dest.join(entry.path().strip_prefix(&src).expect("relative path"));
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).expect("create dir");
+        } else {
+            fs::copy(entry.path(), &target).expect("copy file");
+        }
+    }
+}
+
+#[test]
+fn list_builds_source_dir_in_process() {
+    let temp = TempDir::new().expect("temp dir");
+    let pack_dir = temp.path().join("valid-minimal");
+    copy_fixture("valid-minimal", &pack_dir);
+
+    // An empty PATH proves no external `packc` binary is spawned.
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .env("PATH", "")
+        .env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1")
+        .args([
+            "providers",
+            "list",
+            "--pack",
+            pack_dir.to_str().unwrap(),
+            "--json",
+            "--offline",
+            "--cache-dir",
+            temp.path().join("cache").to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let parsed: Vec<Value> = serde_json::from_slice(&output).expect("json");
+    assert!(parsed.is_empty());
+    assert!(
+        !pack_dir.join("dist").exists(),
+        "no archive should be written"
+    );
+}
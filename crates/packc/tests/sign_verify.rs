@@ -192,3 +192,279 @@ fn sign_and_verify_manifest() {
     ]);
     verify.assert().success();
 }
+
+fn write_keypair(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    let mut secret = [0u8; 32];
+    getrandom::fill(&mut secret).expect("generate random signing key bytes");
+    let signing_key = SigningKey::from_bytes(&secret);
+    let priv_pem = signing_key
+        .to_pkcs8_pem(pkcs8::LineEnding::LF)
+        .expect("priv pem");
+    let pub_pem = signing_key
+        .verifying_key()
+        .to_public_key_pem(pkcs8::LineEnding::LF)
+        .expect("pub pem");
+
+    let priv_path = dir.join(format!("{name}.sk.pem"));
+    let pub_path = dir.join(format!("{name}.pk.pem"));
+    fs::write(&priv_path, priv_pem.as_bytes()).expect("write sk");
+    fs::write(&pub_path, pub_pem.as_bytes()).expect("write pk");
+    (priv_path, pub_path)
+}
+
+fn build_weather_gtpack(dir: &Path) -> PathBuf {
+    let gtpack_out = dir.join("weather.gtpack");
+    let cache_dir = dir.join("cache");
+    let pack_dir = workspace_root().join("examples/weather-demo");
+    write_weather_summary(&pack_dir, &cache_dir);
+
+    let mut build = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    build.current_dir(workspace_root());
+    build.env("GREENTIC_PACK_USE_DESCRIBE_CACHE", "1");
+    build.args([
+        "build",
+        "--in",
+        "examples/weather-demo",
+        "--allow-pack-schema",
+        "--manifest",
+        dir.join("manifest.cbor").to_str().unwrap(),
+        "--gtpack-out",
+        gtpack_out.to_str().unwrap(),
+        "--offline",
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+        "--log",
+        "warn",
+    ]);
+    build.assert().success();
+    gtpack_out
+}
+
+#[test]
+fn sign_and_verify_gtpack_archive() {
+    let temp = tempdir().expect("temp dir");
+    let gtpack_out = build_weather_gtpack(temp.path());
+
+    let (priv_path, pub_path) = write_keypair(temp.path(), "publisher");
+    let (other_priv_path, other_pub_path) = write_keypair(temp.path(), "other");
+
+    let mut sign = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    sign.args([
+        "sign",
+        "--pack",
+        gtpack_out.to_str().unwrap(),
+        "--key",
+        priv_path.to_str().unwrap(),
+    ]);
+    sign.assert().success();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .args([
+            "--json",
+            "verify",
+            gtpack_out.to_str().unwrap(),
+            "--key",
+            pub_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run verify");
+    assert!(output.status.success(), "verify failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(report["signatures"][0]["valid"], true);
+    assert_eq!(report["signatures"][0]["trusted"], true);
+    assert_eq!(
+        report["signatures"][0]["trust"]["rule"],
+        "pinned-key:publisher.pk"
+    );
+
+    let mut untrusted = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    untrusted.args([
+        "verify",
+        gtpack_out.to_str().unwrap(),
+        "--key",
+        other_pub_path.to_str().unwrap(),
+    ]);
+    untrusted.assert().failure();
+
+    // Trust directory with a publisher rule for the demo's `publisher: Greentic`.
+    let trust_dir = temp.path().join("trust");
+    fs::create_dir_all(trust_dir.join("keys")).expect("keys dir");
+    fs::copy(&pub_path, trust_dir.join("keys/release.pem")).expect("copy key");
+    fs::copy(&other_pub_path, trust_dir.join("keys/other.pem")).expect("copy key");
+    let verify_with_rule = |allowed: &str| {
+        fs::write(
+            trust_dir.join("policy.yaml"),
+            format!("publishers:\n  Greentic:\n    keys: [{allowed}]\n"),
+        )
+        .expect("write policy");
+        Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+            .args([
+                "--json",
+                "verify",
+                gtpack_out.to_str().unwrap(),
+                "--trust-dir",
+                trust_dir.to_str().unwrap(),
+            ])
+            .output()
+            .expect("run verify")
+    };
+
+    let output = verify_with_rule("release");
+    assert!(output.status.success(), "verify failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(
+        report["signatures"][0]["trust"]["rule"],
+        "publisher:Greentic"
+    );
+
+    let output = verify_with_rule("other");
+    assert!(!output.status.success(), "publisher rule must reject");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(
+        report["signatures"][0]["trust"]["rule"],
+        "publisher:Greentic"
+    );
+    assert_eq!(report["signatures"][0]["trust"]["accepted"], false);
+
+    // Two keys with an approver role required: fails until the countersignature lands.
+    let verify_threshold = || {
+        Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+            .args([
+                "--json",
+                "verify",
+                gtpack_out.to_str().unwrap(),
+                "--key",
+                pub_path.to_str().unwrap(),
+                "--key",
+                other_pub_path.to_str().unwrap(),
+                "--threshold",
+                "2",
+                "--require-role",
+                "approver",
+            ])
+            .output()
+            .expect("run verify")
+    };
+    let output = verify_threshold();
+    assert!(
+        !output.status.success(),
+        "single signature must not meet threshold"
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(report["trust"]["rule"], "require");
+
+    let mut countersign = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    countersign.args([
+        "sign",
+        "--pack",
+        gtpack_out.to_str().unwrap(),
+        "--key",
+        other_priv_path.to_str().unwrap(),
+        "--role",
+        "approver",
+        "--append",
+    ]);
+    countersign.assert().success();
+
+    let output = verify_threshold();
+    assert!(output.status.success(), "verify failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(report["signatures"].as_array().map(Vec::len), Some(2));
+    assert_eq!(report["signatures"][1]["role"], "approver");
+    assert_eq!(report["trust"]["accepted"], true);
+}
+
+/// Minimal signing agent: answers `sign` and `chain` requests with a local key.
+#[cfg(unix)]
+fn spawn_mock_agent(socket: &Path, private_pem: &str) {
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD;
+    use greentic_pack::builder::Signer;
+    use greentic_pack::signing::KeySigner;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    let signer = KeySigner::from_pkcs8_pem(private_pem).expect("agent key");
+    let listener = UnixListener::bind(socket).expect("bind agent socket");
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.expect("agent connection");
+            let mut line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut line)
+                .expect("read request");
+            let request: serde_json::Value = serde_json::from_str(&line).expect("request json");
+            assert_eq!(request["version"], 1);
+            let response = match request["op"].as_str() {
+                Some("sign") => {
+                    let payload = STANDARD
+                        .decode(request["payload"].as_str().expect("payload"))
+                        .expect("payload base64");
+                    let signature = signer.sign(&payload).expect("sign");
+                    serde_json::json!({
+                        "alg": signature.alg,
+                        "signature": STANDARD.encode(signature.sig),
+                    })
+                }
+                Some("chain") => serde_json::json!({
+                    "chain_pem": String::from_utf8(signer.chain_pem().expect("chain")).unwrap(),
+                }),
+                other => serde_json::json!({ "error": format!("unknown op {other:?}") }),
+            };
+            writeln!(stream, "{response}").expect("write response");
+        }
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn sign_gtpack_with_signing_agent() {
+    let temp = tempdir().expect("temp dir");
+    let gtpack_out = build_weather_gtpack(temp.path());
+    let (priv_path, pub_path) = write_keypair(temp.path(), "agent");
+
+    let socket = temp.path().join("signer.sock");
+    spawn_mock_agent(
+        &socket,
+        &fs::read_to_string(&priv_path).expect("read agent key"),
+    );
+    // The agent holds the key; the CLI only sees the socket.
+    fs::remove_file(&priv_path).expect("remove private key");
+
+    let mut sign = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    sign.args([
+        "sign",
+        "--pack",
+        gtpack_out.to_str().unwrap(),
+        "--signer",
+        &format!("agent://{}", socket.display()),
+        "--role",
+        "builder",
+    ]);
+    sign.assert().success();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"))
+        .args([
+            "--json",
+            "verify",
+            gtpack_out.to_str().unwrap(),
+            "--key",
+            pub_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run verify");
+    assert!(output.status.success(), "verify failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("verify json");
+    assert_eq!(report["signatures"][0]["role"], "builder");
+    assert_eq!(report["signatures"][0]["trusted"], true);
+
+    let mut missing_agent = Command::new(assert_cmd::cargo::cargo_bin!("greentic-pack"));
+    missing_agent.args([
+        "sign",
+        "--pack",
+        gtpack_out.to_str().unwrap(),
+        "--signer",
+        &format!("agent://{}", temp.path().join("absent.sock").display()),
+    ]);
+    missing_agent.assert().failure();
+}